@@ -0,0 +1,109 @@
+// Sıcak yolların ölçümleri, sürümden önce performans gerilemelerini yakalamak için: cargo bench
+// Sadece bir grubu çalıştırmak için: cargo bench -- search_sql
+use std::hint::black_box;
+
+use besinveri_api::bench::{self, CacheRouters, FoodList, IndexList, SearchDatabase};
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use tokio::runtime::Runtime;
+
+fn sort_foods_by_query(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("sort_foods_by_query");
+    for size in [100, 1000, 5000] {
+        let foods = FoodList::generate(size);
+        for (ranking, trigram) in [("positional", false), ("trigram", true)] {
+            group.bench_with_input(BenchmarkId::new(ranking, size), &foods, |b, foods| {
+                // Sıralama listeyi değiştirdiği için her turda kopyası veriliyor, kopyalama ölçüme dahil değil
+                b.to_async(&runtime).iter_batched(
+                    || foods.clone(),
+                    |mut foods| async move {
+                        bench::sort_foods_by_query(&mut foods, black_box("elma sut"), trigram)
+                            .await;
+                        foods
+                    },
+                    BatchSize::SmallInput,
+                );
+            });
+        }
+        // Aynı yemekler arama indeksindeki normalize edilmiş satırlar olarak, aramalardaki asıl yol bu
+        let entries = IndexList::generate(size);
+        for (ranking, trigram) in [("positional_index", false), ("trigram_index", true)] {
+            group.bench_with_input(BenchmarkId::new(ranking, size), &entries, |b, entries| {
+                b.to_async(&runtime)
+                    .iter(|| bench::sort_index_by_query(entries, black_box("elma sut"), trigram));
+            });
+        }
+    }
+    group.finish();
+}
+
+fn slug(c: &mut Criterion) {
+    c.bench_function("slug", |b| {
+        b.iter(|| bench::slug(black_box("Tavuk Göğsü (Çiğ, Derisiz) Şiş Izgara")))
+    });
+}
+
+fn search_sql(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let database = runtime
+        .block_on(SearchDatabase::seed(10_000))
+        .expect("Benchmark veritabanı hazırlanamadı");
+    let mut group = c.benchmark_group("search_sql");
+    group.bench_function("description", |b| {
+        b.to_async(&runtime).iter(|| async {
+            database
+                .search_description(black_box("elma sut"))
+                .await
+                .unwrap()
+        })
+    });
+    group.bench_function("tags_match_all", |b| {
+        b.to_async(&runtime).iter(|| async {
+            database
+                .search_tags(black_box(&["meyve", "tahil"]), true)
+                .await
+                .unwrap()
+        })
+    });
+    group.bench_function("browse", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { database.browse().await.unwrap() })
+    });
+    group.finish();
+}
+
+fn cache_middleware(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let routers = runtime
+        .block_on(CacheRouters::new(64 * 1024))
+        .expect("Router'lar hazırlanamadı");
+    let mut group = c.benchmark_group("cache_middleware");
+    group.bench_function("without_cache", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { routers.request(false, "/foods/list").await.unwrap() })
+    });
+    group.bench_function("hit", |b| {
+        b.to_async(&runtime)
+            .iter(|| async { routers.request(true, "/foods/list").await.unwrap() })
+    });
+    // Her istek farklı bir sorgu parametresiyle, önbellekte hiç bulunmuyor ve yanıt önbelleğe yazılıyor
+    group.bench_function("miss", |b| {
+        let mut i = 0u64;
+        b.to_async(&runtime).iter(|| {
+            i += 1;
+            let uri = format!("/foods/list?n={}", i);
+            let routers = &routers;
+            async move { routers.request(true, &uri).await.unwrap() }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    sort_foods_by_query,
+    slug,
+    search_sql,
+    cache_middleware
+);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::Deserialize;
+
+#[derive(Debug)]
+pub enum Error {
+    // Client::new'e verilen adres geçerli bir URL değil
+    InvalidUrl(String),
+    // Bağlantı kurulamadı, zaman aşımı veya yanıt gövdesi beklenen biçimde değil
+    Http(reqwest::Error),
+    // Sunucunun standart JSON hata yanıtı: {"code": 404, "error": "FOOD_NOT_FOUND", "message": "..."}
+    Api(ApiError),
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct ApiError {
+    // Yanıttaki code alanı, HTTP durum kodu
+    #[serde(rename = "code")]
+    pub status: u16,
+    // Yanıttaki error alanı, SCREAMING_SNAKE_CASE hata kodu, README'deki listede. Mesajlar değişebilir, kodlar değişmiyor
+    #[serde(rename = "error")]
+    pub code: String,
+    pub message: String,
+    // 429 ve 503'te kaç saniye sonra tekrar denenmesi gerektiği
+    #[serde(default)]
+    pub retry_after: Option<u64>,
+    // Hata bildirirken sunucu loglarında isteği bulmak için
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+impl Error {
+    // Sunucunun döndürdüğü hata kodu, bağlantı hatalarında None
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Error::Api(error) => Some(&error.code),
+            Error::InvalidUrl(_) | Error::Http(_) => None,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidUrl(url) => write!(f, "Geçersiz API adresi: {}", url),
+            Error::Http(error) => write!(f, "HTTP hatası: {}", error),
+            Error::Api(error) => write!(
+                f,
+                "{} (Kod: {}, {})",
+                error.message, error.status, error.code
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(error) => Some(error),
+            Error::InvalidUrl(_) | Error::Api(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::Http(error)
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// Sunucudaki core::food::Food'un yanıttaki hali. Alanlar sunucuyla aynı adlarla, eski sunucularda bulunmayan alanlar boş geliyor.
+// /food/{slug}'ın döndürdüğü variants ve converted gibi ek alanlar burada yok, serde bilinmeyen alanları atlıyor
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Food {
+    pub id: Option<i64>,
+    pub slug: Option<String>,
+    pub description: String,
+    // Yöresel veya başka dillerdeki adları
+    pub aliases: Vec<String>,
+    pub verified: Option<bool>,
+    // Verinin kim tarafından, ne zaman ve hangi yöntemle doğrulandığı; birden fazla doğrulama varsa en sonuncusu
+    pub verification: Option<Verification>,
+    pub image: FoodImage,
+    pub source: String,
+    pub tags: Vec<String>,
+    pub allergens: Vec<Allergen>,
+    pub additives: Vec<Additive>,
+    // Porsiyon açıklaması -> gram
+    pub servings: BTreeMap<String, f64>,
+    pub seasonality: Vec<Seasonality>,
+    pub variant_group: Option<String>,
+    pub brand: Option<String>,
+    // Markalı ürünlerin markasız genel karşılığı
+    pub generic_food: Option<FoodLink>,
+    pub gtin: Option<String>,
+    pub glycemic_index: f64,
+    pub energy: f64,
+    pub carbohydrate: f64,
+    pub protein: f64,
+    pub fat: f64,
+    pub saturated_fat: f64,
+    pub trans_fat: f64,
+    pub sugar: f64,
+    pub added_sugar: f64,
+    pub polyols: f64,
+    pub fiber: f64,
+    pub cholesterol: f64,
+    pub sodium: f64,
+    pub potassium: f64,
+    pub water: f64,
+    pub iron: f64,
+    pub magnesium: f64,
+    pub calcium: f64,
+    pub zinc: f64,
+    pub vitamin_a: f64,
+    pub vitamin_b6: f64,
+    pub vitamin_b12: f64,
+    pub vitamin_c: f64,
+    pub vitamin_d: f64,
+    pub vitamin_e: f64,
+    pub vitamin_k: f64,
+    pub omega_3: f64,
+    pub omega_6: f64,
+    pub folate: f64,
+    pub niacin: f64,
+    pub phosphorus: f64,
+    pub selenium: f64,
+    pub copper: f64,
+    // Değerlerin neye göre verildiği: "per_100g" veya "serving:<porsiyon>"
+    pub basis: String,
+    // Ölçülmüş sayılan besin değerlerinin oranı (0-1)
+    pub completeness: f64,
+    // Diyet bayrağı -> yemek uyuyor mu
+    pub diet_flags: BTreeMap<String, bool>,
+    pub fruit_vegetable_percent: Option<f64>,
+    pub nutri_score: Option<NutriScore>,
+    // NOVA işlenme sınıfı, 1-4
+    pub nova_group: Option<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Verification {
+    pub verified_by: String,
+    // RFC 3339, UTC
+    pub verified_at: String,
+    // "lab_data", "literature" veya "estimated"
+    pub method: String,
+    pub note: Option<String>,
+}
+
+// Resim lisansıyla birlikte geliyor, fotoğraflar atıfsız dağıtılmamalı
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct FoodImage {
+    pub url: String,
+    pub attribution: Option<String>,
+    pub license: Option<String>,
+    pub source_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Allergen {
+    pub name: String,
+    // "contains" veya üretimde bulaşmış olabilecekler için "may_contain"
+    pub level: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Additive {
+    pub e_number: String,
+    pub name: String,
+    pub category: String,
+    pub notes: Option<String>,
+}
+
+// Yemeğin bir bölgede mevsiminde olduğu aylar, 1 (Ocak) ile 12 (Aralık) arası
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct Seasonality {
+    pub region: String,
+    pub months: Vec<u8>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct FoodLink {
+    pub slug: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct NutriScore {
+    // "A" ile "E" arası
+    pub grade: String,
+    // Düşük puan daha iyi
+    pub points: i64,
+}
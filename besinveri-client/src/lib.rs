@@ -0,0 +1,235 @@
+// BesinVeri API için resmi Rust istemcisi. Yanıtlar sunucudaki tiplerle aynı alanlara sahip struct'lara çevriliyor, rate limit
+// (429) ve geçici hatalarda (502, 503, 504, bağlantı hataları) istek Retry-After'a uyularak tekrar deneniyor
+//
+//     let client = besinveri_client::Client::new(besinveri_client::DEFAULT_BASE_URL)?;
+//     let muz = client.get_food("muz").await?;
+use std::{collections::BTreeMap, time::Duration};
+
+use reqwest::{StatusCode, Url, header::RETRY_AFTER};
+use serde::de::DeserializeOwned;
+
+mod error;
+mod food;
+
+pub use error::{ApiError, Error};
+pub use food::{
+    Additive, Allergen, Food, FoodImage, FoodLink, NutriScore, Seasonality, Verification,
+};
+
+pub const DEFAULT_BASE_URL: &str = "https://api.besinveri.com";
+
+// Kademesi olan API anahtarları bu header'la gönderiliyor
+const API_KEY_HEADER: &str = "x-api-key";
+
+// Tekrar denemeler: Retry-After varsa o kadar, yoksa base_delay'den başlayıp her denemede ikiye katlanarak max_delay'e kadar bekleniyor.
+// Sunucu max_delay'den uzun beklenmesini isterse beklenmeden hata dönülüyor, örneğin günlük kota dolduğunda
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    // Tekrar denemeyen politika
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    // attempt'inci (0'dan başlayarak) tekrar denemeden önce beklenecek süre, tekrar denenmeyecekse None
+    fn delay(&self, attempt: u32, retry_after: Option<u64>) -> Option<Duration> {
+        if attempt >= self.max_retries {
+            return None;
+        }
+        match retry_after {
+            Some(seconds) => {
+                Some(Duration::from_secs(seconds)).filter(|delay| *delay <= self.max_delay)
+            }
+            None => Some(
+                self.base_delay
+                    .saturating_mul(2u32.saturating_pow(attempt))
+                    .min(self.max_delay),
+            ),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Client {
+    http: reqwest::Client,
+    base_url: Url,
+    api_key: Option<String>,
+    retry: RetryPolicy,
+}
+
+impl Client {
+    // base_url API'nin kök adresi: https://api.besinveri.com veya API bir path altındaysa https://ornek.com/api
+    pub fn new(base_url: &str) -> Result<Self, Error> {
+        let base_url = Url::parse(base_url).map_err(|e| Error::InvalidUrl(e.to_string()))?;
+        if base_url.cannot_be_a_base() {
+            return Err(Error::InvalidUrl(base_url.to_string()));
+        }
+        Ok(Self {
+            http: reqwest::Client::new(),
+            base_url,
+            api_key: None,
+            retry: RetryPolicy::default(),
+        })
+    }
+
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_owned());
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    // Zaman aşımı, proxy gibi ayarlar için kendi reqwest istemcisini vermek isteyenler için
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    // GET /food/{slug}. Yemek yoksa Error::Api, kodu FOOD_NOT_FOUND
+    pub async fn get_food(&self, slug: &str) -> Result<Food, Error> {
+        self.get(self.url(&["food", slug])).await
+    }
+
+    // GET /foods/search?q=, açıklama ve diğer adlarda arıyor. limit verilmezse sunucu 5 sonuç döndürüyor
+    pub async fn search(&self, query: &str, limit: Option<u64>) -> Result<Vec<Food>, Error> {
+        let mut url = self.url(&["foods", "search"]);
+        url.query_pairs_mut().append_pair("q", query);
+        if let Some(limit) = limit {
+            url.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
+        self.get(url).await
+    }
+
+    // GET /foods/list, tüm yemeklerin slug -> detay URL'si
+    pub async fn list_foods(&self) -> Result<BTreeMap<String, String>, Error> {
+        self.get(self.url(&["foods", "list"])).await
+    }
+
+    // Path parçaları kodlanarak base_url'in sonuna ekleniyor, slug'daki / gibi karakterler başka bir endpoint'e gitmiyor
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("new'de kontrol edildi")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<T, Error> {
+        let mut attempt = 0;
+        loop {
+            let mut request = self.http.get(url.clone());
+            if let Some(api_key) = &self.api_key {
+                request = request.header(API_KEY_HEADER, api_key);
+            }
+
+            let (error, retry_after) = match request.send().await {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.json().await?);
+                }
+                Ok(response) => {
+                    let retryable = matches!(
+                        response.status(),
+                        StatusCode::TOO_MANY_REQUESTS
+                            | StatusCode::BAD_GATEWAY
+                            | StatusCode::SERVICE_UNAVAILABLE
+                            | StatusCode::GATEWAY_TIMEOUT
+                    );
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.trim().parse::<u64>().ok());
+                    let error = api_error(response).await;
+                    if !retryable {
+                        return Err(Error::Api(error));
+                    }
+                    // Header'ı silen proxy'ler için gövdedeki retry_after da kullanılıyor
+                    let retry_after = retry_after.or(error.retry_after);
+                    (Error::Api(error), retry_after)
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => (Error::Http(e), None),
+                Err(e) => return Err(e.into()),
+            };
+
+            match self.retry.delay(attempt, retry_after) {
+                Some(delay) => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return Err(error),
+            }
+        }
+    }
+}
+
+// Hata yanıtları normalde sunucunun JSON formatında, araya giren bir proxy'nin düz metin yanıtı da ApiError'a çevriliyor
+async fn api_error(response: reqwest::Response) -> ApiError {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    serde_json::from_str(&body).unwrap_or_else(|_| ApiError {
+        status: status.as_u16(),
+        code: "UNEXPECTED_RESPONSE".to_owned(),
+        message: body,
+        retry_after: None,
+        request_id: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        };
+        assert_eq!(policy.delay(0, None), Some(Duration::from_millis(100)));
+        assert_eq!(policy.delay(2, None), Some(Duration::from_millis(400)));
+        assert_eq!(policy.delay(3, None), None);
+        // Retry-After üstel beklemenin yerine geçiyor, max_delay'i aşıyorsa beklenmiyor
+        assert_eq!(policy.delay(0, Some(2)), Some(Duration::from_secs(2)));
+        assert_eq!(policy.delay(0, Some(3600)), None);
+        assert_eq!(RetryPolicy::none().delay(0, Some(1)), None);
+    }
+
+    #[test]
+    fn test_url_encodes_segments() -> Result<(), Error> {
+        let client = Client::new("https://ornek.com/api/")?;
+        assert_eq!(
+            client.url(&["food", "muz/../tags"]).as_str(),
+            "https://ornek.com/api/food/muz%2F..%2Ftags"
+        );
+        let client = Client::new(DEFAULT_BASE_URL)?;
+        assert_eq!(
+            client.url(&["foods", "list"]).as_str(),
+            "https://api.besinveri.com/foods/list"
+        );
+        Ok(())
+    }
+}
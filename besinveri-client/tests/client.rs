@@ -0,0 +1,132 @@
+// İstemci, sunucunun router'ı süreç içinde rastgele bir portta çalıştırılarak test ediliyor. Rate limit ve geçici hatalar için
+// yanıtları sırayla dönen küçük bir sahte sunucu kullanılıyor
+use std::{
+    net::SocketAddr,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use anyhow::Error;
+use axum::{
+    Router,
+    http::{StatusCode, header::RETRY_AFTER},
+    response::IntoResponse,
+    routing::get,
+};
+use besinveri_api::bench::LoadTestApp;
+use besinveri_client::{Client, RetryPolicy};
+use tokio::net::TcpListener;
+
+async fn serve(router: Router) -> Result<String, Error> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+    });
+    Ok(format!("http://{}", address))
+}
+
+// /foods/list'e gelen ilk failures istek verilen durum kodu ve Retry-After ile reddediliyor, sonrakiler başarılı
+async fn flaky_server(
+    failures: usize,
+    status: StatusCode,
+    retry_after: Option<&'static str>,
+) -> Result<(String, Arc<AtomicUsize>), Error> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let router = Router::new().route(
+        "/foods/list",
+        get({
+            let calls = calls.clone();
+            move || async move {
+                if calls.fetch_add(1, Ordering::Relaxed) < failures {
+                    let body = format!(
+                        r#"{{"code": {}, "error": "RATE_LIMITED", "message": "Çok fazla istek"}}"#,
+                        status.as_u16()
+                    );
+                    let mut response = (status, body).into_response();
+                    if let Some(retry_after) = retry_after {
+                        response
+                            .headers_mut()
+                            .insert(RETRY_AFTER, retry_after.parse().unwrap());
+                    }
+                    response
+                } else {
+                    r#"{"muz": "http://localhost/food/muz"}"#.into_response()
+                }
+            }
+        }),
+    );
+    Ok((serve(router).await?, calls))
+}
+
+#[tokio::test]
+async fn test_get_food_search_and_list() -> Result<(), Error> {
+    let app = LoadTestApp::new(50, true).await?;
+    let client = Client::new(&serve(app.router).await?)?;
+
+    let food = client.get_food(&app.slugs[0]).await?;
+    assert_eq!(food.slug.as_deref(), Some(app.slugs[0].as_str()));
+    assert!(food.energy > 0.0);
+    assert!(!food.tags.is_empty());
+
+    let foods = client.search("elma", Some(3)).await?;
+    assert!(!foods.is_empty() && foods.len() <= 3);
+    assert!(
+        foods
+            .iter()
+            .all(|food| food.description.to_lowercase().contains("elma"))
+    );
+
+    let list = client.list_foods().await?;
+    assert_eq!(list.len(), 50);
+    assert!(list.contains_key(&app.slugs[0]));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_api_errors_are_typed() -> Result<(), Error> {
+    let app = LoadTestApp::new(5, true).await?;
+    let client = Client::new(&serve(app.router).await?)?;
+
+    let error = client.get_food("olmayan-yemek").await.unwrap_err();
+    assert_eq!(error.code(), Some("FOOD_NOT_FOUND"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_retries_after_rate_limit() -> Result<(), Error> {
+    let (url, calls) = flaky_server(1, StatusCode::TOO_MANY_REQUESTS, Some("0")).await?;
+    let list = Client::new(&url)?.list_foods().await?;
+    assert_eq!(calls.load(Ordering::Relaxed), 2);
+    assert!(list.contains_key("muz"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_long_retry_after_is_not_waited() -> Result<(), Error> {
+    let (url, calls) = flaky_server(1, StatusCode::TOO_MANY_REQUESTS, Some("3600")).await?;
+    let error = Client::new(&url)?.list_foods().await.unwrap_err();
+    assert_eq!(error.code(), Some("RATE_LIMITED"));
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_gives_up_after_max_retries() -> Result<(), Error> {
+    let (url, calls) = flaky_server(10, StatusCode::SERVICE_UNAVAILABLE, None).await?;
+    let client = Client::new(&url)?.with_retry_policy(RetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(10),
+    });
+    assert!(client.list_foods().await.is_err());
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+    Ok(())
+}
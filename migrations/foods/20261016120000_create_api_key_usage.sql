@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    key_name    TEXT NOT NULL,
+    period      TEXT NOT NULL, -- Günlük kayıtlar için YYYY-MM-DD, aylık kayıtlar için YYYY-MM
+    requests    INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (key_name, period)
+)
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS search_analytics (
+    day             TEXT NOT NULL, -- YYYY-MM-DD, UTC
+    mode            TEXT NOT NULL, -- description veya tag
+    query           TEXT NOT NULL, -- NFC'ye normalize edilmiş, küçük harfli sorgu
+    searches        INTEGER NOT NULL DEFAULT 0,
+    zero_results    INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, mode, query)
+)
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS config_changes (
+    id              INTEGER PRIMARY KEY AUTOINCREMENT,
+    changed_at      TEXT NOT NULL, -- RFC 3339, UTC
+    source          TEXT NOT NULL, -- startup, admin, ...
+    key             TEXT NOT NULL, -- ör: rate_limit.default.max_requests
+    old_value       TEXT,          -- Alan yoksa NULL, gizli alanlarda maskeli
+    new_value       TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_changes_changed_at ON config_changes (changed_at);
+
+-- Son etkin config'in alan yolu -> değer JSON'ı, bir sonraki değişiklikte farkı bulmak için tek satır
+CREATE TABLE IF NOT EXISTS config_snapshot (
+    id              INTEGER PRIMARY KEY CHECK (id = 1),
+    config_values   TEXT NOT NULL,
+    updated_at      TEXT NOT NULL
+);
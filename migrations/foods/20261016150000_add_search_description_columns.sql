@@ -0,0 +1,14 @@
+-- Aramalar Türkçe karakter ve büyük/küçük harf duyarsız olsun diye: "sut" Süt'ü, "SIGIR" Sığır'ı bulmalı.
+-- Bu ifade core::str::to_search_key ile aynı dönüşümü yapıyor, sorgular da onunla normalize ediliyor; biri değişirse diğeri de değişmeli.
+-- SQLite'ın lower() fonksiyonu sadece ASCII harfleri küçültüyor, Türkçe harfleri önce replace ile çeviriyoruz
+ALTER TABLE foods ADD COLUMN search_description TEXT GENERATED ALWAYS AS (
+    lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(description, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_foods_search_description ON foods (search_description);
+
+ALTER TABLE tags ADD COLUMN search_description TEXT GENERATED ALWAYS AS (
+    lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(description, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_tags_search_description ON tags (search_description);
@@ -0,0 +1,7 @@
+-- allergen_free=gluten,fındık filtresi de etiketler gibi Türkçe karakter ve büyük/küçük harf duyarsız olsun diye: "FINDIK" fındık'ı dışlamalı.
+-- İfade 20261016150000_add_search_description_columns ile, dolayısıyla core::str::to_search_key ile aynı
+ALTER TABLE allergens ADD COLUMN search_description TEXT GENERATED ALWAYS AS (
+    lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(description, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_allergens_search_description ON allergens (search_description);
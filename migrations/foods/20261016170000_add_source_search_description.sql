@@ -0,0 +1,5 @@
+-- mode=all aramasında sorgu kaynak adlarında da aranıyor, açıklama ve etiketlerle aynı şekilde Türkçe karakter ve büyük/küçük harf duyarsız.
+-- İfade 20261016150000_add_search_description_columns ile, dolayısıyla core::str::to_search_key ile aynı
+ALTER TABLE food_sources ADD COLUMN search_description TEXT GENERATED ALWAYS AS (
+    lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(description, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+) VIRTUAL;
@@ -0,0 +1,12 @@
+-- Aramada birbirinin yerine kullanılan terimler: aynı set_id'ye sahip terimlerden biri aranınca diğerleri de aranıyor (patates <-> potato).
+-- Bir terim sadece bir sette olabiliyor, karşılaştırma aramalar gibi Türkçe karakter ve büyük/küçük harf duyarsız
+CREATE TABLE IF NOT EXISTS search_synonyms (
+    set_id      INTEGER NOT NULL,
+    term        TEXT NOT NULL,
+    search_term TEXT GENERATED ALWAYS AS (
+        lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(term, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+    ) VIRTUAL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_search_synonyms_search_term ON search_synonyms (search_term);
+CREATE INDEX IF NOT EXISTS idx_search_synonyms_set_id ON search_synonyms (set_id);
@@ -0,0 +1,21 @@
+-- Yemeklerden oluşan tarifler. Besin değerleri saklanmıyor, her istekte malzemelerin güncel değerlerinden hesaplanıyor
+CREATE TABLE IF NOT EXISTS recipes (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    slug        TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL UNIQUE,
+    -- Tarifin kaç porsiyon çıkardığı, porsiyon başına değerler toplamın bu sayıya bölümü
+    servings    INTEGER NOT NULL,
+    created_at  INTEGER DEFAULT (strftime('%s', 'now')),
+    updated_at  INTEGER DEFAULT 0
+);
+
+-- Tarifteki yemekler ve gramları, position tarifteki sırası
+CREATE TABLE IF NOT EXISTS recipe_ingredients (
+    recipe_id   INTEGER NOT NULL,
+    position    INTEGER NOT NULL,
+    food_id     INTEGER NOT NULL,
+    grams       REAL NOT NULL,
+    PRIMARY KEY (recipe_id, position)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recipe_ingredients_food_id ON recipe_ingredients (food_id);
@@ -0,0 +1,13 @@
+-- Kaynaklarda ölçülmemiş besin değerleri 0 olarak girilmiş, gerçekten 0 olanlardan ayırt edilemiyor. Tamlık, 23 besin değerinden
+-- (glisemik indeks hariç) sıfır olmayanların oranı: ölçülmüş sayılanlar. Kolesterolü gerçekten 0 olan bitkiler gibi yemekler de
+-- biraz düşük görünüyor, ama neredeyse tüm değerleri 0 olan yemekleri ayırmak için yeterli. Değerler değişince kendisi güncelleniyor
+ALTER TABLE foods ADD COLUMN completeness REAL GENERATED ALWAYS AS (
+    round((
+        (energy <> 0) + (carbohydrate <> 0) + (protein <> 0) + (fat <> 0) + (saturated_fat <> 0) + (trans_fat <> 0)
+        + (sugar <> 0) + (fiber <> 0) + (cholesterol <> 0) + (sodium <> 0) + (potassium <> 0) + (water <> 0)
+        + (iron <> 0) + (magnesium <> 0) + (calcium <> 0) + (zinc <> 0) + (vitamin_a <> 0) + (vitamin_b6 <> 0)
+        + (vitamin_b12 <> 0) + (vitamin_c <> 0) + (vitamin_d <> 0) + (vitamin_e <> 0) + (vitamin_k <> 0)
+    ) / 23.0, 2)
+) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_foods_completeness ON foods (completeness);
@@ -0,0 +1,18 @@
+-- %DV hesaplarında kullanılan günlük referans alım profilleri. Değerler açılışta db/reference_intakes altındaki JSON'lardan
+-- ekleniyor, yemekler gibi sadece veritabanında olmayan profiller; sonradan veritabanında yapılan değişikliklerin üzerine yazılmıyor
+CREATE TABLE IF NOT EXISTS reference_intake_profiles (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    slug        TEXT NOT NULL UNIQUE,
+    description TEXT NOT NULL,
+    -- Değerlerin alındığı kaynak, ör: AB 1169/2011 veya Türkiye Beslenme Rehberi
+    source      TEXT NOT NULL DEFAULT '',
+    created_at  INTEGER DEFAULT (strftime('%s', 'now'))
+);
+
+-- Profilin besin başına günlük değeri, Food'daki birimlerle. Satırı olmayan besinlerin bu profilde referansı yok
+CREATE TABLE IF NOT EXISTS reference_intakes (
+    profile_id  INTEGER NOT NULL,
+    nutrient    TEXT NOT NULL,
+    amount      REAL NOT NULL,
+    PRIMARY KEY (profile_id, nutrient)
+);
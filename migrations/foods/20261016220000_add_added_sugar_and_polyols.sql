@@ -0,0 +1,4 @@
+-- Eklenmiş şeker, toplam şekerin (sugar) içinde: meyvenin kendi şekeri değil, üretimde eklenen. Poliollar (şeker alkolleri)
+-- şekere dahil değil, karbonhidratın içinde. Eski veride bilinmedikleri için 0
+ALTER TABLE foods ADD COLUMN added_sugar REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN polyols REAL NOT NULL DEFAULT 0;
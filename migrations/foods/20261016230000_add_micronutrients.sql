@@ -0,0 +1,9 @@
+-- Diyetisyenlerin ihtiyaç duyduğu ek besin değerleri, 100 gram için. Omega-3 ve omega-6 g; folat (µg DFE) ve selenyum µg;
+-- niasin, fosfor ve bakır mg. Eski veride bilinmedikleri için 0
+ALTER TABLE foods ADD COLUMN omega_3 REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN omega_6 REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN folate REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN niacin REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN phosphorus REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN selenium REAL NOT NULL DEFAULT 0;
+ALTER TABLE foods ADD COLUMN copper REAL NOT NULL DEFAULT 0;
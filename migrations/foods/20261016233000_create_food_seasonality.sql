@@ -0,0 +1,16 @@
+-- Meyve ve sebzelerin hangi bölgede hangi aylarda mevsiminde olduğu, her ay ayrı bir satır. Aylar 1 (Ocak) ile 12 (Aralık) arası
+CREATE TABLE IF NOT EXISTS food_seasonality (
+    food_id     INTEGER NOT NULL,
+    region      TEXT NOT NULL,
+    month       INTEGER NOT NULL CHECK (month BETWEEN 1 AND 12),
+    PRIMARY KEY (food_id, region, month)
+);
+
+-- region=akdeniz filtresi de etiketler gibi Türkçe karakter ve büyük/küçük harf duyarsız olsun diye.
+-- İfade 20261016150000_add_search_description_columns ile, dolayısıyla core::str::to_search_key ile aynı
+ALTER TABLE food_seasonality ADD COLUMN search_region TEXT GENERATED ALWAYS AS (
+    lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(region, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+) VIRTUAL;
+
+-- /foods/in-season aya göre arıyor
+CREATE INDEX IF NOT EXISTS idx_food_seasonality_month ON food_seasonality (month, search_region);
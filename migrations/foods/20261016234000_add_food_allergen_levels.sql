@@ -0,0 +1,4 @@
+-- Alerjenin yemeğin kendisinde mi olduğu (contains) yoksa üretimde bulaşma ihtimali mi olduğu (may_contain) ve isteğe bağlı bir not.
+-- Eski kayıtlar alerjenin yemekte olduğunu gösterdiği için contains
+ALTER TABLE food_allergens ADD COLUMN level TEXT NOT NULL DEFAULT 'contains' CHECK (level IN ('contains', 'may_contain'));
+ALTER TABLE food_allergens ADD COLUMN note TEXT;
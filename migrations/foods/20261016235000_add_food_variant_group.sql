@@ -0,0 +1,5 @@
+-- Aynı yemeğin çiğ, haşlanmış, kızartma gibi hallerini birbirine bağlayan grup, örneğin Patates (Çiğ) ve Patates (Haşlanmış) için
+-- "patates". Grubu olmayan yemeklerde NULL
+ALTER TABLE foods ADD COLUMN variant_group TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_foods_variant_group ON foods (variant_group);
@@ -0,0 +1,6 @@
+-- Markalı ürünler: brand markanın adı, generic_food_id ise ürünün markasız genel karşılığı (örneğin bir markanın yulaf ezmesi
+-- için Yulaf Ezmesi). Genel yemeklerde ikisi de NULL
+ALTER TABLE foods ADD COLUMN brand TEXT;
+ALTER TABLE foods ADD COLUMN generic_food_id INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_foods_generic_food_id ON foods (generic_food_id);
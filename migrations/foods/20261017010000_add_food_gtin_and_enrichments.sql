@@ -0,0 +1,20 @@
+-- Paketli ürünlerin barkodu (GTIN-8, -12, -13 veya -14). Her barkod tek bir yemeğe ait olabilir
+ALTER TABLE foods ADD COLUMN gtin TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_foods_gtin ON foods (gtin) WHERE gtin IS NOT NULL;
+
+-- GTIN'i olan yemekler için dış kaynaklardan (şimdilik sadece Open Food Facts) çekilen bilgiler. Yemeğin kendi verisinin üzerine
+-- yazılmıyor, kaynağı ve lisansıyla ayrı tutuluyor. Ürün kaynakta bulunamazsa found = 0 ile kaydediliyor ki her seferinde
+-- tekrar sorulmasın, fetched_at refresh_after_days'i geçince tekrar deneniyor
+CREATE TABLE IF NOT EXISTS food_enrichments (
+    food_id INTEGER PRIMARY KEY,
+    source TEXT NOT NULL,
+    source_url TEXT NOT NULL,
+    license TEXT NOT NULL,
+    found INTEGER NOT NULL,
+    image_url TEXT,
+    ingredients TEXT,
+    nutri_score TEXT CHECK (nutri_score IN ('a', 'b', 'c', 'd', 'e')),
+    -- RFC 3339, UTC
+    fetched_at TEXT NOT NULL
+);
@@ -0,0 +1,5 @@
+-- Fotoğraflar atıfsız dağıtılamadığı için resimlerin kime ait olduğu, lisansı ve alındığı orijinal sayfa. Projenin kendi
+-- resimlerinde boş kalabiliyor
+ALTER TABLE food_images ADD COLUMN attribution TEXT;
+ALTER TABLE food_images ADD COLUMN license TEXT;
+ALTER TABLE food_images ADD COLUMN source_url TEXT;
@@ -0,0 +1,9 @@
+-- Yemeklerin kendi porsiyonlarına ek olarak kategorilere göre ev ölçüleri ("1 dilim", "1 su bardağı"). Kategori yemek
+-- etiketleriyle eşleşiyor, etiketler gibi küçük harfle tutuluyor
+CREATE TABLE IF NOT EXISTS standard_portions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    category TEXT NOT NULL,
+    description TEXT NOT NULL,
+    weight REAL NOT NULL CHECK (weight > 0),
+    UNIQUE (category, description)
+);
@@ -0,0 +1,11 @@
+-- Config'deki diet_flags kurallarından hesaplanan bayraklar. Kurallar değişebildiği için açılışta tüm tablo yeniden yazılıyor,
+-- yanıtta false olanlar da gösterilsin diye her yemek için her bayrak tutuluyor
+CREATE TABLE IF NOT EXISTS food_diet_flags (
+    food_id     INTEGER NOT NULL,
+    flag        TEXT NOT NULL,
+    value       INTEGER NOT NULL CHECK (value IN (0, 1)),
+    PRIMARY KEY (food_id, flag)
+);
+
+-- /foods/search?diet_flags= bayrağa göre arıyor
+CREATE INDEX IF NOT EXISTS idx_food_diet_flags_flag ON food_diet_flags (flag, value);
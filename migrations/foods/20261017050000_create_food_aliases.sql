@@ -0,0 +1,13 @@
+-- Yemeklerin diğer adları: yöresel isimler ("kelem" -> Lahana) ve başka dillerdeki karşılıkları ("aubergine" -> Patlıcan).
+-- Açıklama ve tüm alanlar aramasında açıklamayla birlikte aranıyor, karşılaştırma aramalar gibi Türkçe karakter ve büyük/küçük
+-- harf duyarsız. Aynı ad farklı yemeklerin de adı olabiliyor, bir yemekte bir kere
+CREATE TABLE IF NOT EXISTS food_aliases (
+    id           INTEGER PRIMARY KEY AUTOINCREMENT,
+    food_id      INTEGER NOT NULL,
+    alias        TEXT NOT NULL,
+    search_alias TEXT GENERATED ALWAYS AS (
+        lower(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(replace(alias, 'Ç', 'c'), 'ç', 'c'), 'Ğ', 'g'), 'ğ', 'g'), 'İ', 'i'), 'ı', 'i'), 'Ö', 'o'), 'ö', 'o'), 'Ş', 's'), 'ş', 's'), 'Ü', 'u'), 'ü', 'u'))
+    ) VIRTUAL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_food_aliases_food_id ON food_aliases (food_id, search_alias);
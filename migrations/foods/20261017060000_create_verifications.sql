@@ -0,0 +1,14 @@
+-- Yemeklerin kim tarafından, ne zaman ve nasıl doğrulandığı. foods.verified yemeğin yayında olup olmadığını gösteriyor, bu tablo
+-- ise verinin kalitesini: laboratuvar ölçümü (lab_data), yayınlanmış kaynak (literature) veya tahmin (estimated). Geçmiş
+-- korunuyor, yanıtta en son doğrulama gösteriliyor
+CREATE TABLE IF NOT EXISTS verifications (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    food_id     INTEGER NOT NULL,
+    verified_by TEXT NOT NULL,
+    -- RFC 3339, UTC
+    verified_at TEXT NOT NULL,
+    method      TEXT NOT NULL CHECK (method IN ('lab_data', 'literature', 'estimated')),
+    note        TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_verifications_food_id ON verifications (food_id, verified_at);
@@ -0,0 +1,17 @@
+-- Gıda katkı maddelerinin E numaralı referans listesi, paketli ürünlerde içindekilerin şeffaf gösterilebilmesi için. E numarası
+-- standart yazılışıyla ("E330", "E160a") tutuluyor, sınıf etiketler gibi küçük harfle
+CREATE TABLE IF NOT EXISTS additives (
+    id       INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    e_number TEXT NOT NULL UNIQUE,
+    name     TEXT NOT NULL,
+    category TEXT NOT NULL,
+    notes    TEXT
+);
+
+CREATE TABLE IF NOT EXISTS food_additives (
+    food_id     INTEGER NOT NULL,
+    additive_id INTEGER NOT NULL,
+    PRIMARY KEY (food_id, additive_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_food_additives_additive_id ON food_additives (additive_id);
@@ -0,0 +1,11 @@
+-- Nutri-Score ve NOVA işlenme sınıfı, yemek eklenirken uygulamada hesaplanıp saklanıyor ki aramada filtrelenip sıralanabilsin.
+-- fruit_vegetable_percent ve nova_group_declared JSON'dan gelen ek veriler, hesaplama kuralları değişirse açılışta bunlardan
+-- yeniden hesaplanıyor
+ALTER TABLE foods ADD COLUMN fruit_vegetable_percent REAL CHECK (fruit_vegetable_percent BETWEEN 0 AND 100);
+ALTER TABLE foods ADD COLUMN nova_group_declared INTEGER CHECK (nova_group_declared IN (1, 2, 3, 4));
+ALTER TABLE foods ADD COLUMN nutri_score TEXT CHECK (nutri_score IN ('A', 'B', 'C', 'D', 'E'));
+ALTER TABLE foods ADD COLUMN nutri_score_points INTEGER;
+ALTER TABLE foods ADD COLUMN nova_group INTEGER CHECK (nova_group IN (1, 2, 3, 4));
+
+CREATE INDEX IF NOT EXISTS idx_foods_nutri_score_points ON foods (nutri_score_points);
+CREATE INDEX IF NOT EXISTS idx_foods_nova_group ON foods (nova_group);
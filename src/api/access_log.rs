@@ -0,0 +1,178 @@
+use std::{net::IpAddr, time::Instant};
+
+use axum::{
+    body::{Body, HttpBody},
+    extract::{OriginalUri, Request, State},
+    http::header::{CONTENT_LENGTH, REFERER, USER_AGENT},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use real::RealIp;
+use serde::Serialize;
+use tracing::info;
+
+use crate::{SharedState, api::request_id::X_REQUEST_ID, core::config::AccessLogFormat};
+
+#[derive(Serialize)]
+struct AccessLogEntry {
+    time: String,
+    ip: Option<IpAddr>,
+    method: String,
+    path: String,
+    version: String,
+    status: u16,
+    bytes: Option<u64>,
+    duration_ms: f64,
+    referer: Option<String>,
+    user_agent: Option<String>,
+    request_id: Option<String>,
+}
+
+// Her isteği tek satırda logluyoruz. client_ip_middleware'den sonra kurulduğu için reverse proxy arkasında da gerçek istemci IP'si yazılıyor,
+// rate limiter, bakım modu gibi iç katmanların döndürdüğü yanıtlar da loglanıyor
+pub(crate) async fn access_log_middleware(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let (enabled, format, timezone) = {
+        let config_guard = shared_state.config.load();
+        (
+            config_guard.access_log.enabled,
+            config_guard.access_log.format,
+            config_guard.core.timezone,
+        )
+    };
+    if !enabled {
+        return next.run(request).await;
+    }
+
+    let start = Instant::now();
+    let received_at = Utc::now();
+    // Request'e referans tutan closure await'ten önce düşürülmeli, Body Sync olmadığı için future Send olmaz
+    let mut entry = {
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_owned())
+        };
+        AccessLogEntry {
+            time: String::new(),
+            ip: request.extensions().get::<RealIp>().map(|ip| ip.ip()),
+            method: request.method().to_string(),
+            // Nest edilmiş router'da path'in başındaki API prefix'i siliniyor, logda istemcinin istediği tam path görünsün
+            path: request
+                .extensions()
+                .get::<OriginalUri>()
+                .map(|uri| &uri.0)
+                .unwrap_or(request.uri())
+                .path_and_query()
+                .map(|path| path.to_string())
+                .unwrap_or_else(|| "/".to_owned()),
+            version: format!("{:?}", request.version()),
+            status: 0,
+            bytes: None,
+            duration_ms: 0.0,
+            referer: header(REFERER.as_str()),
+            user_agent: header(USER_AGENT.as_str()),
+            request_id: header(X_REQUEST_ID),
+        }
+    };
+
+    let response = next.run(request).await;
+
+    entry.status = response.status().as_u16();
+    entry.bytes = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .or_else(|| response.body().size_hint().exact());
+    entry.duration_ms = (start.elapsed().as_secs_f64() * 1000.0 * 1000.0).round() / 1000.0;
+
+    let line = match format {
+        AccessLogFormat::Combined => format_combined(&entry, received_at, timezone),
+        AccessLogFormat::Json => {
+            entry.time = received_at.with_timezone(&timezone).to_rfc3339();
+            serde_json::to_string(&entry).unwrap_or_default()
+        }
+    };
+    info!(target: "access_log", "{}", line);
+
+    response
+}
+
+// 203.0.113.7 - - [16/Oct/2026:14:02:11 +0300] "GET /foods/search?q=elma HTTP/1.1" 200 1834 "-" "curl/8.5.0" 2.481
+// Combined formatının sonuna milisaniye cinsinden süreyi ekliyoruz, log analiz araçları fazladan alanı görmezden geliyor
+fn format_combined(entry: &AccessLogEntry, received_at: DateTime<Utc>, timezone: Tz) -> String {
+    let quoted = |value: &Option<String>| match value {
+        Some(value) => format!("\"{}\"", value.replace('"', "\\\"")),
+        None => "\"-\"".to_owned(),
+    };
+    format!(
+        "{} - - [{}] \"{} {} {}\" {} {} {} {} {}",
+        entry
+            .ip
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "-".to_owned()),
+        received_at
+            .with_timezone(&timezone)
+            .format("%d/%b/%Y:%H:%M:%S %z"),
+        entry.method,
+        entry.path,
+        entry.version,
+        entry.status,
+        entry
+            .bytes
+            .map(|bytes| bytes.to_string())
+            .unwrap_or_else(|| "-".to_owned()),
+        quoted(&entry.referer),
+        quoted(&entry.user_agent),
+        entry.duration_ms,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> AccessLogEntry {
+        AccessLogEntry {
+            time: String::new(),
+            ip: Some("203.0.113.7".parse().unwrap()),
+            method: "GET".to_owned(),
+            path: "/api/foods/search?q=elma".to_owned(),
+            version: "HTTP/1.1".to_owned(),
+            status: 200,
+            bytes: Some(1834),
+            duration_ms: 2.481,
+            referer: None,
+            user_agent: Some("curl/8.5.0 \"test\"".to_owned()),
+            request_id: Some("istek-1".to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_format_combined() {
+        let received_at = DateTime::parse_from_rfc3339("2026-10-16T11:02:11Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            format_combined(&entry(), received_at, chrono_tz::Europe::Istanbul),
+            r#"203.0.113.7 - - [16/Oct/2026:14:02:11 +0300] "GET /api/foods/search?q=elma HTTP/1.1" 200 1834 "-" "curl/8.5.0 \"test\"" 2.481"#
+        );
+
+        let mut entry = entry();
+        entry.ip = None;
+        entry.bytes = None;
+        assert!(
+            format_combined(&entry, received_at, Tz::UTC)
+                .starts_with("- - - [16/Oct/2026:11:02:11 +0000]")
+        );
+        assert!(format_combined(&entry, received_at, Tz::UTC).contains(" 200 - "));
+    }
+}
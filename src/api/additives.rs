@@ -0,0 +1,61 @@
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use real::RealIp;
+use serde::Deserialize;
+use tracing::{error, instrument};
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::APIError,
+        foods::{MAX_INPUT_CHARS, record_offense, validate_input},
+        metrics::Offense,
+    },
+    core::additives::Additive,
+};
+
+#[derive(Deserialize)]
+pub(crate) struct AdditivesParams {
+    // Verilirse sadece bu sınıftakiler ("koruyucu"), Türkçe karakter ve büyük/küçük harf duyarsız
+    category: Option<String>,
+}
+
+// E numaralı katkı maddeleri referans listesi. Liste küçük olduğu için sayfalama yok, sınıf filtresi bellekte uygulanıyor
+#[instrument(skip_all)]
+pub(crate) async fn additives(
+    Query(params): Query<AdditivesParams>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<Json<Vec<Additive>>, APIError> {
+    let category = match params
+        .category
+        .as_deref()
+        .map(|category| validate_input(category, MAX_INPUT_CHARS))
+        .transpose()
+    {
+        Ok(category) => category,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+    let additives = database::select_additives(&shared_state.api_db)
+        .await
+        .map_err(|e| {
+            error!("Katkı maddeleri sorgulanırken hata oluştu: {:?}", e);
+            APIError::database(
+                &e,
+                "Veritabanı katkı maddesi sorgusu yapılırken hata oluştu",
+            )
+        })?;
+    Ok(Json(match category {
+        Some(category) => additives
+            .into_iter()
+            .filter(|additive| additive.in_category(&category))
+            .collect(),
+        None => additives,
+    }))
+}
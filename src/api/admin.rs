@@ -0,0 +1,1098 @@
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, Query, Request, State},
+    http::{StatusCode, header::AUTHORIZATION},
+    middleware::{self, Next},
+    response::Response,
+    routing::{get, post, put},
+};
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::{
+    SharedState,
+    api::{
+        analytics::AnalyticsWindow,
+        cache::CacheShardStats,
+        config_audit::{self, ConfigChangeRecord},
+        database::{self, RecipeWrite, SynonymSetWrite},
+        error::{APIError, ErrorCode},
+        metrics::{
+            CacheStats, DATABASE_QUERIES, Offender, QueryLatencySummary, RouteLatencySummary,
+            SloTarget,
+        },
+        recipes::{Recipe, recipe_not_found},
+        runtime::{PoolStats, RuntimeStats, pool_stats, runtime_stats},
+        security,
+    },
+    core::{
+        config::Config,
+        food::{Verification, VerificationMethod},
+        str,
+    },
+};
+
+// Kullanım geçmişinde son 2 ay kadar günlük kayıt ve aylık kayıtlar görünsün
+const USAGE_HISTORY_LIMIT: u32 = 64;
+
+// Her terim aramada ayrı bir sorgu oluşturuyor, setler küçük kalmalı
+const MAX_SYNONYM_SET_TERMS: usize = 10;
+const MAX_SYNONYM_CHARS: usize = 100;
+
+// Her ad açıklama ve tüm alanlar aramasında ayrı bir koşul
+const MAX_FOOD_ALIASES: usize = 20;
+const MAX_ALIAS_CHARS: usize = 100;
+
+// Tarif limitleri, hesaplama her istekte malzemeler üzerinden yapıldığı için tarifler küçük kalmalı
+const MAX_RECIPE_DESCRIPTION_CHARS: usize = 100;
+const MAX_RECIPE_SERVINGS: u32 = 100;
+const MAX_RECIPE_INGREDIENTS: usize = 50;
+const MAX_INGREDIENT_GRAMS: f64 = 10_000.0;
+
+// Açılış özetinde gösterilen route tablosu, admin_router'a route eklenince buraya da eklenmeli; test_app tablodaki her route'u istiyor
+pub(crate) const ADMIN_ROUTES: [(&str, &str); 23] = [
+    ("POST", "/admin/cache/purge"),
+    ("POST", "/admin/reload"),
+    ("GET", "/admin/keys/{name}/usage"),
+    ("GET", "/admin/metrics/suspicious"),
+    ("GET", "/admin/metrics/queries"),
+    ("GET", "/admin/metrics/cache"),
+    ("GET", "/admin/metrics/database-pool"),
+    ("GET", "/admin/analytics/searches"),
+    ("GET", "/admin/debug/runtime"),
+    ("GET", "/admin/config/changes"),
+    ("GET", "/admin/synonyms"),
+    ("POST", "/admin/synonyms"),
+    ("PUT", "/admin/synonyms/{id}"),
+    ("DELETE", "/admin/synonyms/{id}"),
+    ("GET", "/admin/foods/{slug}/aliases"),
+    ("PUT", "/admin/foods/{slug}/aliases"),
+    ("POST", "/admin/foods/{slug}/verifications"),
+    ("POST", "/admin/recipes"),
+    ("PUT", "/admin/recipes/{slug}"),
+    ("DELETE", "/admin/recipes/{slug}"),
+    ("GET", "/admin/slo"),
+    ("GET", "/admin/maintenance"),
+    ("POST", "/admin/maintenance"),
+];
+
+// Admin endpoint'leri önbellek katmanının dışında kalsın diye ayrı bir router olarak kuruyoruz, api_router bunu merge ediyor
+pub(crate) fn admin_router(shared_state: SharedState) -> Router {
+    Router::new()
+        .route("/admin/cache/purge", post(cache_purge))
+        .route("/admin/reload", post(reload))
+        .route("/admin/keys/{name}/usage", get(key_usage))
+        .route("/admin/metrics/suspicious", get(suspicious_traffic))
+        .route("/admin/metrics/queries", get(database_queries))
+        .route("/admin/metrics/cache", get(cache_metrics))
+        .route("/admin/metrics/database-pool", get(database_pool))
+        .route("/admin/analytics/searches", get(search_analytics))
+        .route("/admin/debug/runtime", get(runtime_debug))
+        .route("/admin/config/changes", get(config_changes))
+        .route(
+            "/admin/synonyms",
+            get(synonym_sets).post(create_synonym_set),
+        )
+        .route(
+            "/admin/synonyms/{id}",
+            put(update_synonym_set).delete(delete_synonym_set),
+        )
+        .route(
+            "/admin/foods/{slug}/aliases",
+            get(food_aliases).put(update_food_aliases),
+        )
+        .route(
+            "/admin/foods/{slug}/verifications",
+            post(create_food_verification),
+        )
+        .route("/admin/recipes", post(create_recipe))
+        .route(
+            "/admin/recipes/{slug}",
+            put(update_recipe).delete(delete_recipe),
+        )
+        .route("/admin/slo", get(slo))
+        .route(
+            "/admin/maintenance",
+            get(maintenance_status).post(set_maintenance),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            admin_auth,
+        ))
+        .with_state(shared_state)
+}
+
+// Authorization: Bearer <token> header'ını config'deki admin token'ı ile karşılaştırıyoruz
+// Token ayarlanmamışsa admin endpoint'leri hiç yokmuş gibi davranıyoruz
+async fn admin_auth(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, APIError> {
+    let Some(token) = shared_state.config.load().admin.token.clone() else {
+        return Err(APIError::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::EndpointNotFound,
+            "İstenen API endpoint'i bulunamadı",
+        ));
+    };
+
+    let provided = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    if !provided.is_some_and(|provided| security::secrets_match(provided, &token)) {
+        return Err(APIError::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "Bu işlem için yetkiniz bulunmuyor",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PurgeRequest {
+    // API köküne göre path öneki, ör: /foods/search
+    prefix: Option<String>,
+    // Tek bir yemeğin /food/{slug} altındaki tüm yanıtlarını (detay, porsiyonlar) silmek için kısayol
+    slug: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct PurgeResponse {
+    purged: u64,
+}
+
+// Veritabanında elle düzeltme yapıldığında önbellek ömrünün dolmasını beklememek için eşleşen kayıtları siliyoruz
+pub(crate) async fn cache_purge(
+    State(shared_state): State<SharedState>,
+    Json(purge): Json<PurgeRequest>,
+) -> Result<Json<PurgeResponse>, APIError> {
+    // Önbellek anahtarları "path?sorgu|gösterim" formatında (bkz. cache::cache_key), bu yüzden önekle karşılaştırabiliyoruz
+    let prefix = match (&purge.prefix, &purge.slug) {
+        (Some(prefix), None) if prefix.starts_with('/') => prefix.trim_end_matches('/').to_owned(),
+        (None, Some(slug)) if !slug.is_empty() => format!("/food/{}", slug),
+        _ => {
+            return Err(APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidParameters,
+                "'/' ile başlayan bir prefix veya bir slug belirtmelisiniz",
+            ));
+        }
+    };
+
+    Ok(Json(PurgeResponse {
+        purged: purge_cache_prefix(&shared_state, &prefix).await,
+    }))
+}
+
+#[derive(Serialize)]
+pub(crate) struct ReloadResponse {
+    diet_flags: u64,
+    scores: u64,
+    search_index: usize,
+    suggestions: usize,
+}
+
+// Veritabanında elle yapılan bir düzeltmeden sonra açılışta hesaplanan verileri yeniden hesaplıyoruz: etiket, alerjen veya
+// besin değerleri diyet bayraklarını ve skorları, isimler de arama indeksini ve önerileri değiştirebilir. Önbellek silinmiyor,
+// güncel yanıtlar için ardından /admin/cache/purge çağrılmalı
+pub(crate) async fn reload(
+    State(shared_state): State<SharedState>,
+) -> Result<Json<ReloadResponse>, APIError> {
+    let pool = shared_state.api_db.clone();
+    let rules = shared_state.config.load().diet_flags.clone();
+    let diet_flags = database::refresh_diet_flags(&pool, &rules)
+        .await
+        .map_err(reload_error)?;
+    let scores = database::refresh_food_scores(&pool)
+        .await
+        .map_err(reload_error)?;
+    // İndeks diyet bayraklarını da tutuyor, bayraklar hesaplandıktan sonra yükleniyor
+    let search_index = shared_state
+        .search_index
+        .reload(&pool)
+        .await
+        .map_err(reload_error)?;
+    let suggestions = shared_state
+        .suggestions
+        .reload(&pool)
+        .await
+        .map_err(reload_error)?;
+
+    info!(
+        "Türetilmiş veriler yeniden yüklendi: {} diyet bayrağı, {} skor, {} indeks, {} öneri",
+        diet_flags, scores, search_index, suggestions
+    );
+    Ok(Json(ReloadResponse {
+        diet_flags,
+        scores,
+        search_index,
+        suggestions,
+    }))
+}
+
+fn reload_error(e: anyhow::Error) -> APIError {
+    error!("Türetilmiş veriler yeniden yüklenemedi: {:?}", e);
+    APIError::database(&e, "Türetilmiş veriler yeniden yüklenemedi")
+}
+
+async fn purge_cache_prefix(shared_state: &SharedState, prefix: &str) -> u64 {
+    let mut purged = 0;
+    for cache in shared_state
+        .cache
+        .shards()
+        .chain([&shared_state.negative_cache])
+    {
+        for (key, _) in cache.iter() {
+            if matches_path_prefix(&key, prefix) {
+                cache.invalidate(key.as_ref()).await;
+                purged += 1;
+            }
+        }
+    }
+
+    // Popüler arama önbelleği URL'ye göre tutulmuyor, arama sonuçlarını etkileyebilecek her purge'de tamamen boşaltılıyor
+    if matches_path_prefix("/foods/search", prefix) || matches_path_prefix(prefix, "/foods/search")
+    {
+        purged += shared_state.search_hot_cache.iter().count() as u64;
+        shared_state.search_hot_cache.invalidate_all();
+    }
+
+    info!("Önbellekten {} kayıt silindi (önek: {})", purged, prefix);
+    purged
+}
+
+// Önek path parçalarının sınırında eşleşiyor: /food önekiyle /food ve /food/muz siliniyor ama /foods/list silinmiyor
+fn matches_path_prefix(key: &str, prefix: &str) -> bool {
+    let Some(rest) = key.strip_prefix(prefix) else {
+        return false;
+    };
+    prefix.ends_with(['/', '?', '|']) || rest.is_empty() || rest.starts_with(['/', '?', '|'])
+}
+
+#[derive(Deserialize)]
+pub(crate) struct MaintenanceRequest {
+    enabled: bool,
+    // Belirtilmezse config'deki süre korunuyor
+    retry_after_seconds: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MaintenanceResponse {
+    enabled: bool,
+    retry_after_seconds: u64,
+}
+
+pub(crate) async fn maintenance_status(
+    State(shared_state): State<SharedState>,
+) -> Json<MaintenanceResponse> {
+    let maintenance = shared_state.config.load().maintenance.clone();
+    Json(MaintenanceResponse {
+        enabled: maintenance.enabled,
+        retry_after_seconds: maintenance.retry_after_seconds,
+    })
+}
+
+// Bakım modu sadece bellekte değişiyor, yeniden başlatınca config dosyasındaki değer geçerli oluyor
+pub(crate) async fn set_maintenance(
+    State(shared_state): State<SharedState>,
+    Json(request): Json<MaintenanceRequest>,
+) -> Json<MaintenanceResponse> {
+    let (response, values) = {
+        // Okuyanlar kilitlenmesin diye config yerinde değiştirilmiyor, değiştirilmiş bir kopyası yazılıyor
+        shared_state.config.rcu(|config| {
+            let mut config = Config::clone(config);
+            config.maintenance.enabled = request.enabled;
+            if let Some(retry_after_seconds) = request.retry_after_seconds {
+                config.maintenance.retry_after_seconds = retry_after_seconds;
+            }
+            config
+        });
+        let config_guard = shared_state.config.load();
+        let maintenance = &config_guard.maintenance;
+        info!(
+            "Bakım modu {}",
+            if maintenance.enabled {
+                "açıldı"
+            } else {
+                "kapatıldı"
+            }
+        );
+        (
+            MaintenanceResponse {
+                enabled: maintenance.enabled,
+                retry_after_seconds: maintenance.retry_after_seconds,
+            },
+            config_audit::flatten(&config_guard),
+        )
+    };
+
+    // Kayıt başarısız olsa da bakım modu değişti, isteği hata ile döndürmüyoruz
+    let pool = shared_state.api_db.clone();
+    if let Err(e) = async { config_audit::record(&pool, values?, "admin").await }.await {
+        error!("Bakım modu değişikliği kaydedilemedi: {:?}", e);
+    }
+
+    Json(response)
+}
+
+#[derive(Serialize)]
+pub(crate) struct KeyUsageResponse {
+    name: String,
+    tier: String,
+    daily_quota: Option<u64>,
+    monthly_quota: Option<u64>,
+    today: u64,
+    this_month: u64,
+    // En yeni periyot önce, günlük (YYYY-MM-DD) ve aylık (YYYY-MM) kayıtlar birlikte
+    history: Vec<UsagePeriod>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct UsagePeriod {
+    period: String,
+    requests: u64,
+}
+
+// Anahtarlar config'de isimleriyle tanımlı, anahtarın kendisini URL'de taşımamak için isim kullanıyoruz
+pub(crate) async fn key_usage(
+    State(shared_state): State<SharedState>,
+    Path(name): Path<String>,
+) -> Result<Json<KeyUsageResponse>, APIError> {
+    let Some((tier, daily_quota, monthly_quota)) = shared_state
+        .config
+        .load()
+        .api_keys
+        .iter()
+        .find(|api_key| api_key.name == name)
+        .map(|api_key| {
+            (
+                api_key.tier.clone(),
+                api_key.daily_quota,
+                api_key.monthly_quota,
+            )
+        })
+    else {
+        return Err(APIError::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::ApiKeyNotFound,
+            "Bu isimde bir API anahtarı bulunamadı",
+        ));
+    };
+
+    // Tamponda bekleyen sayaçları önce yazıyoruz ki veritabanından güncel kullanımı okuyalım
+    let pool = shared_state.api_db.clone();
+    shared_state.usage.flush(&pool).await;
+
+    let history = database::select_api_key_usage_history(&pool, &name, USAGE_HISTORY_LIMIT)
+        .await
+        .map_err(|e| {
+            error!("API anahtarı kullanımı okunurken hata oluştu: {:?}", e);
+            APIError::database(&e, "Kullanım bilgisine ulaşılamadı")
+        })?;
+
+    let now = chrono::Utc::now();
+    let requests_in = |period: String| {
+        history
+            .iter()
+            .find(|(p, _)| *p == period)
+            .map(|(_, requests)| *requests)
+            .unwrap_or(0)
+    };
+    let today = requests_in(now.format("%Y-%m-%d").to_string());
+    let this_month = requests_in(now.format("%Y-%m").to_string());
+
+    Ok(Json(KeyUsageResponse {
+        name,
+        tier,
+        daily_quota,
+        monthly_quota,
+        today,
+        this_month,
+        history: history
+            .into_iter()
+            .map(|(period, requests)| UsagePeriod { period, requests })
+            .collect(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SuspiciousTrafficParams {
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SuspiciousTrafficResponse {
+    // Son 24 saatte en az bir kez şüpheli trafik üreten IP sayısı
+    tracked_ips: usize,
+    offenders: Vec<Offender>,
+}
+
+// Geçersiz sorgu, limit aşan sorgu ve rate limit sayılarına göre en çok sorun çıkaran IP'ler; limitleri ayarlarken ve engel listesini doldururken kullanmak için
+pub(crate) async fn suspicious_traffic(
+    State(shared_state): State<SharedState>,
+    Query(params): Query<SuspiciousTrafficParams>,
+) -> Json<SuspiciousTrafficResponse> {
+    let suspicious = &shared_state.metrics.suspicious;
+    Json(SuspiciousTrafficResponse {
+        tracked_ips: suspicious.tracked_ips(),
+        offenders: suspicious.top_offenders(params.limit.unwrap_or(20).min(100)),
+    })
+}
+
+#[derive(Serialize)]
+pub(crate) struct CacheMetricsResponse {
+    // Tüm sınıfların toplamı, sınıf başına kayıtlar, boyutlar ve kapasite yüzünden atılanlar cache_shards'ta
+    cache: CacheStats,
+    cache_shards: Vec<CacheShardStats>,
+    negative_cache: CacheStats,
+    // Kayıt sayısıyla sınırlı, size_bytes ve capacity_bytes burada bayt değil kayıt sayısı
+    search_hot_cache: CacheStats,
+}
+
+// İsabet oranı düşük ve evictions yüksekse cache_capacity_bytes artırılmalı, expirations yüksekse TTL'ler kısa olabilir
+pub(crate) async fn cache_metrics(
+    State(shared_state): State<SharedState>,
+) -> Json<CacheMetricsResponse> {
+    // Bekleyen silinme bildirimlerinin ve sayımların güncellenmesi için moka'nın arka plan işlerini çalıştırıyoruz
+    shared_state.cache.run_pending_tasks().await;
+    shared_state.negative_cache.run_pending_tasks().await;
+    shared_state.search_hot_cache.run_pending_tasks().await;
+    Json(CacheMetricsResponse {
+        cache: shared_state.metrics.cache.stats_with(
+            shared_state.cache.entry_count(),
+            shared_state.cache.weighted_size(),
+            Some(shared_state.cache.max_capacity()),
+        ),
+        cache_shards: shared_state.cache.shard_stats(),
+        negative_cache: shared_state
+            .metrics
+            .negative_cache
+            .stats(&shared_state.negative_cache),
+        search_hot_cache: shared_state
+            .metrics
+            .search_hot_cache
+            .stats(&shared_state.search_hot_cache),
+    })
+}
+
+// Veritabanı fonksiyonlarının süre dağılımları, toplam süresi en yüksek olan önce
+pub(crate) async fn database_queries() -> Json<Vec<QueryLatencySummary>> {
+    Json(DATABASE_QUERIES.summaries())
+}
+
+// Bağlantı havuzunun anlık kullanımı. acquire_timeouts artıyorsa database.max_connections yetmiyor
+pub(crate) async fn database_pool(State(shared_state): State<SharedState>) -> Json<PoolStats> {
+    let pool = shared_state.api_db.clone();
+    Json(pool_stats(&pool))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SearchAnalyticsParams {
+    window: Option<AnalyticsWindow>,
+    limit: Option<u32>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SearchAnalyticsResponse {
+    window: AnalyticsWindow,
+    // Pencerenin ilk günü, YYYY-MM-DD (UTC)
+    since: String,
+    total_searches: u64,
+    zero_result_searches: u64,
+    modes: Vec<SearchModeTotals>,
+    top_queries: Vec<SearchQueryTotals>,
+    // Sonuç bulunamayan sorgular, veri setinde eksik olan yemekleri gösteriyor
+    zero_result_queries: Vec<SearchQueryTotals>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SearchModeTotals {
+    mode: String,
+    searches: u64,
+    zero_results: u64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct SearchQueryTotals {
+    query: String,
+    searches: u64,
+    zero_results: u64,
+}
+
+// Seçilen pencerede en çok aranan ve hiç sonuç döndürmeyen sorgular, hangi yemeklerin veri setine eklenmesi gerektiğine karar vermek için
+pub(crate) async fn search_analytics(
+    State(shared_state): State<SharedState>,
+    Query(params): Query<SearchAnalyticsParams>,
+) -> Result<Json<SearchAnalyticsResponse>, APIError> {
+    let window = params.window.unwrap_or_default();
+    let limit = params.limit.unwrap_or(20).min(100);
+    let since = window.since(chrono::Utc::now().date_naive());
+
+    // Tamponda bekleyen sayaçları önce yazıyoruz ki son aramalar da görünsün
+    let pool = shared_state.api_db.clone();
+    shared_state.search_analytics.flush(&pool).await;
+
+    let database_error = |e: anyhow::Error| {
+        error!("Arama istatistikleri okunurken hata oluştu: {:?}", e);
+        APIError::database(&e, "Arama istatistiklerine ulaşılamadı")
+    };
+    let modes = database::select_search_mode_totals(&pool, &since)
+        .await
+        .map_err(database_error)?;
+    let top_queries = database::select_top_search_queries(&pool, &since, false, limit)
+        .await
+        .map_err(database_error)?;
+    let zero_result_queries = database::select_top_search_queries(&pool, &since, true, limit)
+        .await
+        .map_err(database_error)?;
+
+    let query_totals = |rows: Vec<(String, u64, u64)>| {
+        rows.into_iter()
+            .map(|(query, searches, zero_results)| SearchQueryTotals {
+                query,
+                searches,
+                zero_results,
+            })
+            .collect()
+    };
+    Ok(Json(SearchAnalyticsResponse {
+        window,
+        since,
+        total_searches: modes.iter().map(|(_, searches, _)| searches).sum(),
+        zero_result_searches: modes.iter().map(|(_, _, zero_results)| zero_results).sum(),
+        modes: modes
+            .into_iter()
+            .map(|(mode, searches, zero_results)| SearchModeTotals {
+                mode,
+                searches,
+                zero_results,
+            })
+            .collect(),
+        top_queries: query_totals(top_queries),
+        zero_result_queries: query_totals(zero_result_queries),
+    }))
+}
+
+// Takılmaları teşhis etmek için: tokio görev ve worker sayaçları, çalışma zamanı tıkanmaları, paylaşılan kilitlerin bekleme süreleri,
+// veritabanı bağlantı havuzu ve bellek kullanımı
+pub(crate) async fn runtime_debug(State(shared_state): State<SharedState>) -> Json<RuntimeStats> {
+    Json(runtime_stats(&shared_state).await)
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ConfigChangesParams {
+    // Sadece bu bölümdeki veya bu isimdeki alanlar, ör: rate_limit, maintenance.enabled
+    key: Option<String>,
+    limit: Option<u32>,
+}
+
+// Açılışta ve admin endpoint'lerinden yapılan config değişiklikleri, en yeni önce. Gizli alanların değerleri maskeli
+pub(crate) async fn config_changes(
+    State(shared_state): State<SharedState>,
+    Query(params): Query<ConfigChangesParams>,
+) -> Result<Json<Vec<ConfigChangeRecord>>, APIError> {
+    let pool = shared_state.api_db.clone();
+    let changes = database::select_config_changes(
+        &pool,
+        params.key.as_deref(),
+        params.limit.unwrap_or(50).min(500),
+    )
+    .await
+    .map_err(|e| {
+        error!("Config değişiklikleri okunurken hata oluştu: {:?}", e);
+        APIError::database(&e, "Config değişikliklerine ulaşılamadı")
+    })?;
+    Ok(Json(changes))
+}
+
+#[derive(Serialize)]
+pub(crate) struct SynonymSet {
+    id: i64,
+    terms: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SynonymSetRequest {
+    terms: Vec<String>,
+}
+
+pub(crate) async fn synonym_sets(
+    State(shared_state): State<SharedState>,
+) -> Result<Json<Vec<SynonymSet>>, APIError> {
+    let pool = shared_state.api_db.clone();
+    let sets = database::select_synonym_sets(&pool)
+        .await
+        .map_err(synonym_database_error)?;
+    Ok(Json(
+        sets.into_iter()
+            .map(|(id, terms)| SynonymSet { id, terms })
+            .collect(),
+    ))
+}
+
+pub(crate) async fn create_synonym_set(
+    State(shared_state): State<SharedState>,
+    Json(request): Json<SynonymSetRequest>,
+) -> Result<(StatusCode, Json<SynonymSet>), APIError> {
+    let set = save_synonym_set(&shared_state, None, request).await?;
+    Ok((StatusCode::CREATED, Json(set)))
+}
+
+pub(crate) async fn update_synonym_set(
+    State(shared_state): State<SharedState>,
+    Path(id): Path<i64>,
+    Json(request): Json<SynonymSetRequest>,
+) -> Result<Json<SynonymSet>, APIError> {
+    Ok(Json(
+        save_synonym_set(&shared_state, Some(id), request).await?,
+    ))
+}
+
+pub(crate) async fn delete_synonym_set(
+    State(shared_state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, APIError> {
+    let pool = shared_state.api_db.clone();
+    if !database::delete_synonym_set(&pool, id)
+        .await
+        .map_err(synonym_database_error)?
+    {
+        return Err(synonym_set_not_found(id));
+    }
+
+    info!("Eş anlamlı seti #{} silindi", id);
+    // Önbellekteki aramalar eski eş anlamlılarla yapılmış olabilir
+    purge_cache_prefix(&shared_state, "/foods/search").await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Terimler aramalardaki gibi normalize ediliyor, aynı terimin tekrarları (ör: Mısır ve misir) tek sayılıyor
+async fn save_synonym_set(
+    shared_state: &SharedState,
+    id: Option<i64>,
+    request: SynonymSetRequest,
+) -> Result<SynonymSet, APIError> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in request.terms {
+        let term = str::normalize_input(&term);
+        if term.is_empty() || term.chars().count() > MAX_SYNONYM_CHARS {
+            return Err(APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidParameters,
+                &format!(
+                    "Terimler en az 1, en fazla {} karakterden oluşabilir",
+                    MAX_SYNONYM_CHARS
+                ),
+            ));
+        }
+        if !terms
+            .iter()
+            .any(|existing| str::to_search_key(existing) == str::to_search_key(&term))
+        {
+            terms.push(term);
+        }
+    }
+    if !(2..=MAX_SYNONYM_SET_TERMS).contains(&terms.len()) {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            &format!(
+                "Bir set en az 2, en fazla {} farklı terimden oluşabilir",
+                MAX_SYNONYM_SET_TERMS
+            ),
+        ));
+    }
+
+    let pool = shared_state.api_db.clone();
+    let id = match database::save_synonym_set(&pool, id, &terms)
+        .await
+        .map_err(synonym_database_error)?
+    {
+        SynonymSetWrite::Saved(id) => id,
+        SynonymSetWrite::Conflict(term) => {
+            return Err(APIError::new(
+                StatusCode::CONFLICT,
+                ErrorCode::SynonymConflict,
+                &format!("'{}' başka bir eş anlamlı setinde zaten var", term),
+            ));
+        }
+        SynonymSetWrite::NotFound => return Err(synonym_set_not_found(id.unwrap_or_default())),
+    };
+
+    info!("Eş anlamlı seti #{} kaydedildi: {}", id, terms.join(", "));
+    purge_cache_prefix(shared_state, "/foods/search").await;
+    Ok(SynonymSet { id, terms })
+}
+
+fn synonym_set_not_found(id: i64) -> APIError {
+    APIError::new(
+        StatusCode::NOT_FOUND,
+        ErrorCode::SynonymSetNotFound,
+        &format!("#{} numaralı eş anlamlı seti bulunamadı", id),
+    )
+}
+
+fn synonym_database_error(e: anyhow::Error) -> APIError {
+    error!(
+        "Eş anlamlılar okunurken veya yazılırken hata oluştu: {:?}",
+        e
+    );
+    APIError::database(&e, "Eş anlamlılara ulaşılamadı")
+}
+
+#[derive(Serialize)]
+pub(crate) struct FoodAliases {
+    slug: String,
+    aliases: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct FoodAliasesRequest {
+    aliases: Vec<String>,
+}
+
+pub(crate) async fn food_aliases(
+    State(shared_state): State<SharedState>,
+    Path(slug): Path<String>,
+) -> Result<Json<FoodAliases>, APIError> {
+    let pool = shared_state.api_db.clone();
+    let aliases = database::select_food_aliases(&pool, &slug)
+        .await
+        .map_err(alias_database_error)?
+        .ok_or_else(|| food_not_found(&slug))?;
+    Ok(Json(FoodAliases { slug, aliases }))
+}
+
+// Yemeğin tüm diğer adlarını değiştiriyor. Adlar eş anlamlılar gibi normalize ediliyor, aynı adın tekrarları (ör: Kelem ve kelem) tek sayılıyor
+pub(crate) async fn update_food_aliases(
+    State(shared_state): State<SharedState>,
+    Path(slug): Path<String>,
+    Json(request): Json<FoodAliasesRequest>,
+) -> Result<Json<FoodAliases>, APIError> {
+    let mut aliases: Vec<String> = Vec::new();
+    for alias in request.aliases {
+        let alias = str::normalize_input(&alias);
+        if alias.is_empty() || alias.chars().count() > MAX_ALIAS_CHARS {
+            return Err(APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidParameters,
+                &format!(
+                    "Adlar en az 1, en fazla {} karakterden oluşabilir",
+                    MAX_ALIAS_CHARS
+                ),
+            ));
+        }
+        if !aliases
+            .iter()
+            .any(|existing| str::to_search_key(existing) == str::to_search_key(&alias))
+        {
+            aliases.push(alias);
+        }
+    }
+    if aliases.len() > MAX_FOOD_ALIASES {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            &format!(
+                "Bir yemeğin en fazla {} farklı adı olabilir",
+                MAX_FOOD_ALIASES
+            ),
+        ));
+    }
+
+    let pool = shared_state.api_db.clone();
+    let aliases = database::replace_food_aliases(&pool, &slug, &aliases)
+        .await
+        .map_err(alias_database_error)?
+        .ok_or_else(|| food_not_found(&slug))?;
+
+    info!(
+        "{} yemeğinin diğer adları kaydedildi: {}",
+        slug,
+        aliases.join(", ")
+    );
+    // Adlar hem /food/{slug} yanıtında hem de arama sonuçlarında
+    reload_search_index(&shared_state, &pool).await;
+    purge_cache_prefix(&shared_state, &format!("/food/{}", slug)).await;
+    purge_cache_prefix(&shared_state, "/foods/search").await;
+    Ok(Json(FoodAliases { slug, aliases }))
+}
+
+// Yüklenemezse indeksteki eski veriyle aramaya devam ediliyor, bir sonraki değişiklikte tekrar deneniyor
+async fn reload_search_index(shared_state: &SharedState, pool: &sqlx::SqlitePool) {
+    if let Err(e) = shared_state.search_index.reload(pool).await {
+        error!("Arama indeksi yeniden yüklenemedi: {:?}", e);
+    }
+}
+
+fn food_not_found(slug: &str) -> APIError {
+    APIError::new(
+        StatusCode::NOT_FOUND,
+        ErrorCode::FoodNotFound,
+        &format!("{} yemeği bulunamadı", slug),
+    )
+}
+
+fn alias_database_error(e: anyhow::Error) -> APIError {
+    error!(
+        "Yemeğin diğer adları okunurken veya yazılırken hata oluştu: {:?}",
+        e
+    );
+    APIError::database(&e, "Yemeğin diğer adlarına ulaşılamadı")
+}
+
+#[derive(Deserialize)]
+pub(crate) struct VerificationRequest {
+    verified_by: String,
+    method: VerificationMethod,
+    note: Option<String>,
+    // Verilmezse şu an, geçmişte yapılmış bir doğrulama da kaydedilebiliyor
+    verified_at: Option<String>,
+}
+
+// Yemeğe bir doğrulama ekliyor, yemek onaylanmamışsa onaylanıyor
+pub(crate) async fn create_food_verification(
+    State(shared_state): State<SharedState>,
+    Path(slug): Path<String>,
+    Json(request): Json<VerificationRequest>,
+) -> Result<(StatusCode, Json<Verification>), APIError> {
+    let verification = Verification {
+        verified_by: request.verified_by,
+        verified_at: request
+            .verified_at
+            .unwrap_or_else(|| Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)),
+        method: request.method,
+        note: request.note,
+    }
+    .normalized()
+    .map_err(|e| APIError::new(StatusCode::BAD_REQUEST, ErrorCode::InvalidParameters, &e))?;
+
+    let pool = shared_state.api_db.clone();
+    let found = database::insert_food_verification(&pool, &slug, &verification)
+        .await
+        .map_err(|e| {
+            error!("Yemeğin doğrulaması kaydedilirken hata oluştu: {:?}", e);
+            APIError::database(&e, "Doğrulama kaydedilemedi")
+        })?;
+    if !found {
+        return Err(food_not_found(&slug));
+    }
+
+    info!(
+        "{} yemeği {} tarafından doğrulandı ({})",
+        slug,
+        verification.verified_by,
+        verification.method.name()
+    );
+    // Yemek yeni onaylanmış olabilir, listeler ve öneriler de güncellenmeli
+    if let Err(e) = shared_state.suggestions.reload(&pool).await {
+        error!("Öneriler için yemek isimleri yeniden yüklenemedi: {:?}", e);
+    }
+    reload_search_index(&shared_state, &pool).await;
+    purge_cache_prefix(&shared_state, &format!("/food/{}", slug)).await;
+    purge_cache_prefix(&shared_state, "/foods").await;
+    Ok((StatusCode::CREATED, Json(verification)))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RecipeRequest {
+    description: String,
+    servings: u32,
+    ingredients: Vec<RecipeIngredientRequest>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RecipeIngredientRequest {
+    // Yemeğin slug'ı, sadece onaylanmış yemekler kullanılabiliyor
+    slug: String,
+    grams: f64,
+}
+
+pub(crate) async fn create_recipe(
+    State(shared_state): State<SharedState>,
+    Json(request): Json<RecipeRequest>,
+) -> Result<(StatusCode, Json<Recipe>), APIError> {
+    let recipe = save_recipe(&shared_state, None, request).await?;
+    Ok((StatusCode::CREATED, Json(recipe)))
+}
+
+pub(crate) async fn update_recipe(
+    State(shared_state): State<SharedState>,
+    Path(slug): Path<String>,
+    Json(request): Json<RecipeRequest>,
+) -> Result<Json<Recipe>, APIError> {
+    Ok(Json(
+        save_recipe(&shared_state, Some(&slug), request).await?,
+    ))
+}
+
+pub(crate) async fn delete_recipe(
+    State(shared_state): State<SharedState>,
+    Path(slug): Path<String>,
+) -> Result<StatusCode, APIError> {
+    let pool = shared_state.api_db.clone();
+    if !database::delete_recipe(&pool, &slug)
+        .await
+        .map_err(recipe_database_error)?
+    {
+        return Err(recipe_not_found(&slug));
+    }
+
+    info!("{} tarifi silindi", slug);
+    // /recipes listesi ve /recipe/{slug}
+    purge_cache_prefix(&shared_state, "/recipes").await;
+    purge_cache_prefix(&shared_state, "/recipe").await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Kaydedilen tarif besin değerleriyle birlikte, GET /recipe/{slug}'daki haliyle dönüyor
+async fn save_recipe(
+    shared_state: &SharedState,
+    slug: Option<&str>,
+    request: RecipeRequest,
+) -> Result<Recipe, APIError> {
+    let invalid = |message: &str| {
+        APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            message,
+        )
+    };
+    let description = str::normalize_input(&request.description);
+    if description.is_empty() || description.chars().count() > MAX_RECIPE_DESCRIPTION_CHARS {
+        return Err(invalid(&format!(
+            "Tarif açıklaması en az 1, en fazla {} karakterden oluşabilir",
+            MAX_RECIPE_DESCRIPTION_CHARS
+        )));
+    }
+    if !(1..=MAX_RECIPE_SERVINGS).contains(&request.servings) {
+        return Err(invalid(&format!(
+            "Porsiyon sayısı 1 ile {} arasında olmalı",
+            MAX_RECIPE_SERVINGS
+        )));
+    }
+    if !(1..=MAX_RECIPE_INGREDIENTS).contains(&request.ingredients.len()) {
+        return Err(invalid(&format!(
+            "Bir tarif en az 1, en fazla {} malzemeden oluşabilir",
+            MAX_RECIPE_INGREDIENTS
+        )));
+    }
+    let mut ingredients: Vec<(String, f64)> = Vec::new();
+    for ingredient in request.ingredients {
+        if !(ingredient.grams.is_finite()
+            && ingredient.grams > 0.0
+            && ingredient.grams <= MAX_INGREDIENT_GRAMS)
+        {
+            return Err(invalid(&format!(
+                "Malzeme gramı 0'dan büyük, en fazla {} olmalı",
+                MAX_INGREDIENT_GRAMS
+            )));
+        }
+        ingredients.push((str::normalize_input(&ingredient.slug), ingredient.grams));
+    }
+
+    let pool = shared_state.api_db.clone();
+    let slug =
+        match database::save_recipe(&pool, slug, &description, request.servings, &ingredients)
+            .await
+            .map_err(recipe_database_error)?
+        {
+            RecipeWrite::Saved(slug) => slug,
+            RecipeWrite::Conflict(existing) => {
+                return Err(APIError::new(
+                    StatusCode::CONFLICT,
+                    ErrorCode::RecipeConflict,
+                    &format!("'{}' isimli bir tarif zaten var", existing),
+                ));
+            }
+            RecipeWrite::NotFound => return Err(recipe_not_found(slug.unwrap_or_default())),
+            RecipeWrite::UnknownFood(food) => {
+                return Err(invalid(&format!(
+                    "{} yemeği bulunamadı veya henüz onaylanmadı",
+                    food
+                )));
+            }
+        };
+
+    info!("{} tarifi kaydedildi", slug);
+    // Açıklaması değişen tarifin eski slug'ı da önbellekte olabilir
+    purge_cache_prefix(shared_state, "/recipes").await;
+    purge_cache_prefix(shared_state, "/recipe").await;
+    let record = database::select_recipe_by_slug(&pool, &slug)
+        .await
+        .map_err(recipe_database_error)?
+        .ok_or_else(|| recipe_not_found(&slug))?;
+    Ok(Recipe::from(record))
+}
+
+fn recipe_database_error(e: anyhow::Error) -> APIError {
+    error!("Tarifler okunurken veya yazılırken hata oluştu: {:?}", e);
+    APIError::database(&e, "Tariflere ulaşılamadı")
+}
+
+// Süreç başladığından beri route bazında gecikme yüzdelikleri ve SLO durumu, en çok bütçe yakan route önce.
+// Sayaçlar yeniden başlatınca sıfırlandığı için bir sürümden sonra hangi endpoint'in yavaşladığı doğrudan görülebiliyor
+pub(crate) async fn slo(State(shared_state): State<SharedState>) -> Json<Vec<RouteLatencySummary>> {
+    let (default_threshold_ms, objective, route_thresholds_ms) = {
+        let config_guard = shared_state.config.load();
+        (
+            config_guard.slo.default_threshold_ms,
+            config_guard.slo.objective,
+            config_guard.slo.route_thresholds_ms.clone(),
+        )
+    };
+
+    let mut summaries: Vec<RouteLatencySummary> = shared_state
+        .metrics
+        .latencies
+        .histograms()
+        .into_iter()
+        .map(|(route, histogram)| {
+            let threshold_ms = route_thresholds_ms
+                .get(&route)
+                .copied()
+                .unwrap_or(default_threshold_ms);
+            let slo = SloTarget {
+                threshold: std::time::Duration::from_millis(threshold_ms),
+                objective,
+            };
+            histogram.summary(route, &slo)
+        })
+        .collect();
+    summaries.sort_by(|a, b| b.burn_rate.total_cmp(&a.burn_rate));
+    Json(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, http::Request};
+
+    use super::*;
+
+    // Anahtarlar önbellek middleware'inin ürettiği gibi olsun, format değişince testler eski formatta kalmasın
+    fn key(uri: &str) -> String {
+        crate::api::cache::cache_key(&Request::get(uri).body(Body::empty()).unwrap())
+    }
+
+    #[test]
+    fn test_matches_path_prefix() {
+        assert!(matches_path_prefix(&key("/food/muz"), "/food"));
+        assert!(matches_path_prefix(&key("/food"), "/food"));
+        assert!(!matches_path_prefix(&key("/foods/list"), "/food"));
+        assert!(matches_path_prefix(&key("/food/muz"), "/food/muz?"));
+        assert!(!matches_path_prefix(&key("/food/muz-ezmesi"), "/food/muz?"));
+        // Slug ile purge porsiyonları da siliyor ama benzer slug'ları silmiyor
+        assert!(matches_path_prefix(&key("/food/muz/portions"), "/food/muz"));
+        assert!(!matches_path_prefix(&key("/food/muz-ezmesi"), "/food/muz"));
+        // Boş önek ("/" isteği) her şeyi siliyor
+        assert!(matches_path_prefix(&key("/tags"), ""));
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    sync::{Arc, atomic::Ordering},
+    time::{Duration, Instant},
+};
+
+use reqwest::Client;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::{
+    api::metrics::{DATABASE_QUERIES, Metrics},
+    core::config::AlertingConfig,
+};
+
+// Slack gelen webhook'ları mesajı "text", Discord webhook'ları "content" alanından okuyor, bilmedikleri alanı görmezden geliyorlar.
+// İkisini birden doldurarak aynı URL ayarıyla iki servisi de destekliyoruz
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    text: &'a str,
+    content: &'a str,
+}
+
+#[derive(Clone, Copy)]
+enum AlertKind {
+    ServerErrors,
+    DatabaseFailures,
+}
+
+impl AlertKind {
+    fn describe(self) -> &'static str {
+        match self {
+            AlertKind::ServerErrors => "5xx yanıt oranı",
+            AlertKind::DatabaseFailures => "başarısız veritabanı sorgusu oranı",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum AlertEvent {
+    Firing {
+        rate: f64,
+        failures: u64,
+        samples: u64,
+    },
+    Resolved {
+        rate: f64,
+    },
+}
+
+// Sayaçlar süreç başından beri birikiyor, her kontrolde bir önceki değerle farkına bakarak sadece son aralığın oranını hesaplıyoruz
+struct RateMonitor {
+    kind: AlertKind,
+    threshold: f64,
+    last_samples: u64,
+    last_failures: u64,
+    // Alarm durumundaysa son mesajın gönderildiği an
+    last_sent: Option<Instant>,
+}
+
+impl RateMonitor {
+    fn new(kind: AlertKind, threshold: f64, (samples, failures): (u64, u64)) -> Self {
+        Self {
+            kind,
+            threshold,
+            last_samples: samples,
+            last_failures: failures,
+            last_sent: None,
+        }
+    }
+
+    fn observe(
+        &mut self,
+        (samples, failures): (u64, u64),
+        min_samples: u64,
+        repeat_after: Duration,
+        now: Instant,
+    ) -> Option<AlertEvent> {
+        let interval_samples = samples.saturating_sub(self.last_samples);
+        let interval_failures = failures.saturating_sub(self.last_failures);
+        self.last_samples = samples;
+        self.last_failures = failures;
+
+        let rate = if interval_samples == 0 {
+            0.0
+        } else {
+            interval_failures as f64 / interval_samples as f64
+        };
+
+        if interval_samples >= min_samples.max(1) && rate >= self.threshold {
+            // Süren bir sorunda her aralıkta mesaj atıp kanalı doldurmuyoruz
+            if self
+                .last_sent
+                .is_some_and(|last_sent| now.duration_since(last_sent) < repeat_after)
+            {
+                return None;
+            }
+            self.last_sent = Some(now);
+            return Some(AlertEvent::Firing {
+                rate,
+                failures: interval_failures,
+                samples: interval_samples,
+            });
+        }
+
+        // Trafik düşünce oran hesaplanamıyor, hiç hata yoksa sorunun geçtiğini kabul ediyoruz
+        if self.last_sent.is_some() && (interval_failures == 0 || interval_samples >= min_samples) {
+            self.last_sent = None;
+            return Some(AlertEvent::Resolved { rate });
+        }
+
+        None
+    }
+
+    fn message(&self, event: &AlertEvent, interval: Duration, instance: &str) -> String {
+        match event {
+            AlertEvent::Firing {
+                rate,
+                failures,
+                samples,
+            } => format!(
+                ":rotating_light: BesinVeri API ({}): son {} saniyede {} %{:.1} ({}/{}), eşik %{:.1}",
+                instance,
+                interval.as_secs(),
+                self.kind.describe(),
+                rate * 100.0,
+                failures,
+                samples,
+                self.threshold * 100.0,
+            ),
+            AlertEvent::Resolved { rate } => format!(
+                ":white_check_mark: BesinVeri API ({}): {} normale döndü (%{:.1})",
+                instance,
+                self.kind.describe(),
+                rate * 100.0,
+            ),
+        }
+    }
+}
+
+// Küçük kurulumlarda genellikle harici bir alarm sistemi olmuyor, hata oranları eşiği aşınca webhook'a haber veriyoruz
+pub(crate) async fn run_monitor(metrics: Arc<Metrics>, config: AlertingConfig, instance: String) {
+    let Some(webhook_url) = config.webhook_url else {
+        return;
+    };
+    let client = match Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Alarm webhook istemcisi oluşturulamadı: {}", e);
+            return;
+        }
+    };
+
+    // (örnek sayısı, başarısız örnek sayısı)
+    let counts = |kind| match kind {
+        AlertKind::ServerErrors => (
+            metrics.responses.total.load(Ordering::Relaxed),
+            metrics.responses.server_errors.load(Ordering::Relaxed),
+        ),
+        AlertKind::DatabaseFailures => DATABASE_QUERIES.totals(),
+    };
+    let interval = Duration::from_secs(config.check_interval_seconds);
+    let repeat_after = Duration::from_secs(config.repeat_after_seconds);
+    let mut monitors = [
+        RateMonitor::new(
+            AlertKind::ServerErrors,
+            config.server_error_rate_threshold,
+            counts(AlertKind::ServerErrors),
+        ),
+        RateMonitor::new(
+            AlertKind::DatabaseFailures,
+            config.database_failure_rate_threshold,
+            counts(AlertKind::DatabaseFailures),
+        ),
+    ];
+    info!("Hata oranları izleniyor, eşik aşılınca webhook'a bildirilecek");
+
+    let mut ticker = tokio::time::interval(interval);
+    ticker.tick().await; // İlk tick hemen dönüyor
+    loop {
+        ticker.tick().await;
+        for monitor in &mut monitors {
+            let Some(event) = monitor.observe(
+                counts(monitor.kind),
+                config.min_samples,
+                repeat_after,
+                Instant::now(),
+            ) else {
+                continue;
+            };
+
+            let message = monitor.message(&event, interval, &instance);
+            warn!("{}", message);
+            let payload = WebhookPayload {
+                text: &message,
+                content: &message,
+            };
+            match client.post(&webhook_url).json(&payload).send().await {
+                Ok(response) if response.status().is_success() => {}
+                Ok(response) => warn!("Alarm webhook'u {} döndürdü", response.status()),
+                Err(e) => warn!("Alarm webhook'una gönderilemedi: {}", e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_monitor() {
+        let repeat_after = Duration::from_secs(3600);
+        let start = Instant::now();
+        let mut monitor = RateMonitor::new(AlertKind::ServerErrors, 0.05, (1000, 10));
+
+        // Aralıkta yeterli istek yok, tek hata alarm çaldırmıyor
+        assert_eq!(monitor.observe((1005, 11), 20, repeat_after, start), None);
+        // Oran sadece son aralıktan hesaplanıyor: 10/100
+        assert_eq!(
+            monitor.observe((1105, 21), 20, repeat_after, start),
+            Some(AlertEvent::Firing {
+                rate: 0.1,
+                failures: 10,
+                samples: 100,
+            })
+        );
+        // Sorun sürüyor ama tekrar süresi dolmadı
+        assert_eq!(monitor.observe((1205, 31), 20, repeat_after, start), None);
+        assert!(matches!(
+            monitor.observe((1305, 41), 20, repeat_after, start + repeat_after),
+            Some(AlertEvent::Firing { .. })
+        ));
+        assert_eq!(
+            monitor.observe((1405, 42), 20, repeat_after, start + repeat_after),
+            Some(AlertEvent::Resolved { rate: 0.01 })
+        );
+        assert_eq!(monitor.observe((1505, 43), 20, repeat_after, start), None);
+    }
+}
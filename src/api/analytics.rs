@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Query, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::{SharedState, api::database, core::str};
+
+// Bir yazma periyodunda tutulan farklı (gün, mod, sorgu) sayısı, rastgele sorgular gönderen botlar belleği dolduramasın
+const MAX_PENDING_QUERIES: usize = 10_000;
+
+// Günlük kayıtlar bu kadar gün saklanıyor, en geniş analiz penceresi de bu kadar
+const RETENTION_DAYS: i64 = 90;
+
+#[derive(Default)]
+struct SearchCounts {
+    searches: u64,
+    zero_results: u64,
+}
+
+// Arama sayaçları da kota sayaçları gibi bellekte toplanıp USAGE_FLUSH_INTERVAL aralıklarla veritabanına yazılıyor.
+// Veri setine hangi yemeklerin eklenmesi gerektiğini görmek için en çok aranan ve sonuç bulunamayan sorgular tutuluyor
+#[derive(Default)]
+pub(crate) struct SearchAnalytics {
+    // (gün, mod, sorgu) -> henüz yazılmamış sayaçlar
+    pending: Mutex<HashMap<(String, String, String), SearchCounts>>,
+}
+
+impl SearchAnalytics {
+    pub(crate) async fn record(&self, day: &str, mode: &str, query: &str, results: usize) {
+        let mut pending = self.pending.lock().await;
+        let key = (day.to_owned(), mode.to_owned(), query.to_owned());
+        if pending.len() >= MAX_PENDING_QUERIES && !pending.contains_key(&key) {
+            debug!("Arama istatistikleri tamponu dolu, sorgu sayılmadı");
+            return;
+        }
+
+        let counts = pending.entry(key).or_default();
+        counts.searches += 1;
+        if results == 0 {
+            counts.zero_results += 1;
+        }
+    }
+
+    // Bekleyen sayaçları veritabanına yazıyor, yazılamayanlar bir sonraki sefer için geri ekleniyor. Saklama süresi geçen kayıtları da siliyor
+    pub(crate) async fn flush(&self, pool: &SqlitePool) {
+        let pending = std::mem::take(&mut *self.pending.lock().await);
+        for ((day, mode, query), counts) in pending {
+            if let Err(e) = database::add_search_analytics(
+                pool,
+                &day,
+                &mode,
+                &query,
+                counts.searches,
+                counts.zero_results,
+            )
+            .await
+            {
+                warn!("Arama istatistikleri veritabanına yazılamadı: {}", e);
+                let mut pending = self.pending.lock().await;
+                let unwritten = pending.entry((day, mode, query)).or_default();
+                unwritten.searches += counts.searches;
+                unwritten.zero_results += counts.zero_results;
+            }
+        }
+
+        let oldest_day = (chrono::Utc::now() - chrono::Duration::days(RETENTION_DAYS))
+            .format("%Y-%m-%d")
+            .to_string();
+        if let Err(e) = database::delete_search_analytics_before(pool, &oldest_day).await {
+            warn!("Eski arama istatistikleri silinemedi: {}", e);
+        }
+    }
+}
+
+// GET /admin/analytics/searches?window=7d
+#[derive(Clone, Copy, Serialize, Deserialize, Default)]
+pub(crate) enum AnalyticsWindow {
+    #[serde(rename = "1d")]
+    Day,
+    #[serde(rename = "7d")]
+    #[default]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+    #[serde(rename = "90d")]
+    Quarter,
+}
+
+impl AnalyticsWindow {
+    // Pencerenin ilk günü, YYYY-MM-DD. 1d sadece bugünü, 7d bugün ve önceki 6 günü kapsıyor
+    pub(crate) fn since(self, today: chrono::NaiveDate) -> String {
+        let days = match self {
+            AnalyticsWindow::Day => 1,
+            AnalyticsWindow::Week => 7,
+            AnalyticsWindow::Month => 30,
+            AnalyticsWindow::Quarter => RETENTION_DAYS,
+        };
+        (today - chrono::Duration::days(days - 1))
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    mode: Option<String>,
+    offset: Option<u64>,
+}
+
+// Aramaları önbellek katmanının dışında sayıyoruz ki önbellekten dönen popüler aramalar da istatistiklere girsin.
+// Önbellekten dönen yanıtlarda handler hiç çalışmadığı için sonuç sayısını yanıt gövdesinden buluyoruz
+pub(crate) async fn search_analytics_middleware(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if request.uri().path() != "/foods/search" {
+        return next.run(request).await;
+    }
+    let Ok(Query(params)) = Query::<SearchQuery>::try_from_uri(request.uri()) else {
+        return next.run(request).await;
+    };
+    // Sonraki sayfalar yeni bir arama değil, sadece ilk sayfayı sayıyoruz. q'su boş göz atma istekleri de arama sayılmıyor
+    if params.offset.unwrap_or(0) > 0 || params.q.trim().is_empty() {
+        return next.run(request).await;
+    }
+
+    let response = next.run(request).await;
+    // Geçersiz sorgular ve hatalar sayılmıyor, handler'ın kabul ettiği aramalarla ilgileniyoruz
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    // Arama yanıtları önbellek katmanında zaten tamamen belleğe alınmış oluyor, burada tekrar okumak ucuz
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Arama yanıtı okunamadı: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    if let Ok(foods) = serde_json::from_slice::<Vec<IgnoredAny>>(&bytes) {
+        let mode = match params.mode.map(|mode| mode.to_lowercase()).as_deref() {
+            Some("tag") => "tag",
+            Some("all") => "all",
+            // name, description'ın takma adı
+            _ => "description",
+        };
+        let query = str::normalize_input(&params.q).to_lowercase();
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        shared_state
+            .search_analytics
+            .record(&today, mode, &query, foods.len())
+            .await;
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_since() {
+        let today = chrono::NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(AnalyticsWindow::Day.since(today), "2026-10-16");
+        assert_eq!(AnalyticsWindow::Week.since(today), "2026-10-10");
+        assert_eq!(AnalyticsWindow::Month.since(today), "2026-09-17");
+    }
+
+    #[tokio::test]
+    async fn test_record_caps_pending_queries() {
+        let analytics = SearchAnalytics::default();
+        analytics
+            .record("2026-10-16", "description", "elma", 2)
+            .await;
+        analytics
+            .record("2026-10-16", "description", "elma", 0)
+            .await;
+        for i in 0..MAX_PENDING_QUERIES {
+            analytics
+                .record("2026-10-16", "description", &i.to_string(), 1)
+                .await;
+        }
+
+        let pending = analytics.pending.lock().await;
+        assert_eq!(pending.len(), MAX_PENDING_QUERIES);
+        let elma = &pending[&(
+            "2026-10-16".to_owned(),
+            "description".to_owned(),
+            "elma".to_owned(),
+        )];
+        assert_eq!((elma.searches, elma.zero_results), (2, 1));
+    }
+}
@@ -1,63 +1,980 @@
+use std::{
+    fs,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Error;
 use axum::{
-    body::Body,
+    body::{Body, Bytes},
     extract::{Request, State},
     middleware::Next,
     response::Response,
 };
-use reqwest::{header::{CACHE_CONTROL, CONTENT_TYPE}, StatusCode};
+use moka::{future::Cache, notification::RemovalCause};
+use reqwest::{
+    Method, StatusCode,
+    header::{
+        ACCEPT, AGE, CACHE_CONTROL, CONTENT_TYPE, ETAG, HeaderName, HeaderValue, IF_NONE_MATCH,
+    },
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+use crate::{
+    SharedState,
+    api::{
+        error::{APIError, ErrorCode},
+        foods::X_TOTAL_COUNT,
+        metrics::Metrics,
+    },
+    core::config::{CacheShardConfig, ResponseCacheConfig},
+};
+
+// Bulunamayan yemekler kısa bir süre önbellekte tutulacak, yemek eklendiğinde de en fazla 1 dakika içinde görünür olacak
+pub(crate) const NEGATIVE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+// Standart bir header değil ama CDN'lerin de kullandığı yaygın bir format: HIT, MISS veya BYPASS
+const X_CACHE: &str = "x-cache";
+
+// Handler'ın yanıta eklediği ve önbellekten dönen yanıtlarda da bulunması gereken header'lar
+const PRESERVED_HEADERS: [HeaderName; 2] = [CONTENT_TYPE, HeaderName::from_static(X_TOTAL_COUNT)];
+
+// Önbellekteki her kayıt, gövdesiyle birlikte ne zaman kaydedildiğini de tutuyor; Age header'ını hesaplamak için gerekli
+// Serialize/Deserialize ise önbelleği kapanışta diske yazıp açılışta geri yükleyebilmek için.
+// Gövde ve header'lar Bytes tabanlı tutuluyor, isabetlerde büyük liste yanıtları kopyalanmadan yanıta konuyor
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct CachedResponse {
+    #[serde(with = "body_text")]
+    pub(crate) body: Bytes,
+    pub(crate) cached_at: SystemTime,
+    // PRESERVED_HEADERS'tan yanıtta bulunanlar ve ETag. Eski snapshot'larda bu alan yok
+    #[serde(default, with = "header_pairs")]
+    pub(crate) headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl CachedResponse {
+    fn new(body: Bytes) -> Self {
+        Self {
+            body,
+            cached_at: SystemTime::now(),
+            headers: Vec::new(),
+        }
+    }
+
+    fn age(&self) -> u64 {
+        self.cached_at.elapsed().unwrap_or_default().as_secs()
+    }
+}
+
+// Yanıt önbelleğinin bölündüğü endpoint sınıfları. Her sınıfın kendi moka önbelleği, kapasitesi ve TTL'i var;
+// tekil arama sorgularından oluşan bir dalga sadece arama önbelleğini dolduruyor, /foods/list ve /tags kayıtları atılmıyor
+#[derive(Clone, Copy, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum EndpointClass {
+    // Tek bir kayda ait yanıtlar
+    Detail,
+    // Sayısı az ve sürekli istenen listeler, varsayılan sınıf
+    List,
+    // Anahtarı sorguyla değişen, sayısı sınırsız yanıtlar
+    Search,
+}
+
+impl EndpointClass {
+    const ALL: [EndpointClass; 3] = [Self::Detail, Self::List, Self::Search];
+
+    // Önbellek anahtarı da path ile başladığı için hem isteğin path'iyle hem anahtarla çağrılabiliyor
+    pub(crate) fn of(path: &str) -> Self {
+        let path = path.split(['?', '|']).next().unwrap_or_default();
+        match path {
+            "/foods/search" | "/foods/suggest" | "/foods/summaries" | "/convert"
+            | "/calc/energy" => Self::Search,
+            _ if ["/food/", "/recipe/", "/reference-intake/"]
+                .iter()
+                .any(|prefix| path.starts_with(prefix)) =>
+            {
+                Self::Detail
+            }
+            _ => Self::List,
+        }
+    }
+
+    fn config(self, config: &ResponseCacheConfig) -> CacheShardConfig {
+        match self {
+            Self::Detail => config.detail,
+            Self::List => config.list,
+            Self::Search => config.search,
+        }
+    }
+}
+
+// Endpoint sınıfı başına bir moka önbelleği, anahtarlar path'e göre ilgili önbelleğe gidiyor
+pub(crate) struct ResponseCache {
+    shards: [CacheShard; 3],
+}
+
+struct CacheShard {
+    class: EndpointClass,
+    cache: Cache<String, CachedResponse>,
+    // Toplam sayaçlar metrics.cache'te, hangi sınıfın kapasitesinin yetmediğini görmek için kapasite yüzünden atılanlar ayrıca sayılıyor
+    evictions: Arc<AtomicU64>,
+}
+
+// /admin/metrics/cache'te sınıf başına gösterilen durum
+#[derive(Serialize)]
+pub(crate) struct CacheShardStats {
+    class: EndpointClass,
+    entries: u64,
+    size_bytes: u64,
+    capacity_bytes: Option<u64>,
+    ttl_seconds: Option<u64>,
+    evictions: u64,
+}
+
+impl ResponseCache {
+    // capacity core.cache_capacity_bytes, her sınıf config'deki payı kadarını kullanıyor
+    pub(crate) fn new(config: &ResponseCacheConfig, capacity: u64, metrics: Arc<Metrics>) -> Self {
+        let shards = EndpointClass::ALL.map(|class| {
+            let shard = class.config(config);
+            let evictions = Arc::new(AtomicU64::new(0));
+            let cache = Cache::builder()
+                .weigher(|key: &String, value| weigh_entry(key, value))
+                .max_capacity((capacity as f64 * shard.share) as u64)
+                .time_to_live(Duration::from_secs(shard.ttl_seconds))
+                .eviction_listener({
+                    let metrics = metrics.clone();
+                    let evictions = evictions.clone();
+                    move |_key, _value, cause| {
+                        if cause == RemovalCause::Size {
+                            evictions.fetch_add(1, Ordering::Relaxed);
+                        }
+                        metrics.cache.record_removal(cause)
+                    }
+                })
+                .build();
+            CacheShard {
+                class,
+                cache,
+                evictions,
+            }
+        });
+        Self { shards }
+    }
+
+    pub(crate) fn shard(&self, class: EndpointClass) -> &Cache<String, CachedResponse> {
+        &self
+            .shards
+            .iter()
+            .find(|shard| shard.class == class)
+            .expect("Her sınıfın bir önbelleği var")
+            .cache
+    }
+
+    // Sınıfın response_cache'teki ttl_seconds'ı, önbellek açılışta bununla kuruluyor
+    pub(crate) fn ttl(&self, class: EndpointClass) -> Duration {
+        self.shard(class)
+            .policy()
+            .time_to_live()
+            .expect("Her sınıfın önbelleği bir TTL ile kuruluyor")
+    }
+
+    // Anahtarın path'ine göre kaydın bulunduğu önbellek
+    pub(crate) fn shard_for(&self, key: &str) -> &Cache<String, CachedResponse> {
+        self.shard(EndpointClass::of(key))
+    }
+
+    pub(crate) fn shards(&self) -> impl Iterator<Item = &Cache<String, CachedResponse>> {
+        self.shards.iter().map(|shard| &shard.cache)
+    }
 
-use crate::SharedState;
+    pub(crate) fn entry_count(&self) -> u64 {
+        self.shards().map(|cache| cache.entry_count()).sum()
+    }
+
+    pub(crate) fn weighted_size(&self) -> u64 {
+        self.shards().map(|cache| cache.weighted_size()).sum()
+    }
+
+    pub(crate) fn max_capacity(&self) -> u64 {
+        self.shards()
+            .filter_map(|cache| cache.policy().max_capacity())
+            .sum()
+    }
+
+    pub(crate) async fn run_pending_tasks(&self) {
+        for cache in self.shards() {
+            cache.run_pending_tasks().await;
+        }
+    }
+
+    pub(crate) fn shard_stats(&self) -> Vec<CacheShardStats> {
+        self.shards
+            .iter()
+            .map(|shard| CacheShardStats {
+                class: shard.class,
+                entries: shard.cache.entry_count(),
+                size_bytes: shard.cache.weighted_size(),
+                capacity_bytes: shard.cache.policy().max_capacity(),
+                ttl_seconds: shard.cache.policy().time_to_live().map(|ttl| ttl.as_secs()),
+                evictions: shard.evictions.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
+
+// Moka'nın kapasitesini girdi sayısı yerine bayt olarak kullanabilmek için anahtar ve gövde boyutlarını topluyoruz
+// Böylece büyük /foods/list yanıtı ile küçük bir /tags yanıtı aynı ağırlıkta sayılmıyor
+pub(crate) fn weigh_entry(key: &str, value: &CachedResponse) -> u32 {
+    let headers_len: usize = value
+        .headers
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len())
+        .sum();
+    (key.len() + value.body.len() + headers_len)
+        .try_into()
+        .unwrap_or(u32::MAX)
+}
 
 pub async fn cache_middleware(
     State(state): State<SharedState>,
     request: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    let url = request.uri().to_string();
-    let ttl = match request.uri().path() {
-        "/api" | "/api/foods" => std::time::Duration::MAX, // Bu 2 endpoint zaten statik o yüzden bir defa cache atmamız yeterli,
-        "/api/health" => std::time::Duration::from_secs(600), // Timestamp attığı ve anlık önemli olduğu için 10 dakikada 1 cache
-        path if path.starts_with("/api/food") => std::time::Duration::from_secs(28800), // 8 saatte bir diğer yemek endpointleri için şimdilik güzel
-        _ => std::time::Duration::from_secs(3600), // Varsayılan 1 saat, başka bir endpoint yok ama yine de ekleyelim
-    };
+    // Sadece GET isteklerini önbelleğe alıyoruz, diğer metotlar durum değiştirebilir.
+    // Liveness/readiness probe'ları da anlık durumu göstermeli, önbellekten dönerlerse kapanan bir sunucu hazır görünür.
+    // NDJSON yanıtları akış olarak gönderiliyor, önbelleğe almak için tamamını belleğe okumak akışın amacına ters
+    if request.method() != Method::GET
+        || request.uri().path().starts_with("/health/")
+        || request.uri().path().ends_with(".ndjson")
+    {
+        state.metrics.cache.bypasses.fetch_add(1, Ordering::Relaxed);
+        let mut response = next.run(request).await;
+        response
+            .headers_mut()
+            .insert(X_CACHE, HeaderValue::from_static("BYPASS"));
+        return Ok(response);
+    }
+
+    let url = cache_key(&request);
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+    // İstemcilere de yanıtın sunucunun önbelleğinde kaldığı süre bildiriliyor, Age ile birlikte kalan süreyi hesaplayabiliyorlar
+    let class = EndpointClass::of(request.uri().path());
+    let ttl = state.cache.ttl(class);
+
+    // Var olmayan yemekler için de kısa süreli bir "bulunamadı" önbelleği tutuyoruz, botların rastgele slug denemeleri veritabanına gitmesin
+    let is_food_lookup = request.uri().path().starts_with("/food/");
+    let cache = state.cache.shard(class);
+    if is_food_lookup && let Some(response) = negative_cache_hit(&state, &url).await {
+        return response;
+    }
+    if is_food_lookup {
+        state
+            .metrics
+            .negative_cache
+            .misses
+            .fetch_add(1, Ordering::Relaxed);
+    }
 
     // Önce veri önbelleğe zaten kaydedilmiş mi bakıyoruz
     // Eğer cache edilen sayfanın ömrü bittiyse zaten moka halletmiş olacak, bizim bir ttl kontrolü yapmamıza gerek yok
-    if let Some(cached) = state.cache.get(&url).await {
-        let response = Response::builder()
-            .status(StatusCode::OK)
-            .header(CONTENT_TYPE, "application/json")
-            .header(CACHE_CONTROL, format!("public, max-age={}", ttl.as_secs()))
-            .body(cached.into())
-            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(cached) = cache.get(&url).await {
+        state.metrics.cache.hits.fetch_add(1, Ordering::Relaxed);
+        let age = cached.age();
+        return build_response(
+            StatusCode::OK,
+            cached.body,
+            &cached.headers,
+            ttl,
+            "HIT",
+            Some(age),
+            if_none_match.as_ref(),
+        );
+    }
 
-        return Ok(response);
+    // Önbellekte yoksa yani ilk defa giriliyorsa veya ömrü bittiyse cache'in handlerı çalıştıracağız.
+    // Aynı anahtarı aynı anda isteyenlerden sadece biri handler'ı çalıştırıyor, moka diğerlerini onun sonucuna bekletiyor.
+    // Böylece TTL'i dolan /foods/list'e aynı anda gelen onlarca istek veritabanına tek sorgu olarak gidiyor.
+    // Handler'ı çalıştıran isteğin bağlantısı koparsa moka bekleyenlerden birinin handler'ını çalıştırıyor
+    let mut pending = Some((request, next));
+    let mut uncached = None;
+    let entry = cache
+        .entry_by_ref(&url)
+        .or_try_insert_with(async {
+            let (request, next) = pending.take().expect("İstek sadece bir kez çalıştırılıyor");
+            state.metrics.cache.misses.fetch_add(1, Ordering::Relaxed);
+            let response = next.run(request).await;
+            cacheable(&state, &url, is_food_lookup, response)
+                .await
+                .map_err(|response| {
+                    uncached = Some(response);
+                    NotCached
+                })
+        })
+        .await;
+
+    match entry {
+        Ok(entry) => {
+            // Handler'ı bu istek çalıştırdıysa MISS, başka bir isteğin sonucunu beklediyse önbellekten dönmüş gibi HIT
+            let (x_cache, age) = if entry.is_fresh() {
+                state.metrics.cache.inserts.fetch_add(1, Ordering::Relaxed);
+                ("MISS", None)
+            } else {
+                state
+                    .metrics
+                    .cache
+                    .coalesced
+                    .fetch_add(1, Ordering::Relaxed);
+                ("HIT", Some(entry.value().age()))
+            };
+            let cached = entry.into_value();
+            build_response(
+                StatusCode::OK,
+                cached.body,
+                &cached.headers,
+                ttl,
+                x_cache,
+                age,
+                if_none_match.as_ref(),
+            )
+        }
+        Err(_) => match uncached {
+            Some(response) => response,
+            // Beklenen yanıt önbelleğe girmedi (hata veya bulunamadı), hatalar geçici olabileceği için paylaşılmıyor ve
+            // bekleyen istekler handler'ı kendileri çalıştırıyor. Bulunamadıysa negatif önbellekte olacak
+            None => {
+                if is_food_lookup && let Some(response) = negative_cache_hit(&state, &url).await {
+                    return response;
+                }
+                let (request, next) = pending.take().expect("İstek henüz çalıştırılmadı");
+                state.metrics.cache.misses.fetch_add(1, Ordering::Relaxed);
+                let response = next.run(request).await;
+                match cacheable(&state, &url, is_food_lookup, response).await {
+                    Ok(cached) => {
+                        cache.insert(url, cached.clone()).await;
+                        state.metrics.cache.inserts.fetch_add(1, Ordering::Relaxed);
+                        build_response(
+                            StatusCode::OK,
+                            cached.body,
+                            &cached.headers,
+                            ttl,
+                            "MISS",
+                            None,
+                            if_none_match.as_ref(),
+                        )
+                    }
+                    Err(response) => response,
+                }
+            }
+        },
+    }
+}
+
+// Handler'ın yanıtı önbelleğe girmediğinde bekleyen isteklere dönen hata, asıl yanıt handler'ı çalıştıran istekte kalıyor
+struct NotCached;
+
+// Handler'ın 200 yanıtını önbelleğe girecek hale getiriyor. Diğer yanıtlar olduğu gibi dönülmek üzere Err içinde
+async fn cacheable(
+    state: &SharedState,
+    url: &str,
+    is_food_lookup: bool,
+    mut response: Response,
+) -> Result<CachedResponse, Result<Response, StatusCode>> {
+    // Yemek bulunamadıysa hata gövdesini negatif önbelleğe kısa bir süreliğine kaydediyoruz. Aynı path'ten dönen diğer 404'ler
+    // (ör: yanlış yazılmış bir alt path) önbelleğe girmiyor, sadece kodu FOOD_NOT_FOUND olanlar
+    if is_food_lookup && response.status() == StatusCode::NOT_FOUND {
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, 64 * 1024)
+            .await
+            .map_err(|_| Err(StatusCode::INTERNAL_SERVER_ERROR))?;
+        let food_not_found = serde_json::from_slice::<APIError>(&body)
+            .is_ok_and(|error| error.error == ErrorCode::FoodNotFound);
+        if food_not_found {
+            state
+                .negative_cache
+                .insert(url.to_owned(), CachedResponse::new(body.clone()))
+                .await;
+            state
+                .metrics
+                .negative_cache
+                .inserts
+                .fetch_add(1, Ordering::Relaxed);
+
+            return Err(build_response(
+                StatusCode::NOT_FOUND,
+                body,
+                &[],
+                NEGATIVE_CACHE_TTL,
+                "MISS",
+                None,
+                None,
+            ));
+        }
+        response = Response::from_parts(parts, Body::from(body));
     }
 
-    // Önbellekte yoksa yani ilk defa giriliyorsa veya ömrü bittiyse cache'in handlerı çalıştıracağız
-    let response = next.run(request).await;
     // Eğer hata döndürüyorsa cache atmıyoruz çünkü geçici bir durum olabilir, direkt döndürüyoruz
     if response.status() != StatusCode::OK {
-        return Ok(response);
+        state.metrics.cache.bypasses.fetch_add(1, Ordering::Relaxed);
+        response
+            .headers_mut()
+            .insert(X_CACHE, HeaderValue::from_static("BYPASS"));
+        return Err(Ok(response));
     }
 
+    let mut headers: Vec<(HeaderName, HeaderValue)> = PRESERVED_HEADERS
+        .iter()
+        .filter_map(|name| Some((name.clone(), response.headers().get(name)?.clone())))
+        .collect();
+
     // Body boyutunu garip sorgulara karşı 10MB olarak limitleyelim, DoS saldırılarına karşı yardımcı olabilir
     let body = axum::body::to_bytes(response.into_body(), 10 * 1024 * 1024)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    let body = std::str::from_utf8(&body)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .to_owned();
-    // Daha sonra cache'e ekleyeceğiz, yanıt başarılı veya başarısız olabilir
-    state.cache.insert(url, body.to_owned()).await;
-
-    // Cache-Control başlığını da unutmuyoruz header olarak, client tarafında da cache için
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .header(CONTENT_TYPE, "application/json")
+        .map_err(|_| Err(StatusCode::INTERNAL_SERVER_ERROR))?;
+    // Snapshot'ta gövdeler metin olarak yazılıyor
+    std::str::from_utf8(&body).map_err(|_| Err(StatusCode::INTERNAL_SERVER_ERROR))?;
+    headers.push((ETAG, etag(&body)));
+    Ok(CachedResponse {
+        headers,
+        ..CachedResponse::new(body)
+    })
+}
+
+async fn negative_cache_hit(
+    state: &SharedState,
+    url: &str,
+) -> Option<Result<Response, StatusCode>> {
+    let cached = state.negative_cache.get(url).await?;
+    let hits = state
+        .metrics
+        .negative_cache
+        .hits
+        .fetch_add(1, Ordering::Relaxed)
+        + 1;
+    debug!("Negatif önbellek isabeti: {} (toplam {} isabet)", url, hits);
+
+    let age = cached.age();
+    Some(build_response(
+        StatusCode::NOT_FOUND,
+        cached.body,
+        &[],
+        NEGATIVE_CACHE_TTL,
+        "HIT",
+        Some(age),
+        None,
+    ))
+}
+
+// Gövdenin SHA-256 özetinin ilk 16 baytı, aynı gövde her sunucuda ve yeniden başlatmalardan sonra aynı ETag'i alıyor
+fn etag(body: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    HeaderValue::try_from(format!("\"{}\"", hex::encode(&digest[..16])))
+        .expect("ETag geçerli bir header değeri")
+}
+
+// If-None-Match'teki ETag'lerden biri (veya *) kayıttakiyle eşleşiyorsa istemcideki kopya güncel
+fn is_not_modified(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(if_none_match) = if_none_match.to_str() else {
+        return false;
+    };
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/").as_bytes() == etag.as_bytes())
+}
+
+// Önbellekten veya handler'dan gelen gövdeyle yanıtı oluşturuyoruz
+// Cache-Control başlığını da unutmuyoruz header olarak, client tarafında da cache için
+// X-Cache ve Age ile de istemci geliştiricileri yanıtın önbellekten gelip gelmediğini görebiliyor
+// İstemci If-None-Match ile güncel ETag'i gönderdiyse gövdesiz 304 dönüyor
+fn build_response(
+    status: StatusCode,
+    body: Bytes,
+    headers: &[(HeaderName, HeaderValue)],
+    ttl: Duration,
+    x_cache: &'static str,
+    age: Option<u64>,
+    if_none_match: Option<&HeaderValue>,
+) -> Result<Response, StatusCode> {
+    let not_modified = if_none_match.is_some_and(|if_none_match| {
+        headers
+            .iter()
+            .any(|(name, etag)| name == ETAG && is_not_modified(if_none_match, etag))
+    });
+    let mut builder = Response::builder()
+        .status(if not_modified {
+            StatusCode::NOT_MODIFIED
+        } else {
+            status
+        })
         .header(CACHE_CONTROL, format!("public, max-age={}", ttl.as_secs()))
-        .body(body.into())
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .header(X_CACHE, x_cache);
+    if let Some(age) = age {
+        builder = builder.header(AGE, age);
+    }
+    // Eski snapshot'lardan gelen kayıtlarda Content-Type yok, handler'ların hepsi zaten JSON dönüyordu
+    if !headers.iter().any(|(name, _)| name == CONTENT_TYPE) {
+        builder = builder.header(CONTENT_TYPE, "application/json");
+    }
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+
+    builder
+        .body(if not_modified {
+            Body::empty()
+        } else {
+            body.into()
+        })
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+// Snapshot'ta gövde eskisi gibi metin olarak yazılıyor, önbelleğe sadece UTF-8 gövdeler giriyor
+mod body_text {
+    use axum::body::Bytes;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error as _, ser::Error as _};
+
+    pub(super) fn serialize<S: Serializer>(body: &Bytes, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(std::str::from_utf8(body).map_err(S::Error::custom)?)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Bytes, D::Error> {
+        String::deserialize(deserializer)
+            .map(Bytes::from)
+            .map_err(D::Error::custom)
+    }
+}
+
+// Header'lar snapshot'ta (ad, değer) metin çiftleri olarak duruyor
+mod header_pairs {
+    use reqwest::header::{HeaderName, HeaderValue};
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer, de::Error as _, ser::Error as _,
+    };
+
+    pub(super) fn serialize<S: Serializer>(
+        headers: &[(HeaderName, HeaderValue)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        headers
+            .iter()
+            .map(|(name, value)| Ok((name.as_str(), value.to_str().map_err(S::Error::custom)?)))
+            .collect::<Result<Vec<_>, S::Error>>()?
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(HeaderName, HeaderValue)>, D::Error> {
+        Vec::<(String, String)>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, value)| {
+                Ok((
+                    HeaderName::try_from(name).map_err(D::Error::custom)?,
+                    HeaderValue::try_from(value).map_err(D::Error::custom)?,
+                ))
+            })
+            .collect()
+    }
+}
+
+// Kapanışta önbellekteki kayıtları diske yazıyoruz, böylece her deploy sonrası önbellek sıfırdan ısınmak zorunda kalmıyor
+pub(crate) fn save_snapshot(cache: &ResponseCache, path: &str) -> Result<usize, Error> {
+    let entries: Vec<(String, CachedResponse)> = cache
+        .shards()
+        .flat_map(|shard| shard.iter())
+        .map(|(key, value)| (key.as_ref().to_owned(), value))
+        .collect();
 
-    Ok(response)
+    fs::write(path, serde_json::to_vec(&entries)?)?;
+    Ok(entries.len())
+}
+
+// Açılışta diskteki kayıtları sınıflarının önbelleğine geri yüklüyoruz, o sınıfın TTL'ine göre ömrü zaten dolmuş olanları atlıyoruz
+pub(crate) async fn load_snapshot(cache: &ResponseCache, path: &str) -> Result<usize, Error> {
+    if !fs::exists(path)? {
+        return Ok(0);
+    }
+
+    let entries: Vec<(String, CachedResponse)> = serde_json::from_slice(&fs::read(path)?)?;
+    let mut loaded = 0;
+    for (key, value) in entries {
+        let shard = cache.shard_for(&key);
+        let ttl = shard.policy().time_to_live().unwrap_or(Duration::MAX);
+        if value.age() < ttl.as_secs() {
+            shard.insert(key, value).await;
+            loaded += 1;
+        }
+    }
+
+    Ok(loaded)
+}
+
+// Ham URL'yi anahtar olarak kullanırsak ?q=elma&limit=5 ile ?limit=5&q=elma farklı kayıtlar oluyor, bu yüzden anahtarı normalize ediyoruz
+// Path + alfabetik sıralanmış sorgu parametreleri + Accept'e göre seçilen gösterim anahtarı oluşturuyor. Yanıtlar dile göre
+// değişmediği için Accept-Language anahtara girmiyor, girseydi her tarayıcı dili aynı yanıtın ayrı bir kopyasını tutardı
+pub(crate) fn cache_key(request: &Request<Body>) -> String {
+    let uri = request.uri();
+
+    // Boş parametreleri (ör: ?q=elma&&limit=5) atıyoruz, sıralama da aynı parametre sırasından bağımsız aynı anahtarı üretecek
+    let mut query_pairs: Vec<&str> = uri
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .collect();
+    query_pairs.sort_unstable();
+
+    let header_value = |name| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_lowercase())
+            .unwrap_or_default()
+    };
+
+    format!(
+        "{}?{}|{}",
+        uri.path().trim_end_matches('/'),
+        query_pairs.join("&"),
+        representation(&header_value(ACCEPT))
+    )
+}
+
+// Accept'in ham hali tarayıcıdan tarayıcıya değişiyor ve önbelleği gereksiz yere bölüyordu. Yanıtlar JSON olduğu için anahtara
+// sadece isteğin JSON kabul edip etmediği giriyor
+fn representation(accept: &str) -> &'static str {
+    let accepts_json = accept.is_empty()
+        || accept.split(',').any(|media_range| {
+            let media_type = media_range.split(';').next().unwrap_or_default().trim();
+            matches!(media_type, "*/*" | "application/*" | "application/json")
+                || media_type.ends_with("+json")
+        });
+    if accepts_json { "json" } else { "other" }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header::ACCEPT_LANGUAGE;
+
+    use super::*;
+
+    fn request(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    fn response_cache(capacity: u64) -> ResponseCache {
+        ResponseCache::new(
+            &ResponseCacheConfig::default(),
+            capacity,
+            Arc::new(Metrics::default()),
+        )
+    }
+
+    #[test]
+    fn test_weigh_entry_uses_byte_size() {
+        let tags = CachedResponse::new(Bytes::from_static(b"[]"));
+        let list = CachedResponse::new(Bytes::from("x".repeat(4096)));
+        assert_eq!(weigh_entry("/tags", &tags), 7);
+        assert!(weigh_entry("/foods/list", &list) > weigh_entry("/tags", &tags));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_round_trip() -> Result<(), Error> {
+        let path = std::env::temp_dir().join(format!(
+            "besinveri-test-cache-snapshot-{}.json",
+            std::process::id()
+        ));
+        let path = path.to_str().unwrap();
+        let tags_key = cache_key(&request("/tags"));
+        let food_key = cache_key(&request("/food/muz"));
+        let cache = response_cache(1024 * 1024);
+        cache
+            .shard(EndpointClass::List)
+            .insert(
+                tags_key.clone(),
+                CachedResponse::new(Bytes::from_static(b"[]")),
+            )
+            .await;
+        cache
+            .shard(EndpointClass::Detail)
+            .insert(
+                food_key.clone(),
+                CachedResponse {
+                    headers: vec![(ETAG, etag(b"{}"))],
+                    ..CachedResponse::new(Bytes::from_static(b"{}"))
+                },
+            )
+            .await;
+        cache.run_pending_tasks().await;
+
+        assert_eq!(save_snapshot(&cache, path)?, 2);
+
+        let restored = response_cache(1024 * 1024);
+        assert_eq!(load_snapshot(&restored, path).await?, 2);
+        assert_eq!(
+            restored
+                .shard(EndpointClass::List)
+                .get(&tags_key)
+                .await
+                .map(|cached| cached.body),
+            Some(Bytes::from_static(b"[]"))
+        );
+        assert_eq!(
+            restored
+                .shard(EndpointClass::Detail)
+                .get(&food_key)
+                .await
+                .map(|cached| cached.headers),
+            Some(vec![(ETAG, etag(b"{}"))])
+        );
+
+        fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_endpoint_class() {
+        assert_eq!(EndpointClass::of("/food/muz"), EndpointClass::Detail);
+        assert_eq!(
+            EndpointClass::of(&cache_key(&request("/recipe/menemen"))),
+            EndpointClass::Detail
+        );
+        assert_eq!(
+            EndpointClass::of(&cache_key(&request("/foods/search?q=elma"))),
+            EndpointClass::Search
+        );
+        assert_eq!(
+            EndpointClass::of(&cache_key(&request("/foods/list"))),
+            EndpointClass::List
+        );
+        assert_eq!(EndpointClass::of("/tags"), EndpointClass::List);
+        // /foods ile başlayan ama arama olmayan listeler
+        assert_eq!(EndpointClass::of("/foods/in-season"), EndpointClass::List);
+    }
+
+    #[tokio::test]
+    async fn test_search_burst_does_not_evict_lists() {
+        let cache = response_cache(64 * 1024);
+        let list = CachedResponse::new(Bytes::from("x".repeat(4096)));
+        let list_keys = [
+            cache_key(&request("/foods/list")),
+            cache_key(&request("/tags")),
+        ];
+        for key in &list_keys {
+            cache.shard_for(key).insert(key.clone(), list.clone()).await;
+        }
+
+        // Arama payının birkaç katı kadar tekil arama yanıtı
+        for i in 0..200 {
+            let key = cache_key(&request(&format!("/foods/search?q=sorgu{}", i)));
+            cache
+                .shard_for(&key)
+                .insert(key, CachedResponse::new(Bytes::from("y".repeat(1024))))
+                .await;
+        }
+        cache.run_pending_tasks().await;
+
+        let list_shard = cache.shard(EndpointClass::List);
+        for key in &list_keys {
+            assert!(list_shard.contains_key(key));
+        }
+        let stats = cache.shard_stats();
+        let search = stats
+            .iter()
+            .find(|stats| stats.class == EndpointClass::Search)
+            .unwrap();
+        assert!(search.evictions > 0);
+        assert!(search.size_bytes <= search.capacity_bytes.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_load_snapshot_missing_file() -> Result<(), Error> {
+        let cache = response_cache(1024 * 1024);
+        assert_eq!(
+            load_snapshot(
+                &cache,
+                std::env::temp_dir()
+                    .join("besinveri-test-nonexistent-snapshot.json")
+                    .to_str()
+                    .unwrap()
+            )
+            .await?,
+            0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_not_modified() {
+        let etag = etag(b"[]");
+        let header = |value: &str| HeaderValue::from_str(value).unwrap();
+        assert!(is_not_modified(&etag, &etag));
+        assert!(is_not_modified(
+            &header(&format!("\"eski\", W/{}", etag.to_str().unwrap())),
+            &etag
+        ));
+        assert!(is_not_modified(&header("*"), &etag));
+        assert!(!is_not_modified(&header("\"eski\""), &etag));
+        assert_ne!(etag, super::etag(b"{}"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_misses_run_handler_once() -> Result<(), Error> {
+        use std::sync::{Arc, atomic::AtomicUsize};
+
+        use axum::{Router, middleware, routing::get};
+        use tower::ServiceExt;
+
+        let pool = crate::api::database::memory_pool().await?;
+        let state = SharedState::with_pool(crate::core::config::get_default_config(), pool);
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = Router::new()
+            .route(
+                "/foods/list",
+                get({
+                    let calls = calls.clone();
+                    move || async move {
+                        calls.fetch_add(1, Ordering::Relaxed);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        "[]"
+                    }
+                }),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                cache_middleware,
+            ));
+
+        let responses = futures_util::future::try_join_all(
+            (0..20).map(|_| router.clone().oneshot(request("/foods/list"))),
+        )
+        .await?;
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(
+            responses
+                .iter()
+                .all(|response| response.status() == StatusCode::OK)
+        );
+        let misses = responses
+            .iter()
+            .filter(|response| response.headers()[X_CACHE] == "MISS")
+            .count();
+        assert_eq!(misses, 1);
+        assert_eq!(state.metrics.cache.coalesced.load(Ordering::Relaxed), 19);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_only_stores_food_not_found() -> Result<(), Error> {
+        use axum::{Router, extract::Path, middleware, routing::get};
+        use tower::ServiceExt;
+
+        let pool = crate::api::database::memory_pool().await?;
+        let state = SharedState::with_pool(crate::core::config::get_default_config(), pool);
+        let router = Router::new()
+            .route(
+                "/food/{slug}",
+                get(|Path(slug): Path<String>| async move {
+                    let code = if slug == "yok" {
+                        ErrorCode::FoodNotFound
+                    } else {
+                        ErrorCode::EndpointNotFound
+                    };
+                    Err::<(), _>(APIError::new(StatusCode::NOT_FOUND, code, "Bulunamadı"))
+                }),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                cache_middleware,
+            ));
+
+        let response = router.clone().oneshot(request("/food/yok")).await?;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = router.clone().oneshot(request("/food/yok")).await?;
+        assert_eq!(response.headers()[X_CACHE], "HIT");
+
+        // Başka kodlu 404'ler olduğu gibi dönüyor ve önbelleğe girmiyor
+        for _ in 0..2 {
+            let response = router.clone().oneshot(request("/food/baska")).await?;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_eq!(response.headers()[X_CACHE], "BYPASS");
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+            assert_eq!(
+                serde_json::from_slice::<APIError>(&body)?.error,
+                ErrorCode::EndpointNotFound
+            );
+        }
+        assert_eq!(
+            state.metrics.negative_cache.inserts.load(Ordering::Relaxed),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_key_sorts_query_params() {
+        assert_eq!(
+            cache_key(&request("/foods/search?q=elma&limit=5")),
+            cache_key(&request("/foods/search?limit=5&q=elma"))
+        );
+        assert_eq!(
+            cache_key(&request("/foods/search?q=elma&&limit=5")),
+            cache_key(&request("/foods/search?limit=5&q=elma"))
+        );
+    }
+
+    #[test]
+    fn test_cache_key_distinguishes_values() {
+        assert_ne!(
+            cache_key(&request("/foods/search?q=elma")),
+            cache_key(&request("/foods/search?q=muz"))
+        );
+    }
+
+    #[test]
+    fn test_cache_key_ignores_accept_language() {
+        let with_language = |language: &str| {
+            cache_key(
+                &Request::builder()
+                    .uri("/food/muz")
+                    .header(ACCEPT_LANGUAGE, language)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+        let key = cache_key(&request("/food/muz"));
+        assert_eq!(with_language("tr-TR"), key);
+        assert_eq!(with_language("en-US,en;q=0.9"), key);
+    }
+
+    #[test]
+    fn test_cache_key_normalizes_accept() {
+        let with_accept = |accept: &str| {
+            cache_key(
+                &Request::builder()
+                    .uri("/food/muz")
+                    .header(ACCEPT, accept)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        };
+        let json = cache_key(&request("/food/muz"));
+        assert_eq!(with_accept("application/json"), json);
+        assert_eq!(with_accept("*/*"), json);
+        assert_eq!(
+            with_accept("text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"),
+            json
+        );
+        assert_eq!(with_accept("Application/JSON; charset=utf-8"), json);
+        assert_ne!(with_accept("text/html"), json);
+        assert_eq!(with_accept("text/html"), with_accept("text/csv"));
+    }
 }
@@ -0,0 +1,80 @@
+use axum::{Json, extract::Query, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::error::{APIError, ErrorCode},
+    core::{
+        calc::{self, ActivityLevel, Formula, Person, Sex},
+        nutrition::round,
+    },
+};
+
+#[derive(Deserialize)]
+pub(crate) struct EnergyParams {
+    age: Option<f64>,
+    sex: Option<String>,
+    weight: Option<f64>,
+    height: Option<f64>,
+    // Verilmezse sedentary
+    activity: Option<String>,
+    // Verilmezse mifflin_st_jeor
+    formula: Option<String>,
+    body_fat: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct EnergyRequirement {
+    formula: Formula,
+    activity: ActivityLevel,
+    activity_factor: f64,
+    // Bazal metabolizma hızı ve günlük toplam enerji harcaması, kcal/gün
+    bmr: f64,
+    tdee: f64,
+}
+
+// Diyet uygulamaları formülleri kendileri yazmasın diye BMR ve günlük enerji gereksinimi, sadece hesap yapıyor veritabanına gitmiyor
+pub(crate) async fn energy(
+    Query(params): Query<EnergyParams>,
+) -> Result<Json<EnergyRequirement>, APIError> {
+    let invalid = |message: &str| {
+        APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            message,
+        )
+    };
+    let (Some(age), Some(sex), Some(weight), Some(height)) =
+        (params.age, params.sex, params.weight, params.height)
+    else {
+        return Err(invalid("age, sex, weight ve height parametreleri gerekli"));
+    };
+    let sex = Sex::parse(&sex).ok_or_else(|| invalid("sex m veya f olmalı"))?;
+    let activity = match params.activity {
+        Some(activity) => ActivityLevel::parse(&activity).ok_or_else(|| {
+            invalid("activity sedentary, light, moderate, active veya very_active olmalı")
+        })?,
+        None => ActivityLevel::Sedentary,
+    };
+    let formula = match params.formula {
+        Some(formula) => Formula::parse(&formula).ok_or_else(|| {
+            invalid("formula mifflin_st_jeor, harris_benedict veya katch_mcardle olmalı")
+        })?,
+        None => Formula::MifflinStJeor,
+    };
+
+    let person = Person {
+        age,
+        sex,
+        weight,
+        height,
+        body_fat: params.body_fat,
+    };
+    let bmr = calc::bmr(formula, &person).map_err(|e| invalid(&e))?;
+    Ok(Json(EnergyRequirement {
+        formula,
+        activity,
+        activity_factor: activity.factor(),
+        bmr: round(bmr),
+        tdee: round(calc::tdee(bmr, activity)),
+    }))
+}
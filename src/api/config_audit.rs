@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use anyhow::Error;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use tracing::info;
+
+use crate::{api::database, core::config::Config};
+
+// Değerleri audit kaydında ve loglarda gösterilmeyen alanlar. Değiştiklerini anlayabilmek için özetlerini saklıyoruz
+const SECRET_FIELDS: [&str; 5] = ["token", "key", "secret", "dsn", "webhook_url"];
+const MASKED_VALUE: &str = "********";
+
+// Değeri değişen, eklenen veya kaldırılan tek bir config alanı. Alan yoksa değer None
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct ConfigChange {
+    pub(crate) key: String,
+    pub(crate) old_value: Option<String>,
+    pub(crate) new_value: Option<String>,
+}
+
+// GET /admin/config/changes'te dönen kayıt
+#[derive(Serialize)]
+pub(crate) struct ConfigChangeRecord {
+    // RFC 3339, UTC
+    pub(crate) changed_at: String,
+    pub(crate) source: String,
+    pub(crate) key: String,
+    pub(crate) old_value: Option<String>,
+    pub(crate) new_value: Option<String>,
+}
+
+// Config'i karşılaştırılabilir "alan yolu -> değer" çiftlerine açıyoruz, ör: rate_limit.default.max_requests -> 5.
+// İsmi olan tablo dizileri (api_keys gibi) sırayla değil isimle adresleniyor ki sıralamayı değiştirmek değişiklik sayılmasın
+pub(crate) fn flatten(config: &Config) -> Result<BTreeMap<String, String>, Error> {
+    let mut values = BTreeMap::new();
+    flatten_value("", &toml::Value::try_from(config)?, &mut values);
+    Ok(values)
+}
+
+fn flatten_value(path: &str, value: &toml::Value, values: &mut BTreeMap<String, String>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                flatten_value(&path, value, values);
+            }
+        }
+        toml::Value::Array(array)
+            if array.iter().all(toml::Value::is_table) && !array.is_empty() =>
+        {
+            for (i, value) in array.iter().enumerate() {
+                let index = value
+                    .get("name")
+                    .and_then(toml::Value::as_str)
+                    .map(str::to_owned)
+                    .unwrap_or_else(|| i.to_string());
+                flatten_value(&format!("{}[{}]", path, index), value, values);
+            }
+        }
+        toml::Value::String(string) if is_secret(path) => {
+            values.insert(path.to_owned(), fingerprint(string));
+        }
+        toml::Value::String(string) => {
+            values.insert(path.to_owned(), string.clone());
+        }
+        value => {
+            values.insert(path.to_owned(), value.to_string());
+        }
+    }
+}
+
+fn is_secret(path: &str) -> bool {
+    let field = path.rsplit('.').next().unwrap_or(path);
+    SECRET_FIELDS.contains(&field)
+}
+
+fn fingerprint(secret: &str) -> String {
+    format!("sha256:{}", hex::encode(Sha256::digest(secret.as_bytes())))
+}
+
+pub(crate) fn diff(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<ConfigChange> {
+    let mask = |key: &str, value: Option<&String>| {
+        value.map(|value| {
+            if is_secret(key) {
+                MASKED_VALUE.to_owned()
+            } else {
+                value.clone()
+            }
+        })
+    };
+
+    let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| old.get(*key) != new.get(*key))
+        .map(|key| ConfigChange {
+            key: key.clone(),
+            old_value: mask(key, old.get(key)),
+            new_value: mask(key, new.get(key)),
+        })
+        .collect()
+}
+
+// Etkin config'i (flatten ile açılmış haliyle) veritabanındaki son kayıtla karşılaştırıp farkları kaynağıyla (startup, admin, ...)
+// birlikte kaydediyor ve logluyor. İlk çalıştırmada karşılaştıracak bir kayıt olmadığı için sadece config kaydediliyor.
+// Config'i değiştiren her yer (ileride config'i yeniden yükleyen kod da) bunu çağırmalı
+pub(crate) async fn record(
+    pool: &SqlitePool,
+    new: BTreeMap<String, String>,
+    source: &str,
+) -> Result<usize, Error> {
+    let Some(old) = database::select_config_snapshot(pool).await? else {
+        database::insert_config_changes(pool, source, &[], &new).await?;
+        return Ok(0);
+    };
+
+    let changes = diff(&old, &new);
+    if changes.is_empty() {
+        return Ok(0);
+    }
+    for change in &changes {
+        info!(
+            "Config değişti ({}): {} = {} -> {}",
+            source,
+            change.key,
+            change.old_value.as_deref().unwrap_or("(yok)"),
+            change.new_value.as_deref().unwrap_or("(yok)"),
+        );
+    }
+    database::insert_config_changes(pool, source, &changes, &new).await?;
+    Ok(changes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_masks_secrets_and_addresses_keys_by_name() {
+        let base = r#"
+            [core]
+            cache_capacity_bytes = 33554432
+            tracing_level = "INFO"
+
+            [api]
+            base_url = "https://api.besinveri.com"
+            static_url = "https://besinveri.com/static"
+            search_max_limit = 10
+            health_internet_check_urls = []
+
+            [admin]
+            token = "eski"
+
+            [[api_keys]]
+            name = "partner"
+            key = "k1"
+            tier = "partner"
+
+            [[api_keys]]
+            name = "mobil"
+            key = "k2"
+            tier = "default"
+        "#;
+        let old = flatten(&toml::from_str(base).unwrap()).unwrap();
+        assert_eq!(old["api_keys[partner].tier"], "partner");
+        assert!(old["admin.token"].starts_with("sha256:"));
+
+        // Son tablo mobil anahtarı, sona eklenen alan ona ait
+        let changed = base
+            .replace("search_max_limit = 10", "search_max_limit = 20")
+            .replace("token = \"eski\"", "token = \"yeni\"")
+            + "daily_quota = 1000\n";
+        let new = flatten(&toml::from_str(&changed).unwrap()).unwrap();
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                ConfigChange {
+                    key: "admin.token".to_owned(),
+                    old_value: Some(MASKED_VALUE.to_owned()),
+                    new_value: Some(MASKED_VALUE.to_owned()),
+                },
+                ConfigChange {
+                    key: "api.search_max_limit".to_owned(),
+                    old_value: Some("10".to_owned()),
+                    new_value: Some("20".to_owned()),
+                },
+                ConfigChange {
+                    key: "api_keys[mobil].daily_quota".to_owned(),
+                    old_value: None,
+                    new_value: Some("1000".to_owned()),
+                },
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+}
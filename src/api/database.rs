@@ -1,51 +1,270 @@
-use std::fs;
+use std::{
+    collections::BTreeMap,
+    fs,
+    num::NonZero,
+    path::PathBuf,
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
 
-use crate::core::{food::Food, str::to_lower_en_kebab_case};
+use crate::{
+    api::{
+        config_audit::{ConfigChange, ConfigChangeRecord},
+        metrics::DATABASE_QUERIES,
+        open_food_facts::Enrichment,
+        search_index::IndexEntry,
+    },
+    core::{
+        additives::{Additive, normalize_e_number},
+        browse::{BrowseFilter, BrowseSort},
+        config::DatabaseConfig,
+        daily_values::ReferenceIntake,
+        diet_flags::{self, DietFlagRule},
+        food::{Food, FoodImage, FoodLink, FoodSummary, Verification, is_valid_gtin},
+        nova, nutri_score,
+        portions::{PortionCategory, StandardPortion},
+        str::{to_lower_en_kebab_case, to_search_key},
+    },
+};
 use anyhow::{Context, Error, anyhow};
-use sqlx::{Pool, Row, Sqlite, SqlitePool};
-use tracing::{info, warn};
+use futures_util::{Stream, StreamExt, TryStreamExt, stream};
+use serde::de::DeserializeOwned;
+use sqlx::{
+    Pool, Row, Sqlite, SqlitePool,
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+};
+use tracing::{debug, info, instrument, warn};
 
-fn load_foods_from_jsons(dir: &str) -> Result<Vec<Food>, Error> {
-    let mut all_foods: Vec<Food> = Vec::new();
+pub(crate) const DATABASE_DIR: &str = "db";
+const DATABASE_PATH: &str = "db/foods.sqlite";
 
-    let paths = fs::read_dir(dir)?;
-    for path in paths {
-        let Ok(path) = path else {
-            warn!("{} dizinindeki bir dosya okunamadı.", dir);
-            continue;
-        };
+// Migration'lar derleme zamanında binary'ye gömülüyor, /health/ready de uygulananları bununla karşılaştırıyor
+pub(crate) static MIGRATOR: Migrator = sqlx::migrate!("./migrations/foods");
 
-        let file_name = path.file_name().to_str().unwrap_or("???").to_owned();
+// Dizindeki JSON dosyalarının her birindeki kayıtlar, dosya adı sırasıyla. Dosyalar blocking thread'lerde ayrıştırılıyor,
+// aynı anda en fazla çekirdek sayısı kadar dosya okunuyor ki büyük veri setlerinde bellek kullanımı sınırlı kalsın.
+// Okunamayan dosyalar atlanıyor
+fn parse_jsons<T: DeserializeOwned + Send + 'static>(
+    dir: &str,
+) -> Result<impl Stream<Item = Vec<T>>, Error> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|path| match path {
+            Ok(path) => Some(path.path()),
+            Err(_) => {
+                warn!("{} dizinindeki bir dosya okunamadı.", dir);
+                None
+            }
+        })
+        .collect();
+    paths.sort();
 
-        let Ok(file) = fs::File::open(path.path()) else {
-            warn!("{} dizinindeki {} dosyası açılamadı!", dir, file_name);
-            continue;
-        };
+    let concurrency = thread::available_parallelism().map_or(4, NonZero::get);
+    let dir = dir.to_owned();
+    Ok(stream::iter(paths)
+        .map(move |path| {
+            let dir = dir.clone();
+            tokio::task::spawn_blocking(move || {
+                let file_name = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("???");
+                let Ok(bytes) = fs::read(&path) else {
+                    warn!("{} dizinindeki {} dosyası açılamadı!", dir, file_name);
+                    return Vec::new();
+                };
+                serde_json::from_slice::<Vec<T>>(&bytes).unwrap_or_else(|_| {
+                    warn!(
+                        "{}/{} dosyası beklenen JSON formatında okunamadı!",
+                        dir, file_name
+                    );
+                    Vec::new()
+                })
+            })
+        })
+        .buffered(concurrency)
+        .map(|items| items.unwrap_or_default()))
+}
 
-        if let Ok(mut foods) = serde_json::from_reader::<_, Vec<Food>>(file) {
-            all_foods.append(&mut foods);
-        } else {
-            warn!(
-                "{}/{} dosyası JSON yemek formatında okunamadı!",
-                dir, file_name
-            );
+// Dizindeki tüm JSON dosyalarındaki kayıtlar, katkı maddeleri ve referans alım profilleri gibi küçük listeler için
+async fn load_from_jsons<T: DeserializeOwned + Send + 'static>(dir: &str) -> Result<Vec<T>, Error> {
+    Ok(parse_jsons(dir)?.concat().await)
+}
+
+// Loglanan parametre değerlerinin azami uzunluğu, LIKE sorgularındaki arama metinleri kullanıcıdan geliyor
+const MAX_LOGGED_PARAM_CHARS: usize = 64;
+
+// SQLITE_BUSY (5) ve SQLITE_LOCKED (6), genişletilmiş kodlarıyla birlikte: başka bir bağlantı yazarken oluşan ve biraz
+// beklenince geçen hatalar
+pub(crate) fn is_transient(e: &Error) -> bool {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Database(e)) => e
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| matches!(code & 0xff, 5 | 6)),
+        _ => false,
+    }
+}
+
+// Kilit hatalarında sorgu artan beklemelerle baştan çalıştırılıyor, transaction'lar da baştan başlıyor. Süre tüm
+// denemeleri kapsıyor, istemcinin beklediği süre bu
+async fn timed<T, F: Future<Output = Result<T, Error>>>(
+    query: &'static str,
+    params: &[(&str, &str)],
+    mut run: impl FnMut() -> F,
+) -> Result<T, Error> {
+    let start = Instant::now();
+    let mut attempt = 0;
+    let result = loop {
+        let result = run().await;
+        let delay = match &result {
+            Err(e) if is_transient(e) => DATABASE_QUERIES.retry_delay(attempt),
+            _ => None,
+        };
+        let Some(delay) = delay else {
+            break result;
         };
+        attempt += 1;
+        DATABASE_QUERIES.record_retry();
+        debug!(query, attempt, "Veritabanı kilitli, sorgu tekrar denenecek");
+        tokio::time::sleep(delay).await;
+    };
+    record_query(query, params, start.elapsed(), &result);
+    result
+}
+
+// Tekrar denenemeyen sorgular için, sadece süresi ölçülüyor
+async fn timed_once<T>(
+    query: &'static str,
+    params: &[(&str, &str)],
+    future: impl Future<Output = Result<T, Error>>,
+) -> Result<T, Error> {
+    let start = Instant::now();
+    let result = future.await;
+    record_query(query, params, start.elapsed(), &result);
+    result
+}
+
+// Sorgunun süresini metriklere yazıyor, config'deki eşiği aşarsa parametreleriyle birlikte logluyor.
+// Üretimde kötü giden LIKE taramalarını hangi aramanın tetiklediğini görmek için
+fn record_query<T>(
+    query: &'static str,
+    params: &[(&str, &str)],
+    elapsed: Duration,
+    result: &Result<T, Error>,
+) {
+    // Denemeler kilit hatasıyla bittiyse /health/ready bunu gösteriyor, herhangi bir sorgu başarılı olunca temizleniyor
+    match result {
+        Ok(_) => DATABASE_QUERIES.set_locked(false),
+        Err(e) if is_transient(e) => {
+            warn!(query, "Veritabanı kilidi tekrar denemelere rağmen açılmadı");
+            DATABASE_QUERIES.set_locked(true);
+        }
+        Err(_) => {}
+    }
+    // Bulunamayan slug gibi boş sonuçlar hata sayılmıyor, sadece gerçek veritabanı hataları
+    let failed = result.as_ref().is_err_and(|e| {
+        !matches!(
+            e.downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::RowNotFound)
+        )
+    });
+    DATABASE_QUERIES.record(query, elapsed, failed);
+    // Havuzda boş bağlantı bulunamadan geçen acquire_timeout, database.max_connections'ın yetmediğini gösteriyor
+    if result.as_ref().is_err_and(|e| {
+        matches!(
+            e.downcast_ref::<sqlx::Error>(),
+            Some(sqlx::Error::PoolTimedOut)
+        )
+    }) {
+        DATABASE_QUERIES.record_pool_timeout();
+    }
+
+    if DATABASE_QUERIES
+        .slow_threshold()
+        .is_some_and(|threshold| elapsed > threshold)
+    {
+        let params: Vec<String> = params
+            .iter()
+            .map(|(name, value)| format!("{}={:?}", name, truncate_param(value)))
+            .collect();
+        warn!(
+            query,
+            duration_ms = elapsed.as_millis() as u64,
+            params = %params.join(" "),
+            "Yavaş veritabanı sorgusu"
+        );
+    }
+}
+
+// Kontrol karakterleri {:?} ile escape ediliyor, burada sadece uzunluğu sınırlıyoruz
+fn truncate_param(value: &str) -> String {
+    match value.char_indices().nth(MAX_LOGGED_PARAM_CHARS) {
+        Some((index, _)) => format!("{}…", &value[..index]),
+        None => value.to_owned(),
     }
+}
+
+// Binary'deki tüm migration'lar veritabanında başarıyla uygulanmış mı
+#[instrument(skip(pool))]
+pub(crate) async fn migrations_applied(pool: &SqlitePool) -> bool {
+    let Ok(applied) =
+        sqlx::query_scalar::<_, i64>("SELECT version FROM _sqlx_migrations WHERE success = 1")
+            .fetch_all(pool)
+            .await
+    else {
+        return false;
+    };
+
+    MIGRATOR
+        .iter()
+        .all(|migration| applied.contains(&migration.version))
+}
+
+// Migration'ları uygulanmış boş bir bellek veritabanı; testler, benchmark'lar ve yük testi bununla başlıyor
+pub(crate) async fn memory_pool() -> Result<SqlitePool, Error> {
+    let pool = SqlitePool::connect("sqlite::memory:").await?;
+    MIGRATOR.run(&pool).await?;
+    Ok(pool)
+}
 
-    Ok(all_foods)
+// Testlerin ve benchmark'ların eklediği, yalnızca adı ve onay durumu belli bir yemek. Diğer alanlar gerektiğinde
+// Food { energy: 52.0, ..test_food("Elma", true) } gibi değiştiriliyor
+pub(crate) fn test_food(description: &str, verified: bool) -> Food {
+    Food {
+        description: description.to_owned(),
+        image: FoodImage::new(&format!(
+            "/images/{}.webp",
+            to_lower_en_kebab_case(description)
+        )),
+        source: "Test".to_owned(),
+        verified: Some(verified),
+        ..Default::default()
+    }
 }
 
-pub(crate) async fn connect_database() -> Result<Pool<Sqlite>, Error> {
+pub(crate) async fn connect_database(config: &DatabaseConfig) -> Result<Pool<Sqlite>, Error> {
     // Veritabanı olarak SQLite kullanıyoruz, db/foods.sqlite dizininde olacak şekilde
-    fs::create_dir_all("db").expect("db/ dizini oluşturulamadı");
-    let database_url = "sqlite:db/foods.sqlite?mode=rwc"; // rwc mod sayesinde eğer veritabanı dosyası yoksa oluşturuyoruz
-    let pool = SqlitePool::connect(database_url)
+    fs::create_dir_all(DATABASE_DIR).expect("db/ dizini oluşturulamadı");
+    let database_url = format!("sqlite:{}?mode=rwc", DATABASE_PATH); // rwc mod sayesinde eğer veritabanı dosyası yoksa oluşturuyoruz
+    // Sorgular kalıcı (persistent) hazırlanıyor, aynı SQL metni bağlantının önbelleğindeki ifadeyi tekrar kullanıyor
+    let options = SqliteConnectOptions::from_str(&database_url)?
+        .statement_cache_capacity(config.statement_cache_capacity);
+    let pool = SqlitePoolOptions::new()
+        .max_connections(config.max_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_seconds))
+        .idle_timeout(
+            (config.idle_timeout_seconds > 0)
+                .then(|| Duration::from_secs(config.idle_timeout_seconds)),
+        )
+        .connect_with(options)
         .await
         .context("Veritabanına bağlanılamadı!")?;
     info!("Veritabanına bağlanıldı!");
 
     // Migration script'lerini çalıştırıyoruz, normalizasyon amaçlı birkaç tablo kullanıyoruz, /migrations/foods klasörünü inceleyebilirsiniz tabloları görmek için
-    sqlx::migrate!("./migrations/foods")
+    MIGRATOR
         .run(&pool)
         .await
         .context("Migration'lar uygulanamadı!")?;
@@ -53,51 +272,131 @@ pub(crate) async fn connect_database() -> Result<Pool<Sqlite>, Error> {
 
     // JSON dosyalarını bulup hepsini veritabanına eğer mevcut değillerse ekliyoruz. Bu sayede toplu şekilde veritabanına kolayca ekleme yapabiliriz
     // Ayrıca veritabanı dosyası .gitignore'da olacağı ve üzerine JSON harici eklemeler yapılacağı için; varsayılan JSON dosyalarının depoda olması yığın eklemeleri kolaylaştıracaktır
-    // *DİKKAT* JSON okuma methodumuz async değil, bu kod sadece bağlantıda yani ilk açılışta çalıştırıldığı için main thread'i bloklamak sorun olmayacaktır
-    if let Ok(foods) = load_foods_from_jsons("./db/foods") {
-        // Eğer yoklar ise bu yemekleri veritabanına eklemeliyiz
-        for food in foods {
-            let food_name = food.description.to_owned();
-
-            match insert_food(&pool, food).await {
-                Ok(updated_food) => {
-                    if let Some(food_id) = updated_food.id {
-                        info!(
-                            "{} başarıyla {} ID'si ile JSON dosyasından, veritabanına eklendi.",
-                            food_name, food_id
-                        );
-                    } else {
-                        // Bu hatanın hiçbir zaman oluşmaması gerek, yine de önlemimizi alalım
-                        warn!(
-                            "{} yemeği veritabanına eklendi ama ID'si alınamadı, kritik hata!",
-                            food_name
-                        );
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        "{} yemeğini JSON dosyasından veritabanına aktarırken bir sorun oluştu: {}",
-                        food_name, e
-                    );
+    // JSON dosyaları paralel ayrıştırılıyor ama yazmalar tek tek yapılıyor, SQLite zaten aynı anda tek bir yazmaya izin veriyor
+    // Yemekler katkı maddelerine E numarasıyla bağlandığı için önce referans listeyi ekliyoruz, olanlar değişmiyor
+    if let Ok(additives) = load_from_jsons::<Additive>("./db/additives").await {
+        let mut inserted = 0;
+        for additive in additives {
+            let e_number = additive.e_number.to_owned();
+            match insert_additive(&pool, additive).await {
+                Ok(true) => inserted += 1,
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "{} katkı maddesi JSON dosyasından veritabanına aktarılamadı: {}",
+                    e_number, e
+                ),
+            }
+        }
+        if inserted > 0 {
+            info!(
+                "{} katkı maddesi JSON dosyasından veritabanına eklendi.",
+                inserted
+            );
+        }
+    }
+
+    if let Ok(files) = parse_jsons::<Food>("./db/foods") {
+        // Sonraki dosyalar ayrıştırılırken öncekilerdeki yemekler ekleniyor. Markalı ürünlerin genel karşılıkları başka
+        // dosyalarda olabilir, onları tüm genel yemekler eklendikten sonra ekliyoruz
+        let mut branded: Vec<Food> = Vec::new();
+        let mut files = std::pin::pin!(files);
+        while let Some(foods) = files.next().await {
+            for food in foods {
+                if food.generic_food.is_some() {
+                    branded.push(food);
+                } else {
+                    insert_seed_food(&pool, food).await;
                 }
             }
         }
+        for food in branded {
+            insert_seed_food(&pool, food).await;
+        }
+    }
+
+    // Referans alım profilleri de aynı şekilde, sadece veritabanında olmayanlar ekleniyor
+    if let Ok(profiles) = load_from_jsons::<ReferenceIntake>("./db/reference_intakes").await {
+        for profile in profiles {
+            match insert_reference_intake(&pool, &profile).await {
+                Ok(true) => info!(
+                    "{} referans alım profili JSON dosyasından veritabanına eklendi.",
+                    profile.slug
+                ),
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "{} referans alım profili veritabanına aktarılamadı: {}",
+                    profile.slug, e
+                ),
+            }
+        }
+    }
+
+    // Standart ölçüler de aynı şekilde, veritabanında olan ölçüler değişmiyor
+    if let Ok(categories) = load_from_jsons::<PortionCategory>("./db/standard_portions").await {
+        for category in categories {
+            match insert_standard_portions(&pool, &category).await {
+                Ok(0) => {}
+                Ok(inserted) => info!(
+                    "{} kategorisi için {} standart ölçü JSON dosyasından veritabanına eklendi.",
+                    category.category, inserted
+                ),
+                Err(e) => warn!(
+                    "{} kategorisinin standart ölçüleri veritabanına aktarılamadı: {}",
+                    category.category, e
+                ),
+            }
+        }
     }
 
     Ok(pool)
 }
 
+// Veritabanında yoksa JSON dosyasındaki yemeği ekliyor, hatalar açılışı durdurmuyor
+async fn insert_seed_food(pool: &SqlitePool, food: Food) {
+    let food_name = food.description.to_owned();
+
+    match insert_food(pool, food).await {
+        Ok(updated_food) => {
+            if let Some(food_id) = updated_food.id {
+                info!(
+                    "{} başarıyla {} ID'si ile JSON dosyasından, veritabanına eklendi.",
+                    food_name, food_id
+                );
+            } else {
+                // Bu hatanın hiçbir zaman oluşmaması gerek, yine de önlemimizi alalım
+                warn!(
+                    "{} yemeği veritabanına eklendi ama ID'si alınamadı, kritik hata!",
+                    food_name
+                );
+            }
+        }
+        Err(e) => {
+            warn!(
+                "{} yemeğini JSON dosyasından veritabanına aktarırken bir sorun oluştu: {}",
+                food_name, e
+            );
+        }
+    }
+}
+
 async fn food_exists_by_description(pool: &SqlitePool, description: &str) -> Result<bool, Error> {
-    Ok(
-        sqlx::query_scalar::<_, i64>("SELECT id FROM foods WHERE description = ?")
-            .bind(description)
-            .fetch_optional(pool)
-            .await?
-            .is_some(),
+    timed(
+        "food_exists_by_description",
+        &[("description", description)],
+        || async move {
+            Ok(
+                sqlx::query_scalar::<_, i64>("SELECT id FROM foods WHERE description = ?")
+                    .bind(description)
+                    .fetch_optional(pool)
+                    .await?
+                    .is_some(),
+            )
+        },
     )
+    .await
 }
 
-async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
+pub(crate) async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     // Yemek halihazırda mevcutsa devam etmeye gerek yok, güncelleme için başka bir method kullanılacak
     if food_exists_by_description(pool, &food.description).await? {
         return Err(anyhow!(
@@ -119,16 +418,89 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
             .fetch_one(&mut *tx)
             .await?;
 
-    sqlx::query("INSERT OR IGNORE INTO food_images (image_url) VALUES (?)")
-        .bind(&food.image_url)
-        .execute(&mut *tx)
-        .await?;
+    // Aynı resmi kullanan yemeklerde atıf ve lisans ilk eklenen yemekten geliyor
+    sqlx::query(
+        "INSERT OR IGNORE INTO food_images (image_url, attribution, license, source_url) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&food.image.url)
+    .bind(&food.image.attribution)
+    .bind(&food.image.license)
+    .bind(&food.image.source_url)
+    .execute(&mut *tx)
+    .await?;
     let image_id =
         sqlx::query_scalar::<_, i64>("SELECT id FROM food_images WHERE image_url = ? LIMIT 1")
-            .bind(&food.image_url)
+            .bind(&food.image.url)
             .fetch_one(&mut *tx)
             .await?;
 
+    // Markalı ürünün genel karşılığı önceden eklenmiş olmalı, açıklaması da yazılabildiği için slug'a çeviriyoruz
+    let generic_food_id = match &food.generic_food {
+        Some(generic_food) => Some(
+            sqlx::query_scalar::<_, i64>("SELECT id FROM foods WHERE slug = ? LIMIT 1")
+                .bind(to_lower_en_kebab_case(&generic_food.slug))
+                .fetch_optional(&mut *tx)
+                .await?
+                .ok_or_else(|| {
+                    anyhow!(
+                        "{} yemeğinin genel karşılığı {} bulunamadı",
+                        food.description,
+                        generic_food.slug
+                    )
+                })?,
+        ),
+        None => None,
+    };
+
+    // Barkod tek bir yemeğe ait olabiliyor, INSERT OR IGNORE'un sessizce atlamaması için önceden kontrol ediyoruz
+    let gtin = food
+        .gtin
+        .as_deref()
+        .map(str::trim)
+        .filter(|gtin| !gtin.is_empty());
+    if let Some(gtin) = gtin {
+        if !is_valid_gtin(gtin) {
+            return Err(anyhow!(
+                "{} yemeğinin GTIN'i geçersiz: {}",
+                food.description,
+                gtin
+            ));
+        }
+        if let Some(other) =
+            sqlx::query_scalar::<_, String>("SELECT description FROM foods WHERE gtin = ? LIMIT 1")
+                .bind(gtin)
+                .fetch_optional(&mut *tx)
+                .await?
+        {
+            return Err(anyhow!(
+                "{} yemeğinin GTIN'i {} zaten {} yemeğinde kullanılıyor",
+                food.description,
+                gtin,
+                other
+            ));
+        }
+    }
+
+    // Nutri-Score ve NOVA için yazılan ek veriler, CHECK kısıtına takılmadan anlaşılır bir hata verelim
+    if food
+        .fruit_vegetable_percent
+        .is_some_and(|percent| !(0.0..=100.0).contains(&percent))
+    {
+        return Err(anyhow!(
+            "{} yemeğinin meyve/sebze oranı 0 ile 100 arasında olmalı",
+            food.description
+        ));
+    }
+    if food
+        .nova_group
+        .is_some_and(|group| !nova::NOVA_GROUPS.contains(&group))
+    {
+        return Err(anyhow!(
+            "{} yemeğinin NOVA sınıfı 1 ile 4 arasında olmalı",
+            food.description
+        ));
+    }
+
     // Resim ve kaynak id'leri yeni bir yemek eklemek için yeterli olacak
 
     // Etiketler ve alerjenler liste olduğu için kendi tabloları var, altta onu da ayarlayacağız. Önce yemek id'sine ihtiyacımız var
@@ -142,41 +514,70 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
             "INSERT OR IGNORE INTO foods (
             slug, description, verified, image_id, source_id, glycemic_index, energy, carbohydrate, protein, fat, saturated_fat, 
             trans_fat, sugar, fiber, water, cholesterol, sodium, potassium, iron, magnesium, calcium, zinc, vitamin_a, vitamin_b6, 
-            vitamin_b12, vitamin_c, vitamin_d, vitamin_e, vitamin_k)
+            vitamin_b12, vitamin_c, vitamin_d, vitamin_e, vitamin_k, added_sugar, polyols,
+            omega_3, omega_6, folate, niacin, phosphorus, selenium, copper, variant_group, brand, generic_food_id, gtin,
+            fruit_vegetable_percent, nova_group_declared)
 
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+            ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             
             RETURNING ID"
         )
         .bind(to_lower_en_kebab_case(&food.description))
         .bind(&food.description)
         .bind(food.verified.unwrap_or(true) as i64)
-        .bind(&image_id)
-        .bind(&source_id)
-        .bind(&food.glycemic_index)
-        .bind(&food.energy)
-        .bind(&food.carbohydrate)
-        .bind(&food.protein)
-        .bind(&food.fat)
-        .bind(&food.saturated_fat)
-        .bind(&food.trans_fat)
-        .bind(&food.sugar)
-        .bind(&food.fiber)
-        .bind(&food.water)
-        .bind(&food.cholesterol)
-        .bind(&food.sodium)
-        .bind(&food.potassium)
-        .bind(&food.iron)
-        .bind(&food.magnesium)
-        .bind(&food.calcium)
-        .bind(&food.zinc)
-        .bind(&food.vitamin_a)
-        .bind(&food.vitamin_b6)
-        .bind(&food.vitamin_b12)
-        .bind(&food.vitamin_c)
-        .bind(&food.vitamin_d)
-        .bind(&food.vitamin_e)
-        .bind(&food.vitamin_k)
+        .bind(image_id)
+        .bind(source_id)
+        .bind(food.glycemic_index)
+        .bind(food.energy)
+        .bind(food.carbohydrate)
+        .bind(food.protein)
+        .bind(food.fat)
+        .bind(food.saturated_fat)
+        .bind(food.trans_fat)
+        .bind(food.sugar)
+        .bind(food.fiber)
+        .bind(food.water)
+        .bind(food.cholesterol)
+        .bind(food.sodium)
+        .bind(food.potassium)
+        .bind(food.iron)
+        .bind(food.magnesium)
+        .bind(food.calcium)
+        .bind(food.zinc)
+        .bind(food.vitamin_a)
+        .bind(food.vitamin_b6)
+        .bind(food.vitamin_b12)
+        .bind(food.vitamin_c)
+        .bind(food.vitamin_d)
+        .bind(food.vitamin_e)
+        .bind(food.vitamin_k)
+        .bind(food.added_sugar)
+        .bind(food.polyols)
+        .bind(food.omega_3)
+        .bind(food.omega_6)
+        .bind(food.folate)
+        .bind(food.niacin)
+        .bind(food.phosphorus)
+        .bind(food.selenium)
+        .bind(food.copper)
+        .bind(
+            food.variant_group
+                .as_deref()
+                .map(str::trim)
+                .filter(|group| !group.is_empty())
+                .map(to_lower_en_kebab_case),
+        )
+        .bind(
+            food.brand
+                .as_deref()
+                .map(str::trim)
+                .filter(|brand| !brand.is_empty()),
+        )
+        .bind(generic_food_id)
+        .bind(gtin)
+        .bind(food.fruit_vegetable_percent)
+        .bind(food.nova_group.map(i64::from))
         .fetch_one(&mut *tx).await?;
 
     // Her tag var mı kontrol edeceğiz, varsa da id'lerini yemekle eşleştirmek için food_tags'e ekleyeceğiz
@@ -184,20 +585,20 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     // * ÖNEMLİ * Etiket ve alerjenler, standart bir kümelendirme olması için tamamen küçük harfler ile kaydedilecektir
     for tag in &food.tags {
         sqlx::query("INSERT OR IGNORE INTO tags (description) VALUES (LOWER(?))")
-            .bind(&tag)
+            .bind(tag)
             .execute(&mut *tx)
             .await?;
         let tag_id = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM tags WHERE description = LOWER(?) LIMIT 1",
         )
-        .bind(&tag)
+        .bind(tag)
         .fetch_one(&mut *tx)
         .await?;
 
         // Şimdi de food_id <-> tag_id olarak birbirine eşleyeceğiz
         sqlx::query("INSERT OR IGNORE INTO food_tags (food_id, tag_id) VALUES (?, ?)")
-            .bind(&food_id)
-            .bind(&tag_id)
+            .bind(food_id)
+            .bind(tag_id)
             .execute(&mut *tx)
             .await?;
     }
@@ -205,45 +606,122 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     // Aynı şekilde alerjenleri de ekliyoruz, tamamen küçük harf olacak alerjenlerin açıklaması da
     for allergen in &food.allergens {
         sqlx::query("INSERT OR IGNORE INTO allergens (description) VALUES (LOWER(?))")
-            .bind(&allergen)
+            .bind(&allergen.name)
             .execute(&mut *tx)
             .await?;
         let allergen_id = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM allergens WHERE description = LOWER(?) LIMIT 1",
         )
-        .bind(&allergen)
+        .bind(&allergen.name)
         .fetch_one(&mut *tx)
         .await?;
 
-        sqlx::query("INSERT OR IGNORE INTO food_allergens (food_id, allergen_id) VALUES (?, ?)")
-            .bind(&food_id)
-            .bind(&allergen_id)
-            .execute(&mut *tx)
-            .await?;
+        sqlx::query(
+            "INSERT OR IGNORE INTO food_allergens (food_id, allergen_id, level, note) VALUES (?, ?, ?, ?)",
+        )
+        .bind(food_id)
+        .bind(allergen_id)
+        .bind(allergen.level.name())
+        .bind(&allergen.note)
+        .execute(&mut *tx)
+        .await?;
     }
 
     // Son olarak porsiyonlarını da kaydetmemiz gerek, her yemeğin farklı porsiyonları ve gramajları mevcut
     // Burada da aynı şekilde açıklama kısmı için normalizasyon yapıyoruz çünkü 'Porsiyon (Orta)' gibi açıklamaları birkaç defa kaydetmek istemiyoruz
     for serving in &food.servings {
         sqlx::query("INSERT OR IGNORE INTO serving_descriptions (description) VALUES (?)")
-            .bind(&serving.0)
+            .bind(serving.0)
             .execute(&mut *tx)
             .await?;
         let serving_description_id = sqlx::query_scalar::<_, i64>(
             "SELECT id FROM serving_descriptions WHERE description = ? LIMIT 1",
         )
-        .bind(&serving.0)
+        .bind(serving.0)
         .fetch_one(&mut *tx)
         .await?;
 
         sqlx::query("INSERT OR IGNORE INTO food_servings (food_id, serving_description_id, weight) VALUES (?, ?, ?)")
-        .bind(&food_id)
-        .bind(&serving_description_id)
+        .bind(food_id)
+        .bind(serving_description_id)
         .bind(serving.1)
         .execute(&mut *tx)
         .await?;
     }
 
+    if let Some(verification) = &food.verification {
+        let verification = verification
+            .clone()
+            .normalized()
+            .map_err(|e| anyhow!("{} yemeğinin doğrulaması geçersiz: {}", food.description, e))?;
+        insert_verification(&mut tx, food_id, &verification).await?;
+    }
+
+    // Katkı maddeleri referans listede olmalı, yanlış yazılmış bir E numarası yemeği eklemiyor
+    for additive in &food.additives {
+        let additive_id = match normalize_e_number(&additive.e_number) {
+            Some(e_number) => {
+                sqlx::query_scalar::<_, i64>("SELECT id FROM additives WHERE e_number = ? LIMIT 1")
+                    .bind(e_number)
+                    .fetch_optional(&mut *tx)
+                    .await?
+            }
+            None => None,
+        }
+        .ok_or_else(|| {
+            anyhow!(
+                "{} yemeğinin katkı maddesi {} referans listede bulunamadı",
+                food.description,
+                additive.e_number
+            )
+        })?;
+        sqlx::query("INSERT OR IGNORE INTO food_additives (food_id, additive_id) VALUES (?, ?)")
+            .bind(food_id)
+            .bind(additive_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // Açıklamayla aynı olan adlar aramaya bir şey katmıyor, tekrarları da benzersiz indeks eliyor
+    for alias in &food.aliases {
+        let alias = alias.trim();
+        if alias.is_empty() || to_search_key(alias) == to_search_key(&food.description) {
+            continue;
+        }
+        sqlx::query("INSERT OR IGNORE INTO food_aliases (food_id, alias) VALUES (?, ?)")
+            .bind(food_id)
+            .bind(alias)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    // Mevsimler her ay için ayrı bir satır, /foods/in-season aya göre arayabilsin diye
+    for seasonality in &food.seasonality {
+        if seasonality.region.trim().is_empty() {
+            return Err(anyhow!("{} yemeğinin mevsim bölgesi boş", food.description));
+        }
+        for month in &seasonality.months {
+            if !(1..=12).contains(month) {
+                return Err(anyhow!(
+                    "{} yemeğinin mevsim ayı 1 ile 12 arasında olmalı: {}",
+                    food.description,
+                    month
+                ));
+            }
+            sqlx::query(
+                "INSERT OR IGNORE INTO food_seasonality (food_id, region, month) VALUES (?, ?, ?)",
+            )
+            .bind(food_id)
+            .bind(seasonality.region.trim())
+            .bind(*month as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+
+    // Katkı maddeleri ve etiketler de eklendikten sonra
+    update_food_scores(&mut tx, food_id).await?;
+
     // Transaction'ı tamamlayalım
     tx.commit().await?;
 
@@ -254,33 +732,475 @@ async fn insert_food(pool: &SqlitePool, food: Food) -> Result<Food, Error> {
     })
 }
 
+#[instrument(skip(pool))]
 pub(crate) async fn select_all_foods_slugs(pool: &SqlitePool) -> Result<Vec<String>, Error> {
-    let mut slugs: Vec<String> = Vec::new();
-    for row in sqlx::query("SELECT slug FROM foods WHERE verified=1")
+    timed("select_all_foods_slugs", &[], || async move {
+        let mut slugs: Vec<String> = Vec::new();
+        for row in sqlx::query("SELECT slug FROM foods WHERE verified=1")
+            .fetch_all(pool)
+            .await?
+        {
+            slugs.push(row.try_get("slug")?);
+        }
+        Ok(slugs)
+    })
+    .await
+}
+
+// Listeler için onaylanmış yemeklerin özetleri, slug sırasıyla. Yemeğin tamamını okuyan sorgu her satır için alt sorgular
+// çalıştırdığından URL ve resim gösterecek listeler bunu kullanmalı
+#[instrument(skip(pool))]
+pub(crate) async fn select_food_summaries(pool: &SqlitePool) -> Result<Vec<FoodSummary>, Error> {
+    timed("select_food_summaries", &[], || async move {
+        Ok(sqlx::query_as(
+            "SELECT F.slug, F.description, COALESCE(FI.image_url, ''), FI.attribution, FI.license, FI.source_url
+             FROM foods F
+             LEFT JOIN food_images FI ON FI.id = F.image_id
+             WHERE F.verified = 1
+             ORDER BY F.slug",
+        )
         .fetch_all(pool)
         .await?
-    {
-        slugs.push(row.try_get("slug")?);
-    }
-    Ok(slugs)
+        .into_iter()
+        .map(
+            |(slug, description, url, attribution, license, source_url)| FoodSummary {
+                slug,
+                description,
+                image: FoodImage {
+                    url,
+                    attribution,
+                    license,
+                    source_url,
+                },
+            },
+        )
+        .collect())
+    })
+    .await
+}
+
+// (slug, açıklama), öneri endpoint'inin bellekte tuttuğu liste için
+#[instrument(skip(pool))]
+pub(crate) async fn select_verified_food_names(
+    pool: &SqlitePool,
+) -> Result<Vec<(String, String)>, Error> {
+    timed("select_verified_food_names", &[], || async move {
+        Ok(
+            sqlx::query_as("SELECT slug, description FROM foods WHERE verified = 1 ORDER BY id")
+                .fetch_all(pool)
+                .await?,
+        )
+    })
+    .await
+}
+
+// Arama indeksi için onaylanmış yemeklerin normalize edilmiş adları, etiketleri ve filtrelerde kullanılan alanları
+#[instrument(skip(pool))]
+pub(crate) async fn select_search_index_entries(
+    pool: &SqlitePool,
+) -> Result<Vec<IndexEntry>, Error> {
+    timed("select_search_index_entries", &[], || async move {
+        let rows = sqlx::query(
+            "SELECT
+                F.id, F.slug, F.search_description, F.completeness, F.brand IS NOT NULL AS branded,
+                COALESCE(FS.search_description, '') AS source,
+                (SELECT json_group_array(FAL.search_alias)
+                 FROM (SELECT search_alias FROM food_aliases WHERE food_id = F.id ORDER BY id) FAL) AS aliases,
+                (SELECT json_group_array(T.search_description)
+                 FROM tags T
+                 INNER JOIN food_tags FT ON T.id = FT.tag_id
+                 WHERE FT.food_id = F.id) AS tags,
+                (SELECT json_group_array(A.search_description)
+                 FROM allergens A
+                 INNER JOIN food_allergens FA ON A.id = FA.allergen_id
+                 WHERE FA.food_id = F.id) AS allergens,
+                (SELECT json_group_array(DF.flag)
+                 FROM food_diet_flags DF
+                 WHERE DF.food_id = F.id AND DF.value = 1) AS diet_flags
+            FROM foods F
+            LEFT JOIN food_sources FS ON FS.id = F.source_id
+            WHERE F.verified = 1
+            ORDER BY F.id",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let json_list = |row: &sqlx::sqlite::SqliteRow, column: &str| -> Result<Vec<String>, Error> {
+            Ok(serde_json::from_str(&row.try_get::<String, _>(column)?)?)
+        };
+        rows.iter()
+            .map(|row| {
+                Ok(IndexEntry {
+                    id: row.try_get("id")?,
+                    slug: row.try_get("slug")?,
+                    description: row.try_get("search_description")?,
+                    aliases: json_list(row, "aliases")?,
+                    tags: json_list(row, "tags")?,
+                    source: row.try_get("source")?,
+                    allergens: json_list(row, "allergens")?,
+                    completeness: row.try_get("completeness")?,
+                    branded: row.try_get("branded")?,
+                    diet_flags: json_list(row, "diet_flags")?,
+                })
+            })
+            .collect()
+    })
+    .await
+}
+
+// (toplam, doğrulanmış) yemek sayısı, sadece doğrulanmış yemekler API'de listeleniyor
+#[instrument(skip(pool))]
+pub(crate) async fn count_foods(pool: &SqlitePool) -> Result<(i64, i64), Error> {
+    timed("count_foods", &[], || async move {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS total, COALESCE(SUM(verified), 0) AS verified FROM foods",
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok((row.try_get("total")?, row.try_get("verified")?))
+    })
+    .await
 }
 
+#[instrument(skip(pool))]
 pub(crate) async fn select_all_tags(pool: &SqlitePool) -> Result<Vec<String>, Error> {
-    let mut tags: Vec<String> = Vec::new();
-    for row in sqlx::query("SELECT description FROM tags")
+    timed("select_all_tags", &[], || async move {
+        let mut tags: Vec<String> = Vec::new();
+        for row in sqlx::query("SELECT description FROM tags")
+            .fetch_all(pool)
+            .await?
+        {
+            tags.push(row.try_get("description")?);
+        }
+        Ok(tags)
+    })
+    .await
+}
+
+// Kota sayaçları bellekte tamponlanıyor, burası sadece periyodik yazmalar ve ilk okuma için kullanılıyor. Aynı istek sayısı
+// tüm periyotlara (gün ve ay) tek transaction'da ekleniyor, yazma yarıda kalırsa günlük ve aylık kayıtlar birbirini tutmaya devam ediyor
+#[instrument(skip(pool))]
+pub(crate) async fn add_api_key_usage(
+    pool: &SqlitePool,
+    key_name: &str,
+    periods: &[&str],
+    requests: u64,
+) -> Result<(), Error> {
+    timed(
+        "add_api_key_usage",
+        &[
+            ("key_name", key_name),
+            ("periods", &periods.join(",")),
+            ("requests", &requests.to_string()),
+        ],
+        || async move {
+            let mut tx = pool.begin().await?;
+            for period in periods {
+                sqlx::query(
+                    "INSERT INTO api_key_usage (key_name, period, requests) VALUES (?, ?, ?)
+                 ON CONFLICT (key_name, period) DO UPDATE SET requests = requests + excluded.requests",
+                )
+                .bind(key_name)
+                .bind(period)
+                .bind(requests as i64)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(())
+        },
+    )
+    .await
+}
+
+#[instrument(skip(pool))]
+pub(crate) async fn select_api_key_usage(
+    pool: &SqlitePool,
+    key_name: &str,
+    period: &str,
+) -> Result<u64, Error> {
+    timed(
+        "select_api_key_usage",
+        &[("key_name", key_name), ("period", period)],
+        || async move {
+            let requests = sqlx::query_scalar::<_, i64>(
+                "SELECT requests FROM api_key_usage WHERE key_name = ? AND period = ?",
+            )
+            .bind(key_name)
+            .bind(period)
+            .fetch_optional(pool)
+            .await?;
+            Ok(requests.unwrap_or(0) as u64)
+        },
+    )
+    .await
+}
+
+// En yeni periyotlar önce gelecek şekilde (periyot, istek sayısı) listesi, günlük ve aylık kayıtlar karışık
+#[instrument(skip(pool))]
+pub(crate) async fn select_api_key_usage_history(
+    pool: &SqlitePool,
+    key_name: &str,
+    limit: u32,
+) -> Result<Vec<(String, u64)>, Error> {
+    timed("select_api_key_usage_history", &[("key_name", key_name), ("limit", &limit.to_string())], || async move {
+        let mut history = Vec::new();
+        for row in sqlx::query(
+            "SELECT period, requests FROM api_key_usage WHERE key_name = ? ORDER BY period DESC LIMIT ?",
+        )
+        .bind(key_name)
+        .bind(limit)
         .fetch_all(pool)
         .await?
-    {
-        tags.push(row.try_get("description")?);
-    }
-    Ok(tags)
+        {
+            history.push((row.try_get("period")?, row.try_get::<i64, _>("requests")? as u64));
+        }
+        Ok(history)
+    })
+    .await
+}
+
+#[instrument(skip(pool))]
+pub(crate) async fn add_search_analytics(
+    pool: &SqlitePool,
+    day: &str,
+    mode: &str,
+    query: &str,
+    searches: u64,
+    zero_results: u64,
+) -> Result<(), Error> {
+    timed(
+        "add_search_analytics",
+        &[("day", day), ("mode", mode), ("query", query)],
+        || async move {
+            sqlx::query(
+                "INSERT INTO search_analytics (day, mode, query, searches, zero_results) VALUES (?, ?, ?, ?, ?)
+                 ON CONFLICT (day, mode, query) DO UPDATE SET
+                    searches = searches + excluded.searches,
+                    zero_results = zero_results + excluded.zero_results",
+            )
+            .bind(day)
+            .bind(mode)
+            .bind(query)
+            .bind(searches as i64)
+            .bind(zero_results as i64)
+            .execute(pool)
+            .await?;
+            Ok(())
+        },
+    )
+    .await
+}
+
+// Saklama süresini geçen günlerin kayıtlarını siliyor, silinen satır sayısını döndürüyor
+#[instrument(skip(pool))]
+pub(crate) async fn delete_search_analytics_before(
+    pool: &SqlitePool,
+    day: &str,
+) -> Result<u64, Error> {
+    timed(
+        "delete_search_analytics_before",
+        &[("day", day)],
+        || async move {
+            Ok(sqlx::query("DELETE FROM search_analytics WHERE day < ?")
+                .bind(day)
+                .execute(pool)
+                .await?
+                .rows_affected())
+        },
+    )
+    .await
+}
+
+// since gününden itibaren (sorgu, arama sayısı, sonuçsuz arama sayısı); zero_results_only ise sadece sonuç bulunamayan sorgular, en çok sonuçsuz kalan önce
+#[instrument(skip(pool))]
+pub(crate) async fn select_top_search_queries(
+    pool: &SqlitePool,
+    since: &str,
+    zero_results_only: bool,
+    limit: u32,
+) -> Result<Vec<(String, u64, u64)>, Error> {
+    timed("select_top_search_queries", &[("since", since)], || async move {
+        let sql = if zero_results_only {
+            "SELECT query, SUM(searches) AS searches, SUM(zero_results) AS zero_results
+             FROM search_analytics WHERE day >= ? GROUP BY query
+             HAVING SUM(zero_results) > 0 ORDER BY SUM(zero_results) DESC, SUM(searches) DESC, query LIMIT ?"
+        } else {
+            "SELECT query, SUM(searches) AS searches, SUM(zero_results) AS zero_results
+             FROM search_analytics WHERE day >= ? GROUP BY query ORDER BY SUM(searches) DESC, query LIMIT ?"
+        };
+        let mut queries = Vec::new();
+        for row in sqlx::query(sql)
+        .bind(since)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?
+        {
+            queries.push((
+                row.try_get("query")?,
+                row.try_get::<i64, _>("searches")? as u64,
+                row.try_get::<i64, _>("zero_results")? as u64,
+            ));
+        }
+        Ok(queries)
+    })
+    .await
+}
+
+// since gününden itibaren (mod, arama sayısı, sonuçsuz arama sayısı)
+#[instrument(skip(pool))]
+pub(crate) async fn select_search_mode_totals(
+    pool: &SqlitePool,
+    since: &str,
+) -> Result<Vec<(String, u64, u64)>, Error> {
+    timed(
+        "select_search_mode_totals",
+        &[("since", since)],
+        || async move {
+            let mut modes = Vec::new();
+            for row in sqlx::query(
+                "SELECT mode, SUM(searches) AS searches, SUM(zero_results) AS zero_results
+             FROM search_analytics WHERE day >= ? GROUP BY mode ORDER BY SUM(searches) DESC",
+            )
+            .bind(since)
+            .fetch_all(pool)
+            .await?
+            {
+                modes.push((
+                    row.try_get("mode")?,
+                    row.try_get::<i64, _>("searches")? as u64,
+                    row.try_get::<i64, _>("zero_results")? as u64,
+                ));
+            }
+            Ok(modes)
+        },
+    )
+    .await
+}
+
+// Son kaydedilen etkin config, hiç kaydedilmediyse None
+#[instrument(skip(pool))]
+pub(crate) async fn select_config_snapshot(
+    pool: &SqlitePool,
+) -> Result<Option<BTreeMap<String, String>>, Error> {
+    timed("select_config_snapshot", &[], || async move {
+        let Some(row) = sqlx::query("SELECT config_values FROM config_snapshot WHERE id = 1")
+            .fetch_optional(pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_str(
+            &row.try_get::<String, _>("config_values")?,
+        )?))
+    })
+    .await
+}
+
+// Değişiklikler ve yeni config aynı transaction'da yazılıyor ki bir sonraki karşılaştırma kaydedilmiş değişikliklerle tutarlı olsun
+#[instrument(skip(pool, changes, snapshot))]
+pub(crate) async fn insert_config_changes(
+    pool: &SqlitePool,
+    source: &str,
+    changes: &[ConfigChange],
+    snapshot: &BTreeMap<String, String>,
+) -> Result<(), Error> {
+    timed("insert_config_changes", &[("source", source)], || async move {
+        let changed_at = chrono::Utc::now().to_rfc3339();
+        let mut tx = pool.begin().await?;
+        for change in changes {
+            sqlx::query(
+                "INSERT INTO config_changes (changed_at, source, key, old_value, new_value) VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(&changed_at)
+            .bind(source)
+            .bind(&change.key)
+            .bind(&change.old_value)
+            .bind(&change.new_value)
+            .execute(&mut *tx)
+            .await?;
+        }
+        sqlx::query(
+            "INSERT INTO config_snapshot (id, config_values, updated_at) VALUES (1, ?, ?)
+             ON CONFLICT (id) DO UPDATE SET config_values = excluded.config_values, updated_at = excluded.updated_at",
+        )
+        .bind(serde_json::to_string(snapshot)?)
+        .bind(&changed_at)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    })
+    .await
+}
+
+// En yeni değişiklik önce; key_prefix verilirse sadece o bölümdeki alanlar, ör: "rate_limit"
+#[instrument(skip(pool))]
+pub(crate) async fn select_config_changes(
+    pool: &SqlitePool,
+    key_prefix: Option<&str>,
+    limit: u32,
+) -> Result<Vec<ConfigChangeRecord>, Error> {
+    timed(
+        "select_config_changes",
+        &[
+            ("key_prefix", key_prefix.unwrap_or("")),
+            ("limit", &limit.to_string()),
+        ],
+        || async move {
+            let mut changes = Vec::new();
+            for row in sqlx::query(
+                "SELECT changed_at, source, key, old_value, new_value FROM config_changes
+                 WHERE ? IS NULL OR key = ? OR key LIKE ? || '.%' OR key LIKE ? || '[%'
+                 ORDER BY id DESC LIMIT ?",
+            )
+            .bind(key_prefix)
+            .bind(key_prefix)
+            .bind(key_prefix)
+            .bind(key_prefix)
+            .bind(limit)
+            .fetch_all(pool)
+            .await?
+            {
+                changes.push(ConfigChangeRecord {
+                    changed_at: row.try_get("changed_at")?,
+                    source: row.try_get("source")?,
+                    key: row.try_get("key")?,
+                    old_value: row.try_get("old_value")?,
+                    new_value: row.try_get("new_value")?,
+                });
+            }
+            Ok(changes)
+        },
+    )
+    .await
 }
 
-const SELECT_FOOD_SQL_QUERY: &str = r#"
+// Yemek sorgularının ortak kısmı. Sorgular concat! ile derleme zamanında sabit metinlere dönüşsün diye const yerine makro;
+// her çağrıda aynı SQL gittiği için bağlantıların hazırlanmış ifade önbelleğindeki ifade tekrar kullanılıyor
+macro_rules! select_food_sql {
+    () => {
+        r#"
         SELECT 
             F.*,
             FI.image_url, 
+            FI.attribution as image_attribution,
+            FI.license as image_license,
+            FI.source_url as image_source_url,
             FS.description as source_description,
+            G.slug as generic_slug,
+            G.description as generic_description,
+
+            -- En son doğrulama, yoksa NULL
+            (SELECT json_object('verified_by', V.verified_by, 'verified_at', V.verified_at, 'method', V.method, 'note', V.note)
+             FROM verifications V
+             WHERE V.food_id = F.id
+             ORDER BY V.verified_at DESC, V.id DESC
+             LIMIT 1) as "verification",
+
+            -- Diğer adlar eklenme sırasıyla
+            (SELECT json_group_array(FAL.alias)
+             FROM (SELECT alias FROM food_aliases WHERE food_id = F.id ORDER BY id) FAL) as "aliases",
 
             -- Etiketleri de JSON yapıyoruz, birden fazla SQL sorgusu atmak istemiyoruz network roundtrip olmaması için
             (SELECT json_group_array(T.description)
@@ -288,306 +1208,3361 @@ const SELECT_FOOD_SQL_QUERY: &str = r#"
              INNER JOIN food_tags FT ON T.id = FT.tag_id
              WHERE FT.food_id = F.id) as "tags",
 
-            -- Alerjenleri bir JSON dizisi yapalım
-            (SELECT json_group_array(A.description)
+            -- Alerjenleri de seviyeleri ve notlarıyla bir JSON dizisi yapalım
+            (SELECT json_group_array(json_object('name', A.description, 'level', FA.level, 'note', FA.note))
              FROM allergens A
              INNER JOIN food_allergens FA ON A.id = FA.allergen_id
              WHERE FA.food_id = F.id) as "allergens",
+
+            -- Katkı maddeleri E numarasına göre sıralı
+            (SELECT json_group_array(json_object('e_number', AD.e_number, 'name', AD.name, 'category', AD.category, 'notes', AD.notes))
+             FROM (SELECT A.* FROM additives A
+                   INNER JOIN food_additives FAD ON A.id = FAD.additive_id
+                   WHERE FAD.food_id = F.id
+                   ORDER BY CAST(SUBSTR(A.e_number, 2, 4) AS INTEGER), A.e_number) AD) as "additives",
             
             -- Porsiyonları bulup bir JSON nesnesi yapıyoruz { "description": weight }
             (SELECT json_group_object(SD.description, FS.weight)
              FROM serving_descriptions SD
              INNER JOIN food_servings FS ON SD.id = FS.serving_description_id
-             WHERE FS.food_id = F.id) as "servings"
+             WHERE FS.food_id = F.id) as "servings",
+
+            -- Mevsimleri bölgelere göre gruplayıp [{"region": ..., "months": [...]}] yapıyoruz. Aylar birincil anahtarın
+            -- (food_id, region, month) sırasıyla geldiği için küçükten büyüğe
+            (SELECT json_group_array(json_object('region', FSE.region, 'months', json(FSE.months)))
+             FROM (SELECT region, json_group_array(month) as months
+                   FROM food_seasonality
+                   WHERE food_id = F.id
+                   GROUP BY region
+                   ORDER BY region) FSE) as "seasonality",
+
+            -- Diyet bayraklarını { "vegan": true } şeklinde bir JSON nesnesi yapıyoruz, 0/1 değerleri JSON boolean'a çevriliyor
+            (SELECT json_group_object(DF.flag, json(CASE WHEN DF.value THEN 'true' ELSE 'false' END))
+             FROM food_diet_flags DF
+             WHERE DF.food_id = F.id) as "diet_flags"
 
         FROM foods F
         
         LEFT JOIN food_images FI ON FI.id = F.image_id
         LEFT JOIN food_sources FS ON FS.id = F.source_id
-        "#;
+        LEFT JOIN foods G ON G.id = F.generic_food_id AND G.verified = 1
+        "#
+    };
+}
 
+#[instrument(skip(pool))]
 pub(crate) async fn select_food_by_slug(pool: &SqlitePool, slug: &str) -> Result<Food, Error> {
-    Ok(
-        sqlx::query_as(&format!("{} WHERE F.slug = ?", SELECT_FOOD_SQL_QUERY))
-            .bind(slug)
-            .fetch_one(pool)
-            .await?,
-    )
+    timed("select_food_by_slug", &[("slug", slug)], || async move {
+        Ok(
+            sqlx::query_as(concat!(select_food_sql!(), " WHERE F.slug = ?"))
+                .bind(slug)
+                .fetch_one(pool)
+                .await?,
+        )
+    })
+    .await
 }
 
-pub(crate) async fn search_foods_by_description_wild(
+// Aramaların ortak filtreleri, parametrelerini bind_food_filters bağlıyor:
+// - allergen_free ile verilen alerjenlerden herhangi birini içeren yemekler dışlanıyor. Alerjenler etiketlerin aksine tam eşleşmeyle
+//   karşılaştırılıyor; alerjisi olan biri için bulaşma ihtimali de risk, may_contain seviyesindeki alerjenler de dışlanıyor
+// - tamlığı min_completeness'tan düşük yemekler dışlanıyor, 0 verilince hepsi geçiyor
+// - diet_flags'teki bayrakların hepsi true olmalı. Bayraklar tekrarsız olmalı, sayı karşılaştırılıyor
+// - include_branded=false verildiğinde markalı ürünler dışlanıyor
+// Listeler JSON dizisi olarak tek parametreyle bağlanıyor, eleman sayısı değişse de SQL metni değişmiyor
+macro_rules! food_filters_sql {
+    () => {
+        "
+        AND NOT EXISTS (
+            SELECT 1 FROM allergens A
+                INNER JOIN food_allergens FA ON A.id = FA.allergen_id
+                WHERE FA.food_id = F.id AND A.search_description IN (SELECT value FROM json_each(?))
+        )
+        AND F.completeness >= ?
+        AND (
+            SELECT COUNT(*) FROM food_diet_flags DF
+                WHERE DF.food_id = F.id AND DF.value = 1 AND DF.flag IN (SELECT value FROM json_each(?))
+        ) = ?
+        AND (? = 0 OR F.brand IS NULL)"
+    };
+}
+
+fn bind_food_filters<'q, O>(
+    query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+    allergen_free: &[&str],
+    min_completeness: f64,
+    diet_flags: &[&str],
+    exclude_branded: bool,
+) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+    let allergens: Vec<String> = allergen_free
+        .iter()
+        .map(|allergen| to_search_key(allergen))
+        .collect();
+    query
+        .bind(json_array(&allergens))
+        .bind(min_completeness)
+        .bind(json_array(diet_flags))
+        .bind(diet_flags.len() as i64)
+        .bind(exclude_branded)
+}
+
+// SQL'de json_each(?) ile okunacak liste
+fn json_array<T: serde::Serialize>(values: &[T]) -> String {
+    serde_json::to_string(values).expect("Liste JSON'a çevrilemedi")
+}
+
+// Açıklama sorgunun kendisi olan, sorguyla başlayan, bir kelimesi sorguyla başlayan ve sorguyu sadece içeren yemekler sırayla;
+// aynı gruptakilerden kısa açıklamalar önce. Uygulamadaki sıralamadan önce adayları seçmek için, 3 parametre bağlanmalı
+macro_rules! description_relevance_order_sql {
+    () => {
+        "
+    ORDER BY CASE
+        WHEN F.search_description = ? THEN 0
+        WHEN F.search_description LIKE ? THEN 1
+        WHEN F.search_description LIKE ? THEN 2
+        ELSE 3
+    END, length(F.search_description), F.id"
+    };
+}
+
+fn bind_description_relevance<'q, O>(
+    query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+    search_key: &str,
+) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+    query
+        .bind(search_key.to_owned())
+        .bind(format!("{}%", search_key))
+        .bind(format!("% {}%", search_key))
+}
+
+// Slug'ları verilen onaylanmış yemekler, sıra ve tekrarlar korunmuyor; bulunamayan slug'lar sonuçta yok
+#[instrument(skip(pool))]
+pub(crate) async fn select_verified_foods_by_slugs(
     pool: &SqlitePool,
-    description: &str,
+    slugs: &[String],
 ) -> Result<Vec<Food>, Error> {
-    Ok(sqlx::query_as(&format!(
-        "{} WHERE F.description LIKE ?",
-        SELECT_FOOD_SQL_QUERY
-    ))
-    // %Elma% şeklinde aratıyoruz ki Fuji Elma, Elma Turtası gibi sonuçlar da çıksın
-    .bind(&format!("%{}%", description))
-    .fetch_all(pool)
-    .await?)
+    if slugs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    timed(
+        "select_verified_foods_by_slugs",
+        &[("slugs", &slugs.join(","))],
+        || async move {
+            Ok(sqlx::query_as(concat!(
+                select_food_sql!(),
+                " WHERE F.verified = 1 AND F.slug IN (SELECT value FROM json_each(?))"
+            ))
+            .bind(json_array(slugs))
+            .fetch_all(pool)
+            .await?)
+        },
+    )
+    .await
 }
 
-pub(crate) async fn search_foods_by_tag_wild(
+// Aynı variant_group'taki diğer onaylanmış yemekler, açıklamaya göre sıralı
+#[instrument(skip(pool))]
+pub(crate) async fn select_food_variants(
     pool: &SqlitePool,
-    tag: &str,
-) -> Result<Vec<Food>, Error> {
-    Ok(sqlx::query_as(&format!(
-        "{} 
-        WHERE EXISTS (
-            SELECT 1 FROM tags T 
-                INNER JOIN food_tags FT ON T.id = FT.tag_id 
-                WHERE FT.food_id = F.id AND T.description LIKE ?
-        )",
-        SELECT_FOOD_SQL_QUERY
-    ))
-    .bind(&format!("%{}%", tag))
-    .fetch_all(pool)
-    .await?)
+    variant_group: &str,
+    food_id: i64,
+) -> Result<Vec<FoodLink>, Error> {
+    timed(
+        "select_food_variants",
+        &[("variant_group", variant_group)],
+        || async move {
+            let variants = sqlx::query_as::<_, (String, String)>(
+                "SELECT slug, description FROM foods
+                    WHERE variant_group = ? AND id <> ? AND verified = 1
+                    ORDER BY search_description",
+            )
+            .bind(variant_group)
+            .bind(food_id)
+            .fetch_all(pool)
+            .await?;
+            Ok(variants
+                .into_iter()
+                .map(|(slug, description)| FoodLink { slug, description })
+                .collect())
+        },
+    )
+    .await
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*; // Üst scope'daki fonksiyonları kullan
+// GTIN'i olup dış kaynaktan hiç çekilmemiş veya stale_before'dan (RFC 3339) önce çekilmiş yemekler, (id, gtin)
+#[instrument(skip(pool))]
+pub(crate) async fn select_foods_to_enrich(
+    pool: &SqlitePool,
+    stale_before: &str,
+) -> Result<Vec<(i64, String)>, Error> {
+    timed("select_foods_to_enrich", &[], || async move {
+        Ok(sqlx::query_as::<_, (i64, String)>(
+            "SELECT F.id, F.gtin FROM foods F
+                LEFT JOIN food_enrichments E ON E.food_id = F.id
+                WHERE F.gtin IS NOT NULL AND (E.food_id IS NULL OR E.fetched_at < ?)
+                ORDER BY F.id",
+        )
+        .bind(stale_before)
+        .fetch_all(pool)
+        .await?)
+    })
+    .await
+}
+
+#[instrument(skip(pool, enrichment))]
+pub(crate) async fn save_food_enrichment(
+    pool: &SqlitePool,
+    food_id: i64,
+    enrichment: &Enrichment,
+) -> Result<(), Error> {
+    timed("save_food_enrichment", &[], || async move {
+        sqlx::query(
+            "INSERT OR REPLACE INTO food_enrichments
+                (food_id, source, source_url, license, found, image_url, ingredients, nutri_score, fetched_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(food_id)
+        .bind(&enrichment.source)
+        .bind(&enrichment.url)
+        .bind(&enrichment.license)
+        .bind(enrichment.found as i64)
+        .bind(&enrichment.image_url)
+        .bind(&enrichment.ingredients)
+        .bind(&enrichment.nutri_score)
+        .bind(&enrichment.fetched_at)
+        .execute(pool)
+        .await?;
+        Ok(())
+    })
+    .await
+}
+
+// Kaynakta bulunamayan ürünlerin kayıtları sadece tekrar sorulmasınlar diye tutuluyor, onlar için None
+#[instrument(skip(pool))]
+pub(crate) async fn select_food_enrichment(
+    pool: &SqlitePool,
+    food_id: i64,
+) -> Result<Option<Enrichment>, Error> {
+    timed("select_food_enrichment", &[], || async move {
+        let row = sqlx::query_as::<
+            _,
+            (
+                String,
+                String,
+                String,
+                Option<String>,
+                Option<String>,
+                Option<String>,
+                String,
+            ),
+        >(
+            "SELECT source, source_url, license, image_url, ingredients, nutri_score, fetched_at
+                FROM food_enrichments WHERE food_id = ? AND found = 1",
+        )
+        .bind(food_id)
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(
+            |(source, url, license, image_url, ingredients, nutri_score, fetched_at)| Enrichment {
+                source,
+                url,
+                license,
+                found: true,
+                image_url,
+                ingredients,
+                nutri_score,
+                fetched_at,
+            },
+        ))
+    })
+    .await
+}
+
+// Bulanık aramada adaylar uygulamada puanlanıyor, veri seti küçük olduğu için tüm onaylanmış yemekleri çekmek sorun değil.
+// Onaylanmamışlar diğer aramalardaki gibi SQL'de eleniyor, hiç Food'a çevrilmiyorlar
+#[instrument(skip(pool))]
+pub(crate) async fn select_verified_foods(
+    pool: &SqlitePool,
+    allergen_free: &[&str],
+    min_completeness: f64,
+    exclude_branded: bool,
+    diet_flags: &[&str],
+) -> Result<Vec<Food>, Error> {
+    timed(
+        "select_verified_foods",
+        &[
+            ("allergen_free", &allergen_free.join(",")),
+            ("min_completeness", &min_completeness.to_string()),
+            ("exclude_branded", &exclude_branded.to_string()),
+            ("diet_flags", &diet_flags.join(",")),
+        ],
+        || async move {
+            let query = sqlx::query_as(concat!(
+                select_food_sql!(),
+                " WHERE F.verified = 1",
+                food_filters_sql!()
+            ));
+            Ok(bind_food_filters(
+                query,
+                allergen_free,
+                min_completeness,
+                diet_flags,
+                exclude_branded,
+            )
+            .fetch_all(pool)
+            .await?)
+        },
+    )
+    .await
+}
+
+// Onaylanmış tüm yemekleri slug sırasıyla satır satır okuyup on_food'a veriyor, yemekler bir Vec'te toplanmıyor. on_food false
+// döndürürse okuma bırakılıyor, örneğin istemci bağlantıyı kapattığında. after verilirse slug'ı ondan sonra gelenlerden başlanıyor.
+// Okunan yemek sayısını döndürüyor
+pub(crate) async fn for_each_verified_food<F, Fut>(
+    pool: &SqlitePool,
+    after: Option<&str>,
+    mut on_food: F,
+) -> Result<u64, Error>
+where
+    F: FnMut(Food) -> Fut,
+    Fut: Future<Output = bool>,
+{
+    // Satırlar gönderilmeye başladıktan sonra baştan okumak aynı yemekleri tekrar gönderir, bu sorgu tekrar denenmiyor
+    let after = after.unwrap_or_default();
+    timed_once("for_each_verified_food", &[("after", after)], async {
+        // Slug'lar boş olamadığı için after verilmediğinde boş metin tüm yemekleri seçiyor
+        let mut foods = sqlx::query_as::<_, Food>(concat!(
+            select_food_sql!(),
+            " WHERE F.verified = 1 AND F.slug > ? ORDER BY F.slug"
+        ))
+        .bind(after)
+        .fetch(pool);
+        let mut count = 0;
+        while let Some(food) = foods.try_next().await? {
+            count += 1;
+            if !on_food(food).await {
+                break;
+            }
+        }
+        Ok(count)
+    })
+    .await
+}
+
+// Açıklaması veya diğer adlarından biri sorgunun tüm parçalarını (core::query::SearchQuery) içeren onaylanmış yemeklerden alakaya göre ilk max_candidates yemek.
+// "a" gibi geniş sorgular tüm tabloyu çekmesin diye aday sayısı sınırlı, asıl sıralama uygulamada yapılıyor
+#[instrument(skip(pool))]
+pub(crate) async fn search_foods_by_description_wild(
+    pool: &SqlitePool,
+    terms: &[String],
+    allergen_free: &[&str],
+    min_completeness: f64,
+    exclude_branded: bool,
+    diet_flags: &[&str],
+    max_candidates: u32,
+) -> Result<Vec<Food>, Error> {
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    timed(
+        "search_foods_by_description_wild",
+        &[
+            ("terms", &terms.join("|")),
+            ("allergen_free", &allergen_free.join(",")),
+            ("min_completeness", &min_completeness.to_string()),
+            ("exclude_branded", &exclude_branded.to_string()),
+            ("diet_flags", &diet_flags.join(",")),
+        ],
+        || async move {
+            // Sorgunun tüm parçaları ya açıklamada ya da diğer adlardan birinde geçmeli, parçalar farklı adlara dağılmamalı. Parçalar
+            // JSON dizisi olarak bağlanıyor, eşleşen parça sayısı toplam parça sayısıyla karşılaştırılıyor.
+            // %Elma% şeklinde aratıyoruz ki Fuji Elma, Elma Turtası gibi sonuçlar da çıksın.
+            // search_description Türkçe karakterlerden arındırılmış, küçük harfli açıklama; "sut" Süt'ü de buluyor
+            let query = sqlx::query_as(concat!(
+                select_food_sql!(),
+                " WHERE F.verified = 1 AND (
+                    (SELECT COUNT(*) FROM json_each(?) Q
+                        WHERE F.search_description LIKE '%' || Q.value || '%') = ?
+                    OR EXISTS (
+                        SELECT 1 FROM food_aliases FAL
+                            WHERE FAL.food_id = F.id AND (
+                                SELECT COUNT(*) FROM json_each(?) Q
+                                    WHERE FAL.search_alias LIKE '%' || Q.value || '%'
+                            ) = ?
+                    )
+                )",
+                food_filters_sql!(),
+                description_relevance_order_sql!(),
+                " LIMIT ?"
+            ))
+            .bind(json_array(terms))
+            .bind(terms.len() as i64)
+            .bind(json_array(terms))
+            .bind(terms.len() as i64);
+            let query = bind_food_filters(
+                query,
+                allergen_free,
+                min_completeness,
+                diet_flags,
+                exclude_branded,
+            );
+            let query = bind_description_relevance(query, &terms.join(" "));
+            Ok(query.bind(max_candidates).fetch_all(pool).await?)
+        },
+    )
+    .await
+}
+
+// Her etiket için ayrı bir EXISTS alt sorgusu kuruluyor; match_all ise yemeğin tüm etiketlere, değilse en az birine sahip olması gerekiyor.
+// Sıralama ve sayfalama da SQL'de: etiketi sorguyla birebir aynı olan yemekler, sorguyla başlayanlar, sadece içerenler sırayla, op=or'da
+// daha çok etikete uyan önce. Sadece istenen sayfa ve toplam sonuç sayısı dönüyor
+#[allow(clippy::too_many_arguments)]
+#[instrument(skip(pool))]
+pub(crate) async fn search_foods_by_tag_wild(
+    pool: &SqlitePool,
+    tags: &[&str],
+    match_all: bool,
+    allergen_free: &[&str],
+    min_completeness: f64,
+    exclude_branded: bool,
+    diet_flags: &[&str],
+    limit: u64,
+    offset: u64,
+) -> Result<(Vec<Food>, u64), Error> {
+    if tags.is_empty() {
+        return Ok((Vec::new(), 0));
+    }
+
+    timed(
+        "search_foods_by_tag_wild",
+        &[
+            ("tags", &tags.join(",")),
+            ("match_all", &match_all.to_string()),
+            ("allergen_free", &allergen_free.join(",")),
+            ("min_completeness", &min_completeness.to_string()),
+            ("exclude_branded", &exclude_branded.to_string()),
+            ("diet_flags", &diet_flags.join(",")),
+        ],
+        || async move {
+            // Etiketler JSON dizisi olarak bağlanıyor; match_all'da hepsi, değilse en az biri eşleşmeli
+            macro_rules! filter_sql {
+                () => {
+                    concat!(
+                        " WHERE F.verified = 1 AND (
+                            SELECT COUNT(*) FROM json_each(?) Q
+                                WHERE EXISTS (
+                                    SELECT 1 FROM tags T
+                                        INNER JOIN food_tags FT ON T.id = FT.tag_id
+                                        WHERE FT.food_id = F.id AND T.search_description LIKE '%' || Q.value || '%'
+                                )
+                        ) >= ?",
+                        food_filters_sql!()
+                    )
+                };
+            }
+            let search_keys: Vec<String> = tags.iter().map(|tag| to_search_key(tag)).collect();
+            let required = if match_all { search_keys.len() } else { 1 };
+            // Sayım ve sayfa sorguları aynı koşulu kullanıyor, parametreleri de aynı sırayla bağlanıyor
+            fn bind_filter<'q, O>(
+                query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+                search_keys: &[String],
+                required: usize,
+                allergen_free: &[&str],
+                min_completeness: f64,
+                diet_flags: &[&str],
+                exclude_branded: bool,
+            ) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>> {
+                bind_food_filters(
+                    query.bind(json_array(search_keys)).bind(required as i64),
+                    allergen_free,
+                    min_completeness,
+                    diet_flags,
+                    exclude_branded,
+                )
+            }
+
+            let (total,): (i64,) = bind_filter(
+                sqlx::query_as(concat!("SELECT COUNT(*) FROM foods F", filter_sql!())),
+                &search_keys,
+                required,
+                allergen_free,
+                min_completeness,
+                diet_flags,
+                exclude_branded,
+            )
+            .fetch_one(pool)
+            .await?;
+
+            // Her etiketin en iyi eşleşmesi tam eşleşmede 3, başta eşleşmede 2, diğerlerinde 1 puan; puanlar toplanıyor
+            let query = sqlx::query_as(concat!(
+                select_food_sql!(),
+                filter_sql!(),
+                " ORDER BY (
+                    SELECT COALESCE(SUM((
+                        SELECT MAX(CASE
+                            WHEN T.search_description = Q.value THEN 3
+                            WHEN T.search_description LIKE Q.value || '%' THEN 2
+                            ELSE 1
+                        END)
+                            FROM tags T
+                            INNER JOIN food_tags FT ON T.id = FT.tag_id
+                            WHERE FT.food_id = F.id AND T.search_description LIKE '%' || Q.value || '%'
+                    )), 0) FROM json_each(?) Q
+                ) DESC, F.id LIMIT ? OFFSET ?"
+            ));
+            let foods = bind_filter(
+                query,
+                &search_keys,
+                required,
+                allergen_free,
+                min_completeness,
+                diet_flags,
+                exclude_branded,
+            )
+            .bind(json_array(&search_keys))
+            .bind(limit.min(i64::MAX as u64) as i64)
+            .bind(offset.min(i64::MAX as u64) as i64)
+            .fetch_all(pool)
+            .await?;
+            Ok((foods, total as u64))
+        },
+    )
+    .await
+}
+
+// Göz atma filtreleri, parametrelerini browse_foods'taki bind_filter bağlıyor. Etiketler tam eşleşmeli ve hepsi bulunmalı; aralıklar
+// {"nutrient", "min", "max"} nesnelerinden oluşan bir JSON dizisi, besin değeri core::browse::NUTRIENTS'taki kolonlardan biri ya da
+// min_completeness'tan gelen completeness.
+// Değeri olmayan yemekler aralık verilen besin değerinde dışlanıyor. Sınıflar boş verildiğinde filtrelenmiyor
+macro_rules! browse_filter_sql {
+    () => {
+        concat!(
+            " WHERE F.verified = 1
+        AND (
+            SELECT COUNT(*) FROM json_each(?) Q
+                WHERE EXISTS (
+                    SELECT 1 FROM tags T
+                        INNER JOIN food_tags FT ON T.id = FT.tag_id
+                        WHERE FT.food_id = F.id AND T.search_description = Q.value
+                )
+        ) = ?
+        AND NOT EXISTS (
+            SELECT 1 FROM json_each(?) R
+                WHERE NOT COALESCE((CASE json_extract(R.value, '$.nutrient')
+                WHEN 'glycemic_index' THEN F.glycemic_index
+                WHEN 'energy' THEN F.energy
+                WHEN 'carbohydrate' THEN F.carbohydrate
+                WHEN 'protein' THEN F.protein
+                WHEN 'fat' THEN F.fat
+                WHEN 'saturated_fat' THEN F.saturated_fat
+                WHEN 'trans_fat' THEN F.trans_fat
+                WHEN 'sugar' THEN F.sugar
+                WHEN 'added_sugar' THEN F.added_sugar
+                WHEN 'polyols' THEN F.polyols
+                WHEN 'fiber' THEN F.fiber
+                WHEN 'cholesterol' THEN F.cholesterol
+                WHEN 'sodium' THEN F.sodium
+                WHEN 'potassium' THEN F.potassium
+                WHEN 'water' THEN F.water
+                WHEN 'iron' THEN F.iron
+                WHEN 'magnesium' THEN F.magnesium
+                WHEN 'calcium' THEN F.calcium
+                WHEN 'zinc' THEN F.zinc
+                WHEN 'vitamin_a' THEN F.vitamin_a
+                WHEN 'vitamin_b6' THEN F.vitamin_b6
+                WHEN 'vitamin_b12' THEN F.vitamin_b12
+                WHEN 'vitamin_c' THEN F.vitamin_c
+                WHEN 'vitamin_d' THEN F.vitamin_d
+                WHEN 'vitamin_e' THEN F.vitamin_e
+                WHEN 'vitamin_k' THEN F.vitamin_k
+                WHEN 'omega_3' THEN F.omega_3
+                WHEN 'omega_6' THEN F.omega_6
+                WHEN 'folate' THEN F.folate
+                WHEN 'niacin' THEN F.niacin
+                WHEN 'phosphorus' THEN F.phosphorus
+                WHEN 'selenium' THEN F.selenium
+                WHEN 'copper' THEN F.copper
+                WHEN 'completeness' THEN F.completeness
+                END) BETWEEN COALESCE(json_extract(R.value, '$.min'), -1e308)
+                    AND COALESCE(json_extract(R.value, '$.max'), 1e308), 0)
+        )
+        AND (? = 0 OR F.nutri_score IN (SELECT value FROM json_each(?)))
+        AND (? = 0 OR F.nova_group IN (SELECT value FROM json_each(?)))",
+            food_filters_sql!()
+        )
+    };
+}
+
+// q verilmeyen aramalarda filtrelere uyan onaylanmış yemekler, istenen sıralamayla. Etiket araması gibi sadece istenen sayfa ve
+// toplam sonuç sayısı dönüyor
+#[instrument(skip(pool))]
+pub(crate) async fn browse_foods(
+    pool: &SqlitePool,
+    filter: &BrowseFilter,
+    sort: BrowseSort,
+    limit: u64,
+    offset: u64,
+) -> Result<(Vec<Food>, u64), Error> {
+    timed(
+        "browse_foods",
+        &[
+            ("tags", &filter.tags.join(",")),
+            ("ranges", &filter.ranges.len().to_string()),
+            ("diet_flags", &filter.diet_flags.join(",")),
+            ("sort", sort.column),
+        ],
+        || async move {
+            let allergen_free: Vec<&str> =
+                filter.allergen_free.iter().map(String::as_str).collect();
+            let diet_flags: Vec<&str> = filter.diet_flags.iter().map(String::as_str).collect();
+            // Sayım ve sayfa sorguları aynı koşulu kullanıyor, parametreleri de aynı sırayla bağlanıyor. Göz atmada tamlık filtresi yok
+            fn bind_filter<'q, O>(
+                query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+                filter: &BrowseFilter,
+                allergen_free: &[&str],
+                diet_flags: &[&str],
+            ) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>
+            {
+                let ranges: Vec<serde_json::Value> = filter
+                    .ranges
+                    .iter()
+                    .map(|range| {
+                        serde_json::json!({
+                            "nutrient": range.nutrient,
+                            "min": range.min,
+                            "max": range.max,
+                        })
+                    })
+                    .collect();
+                let nutri_scores: Vec<&str> = filter
+                    .nutri_scores
+                    .iter()
+                    .map(|grade| grade.name())
+                    .collect();
+                let query = query
+                    .bind(json_array(&filter.tags))
+                    .bind(filter.tags.len() as i64)
+                    .bind(json_array(&ranges))
+                    .bind(!nutri_scores.is_empty())
+                    .bind(json_array(&nutri_scores))
+                    .bind(!filter.nova_groups.is_empty())
+                    .bind(json_array(&filter.nova_groups));
+                bind_food_filters(
+                    query,
+                    allergen_free,
+                    0.0,
+                    diet_flags,
+                    filter.exclude_branded,
+                )
+            }
+
+            let (total,): (i64,) = bind_filter(
+                sqlx::query_as(concat!(
+                    "SELECT COUNT(*) FROM foods F",
+                    browse_filter_sql!()
+                )),
+                filter,
+                &allergen_free,
+                &diet_flags,
+            )
+            .fetch_one(pool)
+            .await?;
+
+            // Sıralama kolonu ve yönü parametre olarak bağlanamıyor, core::browse'daki listeden geldiği için doğrudan yazılıyor.
+            // Kolon ve yön sayısı sınırlı olduğu için önbellekteki hazır ifadeler de sınırlı kalıyor
+            let sql = format!(
+                "{}{} ORDER BY F.{} {} NULLS LAST, F.id LIMIT ? OFFSET ?",
+                select_food_sql!(),
+                browse_filter_sql!(),
+                sort.column,
+                if sort.descending { "DESC" } else { "ASC" }
+            );
+            let foods = bind_filter(sqlx::query_as(&sql), filter, &allergen_free, &diet_flags)
+                .bind(limit.min(i64::MAX as u64) as i64)
+                .bind(offset.min(i64::MAX as u64) as i64)
+                .fetch_all(pool)
+                .await?;
+            Ok((foods, total as u64))
+        },
+    )
+    .await
+}
+
+// Verilen ayda (1-12) mevsiminde olan onaylanmış yemekler, region verilirse sadece o bölgede. Açıklamaya göre sıralı,
+// göz atmadaki gibi sadece istenen sayfa ve toplam sonuç sayısı dönüyor
+#[instrument(skip(pool))]
+pub(crate) async fn select_foods_in_season(
+    pool: &SqlitePool,
+    month: u8,
+    region: Option<&str>,
+    limit: u64,
+    offset: u64,
+) -> Result<(Vec<Food>, u64), Error> {
+    timed(
+        "select_foods_in_season",
+        &[
+            ("month", &month.to_string()),
+            ("region", region.unwrap_or("")),
+        ],
+        || async move {
+            // Bölge verilmediğinde bölge koşulu atlanıyor
+            macro_rules! filter_sql {
+                () => {
+                    " WHERE F.verified = 1 AND EXISTS (
+                        SELECT 1 FROM food_seasonality FSE
+                            WHERE FSE.food_id = F.id AND FSE.month = ? AND (? IS NULL OR FSE.search_region = ?)
+                    )"
+                };
+            }
+            let region = region.map(to_search_key);
+            fn bind_season<'q, O>(
+                query: sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>,
+                month: u8,
+                region: &Option<String>,
+            ) -> sqlx::query::QueryAs<'q, sqlx::Sqlite, O, sqlx::sqlite::SqliteArguments<'q>>
+            {
+                query
+                    .bind(month as i64)
+                    .bind(region.clone())
+                    .bind(region.clone())
+            }
+
+            let (total,): (i64,) = bind_season(
+                sqlx::query_as(concat!("SELECT COUNT(*) FROM foods F", filter_sql!())),
+                month,
+                &region,
+            )
+            .fetch_one(pool)
+            .await?;
+            let query = sqlx::query_as(concat!(
+                select_food_sql!(),
+                filter_sql!(),
+                " ORDER BY F.search_description, F.id LIMIT ? OFFSET ?"
+            ));
+            let foods = bind_season(query, month, &region)
+                .bind(limit.min(i64::MAX as u64) as i64)
+                .bind(offset.min(i64::MAX as u64) as i64)
+                .fetch_all(pool)
+                .await?;
+            Ok((foods, total as u64))
+        },
+    )
+    .await
+}
+
+// Sorgunun her parçası açıklamada, diğer adlarda, herhangi bir etikette veya kaynak adında geçen onaylanmış yemekler. Hangi alanın ne kadar
+// eşleştiği uygulamada puanlanıyor, adaylar açıklama aramasındaki gibi sınırlı; açıklaması eşleşenler önce seçiliyor
+#[instrument(skip(pool))]
+pub(crate) async fn search_foods_by_any_field(
+    pool: &SqlitePool,
+    terms: &[String],
+    allergen_free: &[&str],
+    min_completeness: f64,
+    exclude_branded: bool,
+    diet_flags: &[&str],
+    max_candidates: u32,
+) -> Result<Vec<Food>, Error> {
+    if terms.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    timed(
+        "search_foods_by_any_field",
+        &[
+            ("terms", &terms.join("|")),
+            ("allergen_free", &allergen_free.join(",")),
+            ("min_completeness", &min_completeness.to_string()),
+            ("exclude_branded", &exclude_branded.to_string()),
+            ("diet_flags", &diet_flags.join(",")),
+        ],
+        || async move {
+            // Parçalar JSON dizisi olarak bağlanıyor, her parça alanlardan en az birinde geçmeli
+            let query = sqlx::query_as(concat!(
+                select_food_sql!(),
+                " WHERE F.verified = 1 AND (
+                    SELECT COUNT(*) FROM json_each(?) Q
+                        WHERE F.search_description LIKE '%' || Q.value || '%'
+                            OR FS.search_description LIKE '%' || Q.value || '%'
+                            OR EXISTS (
+                                SELECT 1 FROM tags T
+                                    INNER JOIN food_tags FT ON T.id = FT.tag_id
+                                    WHERE FT.food_id = F.id AND T.search_description LIKE '%' || Q.value || '%'
+                            )
+                            OR EXISTS (
+                                SELECT 1 FROM food_aliases FAL
+                                    WHERE FAL.food_id = F.id AND FAL.search_alias LIKE '%' || Q.value || '%'
+                            )
+                ) = ?",
+                food_filters_sql!(),
+                description_relevance_order_sql!(),
+                " LIMIT ?"
+            ))
+            .bind(json_array(terms))
+            .bind(terms.len() as i64);
+            let query = bind_food_filters(
+                query,
+                allergen_free,
+                min_completeness,
+                diet_flags,
+                exclude_branded,
+            );
+            let query = bind_description_relevance(query, &terms.join(" "));
+            Ok(query.bind(max_candidates).fetch_all(pool).await?)
+        },
+    )
+    .await
+}
+
+// Aranan terimle aynı sette olan diğer terimler, eklenme sırasıyla. Terim hiçbir sette değilse boş
+#[instrument(skip(pool))]
+pub(crate) async fn select_synonyms(pool: &SqlitePool, term: &str) -> Result<Vec<String>, Error> {
+    timed("select_synonyms", &[("term", term)], || async move {
+        let term = to_search_key(term);
+        Ok(sqlx::query_scalar(
+            "SELECT term FROM search_synonyms
+             WHERE set_id = (SELECT set_id FROM search_synonyms WHERE search_term = ?) AND search_term != ?
+             ORDER BY rowid",
+        )
+        .bind(&term)
+        .bind(&term)
+        .fetch_all(pool)
+        .await?)
+    })
+    .await
+}
+
+// (set id, terimler), set id'ye göre sıralı
+#[instrument(skip(pool))]
+pub(crate) async fn select_synonym_sets(
+    pool: &SqlitePool,
+) -> Result<Vec<(i64, Vec<String>)>, Error> {
+    timed("select_synonym_sets", &[], || async move {
+        let mut sets: Vec<(i64, Vec<String>)> = Vec::new();
+        for (set_id, term) in sqlx::query_as::<_, (i64, String)>(
+            "SELECT set_id, term FROM search_synonyms ORDER BY set_id, rowid",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+            match sets.last_mut() {
+                Some((last_id, terms)) if *last_id == set_id => terms.push(term),
+                _ => sets.push((set_id, vec![term])),
+            }
+        }
+        Ok(sets)
+    })
+    .await
+}
+
+async fn insert_verification(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    food_id: i64,
+    verification: &Verification,
+) -> Result<(), Error> {
+    sqlx::query(
+        "INSERT INTO verifications (food_id, verified_by, verified_at, method, note) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(food_id)
+    .bind(&verification.verified_by)
+    .bind(&verification.verified_at)
+    .bind(verification.method.name())
+    .bind(&verification.note)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+// Yemeğe yeni bir doğrulama ekliyor ve yemeği onaylanmış yapıyor. Doğrulama normalize edilmiş olmalı, yemek bulunamadıysa false
+#[instrument(skip(pool))]
+pub(crate) async fn insert_food_verification(
+    pool: &SqlitePool,
+    slug: &str,
+    verification: &Verification,
+) -> Result<bool, Error> {
+    timed(
+        "insert_food_verification",
+        &[("slug", slug), ("method", verification.method.name())],
+        || async move {
+            let mut tx = pool.begin().await?;
+            let Some(food_id) = sqlx::query_scalar::<_, i64>("SELECT id FROM foods WHERE slug = ?")
+                .bind(slug)
+                .fetch_optional(&mut *tx)
+                .await?
+            else {
+                return Ok(false);
+            };
+            insert_verification(&mut tx, food_id, verification).await?;
+            sqlx::query(
+                "UPDATE foods SET verified = 1, updated_at = strftime('%s', 'now') WHERE id = ?",
+            )
+            .bind(food_id)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            Ok(true)
+        },
+    )
+    .await
+}
+
+// Yemeğin diğer adları eklenme sırasıyla, yemek bulunamadıysa None
+#[instrument(skip(pool))]
+pub(crate) async fn select_food_aliases(
+    pool: &SqlitePool,
+    slug: &str,
+) -> Result<Option<Vec<String>>, Error> {
+    timed("select_food_aliases", &[("slug", slug)], || async move {
+        let Some(food_id) = sqlx::query_scalar::<_, i64>("SELECT id FROM foods WHERE slug = ?")
+            .bind(slug)
+            .fetch_optional(pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            sqlx::query_scalar("SELECT alias FROM food_aliases WHERE food_id = ? ORDER BY id")
+                .bind(food_id)
+                .fetch_all(pool)
+                .await?,
+        ))
+    })
+    .await
+}
+
+// Yemeğin diğer adlarını verilenlerle değiştiriyor, boş liste hepsini siliyor. insert_food'daki gibi açıklamayla aynı olan adlar
+// atlanıyor. Yazılan adları döndürüyor, yemek bulunamadıysa None
+#[instrument(skip(pool))]
+pub(crate) async fn replace_food_aliases(
+    pool: &SqlitePool,
+    slug: &str,
+    aliases: &[String],
+) -> Result<Option<Vec<String>>, Error> {
+    timed(
+        "replace_food_aliases",
+        &[("slug", slug), ("aliases", &aliases.join(","))],
+        || async move {
+            let mut tx = pool.begin().await?;
+            let Some((food_id, description)) = sqlx::query_as::<_, (i64, String)>(
+                "SELECT id, description FROM foods WHERE slug = ?",
+            )
+            .bind(slug)
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                return Ok(None);
+            };
+
+            sqlx::query("DELETE FROM food_aliases WHERE food_id = ?")
+                .bind(food_id)
+                .execute(&mut *tx)
+                .await?;
+            let mut saved = Vec::new();
+            for alias in aliases {
+                if to_search_key(alias) == to_search_key(&description) {
+                    continue;
+                }
+                let inserted = sqlx::query(
+                    "INSERT OR IGNORE INTO food_aliases (food_id, alias) VALUES (?, ?)",
+                )
+                .bind(food_id)
+                .bind(alias)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+                if inserted > 0 {
+                    saved.push(alias.clone());
+                }
+            }
+            tx.commit().await?;
+            Ok(Some(saved))
+        },
+    )
+    .await
+}
+
+pub(crate) enum SynonymSetWrite {
+    Saved(i64),
+    // Terim başka bir sette zaten var
+    Conflict(String),
+    NotFound,
+}
+
+// set_id verilmezse yeni bir set oluşturuyor, verilirse setin terimlerini değiştiriyor. Çakışma kontrolü ve yazma aynı transaction'da
+#[instrument(skip(pool))]
+pub(crate) async fn save_synonym_set(
+    pool: &SqlitePool,
+    set_id: Option<i64>,
+    terms: &[String],
+) -> Result<SynonymSetWrite, Error> {
+    timed(
+        "save_synonym_set",
+        &[
+            ("set_id", &format!("{:?}", set_id)),
+            ("terms", &terms.join(",")),
+        ],
+        || async move {
+            let mut tx = pool.begin().await?;
+            let set_id = match set_id {
+                Some(set_id) => {
+                    let exists = sqlx::query_scalar::<_, i64>(
+                        "SELECT COUNT(*) FROM search_synonyms WHERE set_id = ?",
+                    )
+                    .bind(set_id)
+                    .fetch_one(&mut *tx)
+                    .await?
+                        > 0;
+                    if !exists {
+                        return Ok(SynonymSetWrite::NotFound);
+                    }
+                    set_id
+                }
+                None => {
+                    sqlx::query_scalar::<_, i64>(
+                        "SELECT COALESCE(MAX(set_id), 0) + 1 FROM search_synonyms",
+                    )
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+            };
+
+            for term in terms {
+                let existing = sqlx::query_scalar::<_, String>(
+                    "SELECT term FROM search_synonyms WHERE search_term = ? AND set_id != ?",
+                )
+                .bind(to_search_key(term))
+                .bind(set_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+                if let Some(existing) = existing {
+                    return Ok(SynonymSetWrite::Conflict(existing));
+                }
+            }
+
+            sqlx::query("DELETE FROM search_synonyms WHERE set_id = ?")
+                .bind(set_id)
+                .execute(&mut *tx)
+                .await?;
+            for term in terms {
+                sqlx::query("INSERT INTO search_synonyms (set_id, term) VALUES (?, ?)")
+                    .bind(set_id)
+                    .bind(term)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            Ok(SynonymSetWrite::Saved(set_id))
+        },
+    )
+    .await
+}
+
+// Set bulunamadıysa false
+#[instrument(skip(pool))]
+pub(crate) async fn delete_synonym_set(pool: &SqlitePool, set_id: i64) -> Result<bool, Error> {
+    timed(
+        "delete_synonym_set",
+        &[("set_id", &set_id.to_string())],
+        || async move {
+            let result = sqlx::query("DELETE FROM search_synonyms WHERE set_id = ?")
+                .bind(set_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() > 0)
+        },
+    )
+    .await
+}
+
+// (slug, açıklama), açıklamaya göre sıralı
+#[instrument(skip(pool))]
+pub(crate) async fn select_recipe_names(pool: &SqlitePool) -> Result<Vec<(String, String)>, Error> {
+    timed("select_recipe_names", &[], || async move {
+        Ok(
+            sqlx::query_as("SELECT slug, description FROM recipes ORDER BY description")
+                .fetch_all(pool)
+                .await?,
+        )
+    })
+    .await
+}
+
+// Tarifin açıklaması ve porsiyon sayısı ile malzemeleri tarifteki sırayla, yemek ve gramı olarak. Tarif yoksa None
+#[instrument(skip(pool))]
+pub(crate) async fn select_recipe_by_slug(
+    pool: &SqlitePool,
+    slug: &str,
+) -> Result<Option<RecipeRecord>, Error> {
+    timed("select_recipe_by_slug", &[("slug", slug)], || async move {
+        let Some((id, slug, description, servings)) =
+            sqlx::query_as::<_, (i64, String, String, i64)>(
+                "SELECT id, slug, description, servings FROM recipes WHERE slug = ?",
+            )
+            .bind(slug)
+            .fetch_optional(pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let foods: Vec<Food> = sqlx::query_as(concat!(
+            select_food_sql!(),
+            " WHERE F.id IN (SELECT food_id FROM recipe_ingredients WHERE recipe_id = ?)"
+        ))
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+        let mut ingredients = Vec::new();
+        for (food_id, grams) in sqlx::query_as::<_, (i64, f64)>(
+            "SELECT food_id, grams FROM recipe_ingredients WHERE recipe_id = ? ORDER BY position",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await?
+        {
+            // Yemekler tablosunda yabancı anahtar kullanılmıyor, veritabanından elle silinen bir yemek tarifi bozmasın
+            match foods.iter().find(|food| food.id == Some(food_id)) {
+                Some(food) => ingredients.push((food.clone(), grams)),
+                None => warn!(
+                    "{} tarifindeki #{} numaralı yemek bulunamadı",
+                    slug, food_id
+                ),
+            }
+        }
+
+        Ok(Some(RecipeRecord {
+            slug,
+            description,
+            servings: servings as u32,
+            ingredients,
+        }))
+    })
+    .await
+}
+
+pub(crate) struct RecipeRecord {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) servings: u32,
+    pub(crate) ingredients: Vec<(Food, f64)>,
+}
+
+pub(crate) enum RecipeWrite {
+    // Kaydedilen tarifin slug'ı, açıklamadan oluşturuluyor
+    Saved(String),
+    // Aynı açıklamalı veya slug'lı başka bir tarif var
+    Conflict(String),
+    NotFound,
+    // Malzemelerden biri bulunamadı veya onaylanmamış
+    UnknownFood(String),
+}
+
+// slug verilmezse yeni bir tarif oluşturuyor, verilirse o tarifi değiştiriyor. Malzemeler (yemek slug'ı, gram) sırasıyla kaydediliyor
+#[instrument(skip(pool))]
+pub(crate) async fn save_recipe(
+    pool: &SqlitePool,
+    slug: Option<&str>,
+    description: &str,
+    servings: u32,
+    ingredients: &[(String, f64)],
+) -> Result<RecipeWrite, Error> {
+    timed(
+        "save_recipe",
+        &[
+            ("slug", slug.unwrap_or_default()),
+            ("description", description),
+        ],
+        || async move {
+            let mut tx = pool.begin().await?;
+            let id = match slug {
+                Some(slug) => {
+                    let Some(id) =
+                        sqlx::query_scalar::<_, i64>("SELECT id FROM recipes WHERE slug = ?")
+                            .bind(slug)
+                            .fetch_optional(&mut *tx)
+                            .await?
+                    else {
+                        return Ok(RecipeWrite::NotFound);
+                    };
+                    Some(id)
+                }
+                None => None,
+            };
+
+            let new_slug = to_lower_en_kebab_case(description);
+            let existing = sqlx::query_scalar::<_, String>(
+                "SELECT description FROM recipes WHERE (slug = ? OR description = ?) AND id != ?",
+            )
+            .bind(&new_slug)
+            .bind(description)
+            .bind(id.unwrap_or(0))
+            .fetch_optional(&mut *tx)
+            .await?;
+            if let Some(existing) = existing {
+                return Ok(RecipeWrite::Conflict(existing));
+            }
+
+            let mut food_ids = Vec::new();
+            for (food_slug, _) in ingredients {
+                match sqlx::query_scalar::<_, i64>(
+                    "SELECT id FROM foods WHERE slug = ? AND verified = 1",
+                )
+                .bind(food_slug)
+                .fetch_optional(&mut *tx)
+                .await?
+                {
+                    Some(food_id) => food_ids.push(food_id),
+                    None => return Ok(RecipeWrite::UnknownFood(food_slug.clone())),
+                }
+            }
+
+            let id = match id {
+                Some(id) => {
+                    sqlx::query(
+                        "UPDATE recipes SET slug = ?, description = ?, servings = ?,
+                            updated_at = strftime('%s', 'now') WHERE id = ?",
+                    )
+                    .bind(&new_slug)
+                    .bind(description)
+                    .bind(servings)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                    sqlx::query("DELETE FROM recipe_ingredients WHERE recipe_id = ?")
+                        .bind(id)
+                        .execute(&mut *tx)
+                        .await?;
+                    id
+                }
+                None => {
+                    sqlx::query_scalar::<_, i64>(
+                        "INSERT INTO recipes (slug, description, servings) VALUES (?, ?, ?) RETURNING id",
+                    )
+                    .bind(&new_slug)
+                    .bind(description)
+                    .bind(servings)
+                    .fetch_one(&mut *tx)
+                    .await?
+                }
+            };
+            for (position, (food_id, (_, grams))) in food_ids.iter().zip(ingredients).enumerate() {
+                sqlx::query(
+                    "INSERT INTO recipe_ingredients (recipe_id, position, food_id, grams) VALUES (?, ?, ?, ?)",
+                )
+                .bind(id)
+                .bind(position as i64)
+                .bind(food_id)
+                .bind(grams)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(RecipeWrite::Saved(new_slug))
+        },
+    )
+    .await
+}
+
+// Tarif bulunamadıysa false
+#[instrument(skip(pool))]
+pub(crate) async fn delete_recipe(pool: &SqlitePool, slug: &str) -> Result<bool, Error> {
+    timed("delete_recipe", &[("slug", slug)], || async move {
+        let mut tx = pool.begin().await?;
+        let Some(id) = sqlx::query_scalar::<_, i64>("SELECT id FROM recipes WHERE slug = ?")
+            .bind(slug)
+            .fetch_optional(&mut *tx)
+            .await?
+        else {
+            return Ok(false);
+        };
+        sqlx::query("DELETE FROM recipe_ingredients WHERE recipe_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM recipes WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    })
+    .await
+}
+
+// Profil zaten varsa eklenmiyor ve false dönüyor, veritabanında yapılan düzeltmeler açılışta JSON'la ezilmesin
+#[instrument(skip(pool, profile), fields(slug = %profile.slug))]
+pub(crate) async fn insert_reference_intake(
+    pool: &SqlitePool,
+    profile: &ReferenceIntake,
+) -> Result<bool, Error> {
+    profile.validate().map_err(|e| anyhow!(e))?;
+
+    timed(
+        "insert_reference_intake",
+        &[("slug", &profile.slug)],
+        || async move {
+            let mut tx = pool.begin().await?;
+            let Some(profile_id) = sqlx::query_scalar::<_, i64>(
+                "INSERT OR IGNORE INTO reference_intake_profiles (slug, description, source) VALUES (?, ?, ?)
+                 RETURNING id",
+            )
+            .bind(&profile.slug)
+            .bind(&profile.description)
+            .bind(&profile.source)
+            .fetch_optional(&mut *tx)
+            .await?
+            else {
+                return Ok(false);
+            };
+
+            for (nutrient, amount) in &profile.values {
+                sqlx::query(
+                    "INSERT INTO reference_intakes (profile_id, nutrient, amount) VALUES (?, ?, ?)",
+                )
+                .bind(profile_id)
+                .bind(nutrient)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await?;
+            }
+            tx.commit().await?;
+            Ok(true)
+        },
+    )
+    .await
+}
+
+// Değerler de yemeklerin etiketleri gibi tek sorguda JSON nesnesi olarak alınıyor
+macro_rules! select_reference_intake_sql {
+    () => {
+        "
+    SELECT P.slug, P.description, P.source,
+        (SELECT json_group_object(RI.nutrient, RI.amount)
+         FROM reference_intakes RI
+         WHERE RI.profile_id = P.id) AS \"values\"
+    FROM reference_intake_profiles P"
+    };
+}
+
+fn reference_intake_from_row(
+    (slug, description, source, values): (String, String, String, String),
+) -> Result<ReferenceIntake, Error> {
+    Ok(ReferenceIntake {
+        slug,
+        description,
+        source,
+        values: serde_json::from_str(&values)?,
+    })
+}
+
+// Eklenme sırasıyla, varsayılan profiller önce
+#[instrument(skip(pool))]
+pub(crate) async fn select_reference_intakes(
+    pool: &SqlitePool,
+) -> Result<Vec<ReferenceIntake>, Error> {
+    timed("select_reference_intakes", &[], || async move {
+        sqlx::query_as(concat!(select_reference_intake_sql!(), " ORDER BY P.id"))
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(reference_intake_from_row)
+            .collect()
+    })
+    .await
+}
+
+#[instrument(skip(pool))]
+pub(crate) async fn select_reference_intake(
+    pool: &SqlitePool,
+    slug: &str,
+) -> Result<Option<ReferenceIntake>, Error> {
+    timed(
+        "select_reference_intake",
+        &[("slug", slug)],
+        || async move {
+            sqlx::query_as(concat!(select_reference_intake_sql!(), " WHERE P.slug = ?"))
+                .bind(slug)
+                .fetch_optional(pool)
+                .await?
+                .map(reference_intake_from_row)
+                .transpose()
+        },
+    )
+    .await
+}
+
+// Katkı maddesini referans listeye ekliyor, E numarası zaten varsa değiştirmeden false döndürüyor
+#[instrument(skip(pool, additive), fields(e_number = %additive.e_number))]
+pub(crate) async fn insert_additive(pool: &SqlitePool, additive: Additive) -> Result<bool, Error> {
+    let additive = &additive.normalized().map_err(|e| anyhow!(e))?;
+
+    timed(
+        "insert_additive",
+        &[("e_number", &additive.e_number)],
+        || async move {
+            Ok(sqlx::query(
+                "INSERT OR IGNORE INTO additives (e_number, name, category, notes) VALUES (?, ?, ?, ?)",
+            )
+            .bind(&additive.e_number)
+            .bind(&additive.name)
+            .bind(&additive.category)
+            .bind(&additive.notes)
+            .execute(pool)
+            .await?
+            .rows_affected()
+                > 0)
+        },
+    )
+    .await
+}
+
+// Referans listedeki tüm katkı maddeleri, E numarasının sayısal kısmına göre sıralı (E100, E160a, E1422)
+#[instrument(skip(pool))]
+pub(crate) async fn select_additives(pool: &SqlitePool) -> Result<Vec<Additive>, Error> {
+    timed("select_additives", &[], || async move {
+        let additives = sqlx::query_as::<_, (String, String, String, Option<String>)>(
+            "SELECT e_number, name, category, notes FROM additives
+                ORDER BY CAST(SUBSTR(e_number, 2, 4) AS INTEGER), e_number",
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(additives
+            .into_iter()
+            .map(|(e_number, name, category, notes)| Additive {
+                e_number,
+                name,
+                category,
+                notes,
+            })
+            .collect())
+    })
+    .await
+}
+
+// Kategoride olmayan ölçüleri ekliyor ve kaç tanesinin eklendiğini döndürüyor. Kategori etiketler gibi LOWER ile küçültülüyor ki
+// food_tags'teki etiketlerle birebir eşleşsin
+#[instrument(skip(pool, category), fields(category = %category.category))]
+pub(crate) async fn insert_standard_portions(
+    pool: &SqlitePool,
+    category: &PortionCategory,
+) -> Result<u64, Error> {
+    category.validate().map_err(|e| anyhow!(e))?;
+
+    timed(
+        "insert_standard_portions",
+        &[("category", &category.category)],
+        || async move {
+            let mut tx = pool.begin().await?;
+            let mut inserted = 0;
+            for (description, weight) in &category.portions {
+                inserted += sqlx::query(
+                    "INSERT OR IGNORE INTO standard_portions (category, description, weight)
+                        VALUES (LOWER(?), ?, ?)",
+                )
+                .bind(category.category.trim())
+                .bind(description.trim())
+                .bind(weight)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+            }
+            tx.commit().await?;
+            Ok(inserted)
+        },
+    )
+    .await
+}
+
+// Yemeğin etiketleriyle eşleşen kategorilerdeki ölçüler, kategoriye ve gramaja göre sıralı
+#[instrument(skip(pool))]
+pub(crate) async fn select_standard_portions(
+    pool: &SqlitePool,
+    food_id: i64,
+) -> Result<Vec<StandardPortion>, Error> {
+    timed("select_standard_portions", &[], || async move {
+        let portions = sqlx::query_as::<_, (String, f64, String)>(
+            "SELECT SP.description, SP.weight, SP.category FROM standard_portions SP
+                WHERE SP.category IN (
+                    SELECT T.description FROM tags T
+                    INNER JOIN food_tags FT ON T.id = FT.tag_id
+                    WHERE FT.food_id = ?)
+                ORDER BY SP.category, SP.weight, SP.description",
+        )
+        .bind(food_id)
+        .fetch_all(pool)
+        .await?;
+        Ok(portions
+            .into_iter()
+            .map(|(description, weight, category)| StandardPortion {
+                description,
+                weight,
+                category,
+            })
+            .collect())
+    })
+    .await
+}
+
+// Tüm yemeklerin diyet bayraklarını kurallardan yeniden hesaplayıp yazıyor ve kaç yemeğin güncellendiğini döndürüyor. Kurallar
+// config'den geldiği için kaldırılan bayraklar da siliniyor; bayraklar yemeğin diğer verileri gibi sadece açılışta değişiyor
+#[instrument(skip_all)]
+pub(crate) async fn refresh_diet_flags(
+    pool: &SqlitePool,
+    rules: &BTreeMap<String, DietFlagRule>,
+) -> Result<u64, Error> {
+    timed("refresh_diet_flags", &[], || async move {
+        let foods: Vec<Food> = sqlx::query_as(select_food_sql!()).fetch_all(pool).await?;
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM food_diet_flags")
+            .execute(&mut *tx)
+            .await?;
+        for food in &foods {
+            let Some(food_id) = food.id else {
+                continue;
+            };
+            for (flag, value) in diet_flags::evaluate(rules, food) {
+                sqlx::query("INSERT INTO food_diet_flags (food_id, flag, value) VALUES (?, ?, ?)")
+                    .bind(food_id)
+                    .bind(flag)
+                    .bind(value)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        tx.commit().await?;
+        Ok(foods.len() as u64)
+    })
+    .await
+}
+
+// Yemeğin Nutri-Score'unu ve NOVA sınıfını kayıtlı verisinden hesaplayıp saklıyor. Katkı maddelerinin sınıfları gibi JSON'da
+// olmayan bilgiler de gerektiği için yemek veritabanından okunuyor
+async fn update_food_scores(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    food_id: i64,
+) -> Result<(), Error> {
+    let food: Food = sqlx::query_as(concat!(select_food_sql!(), " WHERE F.id = ?"))
+        .bind(food_id)
+        .fetch_one(&mut **tx)
+        .await?;
+    let declared =
+        sqlx::query_scalar::<_, Option<i64>>("SELECT nova_group_declared FROM foods WHERE id = ?")
+            .bind(food_id)
+            .fetch_one(&mut **tx)
+            .await?
+            .and_then(|group| u8::try_from(group).ok());
+
+    let score = nutri_score::compute(&food);
+    sqlx::query(
+        "UPDATE foods SET nutri_score = ?, nutri_score_points = ?, nova_group = ? WHERE id = ?",
+    )
+    .bind(score.grade.name())
+    .bind(score.points)
+    .bind(nova::classify(&food, declared).map(i64::from))
+    .bind(food_id)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+// Hesaplama kuralları değişmiş veya kolonlar yeni eklenmiş olabileceği için açılışta tüm yemeklerin puanları yeniden hesaplanıyor
+#[instrument(skip(pool))]
+pub(crate) async fn refresh_food_scores(pool: &SqlitePool) -> Result<u64, Error> {
+    timed("refresh_food_scores", &[], || async move {
+        let food_ids = sqlx::query_scalar::<_, i64>("SELECT id FROM foods")
+            .fetch_all(pool)
+            .await?;
+        let mut tx = pool.begin().await?;
+        for food_id in &food_ids {
+            update_food_scores(&mut tx, *food_id).await?;
+        }
+        tx.commit().await?;
+        Ok(food_ids.len() as u64)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*; // Üst scope'daki fonksiyonları kullan
+    use crate::core::{
+        browse::NutrientRange,
+        food::{
+            Allergen, ContaminationLevel, FoodImage, FoodLink, Seasonality, VerificationMethod,
+        },
+        nutri_score::Grade,
+        query::SearchQuery,
+    };
+
+    fn terms(query: &str) -> Vec<String> {
+        SearchQuery::parse(query).terms
+    }
+
+    #[tokio::test]
+    async fn test_connect_and_migrate() -> Result<(), Error> {
+        // In-memory veritabanı ile test
+        let _pool = SqlitePool::connect("sqlite::memory:").await?;
+        let _db_pool = connect_database(&DatabaseConfig::default()).await?; // Gerçek dosya tablosu ile test için yorum satırını kaldır
+        info!("Veritabanı bağlantısı ve migration testi geçti.");
+        Ok(())
+    }
+
+    #[test]
+    fn test_truncate_param() {
+        assert_eq!(truncate_param("elma"), "elma");
+        assert_eq!(
+            truncate_param(&"ş".repeat(MAX_LOGGED_PARAM_CHARS + 10)),
+            format!("{}…", "ş".repeat(MAX_LOGGED_PARAM_CHARS))
+        );
+        assert_eq!(format!("{:?}", truncate_param("a\nb")), r#""a\nb""#);
+    }
+
+    #[tokio::test]
+    async fn test_migrations_applied() -> Result<(), Error> {
+        let pool = SqlitePool::connect("sqlite::memory:").await?;
+        assert!(!migrations_applied(&pool).await);
+
+        MIGRATOR.run(&pool).await?;
+        assert!(migrations_applied(&pool).await);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_food_exists_by_description() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let food = test_food("Test Yemek", true);
+
+        insert_food(&pool, food).await?;
+        let exists = food_exists_by_description(&pool, "Test Yemek").await?;
+        assert!(exists);
+
+        let not_exists = food_exists_by_description(&pool, "Nonexistent").await?;
+        assert!(!not_exists);
+
+        info!("food_exists_by_description testi geçti.");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_food_and_load_json() -> Result<(), Error> {
+        // In-memory veritabanı
+        let pool = memory_pool().await?;
+
+        // Test için geçici dizin oluştur
+        let temp_dir = "./db/test_temp";
+        fs::create_dir_all(temp_dir).unwrap();
+
+        // Test JSON dosyası oluştur
+        let test_json = r#"
+            [
+                {
+                    "description": "JSON Test Yemek",
+                    "image": {
+                        "url": "/json_test.jpg",
+                        "attribution": "Test Fotoğrafçı / Wikimedia Commons",
+                        "license": "CC-BY-SA-4.0"
+                    },
+                    "source": "json_source",
+                    "tags": ["json_tag"],
+                    "allergens": [],
+                    "servings": {"Porsiyon": 100},
+                    "glycemic_index": 60.0,
+                    "energy": 120.0,
+                    "carbohydrate": 25.0,
+                    "protein": 6.0,
+                    "fat": 3.0,
+                    "saturated_fat": 1.5,
+                    "trans_fat": 0.0,
+                    "sugar": 12.0,
+                    "added_sugar": 8.0,
+                    "polyols": 1.5,
+                    "fiber": 4.0,
+                    "water": 55.0,
+                    "cholesterol": 0.0,
+                    "sodium": 60.0,
+                    "potassium": 220.0,
+                    "iron": 1.2,
+                    "magnesium": 35.0,
+                    "calcium": 12.0,
+                    "zinc": 0.6,
+                    "vitamin_a": 0.15,
+                    "vitamin_b6": 0.25,
+                    "vitamin_b12": 0.0,
+                    "vitamin_c": 6.0,
+                    "vitamin_d": 0.0,
+                    "vitamin_e": 0.12,
+                    "vitamin_k": 0.06,
+                    "omega_3": 0.1,
+                    "folate": 40.0,
+                    "selenium": 12.0
+                }
+            ]
+        "#;
+        fs::write(format!("{}/test.json", temp_dir), test_json).unwrap();
+
+        // Sadece test dizininden yükle
+        let foods = load_from_jsons::<Food>(temp_dir).await.unwrap();
+        assert_eq!(foods.len(), 1, "Sadece bir yemek yüklenmeli"); // Diğer dosyaları eklemez
+        let food = foods[0].clone();
+
+        // Yemeği ekle
+        let result = insert_food(&pool, food).await;
+        assert!(result.is_ok(), "Yemek eklenemedi");
+        let food_id = result.unwrap().id.unwrap();
+        assert!(food_id > 0, "Geçerli bir ID olmalı");
+        let food = select_food_by_slug(&pool, "json-test-yemek").await?;
+        assert_eq!(
+            (food.sugar, food.added_sugar, food.polyols),
+            (12.0, 8.0, 1.5)
+        );
+        // JSON'da olmayan besin değerleri 0
+        assert_eq!(
+            (food.omega_3, food.omega_6, food.folate, food.selenium),
+            (0.1, 0.0, 40.0, 12.0)
+        );
+        assert_eq!(
+            food.image,
+            FoodImage {
+                url: "/json_test.jpg".to_owned(),
+                attribution: Some("Test Fotoğrafçı / Wikimedia Commons".to_owned()),
+                license: Some("CC-BY-SA-4.0".to_owned()),
+                source_url: None,
+            }
+        );
+
+        info!("insert_food ve load_from_jsons testi geçti.");
+
+        // Testten sonra dosyayı ve dizini sil
+        fs::remove_file(format!("{}/test.json", temp_dir)).unwrap();
+        fs::remove_dir(temp_dir).unwrap();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_load_from_jsons_keeps_file_order() -> Result<(), Error> {
+        let temp_dir = "./db/test_parallel_seed";
+        fs::create_dir_all(temp_dir)?;
+        fs::write(format!("{}/b.json", temp_dir), "[3, 4]")?;
+        fs::write(format!("{}/a.json", temp_dir), "[1, 2]")?;
+        fs::write(format!("{}/c.json", temp_dir), "{bozuk")?;
+        fs::write(format!("{}/d.json", temp_dir), "[5]")?;
+
+        // Bozuk dosya atlanıyor, diğerleri hangisi önce ayrıştırılırsa ayrıştırılsın dosya adı sırasıyla
+        let items = load_from_jsons::<u32>(temp_dir).await;
+        fs::remove_dir_all(temp_dir)?;
+        assert_eq!(items?, vec![1, 2, 3, 4, 5]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_all_foods_slugs() -> Result<(), Error> {
+        // In-memory veritabanı
+        let pool = memory_pool().await?;
+
+        // Test verisi ekle
+        let food1 = test_food("Fuji Elma", true);
+
+        let food2 = test_food("Muz", true);
+
+        // Yemekleri ekle
+        insert_food(&pool, food1).await?;
+        insert_food(&pool, food2).await?;
+
+        // Fonksiyonu çağır
+        let result = select_all_foods_slugs(&pool).await?;
+
+        // Sonuçları doğrula (SLUG kontrolü)
+        assert_eq!(result.len(), 2, "İki yemek slug'ı bekleniyor");
+        assert!(
+            result.contains(&"fuji-elma".to_string()),
+            "İlk slug 'fuji-elma' bulunamadı"
+        );
+        assert!(
+            result.contains(&"muz".to_string()),
+            "İkinci slug 'muz' bulunamadı"
+        );
+
+        // Boş tablo testi
+        sqlx::query("DELETE FROM foods").execute(&pool).await?;
+        let empty_result = select_all_foods_slugs(&pool).await?;
+        assert!(
+            empty_result.is_empty(),
+            "Boş tablo için boş sonuç bekleniyor"
+        );
+
+        info!("select_all_foods_slugs testi geçti.");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_index_matches_database_search() -> Result<(), Error> {
+        use crate::api::search_index::{self, IndexFields, IndexFilter};
+
+        let pool = memory_pool().await?;
+
+        let food = |description: &str, aliases: &[&str], allergens: Vec<Allergen>| Food {
+            aliases: aliases.iter().map(|alias| (*alias).to_owned()).collect(),
+            tags: vec!["meyve".to_owned()],
+            allergens,
+            ..test_food(description, true)
+        };
+        insert_food(&pool, food("Fuji Elma", &[], vec![])).await?;
+        insert_food(&pool, food("Elma", &["apple"], vec![])).await?;
+        insert_food(
+            &pool,
+            food("Elma Turtası", &[], vec![Allergen::contains("gluten")]),
+        )
+        .await?;
+        insert_food(&pool, food("Patlıcan", &["aubergine"], vec![])).await?;
+        insert_food(
+            &pool,
+            Food {
+                verified: Some(false),
+                ..food("Ayva Elma", &[], vec![])
+            },
+        )
+        .await?;
+
+        let entries = select_search_index_entries(&pool).await?;
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[2].description, "elma turtasi");
+        assert_eq!(entries[2].allergens, vec!["gluten"]);
+        assert_eq!(entries[3].aliases, vec!["aubergine"]);
+
+        let ids = |foods: Vec<Food>| -> Vec<i64> {
+            foods.into_iter().filter_map(|food| food.id).collect()
+        };
+        for (query, allergen_free) in [
+            ("elma", vec![]),
+            ("elma", vec!["Gluten"]),
+            ("apple", vec![]),
+            ("meyve", vec![]),
+            ("tur elma", vec![]),
+        ] {
+            let terms: Vec<String> = query.split(' ').map(str::to_owned).collect();
+            let filter = IndexFilter::new(&allergen_free, 0.0, false, &[]);
+            for (fields, expected) in [
+                (
+                    IndexFields::Names,
+                    search_foods_by_description_wild(
+                        &pool,
+                        &terms,
+                        &allergen_free,
+                        0.0,
+                        false,
+                        &[],
+                        10,
+                    )
+                    .await?,
+                ),
+                (
+                    IndexFields::All,
+                    search_foods_by_any_field(&pool, &terms, &allergen_free, 0.0, false, &[], 10)
+                        .await?,
+                ),
+            ] {
+                let found: Vec<i64> =
+                    search_index::candidates(&entries, &terms, fields, &filter, 10)
+                        .into_iter()
+                        .map(|entry| entry.id)
+                        .collect();
+                assert_eq!(found, ids(expected), "{} {:?}", query, fields);
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_food_summaries() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let food = |description: &str, verified: bool| Food {
+            image: FoodImage {
+                url: format!("/images/{}.webp", to_lower_en_kebab_case(description)),
+                license: Some("CC-BY-4.0".to_owned()),
+                ..Default::default()
+            },
+            tags: vec!["meyve".to_owned()],
+            ..test_food(description, verified)
+        };
+        insert_food(&pool, food("Muz", true)).await?;
+        insert_food(&pool, food("Fuji Elma", true)).await?;
+        insert_food(&pool, food("Ayva", false)).await?;
+
+        // Onaylanmamışlar yok, slug sırasıyla
+        assert_eq!(
+            select_food_summaries(&pool).await?,
+            vec![
+                FoodSummary {
+                    slug: "fuji-elma".to_owned(),
+                    description: "Fuji Elma".to_owned(),
+                    image: FoodImage {
+                        url: "/images/fuji-elma.webp".to_owned(),
+                        license: Some("CC-BY-4.0".to_owned()),
+                        ..Default::default()
+                    },
+                },
+                FoodSummary {
+                    slug: "muz".to_owned(),
+                    description: "Muz".to_owned(),
+                    image: FoodImage {
+                        url: "/images/muz.webp".to_owned(),
+                        license: Some("CC-BY-4.0".to_owned()),
+                        ..Default::default()
+                    },
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_ignores_turkish_characters_and_case() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        insert_food(
+            &pool,
+            Food {
+                tags: vec!["Sığır Değil".to_owned()],
+                ..test_food("Tavuk Göğsü (Çiğ)", true)
+            },
+        )
+        .await?;
+
+        for query in ["gogsu", "TAVUK GÖĞSÜ", "cig", "ÇİĞ"] {
+            let foods =
+                search_foods_by_description_wild(&pool, &terms(query), &[], 0.0, false, &[], 200)
+                    .await?;
+            assert_eq!(foods.len(), 1, "{} araması yemeği bulamadı", query);
+        }
+        assert_eq!(
+            search_foods_by_tag_wild(&pool, &["SIGIR"], true, &[], 0.0, false, &[], 10, 0)
+                .await?
+                .1,
+            1
+        );
+        assert!(
+            search_foods_by_description_wild(&pool, &terms("sut"), &[], 0.0, false, &[], 200)
+                .await?
+                .is_empty()
+        );
+        // Tırnaksız kelimeler ayrı ayrı aranıyor, tırnaklı ifade olduğu gibi
+        assert_eq!(
+            search_foods_by_description_wild(
+                &pool,
+                &terms("göğsü tavuk"),
+                &[],
+                0.0,
+                false,
+                &[],
+                200
+            )
+            .await?
+            .len(),
+            1
+        );
+        assert!(
+            search_foods_by_description_wild(
+                &pool,
+                &terms("\"göğsü tavuk\""),
+                &[],
+                0.0,
+                false,
+                &[],
+                200
+            )
+            .await?
+            .is_empty()
+        );
+        assert!(
+            search_foods_by_description_wild(&pool, &terms("tavuk but"), &[], 0.0, false, &[], 200)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_searches_skip_unverified_foods() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        for (description, verified) in [("Elma", true), ("Elma Turtası", false)] {
+            insert_food(
+                &pool,
+                Food {
+                    tags: vec!["meyve".to_owned()],
+                    ..test_food(description, verified)
+                },
+            )
+            .await?;
+        }
+
+        // Onaylanmamış yemekler SQL'de eleniyor, limit de sadece onaylanmışlara uygulanıyor
+        let (foods, total) =
+            search_foods_by_tag_wild(&pool, &["meyve"], true, &[], 0.0, false, &[], 1, 0).await?;
+        assert_eq!((foods.len(), total), (1, 1));
+        assert_eq!(foods[0].description, "Elma");
+        let foods =
+            search_foods_by_description_wild(&pool, &terms("elma"), &[], 0.0, false, &[], 1)
+                .await?;
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].description, "Elma");
+        assert_eq!(
+            search_foods_by_any_field(&pool, &terms("elma"), &[], 0.0, false, &[], 200)
+                .await?
+                .len(),
+            1
+        );
+        assert_eq!(
+            select_verified_foods(&pool, &[], 0.0, false, &[])
+                .await?
+                .len(),
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_for_each_verified_food() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        for (description, verified) in [("Muz", true), ("Armut", false), ("Elma", true)] {
+            insert_food(
+                &pool,
+                Food {
+                    ..test_food(description, verified)
+                },
+            )
+            .await?;
+        }
+
+        // Onaylanmamışlar atlanıyor, slug sırasıyla okunuyor
+        let mut slugs = Vec::new();
+        let count = for_each_verified_food(&pool, None, |food| {
+            slugs.push(food.slug.unwrap_or_default());
+            async { true }
+        })
+        .await?;
+        assert_eq!(count, 2);
+        assert_eq!(slugs, vec!["elma", "muz"]);
+
+        // Yarıda kalan aktarım son gönderilen slug'dan sonra devam ediyor
+        let mut slugs = Vec::new();
+        let count = for_each_verified_food(&pool, Some("elma"), |food| {
+            slugs.push(food.slug.unwrap_or_default());
+            async { true }
+        })
+        .await?;
+        assert_eq!(count, 1);
+        assert_eq!(slugs, vec!["muz"]);
+        assert_eq!(
+            for_each_verified_food(&pool, Some("muz"), |_| async { true }).await?,
+            0
+        );
+
+        // false dönünce okuma bırakılıyor
+        let count = for_each_verified_food(&pool, None, |_| async { false }).await?;
+        assert_eq!(count, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_completeness_filter() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        // 23 besin değerinden Elma'nın 23'ü, Elma Suyu'nun 2'si ölçülmüş
+        let measured = Food {
+            energy: 52.0,
+            carbohydrate: 14.0,
+            protein: 0.3,
+            fat: 0.2,
+            saturated_fat: 0.03,
+            trans_fat: 0.01,
+            sugar: 10.0,
+            fiber: 2.4,
+            cholesterol: 0.1,
+            sodium: 1.0,
+            potassium: 107.0,
+            water: 86.0,
+            iron: 0.12,
+            magnesium: 5.0,
+            calcium: 6.0,
+            zinc: 0.04,
+            vitamin_a: 0.003,
+            vitamin_b6: 0.04,
+            vitamin_b12: 0.01,
+            vitamin_c: 4.6,
+            vitamin_d: 0.01,
+            vitamin_e: 0.18,
+            vitamin_k: 0.002,
+            ..Default::default()
+        };
+        let sparse = Food {
+            energy: 46.0,
+            sugar: 9.6,
+            ..Default::default()
+        };
+        for (description, values) in [("Elma", measured), ("Elma Suyu", sparse)] {
+            insert_food(
+                &pool,
+                Food {
+                    description: description.to_owned(),
+                    image: FoodImage::new(&format!("/images/{}.webp", description)),
+                    source: "Test".to_owned(),
+                    tags: vec!["meyve".to_owned()],
+                    ..values
+                },
+            )
+            .await?;
+        }
+
+        let foods =
+            search_foods_by_description_wild(&pool, &terms("elma"), &[], 0.0, false, &[], 200)
+                .await?;
+        assert_eq!(
+            foods
+                .iter()
+                .map(|food| (food.description.as_str(), food.completeness))
+                .collect::<Vec<_>>(),
+            vec![("Elma", 1.0), ("Elma Suyu", 0.09)]
+        );
+        assert_eq!(
+            search_foods_by_description_wild(&pool, &terms("elma"), &[], 0.8, false, &[], 200)
+                .await?
+                .len(),
+            1
+        );
+        let (foods, total) =
+            search_foods_by_tag_wild(&pool, &["meyve"], true, &[], 0.8, false, &[], 10, 0).await?;
+        assert_eq!((foods.len(), total), (1, 1));
+        assert_eq!(
+            search_foods_by_any_field(&pool, &terms("elma"), &[], 0.8, false, &[], 200)
+                .await?
+                .len(),
+            1
+        );
+        assert_eq!(
+            select_verified_foods(&pool, &[], 0.8, false, &[])
+                .await?
+                .len(),
+            1
+        );
+        let filter = BrowseFilter {
+            ranges: vec![NutrientRange {
+                nutrient: "completeness",
+                min: Some(0.8),
+                max: None,
+            }],
+            ..Default::default()
+        };
+        let (foods, total) = browse_foods(&pool, &filter, BrowseSort::default(), 10, 0).await?;
+        assert_eq!((foods.len(), total), (1, 1));
+        assert_eq!(foods[0].description, "Elma");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nutri_score_and_nova() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+        insert_additive(
+            &pool,
+            Additive {
+                e_number: "E951".to_owned(),
+                name: "Aspartam".to_owned(),
+                category: "tatlandırıcı".to_owned(),
+                notes: None,
+            },
+        )
+        .await?;
+
+        let food = |description: &str, tags: &[&str], energy: f64, sugar: f64| Food {
+            tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            energy,
+            sugar,
+            fiber: 0.4,
+            ..test_food(description, true)
+        };
+        insert_food(&pool, food("Elma", &["meyve"], 52.0, 10.4)).await?;
+        insert_food(
+            &pool,
+            Food {
+                additives: vec![Additive {
+                    e_number: "E951".to_owned(),
+                    name: String::new(),
+                    category: String::new(),
+                    notes: None,
+                }],
+                ..food("Şekerleme", &["atıştırmalık"], 400.0, 60.0)
+            },
+        )
+        .await?;
+        insert_food(
+            &pool,
+            Food {
+                nova_group: Some(3),
+                ..food("Ev Ekmeği", &["ana öğün"], 250.0, 3.0)
+            },
+        )
+        .await?;
+        insert_food(&pool, food("Pilav", &["ana öğün"], 130.0, 0.1)).await?;
+        assert!(
+            insert_food(
+                &pool,
+                Food {
+                    fruit_vegetable_percent: Some(120.0),
+                    ..food("Reçel", &["meyve"], 250.0, 60.0)
+                }
+            )
+            .await
+            .is_err()
+        );
+
+        let scores = |foods: Vec<Food>| {
+            foods
+                .into_iter()
+                .map(|food| {
+                    (
+                        food.description,
+                        food.nutri_score.map(|score| score.grade),
+                        food.nova_group,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let sort = BrowseSort::parse(Some("nutri_score"), None).unwrap();
+        let filter = BrowseFilter {
+            nutri_scores: vec![Grade::A, Grade::D],
+            ..Default::default()
+        };
+        let (foods, total) = browse_foods(&pool, &filter, sort, 10, 0).await?;
+        assert_eq!(total, 2);
+        assert_eq!(
+            scores(foods),
+            vec![
+                ("Elma".to_owned(), Some(Grade::A), Some(1)),
+                ("Şekerleme".to_owned(), Some(Grade::D), Some(4)),
+            ]
+        );
+
+        // Sınıfı tahmin edilemeyen Pilav NOVA'ya göre sıralamada sonda
+        let filter = BrowseFilter {
+            tags: vec!["ana ogun".to_owned()],
+            ..Default::default()
+        };
+        let sort = BrowseSort::parse(Some("nova_group"), Some("desc")).unwrap();
+        let (foods, _) = browse_foods(&pool, &filter, sort, 10, 0).await?;
+        assert_eq!(
+            scores(foods)
+                .into_iter()
+                .map(|(description, _, nova_group)| (description, nova_group))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Ev Ekmeği".to_owned(), Some(3)),
+                ("Pilav".to_owned(), None)
+            ]
+        );
+
+        // Açılıştaki yeniden hesaplama yazılan sınıfı koruyor
+        sqlx::query(
+            "UPDATE foods SET nutri_score = NULL, nutri_score_points = NULL, nova_group = NULL",
+        )
+        .execute(&pool)
+        .await?;
+        assert_eq!(refresh_food_scores(&pool).await?, 4);
+        assert_eq!(
+            select_food_by_slug(&pool, "ev-ekmegi").await?.nova_group,
+            Some(3)
+        );
+        assert_eq!(
+            select_food_by_slug(&pool, "elma")
+                .await?
+                .nutri_score
+                .map(|score| score.points),
+            Some(-3)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_food_variants() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        for (description, variant_group, verified) in [
+            ("Patates (Çiğ)", Some("Patates"), true),
+            ("Patates (Haşlanmış)", Some("patates "), true),
+            ("Patates (Kızartma)", Some("PATATES"), true),
+            ("Patates (Fırın)", Some("patates"), false),
+            ("Tatlı Patates", None, true),
+        ] {
+            insert_food(
+                &pool,
+                Food {
+                    variant_group: variant_group.map(str::to_owned),
+                    ..test_food(description, verified)
+                },
+            )
+            .await?;
+        }
+
+        // Grup adı normalize ediliyor, yemeğin kendisi ve onaylanmamış haller dönmüyor
+        let food = select_food_by_slug(&pool, "patates-cig").await?;
+        assert_eq!(food.variant_group.as_deref(), Some("patates"));
+        let variants = select_food_variants(&pool, "patates", food.id.unwrap()).await?;
+        assert_eq!(
+            variants
+                .iter()
+                .map(|variant| variant.slug.as_str())
+                .collect::<Vec<_>>(),
+            vec!["patates-haslanmis", "patates-kizartma"]
+        );
+        assert_eq!(
+            select_food_by_slug(&pool, "tatli-patates")
+                .await?
+                .variant_group,
+            None
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_generic_food_link() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let food = |description: &str, brand: Option<&str>, generic: Option<&str>| Food {
+            tags: vec!["kahvaltılık".to_owned()],
+            brand: brand.map(str::to_owned),
+            generic_food: generic.map(|slug| FoodLink {
+                slug: slug.to_owned(),
+                description: String::new(),
+            }),
+            ..test_food(description, true)
+        };
+        insert_food(&pool, food("Yulaf Ezmesi", None, None)).await?;
+        // Genel karşılık açıklamasıyla da verilebiliyor
+        insert_food(
+            &pool,
+            food("Marka Yulaf Ezmesi", Some("Marka"), Some("Yulaf Ezmesi")),
+        )
+        .await?;
+        // Genel karşılığı olmayan markalı ürün eklenmiyor
+        assert!(
+            insert_food(&pool, food("Marka Müsli", Some("Marka"), Some("musli")))
+                .await
+                .is_err()
+        );
+
+        let branded = select_food_by_slug(&pool, "marka-yulaf-ezmesi").await?;
+        assert_eq!(branded.brand.as_deref(), Some("Marka"));
+        assert_eq!(
+            branded.generic_food,
+            Some(FoodLink {
+                slug: "yulaf-ezmesi".to_owned(),
+                description: "Yulaf Ezmesi".to_owned(),
+            })
+        );
+        assert_eq!(
+            select_food_by_slug(&pool, "yulaf-ezmesi")
+                .await?
+                .generic_food,
+            None
+        );
+
+        // include_branded=false markalı ürünleri dışlıyor
+        assert_eq!(
+            search_foods_by_description_wild(&pool, &terms("yulaf"), &[], 0.0, false, &[], 200)
+                .await?
+                .len(),
+            2
+        );
+        let foods =
+            search_foods_by_description_wild(&pool, &terms("yulaf"), &[], 0.0, true, &[], 200)
+                .await?;
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].description, "Yulaf Ezmesi");
+        let (_, total) =
+            search_foods_by_tag_wild(&pool, &["kahvaltılık"], true, &[], 0.0, true, &[], 10, 0)
+                .await?;
+        assert_eq!(total, 1);
+        let filter = BrowseFilter {
+            tags: vec!["kahvaltilik".to_owned()],
+            exclude_branded: true,
+            ..Default::default()
+        };
+        let (_, total) = browse_foods(&pool, &filter, BrowseSort::default(), 10, 0).await?;
+        assert_eq!(total, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_diet_flags() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let food = |description: &str, tags: &[&str], allergens: &[&str]| Food {
+            tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            allergens: allergens
+                .iter()
+                .map(|allergen| Allergen::contains(allergen))
+                .collect(),
+            ..test_food(description, true)
+        };
+        insert_food(&pool, food("Mercimek Çorbası", &["çorba"], &[])).await?;
+        insert_food(&pool, food("Yoğurt Çorbası", &["çorba"], &["süt"])).await?;
+        insert_food(&pool, food("Tavuk Çorbası", &["çorba", "et"], &["gluten"])).await?;
+
+        let rules = diet_flags::default_rules();
+        assert_eq!(refresh_diet_flags(&pool, &rules).await?, 3);
+        let yogurt = select_food_by_slug(&pool, "yogurt-corbasi").await?;
+        assert_eq!(yogurt.diet_flags, diet_flags::evaluate(&rules, &yogurt));
+        assert_eq!(
+            (yogurt.diet_flags["vegetarian"], yogurt.diet_flags["vegan"]),
+            (true, false)
+        );
+
+        let descriptions = |foods: Vec<Food>| {
+            foods
+                .into_iter()
+                .map(|food| food.description)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            descriptions(
+                search_foods_by_description_wild(
+                    &pool,
+                    &terms("corba"),
+                    &[],
+                    0.0,
+                    false,
+                    &["vegetarian"],
+                    200
+                )
+                .await?
+            ),
+            vec!["Yoğurt Çorbası", "Mercimek Çorbası"]
+        );
+        assert_eq!(
+            descriptions(
+                select_verified_foods(&pool, &[], 0.0, false, &["vegan", "gluten_free"]).await?
+            ),
+            vec!["Mercimek Çorbası"]
+        );
+        let (_, total) = search_foods_by_tag_wild(
+            &pool,
+            &["çorba"],
+            true,
+            &[],
+            0.0,
+            false,
+            &["gluten_free"],
+            10,
+            0,
+        )
+        .await?;
+        assert_eq!(total, 2);
+        let filter = BrowseFilter {
+            diet_flags: vec!["lactose_free".to_owned()],
+            ..Default::default()
+        };
+        let (foods, total) = browse_foods(&pool, &filter, BrowseSort::default(), 10, 0).await?;
+        assert_eq!(total, 2);
+        assert_eq!(
+            descriptions(foods),
+            vec!["Mercimek Çorbası", "Tavuk Çorbası"]
+        );
+
+        // Kaldırılan kuralların bayrakları da siliniyor
+        let rules = BTreeMap::from([("vegan".to_owned(), rules["vegan"].clone())]);
+        refresh_diet_flags(&pool, &rules).await?;
+        assert_eq!(
+            select_food_by_slug(&pool, "mercimek-corbasi")
+                .await?
+                .diet_flags,
+            BTreeMap::from([("vegan".to_owned(), true)])
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_food_aliases() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let food = |description: &str, aliases: &[&str]| Food {
+            aliases: aliases.iter().map(|alias| (*alias).to_owned()).collect(),
+            tags: vec!["sebze".to_owned()],
+            ..test_food(description, true)
+        };
+        // Açıklamayla aynı ad ve tekrarlar eklenmiyor
+        insert_food(
+            &pool,
+            food(
+                "Patlıcan",
+                &["Aubergine", "aubergine", "patlican", "Eggplant"],
+            ),
+        )
+        .await?;
+        insert_food(&pool, food("Lahana", &["Kelem"])).await?;
+        assert_eq!(
+            select_food_by_slug(&pool, "patlican").await?.aliases,
+            vec!["Aubergine", "Eggplant"]
+        );
+
+        let descriptions = |foods: Vec<Food>| {
+            foods
+                .into_iter()
+                .map(|food| food.description)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            descriptions(
+                search_foods_by_description_wild(
+                    &pool,
+                    &terms("AUBERG"),
+                    &[],
+                    0.0,
+                    false,
+                    &[],
+                    200
+                )
+                .await?
+            ),
+            vec!["Patlıcan"]
+        );
+        assert_eq!(
+            descriptions(
+                search_foods_by_any_field(&pool, &terms("kelem"), &[], 0.0, false, &[], 200)
+                    .await?
+            ),
+            vec!["Lahana"]
+        );
+        // Parçalar farklı adlara dağılmamalı
+        assert!(
+            search_foods_by_description_wild(
+                &pool,
+                &terms("aubergine lahana"),
+                &[],
+                0.0,
+                false,
+                &[],
+                200
+            )
+            .await?
+            .is_empty()
+        );
+
+        let aliases = vec!["Kara Lahana".to_owned(), "lahana".to_owned()];
+        assert_eq!(
+            replace_food_aliases(&pool, "lahana", &aliases).await?,
+            Some(vec!["Kara Lahana".to_owned()])
+        );
+        assert_eq!(
+            select_food_aliases(&pool, "lahana").await?,
+            Some(vec!["Kara Lahana".to_owned()])
+        );
+        assert!(
+            search_foods_by_any_field(&pool, &terms("kelem"), &[], 0.0, false, &[], 200)
+                .await?
+                .is_empty()
+        );
+        assert_eq!(replace_food_aliases(&pool, "yok", &aliases).await?, None);
+        assert_eq!(select_food_aliases(&pool, "yok").await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_food_additives() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let additive = |e_number: &str, name: &str, category: &str| Additive {
+            e_number: e_number.to_owned(),
+            name: name.to_owned(),
+            category: category.to_owned(),
+            notes: None,
+        };
+        assert!(
+            insert_additive(
+                &pool,
+                additive("E330", "Sitrik asit", "asitlik düzenleyici")
+            )
+            .await?
+        );
+        assert!(
+            insert_additive(
+                &pool,
+                additive("e 1422", "Modifiye nişasta", "Modifiye Nişasta")
+            )
+            .await?
+        );
+        assert!(insert_additive(&pool, additive("E202", "Potasyum sorbat", "koruyucu")).await?);
+        // Var olan E numarası değişmiyor, geçersiz olan eklenmiyor
+        assert!(!insert_additive(&pool, additive("E330", "Başka", "koruyucu")).await?);
+        assert!(
+            insert_additive(&pool, additive("330", "Sitrik asit", "koruyucu"))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            select_additives(&pool)
+                .await?
+                .into_iter()
+                .map(|additive| (additive.e_number, additive.category))
+                .collect::<Vec<_>>(),
+            vec![
+                ("E202".to_owned(), "koruyucu".to_owned()),
+                ("E330".to_owned(), "asitlik düzenleyici".to_owned()),
+                ("E1422".to_owned(), "modifiye nişasta".to_owned()),
+            ]
+        );
+
+        let food = |description: &str, additives: &[&str]| Food {
+            additives: additives
+                .iter()
+                .map(|e_number| Additive {
+                    e_number: (*e_number).to_owned(),
+                    ..additive("", "", "")
+                })
+                .collect(),
+            ..test_food(description, true)
+        };
+        insert_food(&pool, food("Meyve Suyu", &["e330", "E202"])).await?;
+        assert_eq!(
+            select_food_by_slug(&pool, "meyve-suyu").await?.additives,
+            vec![
+                additive("E202", "Potasyum sorbat", "koruyucu"),
+                additive("E330", "Sitrik asit", "asitlik düzenleyici"),
+            ]
+        );
+        assert!(insert_food(&pool, food("Gazoz", &["E999"])).await.is_err());
+        assert!(!food_exists_by_description(&pool, "Gazoz").await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_food_verifications() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let verification = |verified_by: &str, verified_at: &str, method| Verification {
+            verified_by: verified_by.to_owned(),
+            verified_at: verified_at.to_owned(),
+            method,
+            note: None,
+        };
+        // JSON'daki doğrulama UTC'ye çevrilip kaydediliyor
+        insert_food(
+            &pool,
+            Food {
+                verification: Some(verification(
+                    " Editör ",
+                    "2026-03-01T12:00:00+03:00",
+                    VerificationMethod::Literature,
+                )),
+                ..test_food("Mercimek", true)
+            },
+        )
+        .await?;
+        assert_eq!(
+            select_food_by_slug(&pool, "mercimek").await?.verification,
+            Some(verification(
+                "Editör",
+                "2026-03-01T09:00:00Z",
+                VerificationMethod::Literature
+            ))
+        );
+
+        insert_food(
+            &pool,
+            Food {
+                ..test_food("Nohut", false)
+            },
+        )
+        .await?;
+        assert_eq!(
+            select_food_by_slug(&pool, "nohut").await?.verification,
+            None
+        );
+
+        // Sonradan eklenen doğrulama yemeği onaylıyor ve en yenisi gösteriliyor
+        let lab = verification("Lab", "2026-05-01T00:00:00Z", VerificationMethod::LabData);
+        assert!(insert_food_verification(&pool, "nohut", &lab).await?);
+        let older = verification(
+            "Editör",
+            "2026-04-01T00:00:00Z",
+            VerificationMethod::Estimated,
+        );
+        assert!(insert_food_verification(&pool, "nohut", &older).await?);
+        let chickpea = select_food_by_slug(&pool, "nohut").await?;
+        assert_eq!(chickpea.verified, Some(true));
+        assert_eq!(chickpea.verification, Some(lab.clone()));
+        assert!(!insert_food_verification(&pool, "yok", &lab).await?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_food_enrichment() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let food = |description: &str, gtin: Option<&str>| Food {
+            gtin: gtin.map(str::to_owned),
+            ..test_food(description, true)
+        };
+        let food_id = insert_food(&pool, food("Marka Yulaf Ezmesi", Some("8690504001010")))
+            .await?
+            .id
+            .unwrap();
+        insert_food(&pool, food("Yulaf Ezmesi", None)).await?;
+        // Kontrol hanesi yanlış veya barkod başka bir üründe
+        assert!(
+            insert_food(&pool, food("Marka Müsli", Some("8690504001019")))
+                .await
+                .is_err()
+        );
+        assert!(
+            insert_food(&pool, food("Marka Müsli", Some("8690504001010")))
+                .await
+                .is_err()
+        );
+        assert_eq!(
+            select_food_by_slug(&pool, "marka-yulaf-ezmesi")
+                .await?
+                .gtin
+                .as_deref(),
+            Some("8690504001010")
+        );
+
+        assert_eq!(
+            select_foods_to_enrich(&pool, "2026-10-01T00:00:00Z").await?,
+            vec![(food_id, "8690504001010".to_owned())]
+        );
+        let mut enrichment = Enrichment {
+            source: "Open Food Facts".to_owned(),
+            url: "https://world.openfoodfacts.org/product/8690504001010".to_owned(),
+            license: "ODbL-1.0".to_owned(),
+            found: false,
+            image_url: None,
+            ingredients: None,
+            nutri_score: None,
+            fetched_at: "2026-10-17T00:00:00Z".to_owned(),
+        };
+        save_food_enrichment(&pool, food_id, &enrichment).await?;
+        // Bulunamadı olarak kaydedilen ürün gösterilmiyor ama eskiyene kadar tekrar sorulmuyor
+        assert_eq!(select_food_enrichment(&pool, food_id).await?, None);
+        assert!(
+            select_foods_to_enrich(&pool, "2026-10-01T00:00:00Z")
+                .await?
+                .is_empty()
+        );
+        assert_eq!(
+            select_foods_to_enrich(&pool, "2026-11-16T00:00:00Z")
+                .await?
+                .len(),
+            1
+        );
+
+        enrichment.found = true;
+        enrichment.nutri_score = Some("a".to_owned());
+        save_food_enrichment(&pool, food_id, &enrichment).await?;
+        assert_eq!(
+            select_food_enrichment(&pool, food_id).await?,
+            Some(enrichment)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_standard_portions() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let category = |category: &str, portions: &[(&str, f64)]| PortionCategory {
+            category: category.to_owned(),
+            portions: portions
+                .iter()
+                .map(|(description, weight)| ((*description).to_owned(), *weight))
+                .collect(),
+        };
+        assert_eq!(
+            insert_standard_portions(
+                &pool,
+                &category("Meyve", &[("1 adet (orta)", 150.0), ("1 avuç", 40.0)])
+            )
+            .await?,
+            2
+        );
+        // Var olan ölçüler değişmiyor
+        assert_eq!(
+            insert_standard_portions(&pool, &category("meyve", &[("1 avuç", 50.0)])).await?,
+            0
+        );
+        insert_standard_portions(&pool, &category("ekmek", &[("1 dilim", 50.0)])).await?;
+        assert!(
+            insert_standard_portions(&pool, &category("tatlı", &[("1 dilim", -1.0)]))
+                .await
+                .is_err()
+        );
+
+        let food_id = insert_food(
+            &pool,
+            Food {
+                tags: vec!["meyve".to_owned(), "tatlı".to_owned()],
+                ..test_food("Elma", true)
+            },
+        )
+        .await?
+        .id
+        .unwrap();
+        assert_eq!(
+            select_standard_portions(&pool, food_id).await?,
+            vec![
+                StandardPortion {
+                    description: "1 avuç".to_owned(),
+                    weight: 40.0,
+                    category: "meyve".to_owned(),
+                },
+                StandardPortion {
+                    description: "1 adet (orta)".to_owned(),
+                    weight: 150.0,
+                    category: "meyve".to_owned(),
+                },
+            ]
+        );
+        Ok(())
+    }
 
     #[tokio::test]
-    async fn test_connect_and_migrate() -> Result<(), Error> {
-        // In-memory veritabanı ile test
-        let _pool = SqlitePool::connect("sqlite::memory:").await?;
-        let _db_pool = connect_database().await?; // Gerçek dosya tablosu ile test için yorum satırını kaldır
-        info!("Veritabanı bağlantısı ve migration testi geçti.");
+    async fn test_select_foods_in_season() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let season = |region: &str, months: &[u8]| Seasonality {
+            region: region.to_owned(),
+            months: months.to_vec(),
+        };
+        for (description, seasonality) in [
+            (
+                "Karpuz",
+                vec![season("Türkiye", &[8, 6, 7]), season("Akdeniz", &[5, 6])],
+            ),
+            ("Portakal", vec![season("Türkiye", &[11, 12, 1])]),
+            ("Yulaf Ezmesi", Vec::new()),
+        ] {
+            insert_food(
+                &pool,
+                Food {
+                    seasonality,
+                    ..test_food(description, true)
+                },
+            )
+            .await?;
+        }
+
+        // Aylar sıralı, bölgeler alfabetik dönüyor
+        let karpuz = select_food_by_slug(&pool, "karpuz").await?;
+        assert_eq!(
+            karpuz.seasonality,
+            vec![season("Akdeniz", &[5, 6]), season("Türkiye", &[6, 7, 8])]
+        );
+        assert!(
+            select_food_by_slug(&pool, "yulaf-ezmesi")
+                .await?
+                .seasonality
+                .is_empty()
+        );
+
+        let (foods, total) = select_foods_in_season(&pool, 6, None, 10, 0).await?;
+        assert_eq!((foods.len(), total), (1, 1));
+        assert_eq!(foods[0].description, "Karpuz");
+        let (foods, total) = select_foods_in_season(&pool, 1, Some("TURKIYE"), 10, 0).await?;
+        assert_eq!((foods.len(), total), (1, 1));
+        assert_eq!(foods[0].description, "Portakal");
+        assert_eq!(
+            select_foods_in_season(&pool, 7, Some("akdeniz"), 10, 0)
+                .await?
+                .1,
+            0
+        );
+
+        // Geçersiz ay yemeği hiç eklemiyor
+        let invalid = Food {
+            seasonality: vec![season("Türkiye", &[5, 13])],
+            ..test_food("Çilek", true)
+        };
+        assert!(insert_food(&pool, invalid).await.is_err());
+        assert!(select_food_by_slug(&pool, "cilek").await.is_err());
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_food_exists_by_description() -> Result<(), Error> {
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+    async fn test_search_foods_by_multiple_tags() -> Result<(), Error> {
+        let pool = memory_pool().await?;
 
-        let food = Food {
-            slug: Some("test-yemek".to_string()),
-            description: "Test Yemek".to_string(),
-            image_url: "/test.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["test".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
-            glycemic_index: 50.0,
-            energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
-            verified: None,
-            id: None,
+        for (description, tags) in [
+            ("Lahana", vec!["sebze", "kışlık"]),
+            ("Karpuz", vec!["meyve", "yaz"]),
+            ("Portakal", vec!["meyve", "kış"]),
+            ("Havuç", vec!["sebze", "kış"]),
+        ] {
+            insert_food(
+                &pool,
+                Food {
+                    tags: tags.into_iter().map(str::to_owned).collect(),
+                    ..test_food(description, true)
+                },
+            )
+            .await?;
+        }
+
+        let descriptions = |(foods, _): (Vec<Food>, u64)| -> Vec<String> {
+            let mut descriptions: Vec<String> =
+                foods.into_iter().map(|food| food.description).collect();
+            descriptions.sort();
+            descriptions
+        };
+        assert_eq!(
+            descriptions(
+                search_foods_by_tag_wild(
+                    &pool,
+                    &["meyve", "yaz"],
+                    true,
+                    &[],
+                    0.0,
+                    false,
+                    &[],
+                    10,
+                    0
+                )
+                .await?
+            ),
+            vec!["Karpuz"]
+        );
+        assert_eq!(
+            descriptions(
+                search_foods_by_tag_wild(
+                    &pool,
+                    &["meyve", "kis"],
+                    true,
+                    &[],
+                    0.0,
+                    false,
+                    &[],
+                    10,
+                    0
+                )
+                .await?
+            ),
+            vec!["Portakal"]
+        );
+        assert_eq!(
+            descriptions(
+                search_foods_by_tag_wild(
+                    &pool,
+                    &["yaz", "sebze"],
+                    false,
+                    &[],
+                    0.0,
+                    false,
+                    &[],
+                    10,
+                    0
+                )
+                .await?
+            ),
+            vec!["Havuç", "Karpuz", "Lahana"]
+        );
+        assert!(
+            search_foods_by_tag_wild(&pool, &["yaz", "sebze"], true, &[], 0.0, false, &[], 10, 0)
+                .await?
+                .0
+                .is_empty()
+        );
+        assert!(
+            search_foods_by_tag_wild(&pool, &[], false, &[], 0.0, false, &[], 10, 0)
+                .await?
+                .0
+                .is_empty()
+        );
+
+        // Etiketi birebir eşleşenler, önce eklenmiş olsa da sadece sorguyla başlayan etiketin önünde
+        let (foods, total) =
+            search_foods_by_tag_wild(&pool, &["kış"], true, &[], 0.0, false, &[], 10, 0).await?;
+        assert_eq!(total, 3);
+        assert_eq!(
+            foods
+                .iter()
+                .map(|food| food.description.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Portakal", "Havuç", "Lahana"]
+        );
+        // Sayfalama SQL'de, toplam sayfadan bağımsız
+        let (foods, total) =
+            search_foods_by_tag_wild(&pool, &["kış"], true, &[], 0.0, false, &[], 1, 1).await?;
+        assert_eq!(total, 3);
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].description, "Havuç");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_browse_foods() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        for (description, tags, energy, protein, verified) in [
+            ("Tavuk Göğsü", vec!["et"], 165.0, 31.0, true),
+            ("Dana Kıyma", vec!["et"], 250.0, 26.0, true),
+            ("Çiğ Köfte", vec!["et"], 200.0, 5.0, false),
+            ("Mercimek", vec!["baklagil"], 116.0, 9.0, true),
+        ] {
+            insert_food(
+                &pool,
+                Food {
+                    tags: tags.into_iter().map(str::to_owned).collect(),
+                    energy,
+                    protein,
+                    ..test_food(description, verified)
+                },
+            )
+            .await?;
+        }
+
+        let descriptions = |(foods, _): &(Vec<Food>, u64)| -> Vec<String> {
+            foods.iter().map(|food| food.description.clone()).collect()
         };
+        let range = |nutrient, min, max| NutrientRange { nutrient, min, max };
 
-        insert_food(&pool, food).await?;
-        let exists = food_exists_by_description(&pool, "Test Yemek").await?;
-        assert!(exists);
+        // Onaylanmamış yemekler hariç, varsayılan sıralama Türkçe karakterlerden bağımsız alfabetik
+        let filter = BrowseFilter {
+            tags: vec!["et".to_owned()],
+            ..Default::default()
+        };
+        let result = browse_foods(&pool, &filter, BrowseSort::default(), 10, 0).await?;
+        assert_eq!(descriptions(&result), vec!["Dana Kıyma", "Tavuk Göğsü"]);
+        assert_eq!(result.1, 2);
 
-        let not_exists = food_exists_by_description(&pool, "Nonexistent").await?;
-        assert!(!not_exists);
+        let filter = BrowseFilter {
+            ranges: vec![
+                range("protein", Some(9.0), None),
+                range("energy", None, Some(200.0)),
+            ],
+            ..Default::default()
+        };
+        let sort = BrowseSort {
+            column: "energy",
+            descending: true,
+        };
+        let result = browse_foods(&pool, &filter, sort, 10, 0).await?;
+        assert_eq!(descriptions(&result), vec!["Tavuk Göğsü", "Mercimek"]);
+        // Sayfalama SQL'de, toplam sayfadan bağımsız
+        let result = browse_foods(&pool, &filter, sort, 1, 1).await?;
+        assert_eq!(descriptions(&result), vec!["Mercimek"]);
+        assert_eq!(result.1, 2);
 
-        info!("food_exists_by_description testi geçti.");
+        // Etiketler tam eşleşmeyle karşılaştırılıyor
+        let filter = BrowseFilter {
+            tags: vec!["e".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(
+            browse_foods(&pool, &filter, BrowseSort::default(), 10, 0)
+                .await?
+                .1,
+            0
+        );
+
+        // Aralık filtresi her besin değeri kolonunu tanımalı
+        for nutrient in crate::core::browse::NUTRIENTS {
+            assert!(
+                browse_filter_sql!()
+                    .contains(&format!("WHEN '{}' THEN F.{}\n", nutrient, nutrient)),
+                "{}",
+                nutrient
+            );
+        }
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_insert_food_and_load_json() -> Result<(), Error> {
-        // In-memory veritabanı
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
-
-        // Test için geçici dizin oluştur
-        let temp_dir = "./db/test_temp";
-        fs::create_dir_all(temp_dir).unwrap();
+    async fn test_search_excludes_allergens() -> Result<(), Error> {
+        let pool = memory_pool().await?;
 
-        // Test JSON dosyası oluştur
-        let test_json = r#"
-            [
-                {
-                    "description": "JSON Test Yemek",
-                    "image_url": "/json_test.jpg",
-                    "source": "json_source",
-                    "tags": ["json_tag"],
-                    "allergens": [],
-                    "servings": {"Porsiyon": 100},
-                    "glycemic_index": 60.0,
-                    "energy": 120.0,
-                    "carbohydrate": 25.0,
-                    "protein": 6.0,
-                    "fat": 3.0,
-                    "saturated_fat": 1.5,
-                    "trans_fat": 0.0,
-                    "sugar": 12.0,
-                    "fiber": 4.0,
-                    "water": 55.0,
-                    "cholesterol": 0.0,
-                    "sodium": 60.0,
-                    "potassium": 220.0,
-                    "iron": 1.2,
-                    "magnesium": 35.0,
-                    "calcium": 12.0,
-                    "zinc": 0.6,
-                    "vitamin_a": 0.15,
-                    "vitamin_b6": 0.25,
-                    "vitamin_b12": 0.0,
-                    "vitamin_c": 6.0,
-                    "vitamin_d": 0.0,
-                    "vitamin_e": 0.12,
-                    "vitamin_k": 0.06
-                }
-            ]
-        "#;
-        fs::write(format!("{}/test.json", temp_dir), test_json).unwrap();
+        for (description, allergens) in [
+            ("Ekmek", vec![Allergen::contains("gluten")]),
+            (
+                "Fındıklı Ekmek",
+                vec![Allergen::contains("gluten"), Allergen::contains("fındık")],
+            ),
+            ("Mısır Ekmeği", vec![]),
+            (
+                "Kepekli Ekmek",
+                vec![
+                    Allergen::contains("gluten"),
+                    Allergen {
+                        name: "Fındık".to_owned(),
+                        level: ContaminationLevel::MayContain,
+                        note: Some("Aynı tesiste işlenir".to_owned()),
+                    },
+                ],
+            ),
+        ] {
+            insert_food(
+                &pool,
+                Food {
+                    tags: vec!["ekmek".to_owned()],
+                    allergens,
+                    ..test_food(description, true)
+                },
+            )
+            .await?;
+        }
 
-        // Sadece test dizininden yükle
-        let foods = load_foods_from_jsons(temp_dir).unwrap();
-        assert_eq!(foods.len(), 1, "Sadece bir yemek yüklenmeli"); // Diğer dosyaları eklemez
-        let food = foods[0].clone();
+        assert_eq!(
+            search_foods_by_description_wild(&pool, &terms("ekme"), &[], 0.0, false, &[], 200)
+                .await?
+                .len(),
+            4
+        );
+        // Bulaşma ihtimali olan alerjenler de dışlanıyor
+        assert_eq!(
+            search_foods_by_description_wild(
+                &pool,
+                &terms("ekme"),
+                &["FINDIK"],
+                0.0,
+                false,
+                &[],
+                200
+            )
+            .await?
+            .len(),
+            2
+        );
+        let (foods, _) = search_foods_by_tag_wild(
+            &pool,
+            &["ekmek"],
+            true,
+            &["fındık", "gluten"],
+            0.0,
+            false,
+            &[],
+            10,
+            0,
+        )
+        .await?;
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].description, "Mısır Ekmeği");
+        // Adaylar sınırlıysa açıklaması sorguyla başlayan önce seçiliyor
+        let foods =
+            search_foods_by_description_wild(&pool, &terms("ekme"), &[], 0.0, false, &[], 1)
+                .await?;
+        assert_eq!(foods.len(), 1);
+        assert_eq!(foods[0].description, "Ekmek");
+        // Alerjenler tam eşleşmeyle karşılaştırılıyor, "glu" gluten'i dışlamıyor
+        assert_eq!(
+            select_verified_foods(&pool, &["glu"], 0.0, false, &[])
+                .await?
+                .len(),
+            4
+        );
+        assert_eq!(
+            select_verified_foods(&pool, &["gluten"], 0.0, false, &[])
+                .await?
+                .len(),
+            1
+        );
+        // mode=all kaynak adında da arıyor
+        assert_eq!(
+            search_foods_by_any_field(&pool, &terms("TEST"), &[], 0.0, false, &[], 200)
+                .await?
+                .len(),
+            4
+        );
+        assert_eq!(
+            search_foods_by_any_field(&pool, &terms("test"), &["gluten"], 0.0, false, &[], 200)
+                .await?
+                .len(),
+            1
+        );
+        // Seviye ve not yemekle birlikte dönüyor
+        let food = select_food_by_slug(&pool, "kepekli-ekmek").await?;
+        assert_eq!(food.allergens[1].level, ContaminationLevel::MayContain);
+        assert_eq!(
+            food.allergens[1].note.as_deref(),
+            Some("Aynı tesiste işlenir")
+        );
+        Ok(())
+    }
 
-        // Yemeği ekle
-        let result = insert_food(&pool, food).await;
-        assert!(result.is_ok(), "Yemek eklenemedi");
-        let food_id = result.unwrap().id.unwrap();
-        assert!(food_id > 0, "Geçerli bir ID olmalı");
+    #[tokio::test]
+    async fn test_reference_intakes() -> Result<(), Error> {
+        let pool = memory_pool().await?;
 
-        info!("insert_food ve load_foods_from_jsons testi geçti.");
+        // Depodaki varsayılan profillerin hepsi geçerli olmalı
+        let profiles = load_from_jsons::<ReferenceIntake>("./db/reference_intakes").await?;
+        assert!(profiles.len() >= 7);
+        for profile in &profiles {
+            assert!(
+                insert_reference_intake(&pool, profile).await?,
+                "{}",
+                profile.slug
+            );
+        }
+        assert_eq!(select_reference_intakes(&pool).await?, profiles);
 
-        // Testten sonra dosyayı ve dizini sil
-        fs::remove_file(format!("{}/test.json", temp_dir)).unwrap();
-        fs::remove_dir(temp_dir).unwrap();
+        // Var olan profil tekrar eklenmiyor, veritabanındaki değerleri korunuyor
+        let mut changed = profiles[0].clone();
+        changed.values.insert("iron".to_owned(), 99.0);
+        assert!(!insert_reference_intake(&pool, &changed).await?);
+        assert_eq!(
+            select_reference_intake(&pool, &changed.slug).await?,
+            Some(profiles[0].clone())
+        );
+        assert_eq!(select_reference_intake(&pool, "yok").await?, None);
 
+        changed.slug = "hatali".to_owned();
+        changed.values.insert("demir".to_owned(), 16.0);
+        assert!(insert_reference_intake(&pool, &changed).await.is_err());
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_select_all_foods_slugs() -> Result<(), Error> {
-        // In-memory veritabanı
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        // Migration'ları çalıştır
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+    async fn test_recipes() -> Result<(), Error> {
+        let pool = memory_pool().await?;
 
-        // Test verisi ekle
-        let food1 = Food {
-            slug: Some("fuji-elma".to_string()),
-            description: "Fuji Elma".to_string(),
-            image_url: "/fuji-elma.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["meyve".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
-            glycemic_index: 40.0,
-            energy: 50.0,
-            carbohydrate: 10.0,
-            protein: 0.5,
-            fat: 0.2,
-            saturated_fat: 0.0,
-            trans_fat: 0.0,
-            sugar: 8.0,
-            fiber: 2.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 150.0,
-            iron: 0.1,
-            magnesium: 5.0,
-            calcium: 6.0,
-            zinc: 0.1,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.0,
-            vitamin_b12: 0.0,
-            vitamin_c: 4.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.0,
-            verified: None,
-            id: None,
-        };
+        for (description, verified) in [("Pirinç", true), ("Tereyağı", true), ("Çiğ Köfte", false)]
+        {
+            insert_food(
+                &pool,
+                Food {
+                    ..test_food(description, verified)
+                },
+            )
+            .await?;
+        }
 
-        let food2 = Food {
-            slug: Some("muz".to_string()),
-            description: "Muz".to_string(),
-            image_url: "/muz.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["meyve".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
-            glycemic_index: 60.0,
-            energy: 90.0,
-            carbohydrate: 20.0,
-            protein: 1.0,
-            fat: 0.3,
-            saturated_fat: 0.1,
-            trans_fat: 0.0,
-            sugar: 15.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 300.0,
-            iron: 0.2,
-            magnesium: 10.0,
-            calcium: 5.0,
-            zinc: 0.15,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.3,
-            vitamin_b12: 0.0,
-            vitamin_c: 10.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.0,
-            verified: None,
-            id: None,
+        let ingredients = |ingredients: &[(&str, f64)]| -> Vec<(String, f64)> {
+            ingredients
+                .iter()
+                .map(|(slug, grams)| ((*slug).to_owned(), *grams))
+                .collect()
         };
+        assert!(matches!(
+            save_recipe(&pool, None, "Pirinç Pilavı", 4, &ingredients(&[("pirinc", 400.0), ("tereyagi", 30.0)])).await?,
+            RecipeWrite::Saved(slug) if slug == "pirinc-pilavi"
+        ));
+        // Malzemeler sırasıyla yükleniyor
+        let recipe = select_recipe_by_slug(&pool, "pirinc-pilavi")
+            .await?
+            .expect("Tarif bulunamadı");
+        assert_eq!(recipe.servings, 4);
+        assert_eq!(
+            recipe
+                .ingredients
+                .iter()
+                .map(|(food, grams)| (food.description.as_str(), *grams))
+                .collect::<Vec<_>>(),
+            vec![("Pirinç", 400.0), ("Tereyağı", 30.0)]
+        );
 
-        // Yemekleri ekle
-        insert_food(&pool, food1).await?;
-        insert_food(&pool, food2).await?;
-
-        // Fonksiyonu çağır
-        let result = select_all_foods_slugs(&pool).await?;
+        assert!(matches!(
+            save_recipe(&pool, None, "PIRINÇ PILAVI", 2, &ingredients(&[("pirinc", 100.0)])).await?,
+            RecipeWrite::Conflict(description) if description == "Pirinç Pilavı"
+        ));
+        assert!(matches!(
+            save_recipe(&pool, None, "Köfteli Pilav", 2, &ingredients(&[("cig-kofte", 100.0)])).await?,
+            RecipeWrite::UnknownFood(slug) if slug == "cig-kofte"
+        ));
+        assert!(matches!(
+            save_recipe(
+                &pool,
+                Some("yok"),
+                "Yok",
+                1,
+                &ingredients(&[("pirinc", 100.0)])
+            )
+            .await?,
+            RecipeWrite::NotFound
+        ));
 
-        // Sonuçları doğrula (SLUG kontrolü)
-        assert_eq!(result.len(), 2, "İki yemek slug'ı bekleniyor");
+        // Açıklama değişince slug da değişiyor, malzemeler tamamen değiştiriliyor
+        assert!(matches!(
+            save_recipe(&pool, Some("pirinc-pilavi"), "Sade Pilav", 2, &ingredients(&[("pirinc", 200.0)])).await?,
+            RecipeWrite::Saved(slug) if slug == "sade-pilav"
+        ));
         assert!(
-            result.contains(&"fuji-elma".to_string()),
-            "İlk slug 'fuji-elma' bulunamadı"
+            select_recipe_by_slug(&pool, "pirinc-pilavi")
+                .await?
+                .is_none()
         );
-        assert!(
-            result.contains(&"muz".to_string()),
-            "İkinci slug 'muz' bulunamadı"
+        assert_eq!(
+            select_recipe_by_slug(&pool, "sade-pilav")
+                .await?
+                .expect("Tarif bulunamadı")
+                .ingredients
+                .len(),
+            1
+        );
+        assert_eq!(
+            select_recipe_names(&pool).await?,
+            vec![("sade-pilav".to_owned(), "Sade Pilav".to_owned())]
         );
 
-        // Boş tablo testi
-        sqlx::query("DELETE FROM foods").execute(&pool).await?;
-        let empty_result = select_all_foods_slugs(&pool).await?;
-        assert!(
-            empty_result.is_empty(),
-            "Boş tablo için boş sonuç bekleniyor"
+        assert!(delete_recipe(&pool, "sade-pilav").await?);
+        assert!(!delete_recipe(&pool, "sade-pilav").await?);
+        assert_eq!(
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM recipe_ingredients")
+                .fetch_one(&pool)
+                .await?,
+            0
         );
+        Ok(())
+    }
 
-        info!("select_all_foods_slugs testi geçti.");
+    #[tokio::test]
+    async fn test_synonym_sets() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        let terms =
+            |terms: &[&str]| -> Vec<String> { terms.iter().map(|t| (*t).to_owned()).collect() };
+        let SynonymSetWrite::Saved(potato) =
+            save_synonym_set(&pool, None, &terms(&["patates", "potato"])).await?
+        else {
+            panic!("Set kaydedilemedi");
+        };
+        let SynonymSetWrite::Saved(corn) =
+            save_synonym_set(&pool, None, &terms(&["mısır", "cin mısırı"])).await?
+        else {
+            panic!("Set kaydedilemedi");
+        };
+        assert_ne!(potato, corn);
+
+        // Terimler Türkçe karakter ve büyük/küçük harf duyarsız eşleşiyor
+        assert_eq!(select_synonyms(&pool, "PATATES").await?, vec!["potato"]);
+        assert_eq!(select_synonyms(&pool, "misir").await?, vec!["cin mısırı"]);
+        assert!(select_synonyms(&pool, "elma").await?.is_empty());
+
+        // Bir terim iki sette olamaz
+        assert!(matches!(
+            save_synonym_set(&pool, None, &terms(&["Potato", "kartoffel"])).await?,
+            SynonymSetWrite::Conflict(term) if term == "potato"
+        ));
+        assert!(matches!(
+            save_synonym_set(&pool, Some(potato), &terms(&["patates", "potato", "kartoffel"])).await?,
+            SynonymSetWrite::Saved(id) if id == potato
+        ));
+        assert_eq!(
+            select_synonyms(&pool, "kartoffel").await?,
+            vec!["patates", "potato"]
+        );
+        assert!(matches!(
+            save_synonym_set(&pool, Some(999), &terms(&["a", "b"])).await?,
+            SynonymSetWrite::NotFound
+        ));
+
+        assert!(delete_synonym_set(&pool, corn).await?);
+        assert!(!delete_synonym_set(&pool, corn).await?);
+        assert_eq!(
+            select_synonym_sets(&pool).await?,
+            vec![(potato, terms(&["patates", "potato", "kartoffel"]))]
+        );
         Ok(())
     }
 
@@ -614,48 +4589,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_select_food_by_slug_basic() -> Result<(), Error> {
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+        let pool = memory_pool().await?;
 
         // Test food oluştur (senin mantığınla)
-        let test_food = Food {
-            slug: Some("test-food".to_string()),
-            description: "Test Food".to_string(),
-            image_url: "/test.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["test".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
+        let food = Food {
             glycemic_index: 50.0,
             energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
-            verified: None,
-            id: None,
+            ..test_food("Test Food", true)
         };
 
         // insert_food ile ekle
-        insert_food(&pool, test_food).await?;
+        insert_food(&pool, food).await?;
 
         // select_food_by_slug çağır
         let result = select_food_by_slug(&pool, "test-food").await?;
@@ -672,8 +4616,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_select_food_by_slug_not_found() -> Result<(), Error> {
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+        let pool = memory_pool().await?;
 
         // Boş tablo
         let result = select_food_by_slug(&pool, "nonexistent").await;
@@ -685,80 +4628,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_select_food_by_slug_multiple_foods() -> Result<(), Error> {
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+        let pool = memory_pool().await?;
 
         // İki farklı food ekle
         let food1 = Food {
-            slug: Some("apple".to_string()),
-            description: "Apple".to_string(),
-            image_url: "/apple.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["fruit".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
-            glycemic_index: 40.0,
             energy: 52.0,
-            carbohydrate: 14.0,
-            protein: 0.3,
-            fat: 0.2,
-            saturated_fat: 0.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 2.4,
-            water: 86.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
-            potassium: 107.0,
-            iron: 0.1,
-            magnesium: 5.0,
-            calcium: 6.0,
-            zinc: 0.1,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.0,
-            vitamin_b12: 0.0,
-            vitamin_c: 4.6,
-            vitamin_d: 0.0,
-            vitamin_e: 0.2,
-            vitamin_k: 0.0,
-            verified: None,
-            id: None,
+            ..test_food("Apple", true)
         };
 
         let food2 = Food {
-            slug: Some("banana".to_string()),
-            description: "Banana".to_string(),
-            image_url: "/banana.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["fruit".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
-            glycemic_index: 51.0,
             energy: 89.0,
-            carbohydrate: 23.0,
-            protein: 1.1,
-            fat: 0.3,
-            saturated_fat: 0.1,
-            trans_fat: 0.0,
-            sugar: 12.0,
-            fiber: 2.6,
-            water: 75.0,
-            cholesterol: 0.0,
-            sodium: 1.0,
             potassium: 358.0,
-            iron: 0.3,
-            magnesium: 27.0,
-            calcium: 5.0,
-            zinc: 0.2,
-            vitamin_a: 0.0,
-            vitamin_b6: 0.4,
-            vitamin_b12: 0.0,
-            vitamin_c: 8.7,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.0,
-            verified: None,
-            id: None,
+            ..test_food("Banana", true)
         };
 
         // İkisini de ekle
@@ -781,47 +4662,12 @@ mod tests {
 
     #[tokio::test]
     async fn test_select_description_by_id_helpers() -> Result<(), Error> {
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+        let pool = memory_pool().await?;
 
         // Test için food ekle (ama relation tabloları da lazım)
-        let test_food = Food {
-            slug: Some("helper-test".to_string()),
-            description: "Helper Test".to_string(),
-            image_url: "/helper.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["test".to_string()],
-            allergens: vec![],
-            servings: std::collections::BTreeMap::new(),
-            glycemic_index: 50.0,
-            energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
-            verified: None,
-            id: None,
-        };
-
-        insert_food(&pool, test_food).await?;
+        let food = test_food("Helper Test", true);
+
+        insert_food(&pool, food).await?;
         let _food_id = 1; // insert_food'dan dönen ID
 
         // Helper fonksiyonları test et (basit versiyon)
@@ -834,47 +4680,17 @@ mod tests {
 
     #[tokio::test]
     async fn test_select_food_allergens_tags_servings_basic() -> Result<(), Error> {
-        let pool = SqlitePool::connect("sqlite::memory:").await?;
-        sqlx::migrate!("./migrations/foods").run(&pool).await?;
+        let pool = memory_pool().await?;
 
         // Basit food ekle
-        let test_food = Food {
-            slug: Some("relations-test".to_string()),
-            description: "Relations Test".to_string(),
-            image_url: "/relations.jpg".to_string(),
-            source: "test_source".to_string(),
-            tags: vec!["test".to_string()],
-            allergens: vec!["nuts".to_string()], // Bu relation tablolarına eklenmeli
-            servings: [("100g".to_string(), 100.0)].iter().cloned().collect(),
-            glycemic_index: 50.0,
+        let food = Food {
+            allergens: vec![Allergen::contains("nuts")],
+            servings: [("100g".to_string(), 100.0)].into_iter().collect(),
             energy: 100.0,
-            carbohydrate: 20.0,
-            protein: 5.0,
-            fat: 2.0,
-            saturated_fat: 1.0,
-            trans_fat: 0.0,
-            sugar: 10.0,
-            fiber: 3.0,
-            water: 55.0,
-            cholesterol: 0.0,
-            sodium: 50.0,
-            potassium: 200.0,
-            iron: 1.0,
-            magnesium: 30.0,
-            calcium: 10.0,
-            zinc: 0.5,
-            vitamin_a: 0.1,
-            vitamin_b6: 0.2,
-            vitamin_b12: 0.0,
-            vitamin_c: 5.0,
-            vitamin_d: 0.0,
-            vitamin_e: 0.1,
-            vitamin_k: 0.05,
-            verified: None,
-            id: None,
-        };
-
-        insert_food(&pool, test_food).await?;
+            ..test_food("Relations Test", true)
+        };
+
+        insert_food(&pool, food).await?;
 
         // Relations'ı test et (boş dönebilir, ama hata vermemeli)
         let food = select_food_by_slug(&pool, "relations-test").await?;
@@ -891,4 +4707,105 @@ mod tests {
         info!("select_food relations basic testi geçti.");
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_search_analytics() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        add_search_analytics(&pool, "2026-10-01", "description", "elma", 3, 0).await?;
+        add_search_analytics(&pool, "2026-10-02", "description", "elma", 2, 0).await?;
+        add_search_analytics(&pool, "2026-10-02", "tag", "elma", 1, 1).await?;
+        add_search_analytics(&pool, "2026-10-02", "description", "ejder meyvesi", 4, 4).await?;
+
+        let top = select_top_search_queries(&pool, "2026-10-01", false, 10).await?;
+        assert_eq!(top[0], ("elma".to_owned(), 6, 1));
+        assert_eq!(top[1], ("ejder meyvesi".to_owned(), 4, 4));
+
+        // Pencere dışında kalan günler sayılmıyor
+        let top = select_top_search_queries(&pool, "2026-10-02", false, 1).await?;
+        assert_eq!(top, vec![("ejder meyvesi".to_owned(), 4, 4)]);
+
+        let zero = select_top_search_queries(&pool, "2026-10-01", true, 10).await?;
+        assert_eq!(zero.len(), 2);
+        assert_eq!(zero[0].0, "ejder meyvesi");
+
+        let modes = select_search_mode_totals(&pool, "2026-10-01").await?;
+        assert_eq!(
+            modes,
+            vec![("description".to_owned(), 9, 4), ("tag".to_owned(), 1, 1)]
+        );
+
+        assert_eq!(
+            delete_search_analytics_before(&pool, "2026-10-02").await?,
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_config_changes() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        assert!(select_config_snapshot(&pool).await?.is_none());
+        let snapshot = BTreeMap::from([("api.search_max_limit".to_owned(), "20".to_owned())]);
+        let changes = [
+            ConfigChange {
+                key: "api.search_max_limit".to_owned(),
+                old_value: Some("10".to_owned()),
+                new_value: Some("20".to_owned()),
+            },
+            ConfigChange {
+                key: "maintenance.enabled".to_owned(),
+                old_value: Some("false".to_owned()),
+                new_value: Some("true".to_owned()),
+            },
+        ];
+        insert_config_changes(&pool, "startup", &changes, &snapshot).await?;
+        assert_eq!(select_config_snapshot(&pool).await?, Some(snapshot));
+
+        let all = select_config_changes(&pool, None, 10).await?;
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].key, "maintenance.enabled");
+        assert_eq!(all[0].source, "startup");
+
+        let api = select_config_changes(&pool, Some("api"), 10).await?;
+        assert_eq!(api.len(), 1);
+        assert_eq!(api[0].new_value.as_deref(), Some("20"));
+        // "api" öneki api_keys'i kapsamamalı
+        assert!(
+            select_config_changes(&pool, Some("api_keys"), 10)
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_key_usage() -> Result<(), Error> {
+        let pool = memory_pool().await?;
+
+        assert_eq!(
+            select_api_key_usage(&pool, "partner", "2025-09-08").await?,
+            0
+        );
+
+        // Aynı periyoda yapılan yazmalar toplanmalı
+        add_api_key_usage(&pool, "partner", &["2025-09-08", "2025-09"], 3).await?;
+        add_api_key_usage(&pool, "partner", &["2025-09-08", "2025-09"], 4).await?;
+        add_api_key_usage(&pool, "başka", &["2025-09-08"], 1).await?;
+
+        assert_eq!(
+            select_api_key_usage(&pool, "partner", "2025-09-08").await?,
+            7
+        );
+        assert_eq!(select_api_key_usage(&pool, "partner", "2025-09").await?, 7);
+
+        let history = select_api_key_usage_history(&pool, "partner", 10).await?;
+        assert_eq!(
+            history,
+            vec![("2025-09-08".to_owned(), 7), ("2025-09".to_owned(), 7)]
+        );
+
+        Ok(())
+    }
 }
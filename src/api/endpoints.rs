@@ -1,45 +1,118 @@
-use std::{collections::BTreeMap, net::SocketAddr};
+use std::collections::BTreeMap;
 
-use axum::{
-    Json,
-    extract::{ConnectInfo, State},
-    http::HeaderMap,
-};
-use tracing::debug;
+use axum::{Json, extract::State};
 
-use crate::{SharedState, api::parse_client_ip};
+use crate::SharedState;
 
 pub(crate) async fn endpoints(
     State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
 ) -> Json<BTreeMap<&'static str, String>> {
-    // Henüz test etmedim ama ne olur ne olmaz diye to_owned atıyorum birkaç ms olsa bile config'e blok atılmaması için
-    let api_base_url = &shared_state.config.lock().await.api.base_url.to_owned();
+    let api_base_url = &shared_state.urls.base_url;
     let mut endpoints: BTreeMap<&'static str, String> = BTreeMap::new();
 
     endpoints.insert("api_health_url", format!("{}/{}", &api_base_url, "health"));
+    endpoints.insert(
+        "api_liveness_url",
+        format!("{}/{}", &api_base_url, "health/live"),
+    );
+    endpoints.insert(
+        "api_readiness_url",
+        format!("{}/{}", &api_base_url, "health/ready"),
+    );
     endpoints.insert(
         "list_all_foods_url",
         format!("{}/{}", &api_base_url, "foods/list"),
     );
+    endpoints.insert(
+        "export_foods_url",
+        format!("{}/{}", &api_base_url, "foods/export.ndjson"),
+    );
+    endpoints.insert(
+        "food_summaries_url",
+        format!("{}/{}", &api_base_url, "foods/summaries"),
+    );
+    endpoints.insert(
+        "in_season_foods_url",
+        format!(
+            "{}/{}",
+            api_base_url,
+            "foods/in-season?month={1-12}&region={region}&limit={limit}&offset={offset}"
+        ),
+    );
+    endpoints.insert(
+        "food_portions_url",
+        format!("{}/{}", api_base_url, "food/{slug}/portions"),
+    );
     endpoints.insert(
         "get_food_url",
-        format!("{}/{}", api_base_url, "food/{slug}"),
+        format!(
+            "{}/{}",
+            api_base_url, "food/{slug}?basis={per_100g, serving:{serving}}&units={nutrient}:{unit}"
+        ),
     );
     endpoints.insert(
         "search_food_url",
         format!(
             "{}/{}",
-            api_base_url, "foods/search?q={query}&mode={description, tag}&limit={limit}"
+            api_base_url,
+            "foods/search?q={query}&mode={description, tag, all}&limit={limit}&offset={offset}&fuzzy={true, false}&op={and, or}&allergen_free={allergens}&min_completeness={0-1}&diet_flags={flags}&include_branded={true, false}"
         ),
     );
-    endpoints.insert("show_all_tags", format!("{}/{}", api_base_url, "tags"));
-
-    debug!(
-        "GET /: ({} bağlantı noktası), {}",
-        endpoints.len(),
-        parse_client_ip(&addr, &headers)
+    endpoints.insert(
+        "browse_foods_url",
+        format!(
+            "{}/{}",
+            api_base_url,
+            "foods/search?tag={tags}&diet={diets}&min_{nutrient}={min}&max_{nutrient}={max}&nutri_score={grades}&nova={groups}&sort={field}&order={asc, desc}&limit={limit}&offset={offset}"
+        ),
+    );
+    endpoints.insert(
+        "additives_url",
+        format!("{}/{}", api_base_url, "additives?category={category}"),
     );
+    endpoints.insert(
+        "energy_calculator_url",
+        format!(
+            "{}/{}",
+            api_base_url,
+            "calc/energy?age={age}&sex={m, f}&weight={kg}&height={cm}&activity={sedentary, light, moderate, active, very_active}&formula={mifflin_st_jeor, harris_benedict, katch_mcardle}&body_fat={percent}"
+        ),
+    );
+    endpoints.insert(
+        "convert_unit_url",
+        format!(
+            "{}/{}",
+            api_base_url, "convert?nutrient={nutrient}&value={value}&from={unit}&to={unit}"
+        ),
+    );
+    endpoints.insert(
+        "meal_analyze_url",
+        format!("{}/{}", api_base_url, "meals/analyze?profile={profile}"),
+    );
+    endpoints.insert(
+        "meal_gaps_url",
+        format!("{}/{}", api_base_url, "meals/gaps?profile={profile}"),
+    );
+    endpoints.insert(
+        "recipe_url",
+        format!("{}/{}", api_base_url, "recipe/{slug}"),
+    );
+    endpoints.insert("recipes_url", format!("{}/{}", api_base_url, "recipes"));
+    endpoints.insert(
+        "reference_intake_url",
+        format!("{}/{}", api_base_url, "reference-intake/{slug}"),
+    );
+    endpoints.insert(
+        "reference_intakes_url",
+        format!("{}/{}", api_base_url, "reference-intakes"),
+    );
+    endpoints.insert(
+        "suggest_food_url",
+        format!(
+            "{}/{}",
+            api_base_url, "foods/suggest?q={query}&limit={limit}"
+        ),
+    );
+    endpoints.insert("show_all_tags", format!("{}/{}", api_base_url, "tags"));
     Json(endpoints)
 }
@@ -3,31 +3,113 @@ use std::fmt::{Display, Formatter};
 use axum::{
     body::Body,
     extract::Request,
-    http::{self, HeaderMap, HeaderValue, header::CONTENT_TYPE},
+    http::{
+        self, HeaderMap, HeaderValue,
+        header::{CONTENT_TYPE, RETRY_AFTER},
+    },
     middleware::Next,
     response::IntoResponse,
 };
 use http::StatusCode;
 use serde::{Deserialize, Serialize};
+use tower::{BoxError, load_shed::error::Overloaded};
 use tracing::error;
 
-#[derive(Serialize, Deserialize)]
+// İstemciler Türkçe mesajları karşılaştırmak yerine bu kodlara göre dallanabilsin diye her hataya sabit bir kod veriyoruz.
+// JSON'da FOOD_NOT_FOUND gibi görünüyorlar; yayınlanmış bir kodun adı değiştirilmemeli, gerekirse yenisi eklenmeli
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(crate) enum ErrorCode {
+    EndpointNotFound,
+    MethodNotAllowed,
+    InvalidParameters,
+    ClientError,
+    FoodNotFound,
+    FoodNotVerified,
+    TagNotFound,
+    QueryTooLarge,
+    InvalidQueryLength,
+    InvalidQueryCharacters,
+    InvalidSearchMode,
+    SearchLimitExceeded,
+    InvalidApiKey,
+    ApiKeyNotFound,
+    SynonymSetNotFound,
+    SynonymConflict,
+    RecipeNotFound,
+    RecipeConflict,
+    ReferenceIntakeNotFound,
+    SignatureRequired,
+    SignatureExpired,
+    SignatureInvalid,
+    SignatureReused,
+    Unauthorized,
+    IpNotAllowed,
+    InvalidHostHeader,
+    RateLimited,
+    QuotaExceeded,
+    Overloaded,
+    UnderMaintenance,
+    DatabaseError,
+    DatabaseBusy,
+    InternalError,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct APIError {
-    // StatusCode kullanmak yerine u16 olarak saklama sebebimiz deserialize ve serialize fonksiyonlarını kullanabilmek
+    // StatusCode kullanmak yerine u16 olarak saklama sebebimiz deserialize ve serialize fonksiyonlarını kullanabilmek.
+    // İlk sürümden beri istemciler code'u sayı olarak okuyor, makinenin okuyacağı hata kodu bu yüzden ayrı bir alanda
     pub(crate) code: u16,
+    pub(crate) error: ErrorCode,
     pub(crate) message: String,
+    // 429 ve 503 gibi geçici hatalarda istemcinin kaç saniye sonra tekrar denemesi gerektiği, Retry-After header'ı olarak da gönderiliyor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) retry_after: Option<u64>,
+    // İsteğin ID'si, kullanıcılar hata bildirirken bu ID ile loglarda isteği bulabiliyoruz. request_id middleware'i dolduruyor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) request_id: Option<String>,
 }
 
 impl APIError {
-    pub(crate) fn new(code: StatusCode, message: &str) -> Self {
+    pub(crate) fn new(status: StatusCode, error: ErrorCode, message: &str) -> Self {
         APIError {
-            code: code.as_u16(),
+            code: status.as_u16(),
+            error,
             message: message.to_owned(),
+            retry_after: None,
+            request_id: None,
+        }
+    }
+
+    pub(crate) fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
+
+    // Veritabanı hataları için; tekrar denemeler de kilit hatasıyla bittiyse sorun geçici, istemci 500 yerine Retry-After'lı 503 alıyor
+    pub(crate) fn database(e: &anyhow::Error, message: &str) -> Self {
+        if crate::api::database::is_transient(e) {
+            APIError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorCode::DatabaseBusy,
+                "Veritabanı şu anda meşgul, lütfen biraz sonra tekrar deneyin",
+            )
+            .with_retry_after(1)
+        } else {
+            APIError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorCode::DatabaseError,
+                message,
+            )
         }
     }
 
     pub(crate) async fn not_found_handler() -> impl IntoResponse {
-        APIError::new(StatusCode::NOT_FOUND, "İstenen API endpoint'i bulunamadı")
+        APIError::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::EndpointNotFound,
+            "İstenen API endpoint'i bulunamadı",
+        )
     }
 }
 
@@ -37,6 +119,7 @@ impl IntoResponse for APIError {
             Ok(code) => code,
             _ => StatusCode::INTERNAL_SERVER_ERROR, // Varsayılan INTERNAL_SERVER_ERROR kullanıyoruz eğer kod geçersizse
         };
+        crate::core::error_reporting::capture_server_error(status, &self.message);
 
         // Serde başarısız olursa manuel yapıyoruz? Tehlikeli olabilri mi bu kod
         let body = serde_json::to_string(&self).unwrap_or_else(|e| {
@@ -47,7 +130,7 @@ impl IntoResponse for APIError {
 
             format!(
                 // {{ ve }} kullanıyoruz escape etmek için
-                r#"{{"code": {}, "message": "Serde JSON serileştirme başarısız oldu: {}"}}"#,
+                r#"{{"code": {}, "error": "INTERNAL_ERROR", "message": "Serde JSON serileştirme başarısız oldu: {}"}}"#,
                 status.as_u16(),
                 self.message.replace("\"", "\\\""), // Özel karakterleri escape ediyoruz
             )
@@ -56,6 +139,9 @@ impl IntoResponse for APIError {
         // JSON için ve belki gelecekte başka değeler için header açalım
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(retry_after) = self.retry_after {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
 
         (status, headers, body).into_response()
     }
@@ -63,7 +149,30 @@ impl IntoResponse for APIError {
 
 impl Display for APIError {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        write!(f, "{} (Kod: {})", self.message, self.code)
+        write!(f, "{} (Kod: {}, {:?})", self.message, self.code, self.error)
+    }
+}
+
+pub(crate) fn service_unavailable(retry_after: u64) -> APIError {
+    APIError::new(
+        StatusCode::SERVICE_UNAVAILABLE,
+        ErrorCode::Overloaded,
+        "Sunucu şu anda çok yoğun, lütfen biraz sonra tekrar deneyin / Service is temporarily overloaded, please retry shortly",
+    )
+    .with_retry_after(retry_after)
+}
+
+// Eşzamanlı istek limiti dolduğunda load shedding katmanı Overloaded hatası döndürüyor, bunu standart 503 formatına çeviriyoruz
+pub(crate) async fn handle_overload(error: BoxError) -> APIError {
+    if error.is::<Overloaded>() {
+        service_unavailable(1)
+    } else {
+        error!("Beklenmeyen middleware hatası: {}", error);
+        APIError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::InternalError,
+            "Sunucu Hatası: Beklenmeyen bir hata oluştu",
+        )
     }
 }
 
@@ -83,17 +192,38 @@ pub(crate) async fn handle_axum_rejections(
     }
     /* let Some içindeki veri aynı koşul içerisinde kullanılamıyormuş mevcut sürümde, üstteki match yapısına geçilmiştir
     https://github.com/rust-lang/rust/issues/53667
-    
+
     if let Some(content_type) = response.headers().get(CONTENT_TYPE)
         && content_type == HeaderValue::from_static("application/json")
     {
         return Ok(response);
     } */
 
+    // Retry-After header'ı varsa istemciye JSON içinde de iletiyoruz
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+
     // Deserializasyon hatalarını yakalamak için response status kontrolü
     match response.status() {
+        // Rate limit ve yük atma katmanları kendi düz metin yanıtlarını döndürebiliyor, bunları da standart formata çeviriyoruz
+        StatusCode::TOO_MANY_REQUESTS => Err(crate::api::rate_limit::too_many_requests(
+            retry_after.unwrap_or(1),
+        )),
+        StatusCode::SERVICE_UNAVAILABLE => Err(service_unavailable(retry_after.unwrap_or(1))),
         status if status.is_client_error() => Err(APIError::new(
             status,
+            match status {
+                StatusCode::NOT_FOUND => ErrorCode::EndpointNotFound,
+                StatusCode::METHOD_NOT_ALLOWED => ErrorCode::MethodNotAllowed,
+                // Query/path parametreleri deserialize edilemediğinde axum 400 veya 422 döndürüyor
+                StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+                    ErrorCode::InvalidParameters
+                }
+                _ => ErrorCode::ClientError,
+            },
             &(format!(
                 "İstemci Hatası: {}",
                 status.canonical_reason().unwrap_or("Tanımsız Davranış")
@@ -101,6 +231,7 @@ pub(crate) async fn handle_axum_rejections(
         )),
         status if status.is_server_error() => Err(APIError::new(
             status,
+            ErrorCode::InternalError,
             &(format!(
                 "Sunucu Hatası: {}",
                 status.canonical_reason().unwrap_or("Tanımsız Davranış")
@@ -109,3 +240,51 @@ pub(crate) async fn handle_axum_rejections(
         _ => Ok(response),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_is_serialized_only_when_set() {
+        let error = APIError::new(StatusCode::NOT_FOUND, ErrorCode::FoodNotFound, "Bulunamadı");
+        assert!(
+            !serde_json::to_string(&error)
+                .unwrap()
+                .contains("retry_after")
+        );
+
+        let error = APIError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::RateLimited,
+            "Bekleyin",
+        )
+        .with_retry_after(2);
+        assert!(
+            serde_json::to_string(&error)
+                .unwrap()
+                .contains(r#""retry_after":2"#)
+        );
+
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers().get(RETRY_AFTER).unwrap(), "2");
+    }
+
+    #[test]
+    fn test_error_is_serialized_as_screaming_snake_case() {
+        let error = APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::QueryTooLarge,
+            "Çok büyük",
+        );
+        assert_eq!(
+            serde_json::to_string(&error).unwrap(),
+            r#"{"code":400,"error":"QUERY_TOO_LARGE","message":"Çok büyük"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ErrorCode::InvalidApiKey).unwrap(),
+            r#""INVALID_API_KEY""#
+        );
+    }
+}
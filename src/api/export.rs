@@ -0,0 +1,102 @@
+use std::io;
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header::{ACCEPT_RANGES, CONTENT_TYPE},
+    response::{IntoResponse, Response},
+};
+use futures_util::stream;
+use real::RealIp;
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tracing::{error, info, instrument};
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::APIError,
+        foods::{MAX_INPUT_CHARS, record_offense, validate_input},
+        metrics::Offense,
+    },
+    core::food::WithStaticUrl,
+};
+
+// Veritabanı okuması istemciden en fazla bu kadar satır öne geçebiliyor, yavaş istemcilerde bellek kullanımı sabit kalıyor
+const BUFFERED_LINES: usize = 64;
+
+#[derive(Deserialize)]
+pub(crate) struct ExportParams {
+    // Yarıda kalan indirmeyi sürdürmek için: sadece slug'ı bundan sonra gelen yemekler gönderiliyor
+    after: Option<String>,
+}
+
+// Toplu kullanıcılar için tüm onaylanmış yemekler, her satırda bir yemeğin JSON'ı (NDJSON). Yemekler veritabanından okundukça
+// gönderiliyor, on binlerce yemek olsa da hepsi belleğe alınmıyor. Yanıt başladıktan sonra bir veritabanı hatası olursa durum kodu
+// değiştirilemediği için bağlantı kesiliyor, istemci yarım kalan dosyayı bu şekilde anlayabiliyor.
+// Satırlar slug sırasıyla geldiği için bağlantısı kopan istemci son tam satırdaki slug'ı ?after= ile vererek kaldığı yerden devam
+// edebiliyor. Bayt aralıkları (Range) desteklenmiyor: yanıt önceden üretilmediği için toplam boyut bilinmiyor ve iki istek arasında
+// yemek eklenirse aynı bayt konumu başka bir satıra denk geliyor, slug'a göre devam etmek bu durumda da doğru sonucu veriyor
+#[instrument(skip_all)]
+pub(crate) async fn foods_export(
+    Query(params): Query<ExportParams>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<Response, APIError> {
+    let after = match params
+        .after
+        .as_deref()
+        .map(|after| validate_input(after, MAX_INPUT_CHARS))
+        .transpose()
+    {
+        Ok(after) => after,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+
+    let pool = shared_state.api_db.clone();
+    let static_url = shared_state.urls.static_url.clone();
+    let (sender, receiver) = mpsc::channel::<Result<String, io::Error>>(BUFFERED_LINES);
+
+    tokio::spawn(async move {
+        let result = database::for_each_verified_food(&pool, after.as_deref(), |food| {
+            let sender = sender.clone();
+            // Diğer endpoint'lerdeki gibi /images/muz.webp şeklindeki resim URL'leri static_url ile tamamlanıyor
+            let food = WithStaticUrl::new(static_url.clone(), food);
+            async move {
+                let line = match serde_json::to_string(&food) {
+                    Ok(json) => Ok(json + "\n"),
+                    Err(e) => Err(io::Error::other(e)),
+                };
+                // Alıcı yoksa istemci bağlantıyı kapatmış, okumayı bırakıyoruz
+                sender.send(line).await.is_ok()
+            }
+        })
+        .await;
+
+        match result {
+            Ok(count) => info!("Dışa aktarımda {} yemek gönderildi", count),
+            Err(e) => {
+                error!("Yemekler dışa aktarılırken hata oluştu: {:?}", e);
+                let _ = sender
+                    .send(Err(io::Error::other("Veritabanı okunurken hata oluştu")))
+                    .await;
+            }
+        }
+    });
+
+    let lines = stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|line| (line, receiver))
+    });
+    Ok((
+        [
+            (CONTENT_TYPE, "application/x-ndjson"),
+            (ACCEPT_RANGES, "none"),
+        ],
+        Body::from_stream(lines),
+    )
+        .into_response())
+}
@@ -1,71 +1,183 @@
-use std::{collections::BTreeMap, net::SocketAddr};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    sync::{Arc, atomic::Ordering},
+};
 
 use axum::{
     Json,
-    extract::{ConnectInfo, Path, Query, State},
-    http::{HeaderMap, StatusCode},
+    extract::{Path, Query, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
 };
 
 use anyhow::Result;
-use serde::Deserialize;
-use tracing::{debug, error};
+use real::RealIp;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tracing::{error, instrument};
 
 use crate::{
     SharedState,
-    api::{database, error::APIError, parse_client_ip},
-    core::food::Food,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+        hot_search::{self, HotSearchResult},
+        metrics::Offense,
+        open_food_facts::Enrichment,
+        search_index::{self, IndexEntry, IndexFields, IndexFilter},
+        suggest::Suggestion,
+        units::{ConvertedValue, convert_food},
+    },
+    core::{
+        browse::{self, BrowseFilter, BrowseSort, Diet, NutrientRange},
+        config::{SearchFieldWeights, SearchRanking},
+        diet_flags::DietFlagRule,
+        food::{Basis, Food, FoodLink, FoodSummary, WithStaticUrl},
+        query::SearchQuery,
+        str,
+        units::parse_unit_requests,
+    },
 };
 
+// Slug ve arama sorguları için karakter limiti
+pub(crate) const MAX_INPUT_CHARS: usize = 100;
+
+// Etiket aramasında her etiket ayrı bir alt sorgu oluşturuyor, sorgunun büyümemesi için sınırlıyoruz
+const MAX_SEARCH_TAGS: usize = 5;
+
+// Açıklama aramasında da her kelime ve tırnaklı ifade ayrı bir koşul
+const MAX_QUERY_TERMS: usize = 8;
+
+// Sayfalamadan önceki toplam sonuç sayısı, istemciler "daha fazla göster" butonunu buna göre gösterebiliyor
+pub(crate) const X_TOTAL_COUNT: &str = "x-total-count";
+
+// Yemek listesi dönen endpoint'lerin gövdesi, resim URL'leri serileştirilirken tamamlanıyor
+type FoodsJson = Json<WithStaticUrl<Vec<Food>>>;
+
+#[derive(Deserialize)]
+pub(crate) struct FoodParams {
+    // vitamin_a:iu,energy:kj gibi, değerlerin ayrıca gösterileceği birimler
+    units: Option<String>,
+    // per_100g (varsayılan) veya serving:<porsiyon açıklaması>
+    basis: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct FoodResponse {
+    #[serde(flatten)]
+    food: Food,
+    // Aynı variant_group'taki diğer haller, grubu olmayan yemeklerde boş
+    variants: Vec<FoodLink>,
+    // GTIN'i olan ürünlerde Open Food Facts'ten çekilen resim, içindekiler ve Nutri-Score, kaynağı ve lisansıyla
+    #[serde(skip_serializing_if = "Option::is_none")]
+    open_food_facts: Option<Enrichment>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    converted: BTreeMap<&'static str, ConvertedValue>,
+}
+
+#[instrument(skip_all)]
 pub(crate) async fn food(
     Path(slug): Path<String>,
+    Query(params): Query<FoodParams>,
     State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
-) -> Result<Json<Food>, APIError> {
+    real_ip: RealIp,
+) -> Result<Json<WithStaticUrl<FoodResponse>>, APIError> {
     // Girilen yemek isminin, istediğimiz limitler içinde olduğuna emin olalım, DoS'a karşı karakter limiti ekleyelim.
-    if slug.is_empty() || slug.len() > 100 {
-        return Err(APIError::new(
-            StatusCode::BAD_REQUEST,
-            "Slug en az 1 karakter, en fazla 100 karakterden oluşabilir",
-        ));
-    }
-
-    sanitize_input(&slug)?;
-
-    let mut food = database::select_food_by_slug(&*shared_state.api_db.lock().await, &slug)
+    let slug = match validate_input(&slug, MAX_INPUT_CHARS) {
+        Ok(slug) => slug,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+    let unit_requests = parse_unit_requests(params.units.as_deref().unwrap_or(""))
+        .map_err(|e| APIError::new(StatusCode::BAD_REQUEST, ErrorCode::InvalidParameters, &e))?;
+    let basis = match params.basis.as_deref() {
+        None => Basis::Per100g,
+        Some(basis) => Basis::parse(basis).ok_or_else(|| {
+            APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidParameters,
+                "basis per_100g veya serving:<porsiyon> olmalı",
+            )
+        })?,
+    };
+    let mut food = database::select_food_by_slug(&shared_state.api_db, &slug)
         .await
         .map_err(|e| {
+            if matches!(
+                e.downcast_ref::<sqlx::Error>(),
+                Some(sqlx::Error::RowNotFound)
+            ) {
+                return APIError::new(
+                    StatusCode::NOT_FOUND,
+                    ErrorCode::FoodNotFound,
+                    "Bu yemekle ilgili veriye ulaşılamadı",
+                );
+            }
             error!("Veritabanı yemek bilgisi sorgularken hata oluştu: {:?}", e);
-            APIError::new(
-                StatusCode::NOT_FOUND,
-                "Bu yemekle ilgili veriye ulaşılamadı",
-            )
+            APIError::database(&e, "Bu yemekle ilgili veriye ulaşılamadı")
         })?;
 
-    fix_image_url(&State(shared_state), &mut food).await;
-
     if food.verified.is_some_and(|verified| verified) {
-        debug!(
-            "GET /food: ({}), {}",
-            slug,
-            parse_client_ip(&addr, &headers)
-        );
-        Ok(Json(food))
+        let variants = match (&food.variant_group, food.id) {
+            (Some(variant_group), Some(food_id)) => {
+                database::select_food_variants(&shared_state.api_db, variant_group, food_id)
+                    .await
+                    .map_err(|e| {
+                        error!("Yemeğin halleri sorgulanırken hata oluştu: {:?}", e);
+                        APIError::database(
+                            &e,
+                            "Veritabanına yemek sorgusu atılırken bir hata oluştu",
+                        )
+                    })?
+            }
+            _ => Vec::new(),
+        };
+        let open_food_facts = match (&food.gtin, food.id) {
+            (Some(_), Some(food_id)) => {
+                database::select_food_enrichment(&shared_state.api_db, food_id)
+                    .await
+                    .map_err(|e| {
+                        error!(
+                            "Yemeğin Open Food Facts bilgileri sorgulanırken hata oluştu: {:?}",
+                            e
+                        );
+                        APIError::database(
+                            &e,
+                            "Veritabanına yemek sorgusu atılırken bir hata oluştu",
+                        )
+                    })?
+            }
+            _ => None,
+        };
+        // Birim çevirileri de ölçeklenmiş değerlerden yapılıyor, ikisi aynı porsiyonu göstersin
+        food.set_basis(basis).map_err(|e| {
+            APIError::new(StatusCode::BAD_REQUEST, ErrorCode::InvalidParameters, &e)
+        })?;
+        Ok(Json(WithStaticUrl::new(
+            shared_state.urls.static_url.clone(),
+            FoodResponse {
+                converted: convert_food(&food, &unit_requests),
+                variants,
+                open_food_facts,
+                food,
+            },
+        )))
     } else {
         Err(APIError::new(
             StatusCode::FORBIDDEN,
+            ErrorCode::FoodNotVerified,
             "Bu yemek henüz onaylanmadığı için gösterilemiyor",
         ))
     }
 }
 
+#[instrument(skip_all)]
 pub(crate) async fn foods(
     State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
 ) -> Json<BTreeMap<&'static str, String>> {
-    // Henüz test etmedim ama ne olur ne olmaz diye to_owned atıyorum birkaç ms olsa bile config'e blok atılmaması için
-    let api_base_url = &shared_state.config.lock().await.api.base_url.to_owned();
+    let api_base_url = &shared_state.urls.base_url;
     let mut endpoints: BTreeMap<&'static str, String> = BTreeMap::new();
 
     endpoints.insert(
@@ -73,110 +185,246 @@ pub(crate) async fn foods(
         format!("{}/{}", &api_base_url, "foods/list"),
     );
     endpoints.insert(
-        "search_food_url",
+        "food_summaries_url",
+        format!("{}/{}", &api_base_url, "foods/summaries"),
+    );
+    endpoints.insert(
+        "in_season_foods_url",
         format!(
             "{}/{}",
-            api_base_url, "foods/search?q={query}&mode={description, tag}&limit={limit}"
+            api_base_url,
+            "foods/in-season?month={1-12}&region={region}&limit={limit}&offset={offset}"
         ),
     );
-
-    debug!(
-        "GET /foods: ({} bağlantı noktası), {}",
-        endpoints.len(),
-        parse_client_ip(&addr, &headers)
+    endpoints.insert(
+        "search_food_url",
+        format!(
+            "{}/{}",
+            api_base_url,
+            "foods/search?q={query}&mode={description, tag, all}&limit={limit}&offset={offset}&fuzzy={true, false}&op={and, or}&allergen_free={allergens}&min_completeness={0-1}&diet_flags={flags}&include_branded={true, false}"
+        ),
     );
     Json(endpoints)
 }
 
 // HashMap yerine BTreeMap kullanma sebebimiz, yemek isimlerini alfabetik sıralamak istememiz. HashMap kullansaydık her seferinde rastgele sıralama olacaktı
+#[instrument(skip_all)]
 pub(crate) async fn foods_list(
     State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
 ) -> Result<Json<BTreeMap<String, String>>, APIError> {
-    let slugs = database::select_all_foods_slugs(&*shared_state.api_db.lock().await)
+    let slugs = database::select_all_foods_slugs(&shared_state.api_db)
         .await
         .map_err(|e| {
             error!(
                 "Veritabanı yemek açıklamaları sorgularken hata oluştu: {:?}",
                 e
             );
-            APIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Veritabanı yemek sorgusu yapılırken hata oluştu",
-            )
+            APIError::database(&e, "Veritabanı yemek sorgusu yapılırken hata oluştu")
         })?;
 
-    let api_base_url = &shared_state.config.lock().await.api.base_url;
-
-    debug!(
-        "GET /foods/list: ({} yemek), {}",
-        slugs.len(),
-        parse_client_ip(&addr, &headers)
-    );
+    let api_base_url = &shared_state.urls.base_url;
     Ok(Json(
         slugs
             .into_iter()
-            .map(|slug| slug)
             // Daha sonra fuji-elma: https://API_BASE.URL/food/food1\n.../food2 şeklinde gösteriyoruz
-            .map(|slug| (slug.clone(), api_base_url.clone() + "/food/" + &slug))
+            .map(|slug| (slug.clone(), format!("{}/food/{}", api_base_url, slug)))
             .collect(),
     ))
 }
 
+#[derive(Serialize)]
+pub(crate) struct FoodSummaryResponse {
+    #[serde(flatten)]
+    summary: FoodSummary,
+    // /foods/list'teki gibi yemeğin detay URL'si
+    url: String,
+}
+
+// Resim ve açıklamayla gösterilecek listeler için /foods/list'in özetli hali, yemekler tamamen okunmuyor
+#[instrument(skip_all)]
+pub(crate) async fn foods_summaries(
+    State(shared_state): State<SharedState>,
+) -> Result<Json<WithStaticUrl<Vec<FoodSummaryResponse>>>, APIError> {
+    let summaries = database::select_food_summaries(&shared_state.api_db)
+        .await
+        .map_err(|e| {
+            error!("Veritabanı yemek özetleri sorgularken hata oluştu: {:?}", e);
+            APIError::database(&e, "Veritabanı yemek sorgusu yapılırken hata oluştu")
+        })?;
+
+    let api_base_url = &shared_state.urls.base_url;
+    Ok(Json(WithStaticUrl::new(
+        shared_state.urls.static_url.clone(),
+        summaries
+            .into_iter()
+            .map(|summary| FoodSummaryResponse {
+                url: format!("{}/food/{}", api_base_url, summary.slug),
+                summary,
+            })
+            .collect(),
+    )))
+}
+
+#[instrument(skip_all)]
 pub(crate) async fn tags_list(
     State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
 ) -> Result<Json<Vec<String>>, APIError> {
-    let tags = database::select_all_tags(&*shared_state.api_db.lock().await)
+    let tags = database::select_all_tags(&shared_state.api_db)
         .await
         .map_err(|e| {
             error!(
                 "Veritabanı etiket açıklamaları sorgularken hata oluştu: {:?}",
                 e
             );
-            APIError::new(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Veritabanı etiket sorgusu yapılırken hata oluştu",
-            )
+            APIError::database(&e, "Veritabanı etiket sorgusu yapılırken hata oluştu")
         })?;
-
-    debug!(
-        "GET /tags: ({} etiket), {}",
-        tags.len(),
-        parse_client_ip(&addr, &headers)
-    );
     Ok(Json(tags))
 }
 
+#[derive(Deserialize)]
+pub(crate) struct InSeasonParams {
+    // 1 (Ocak) ile 12 (Aralık) arası
+    month: Option<u32>,
+    // Verilirse sadece bu bölgede mevsiminde olanlar, Türkçe karakter ve büyük/küçük harf duyarsız
+    region: Option<String>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+}
+
+// Menü planlama uygulamaları için verilen ayda mevsiminde olan meyve ve sebzeler
+#[instrument(skip_all)]
+pub(crate) async fn foods_in_season(
+    Query(params): Query<InSeasonParams>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<(HeaderMap, FoodsJson), APIError> {
+    let Some(month) = params
+        .month
+        .filter(|month| (1..=12).contains(month))
+        .map(|month| month as u8)
+    else {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            "month 1 ile 12 arasında olmalı",
+        ));
+    };
+    let region = match &params.region {
+        Some(region) => match validate_input(region, MAX_INPUT_CHARS) {
+            Ok(region) => Some(region),
+            Err(e) => {
+                record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+                return Err(e);
+            }
+        },
+        None => None,
+    };
+    // Aramadaki gibi sayfalanıyor, ama bir aydaki yemekler genelde tek sayfada istendiği için varsayılan limit en yüksek limit
+    let max_limit = shared_state.config.load().api.search_max_limit;
+    let limit = params.limit.unwrap_or(max_limit);
+    if limit > max_limit {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::SearchLimitExceeded,
+            "Arama limitini geçtiniz!",
+        ));
+    }
+    let (foods, total) = database::select_foods_in_season(
+        &shared_state.api_db,
+        month,
+        region.as_deref(),
+        limit,
+        params.offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|e| {
+        error!("Mevsimindeki yemekler sorgulanırken hata oluştu: {:?}", e);
+        APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+    })?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(X_TOTAL_COUNT, HeaderValue::from(total));
+    Ok((
+        headers,
+        Json(WithStaticUrl::new(
+            shared_state.urls.static_url.clone(),
+            foods,
+        )),
+    ))
+}
+
 #[derive(Deserialize)]
 pub(crate) struct SearchParams {
-    // Sorgu değeri: q
-    q: String,
+    // Sorgu değeri: q. Verilmezse arama yerine filtrelere göre göz atılıyor
+    q: Option<String>,
     mode: Option<String>,
     limit: Option<u64>,
+    // Sıralamadan sonra atlanacak sonuç sayısı, limit ile birlikte sayfalama için
+    offset: Option<u64>,
+    // Yazım hatalarını tolere eden arama, sadece description modunda
+    fuzzy: Option<bool>,
+    // Etiket modunda virgülle ayrılmış etiketlerin hepsi mi (and) yoksa herhangi biri mi (or) aranıyor
+    op: Option<TagOperator>,
+    // Virgülle ayrılmış alerjenler, bunlardan herhangi birini içeren yemekler sonuçlardan çıkarılıyor: allergen_free=gluten,fındık
+    allergen_free: Option<String>,
+    // Tamlığı (Food::completeness) bundan düşük, değerlerinin çoğu ölçülmemiş yemekler sonuçlardan çıkarılıyor: min_completeness=0.8
+    min_completeness: Option<f64>,
+    // Virgülle ayrılmış diyet bayrakları (core::diet_flags), hepsi true olan yemekler dönüyor: diet_flags=vegan,gluten_free
+    diet_flags: Option<String>,
+    // false verilirse markalı ürünler (Food::brand) sonuçlardan çıkarılıyor, sadece genel yemekler dönüyor
+    include_branded: Option<bool>,
+    // Sadece q olmadan göz atarken: virgülle ayrılmış, hepsine sahip olunması gereken etiketler ve core::browse::Diet'ler.
+    // Besin aralıkları (min_protein=20 gibi) isimleri değişken olduğu için ayrıca ham parametrelerden okunuyor
+    tag: Option<String>,
+    diet: Option<String>,
+    // Virgülle ayrılmış Nutri-Score sınıfları (nutri_score=A,B) ve NOVA sınıfları (nova=1,2)
+    nutri_score: Option<String>,
+    nova: Option<String>,
+    sort: Option<String>,
+    order: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TagOperator {
+    #[default]
+    And,
+    Or,
 }
 
 impl SearchParams {
     fn size(self: &SearchParams) -> usize {
-        let query_size = self.q.len();
-        let mode_size = self.mode.as_ref().map_or(0, |m| m.len());
-        // SearchParams'ın statik boyutunu da ekliyoruz
-        size_of::<SearchParams>() + query_size + mode_size
+        // Sadece istemcinin gönderdiği metinleri sayıyoruz. SearchParams'ın statik boyutunu da saymak her yeni
+        // parametrede sorgulara kalan payı küçültüyordu, allergen_free ile birlikte hiç pay kalmıyordu
+        [
+            &self.q,
+            &self.mode,
+            &self.allergen_free,
+            &self.diet_flags,
+            &self.tag,
+            &self.diet,
+            &self.nutri_score,
+            &self.nova,
+        ]
+        .iter()
+        .map(|param| param.as_ref().map_or(0, String::len))
+        .sum()
     }
 }
 
+#[instrument(skip_all)]
 pub(crate) async fn foods_search(
     params: Query<SearchParams>,
+    Query(raw_params): Query<BTreeMap<String, String>>,
     State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
-) -> Result<Json<Vec<Food>>, APIError> {
+    real_ip: RealIp,
+) -> Result<(HeaderMap, FoodsJson), APIError> {
     // Parametrelerin boyutunun 96 baytı geçmesini beklemiyoruz, DoS tarzı saldırıları önlemek için böyle bir önlem alıyoruz
     if params.size() > 96 {
+        record_offense(&shared_state, &real_ip, Offense::OversizedQuery).await;
         return Err(APIError::new(
             StatusCode::BAD_REQUEST,
+            ErrorCode::QueryTooLarge,
             "Gönderdiğiniz sorgu 96 bayt limitini aşıyor!",
         ));
     }
@@ -190,152 +438,875 @@ pub(crate) async fn foods_search(
     // Eğer limit girilmemişse ilk 5 sonucu varsayılan olarak döndüreceğiz çünkü arama menülerinde genellikle bu şekilde kullanılıyor
     // Bu limiti daha sonra ekleyeceğiz, sort yapmadan önce eklersek asıl göstermemiz gereken en alakalı yemekleri gösteremeyebiliriz
     let limit = params.limit.unwrap_or(5);
-    if limit > shared_state.config.lock().await.api.search_max_limit {
+    if limit > shared_state.config.load().api.search_max_limit {
         return Err(APIError::new(
             StatusCode::BAD_REQUEST,
+            ErrorCode::SearchLimitExceeded,
             "Arama limitini geçtiniz!",
         ));
     }
 
-    sanitize_input(&params.q)?;
+    let allergen_free = match &params.allergen_free {
+        Some(allergen_free) => match validate_input(allergen_free, MAX_INPUT_CHARS) {
+            Ok(allergen_free) => allergen_free,
+            Err(e) => {
+                record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+                return Err(e);
+            }
+        },
+        None => String::new(),
+    };
+    let allergen_free: Vec<&str> = allergen_free
+        .split(',')
+        .map(str::trim)
+        .filter(|allergen| !allergen.is_empty())
+        .collect();
+    let diet_flags = match &params.diet_flags {
+        Some(diet_flags) => match validate_input(diet_flags, MAX_INPUT_CHARS) {
+            Ok(diet_flags) => diet_flags,
+            Err(e) => {
+                record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+                return Err(e);
+            }
+        },
+        None => String::new(),
+    };
+    let diet_flags = parse_diet_flags(&diet_flags, &shared_state.config.load().diet_flags)?;
+    if params
+        .min_completeness
+        .is_some_and(|min| !(0.0..=1.0).contains(&min))
+    {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            "min_completeness 0 ile 1 arasında olmalı",
+        ));
+    }
+
+    // q boşsa arama yerine filtrelere göre göz atılıyor, göz atma parametreleri de sadece o zaman geçerli
+    let is_browsing = params.tag.is_some()
+        || params.diet.is_some()
+        || params.nutri_score.is_some()
+        || params.nova.is_some()
+        || params.sort.is_some()
+        || params.order.is_some()
+        || raw_params
+            .keys()
+            .filter(|key| *key != "min_completeness")
+            .any(|key| key.starts_with("min_") || key.starts_with("max_"));
+    let (foods, total) = match params.q.as_deref().filter(|q| !q.trim().is_empty()) {
+        None => {
+            let tag = match &params.tag {
+                Some(tag) => match validate_input(tag, MAX_INPUT_CHARS) {
+                    Ok(tag) => tag,
+                    Err(e) => {
+                        record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+                        return Err(e);
+                    }
+                },
+                None => String::new(),
+            };
+            let filter = browse_filter(&params, &raw_params, &tag, &allergen_free, &diet_flags)?;
+            let sort = BrowseSort::parse(params.sort.as_deref(), params.order.as_deref()).map_err(
+                |e| APIError::new(StatusCode::BAD_REQUEST, ErrorCode::InvalidParameters, &e),
+            )?;
+            database::browse_foods(
+                &shared_state.api_db,
+                &filter,
+                sort,
+                limit,
+                params.offset.unwrap_or(0),
+            )
+            .await
+            .map_err(|e| {
+                error!(
+                    "Filtrelere göre yemekler listelenirken hata oluştu: {:?}",
+                    e
+                );
+                APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+            })?
+        }
+        Some(_) if is_browsing => {
+            return Err(APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidParameters,
+                "tag, diet, nutri_score, nova, sort, order ve min_/max_ besin parametreleri sadece q verilmeden göz atarken kullanılabilir",
+            ));
+        }
+        Some(query) => {
+            let query = match validate_input(query, MAX_INPUT_CHARS) {
+                Ok(query) => query,
+                Err(e) => {
+                    record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+                    return Err(e);
+                }
+            };
+            search_with_hot_cache(
+                &shared_state,
+                &params,
+                &mode,
+                &query,
+                &allergen_free,
+                &diet_flags,
+                limit,
+            )
+            .await?
+        }
+    };
+
+    // Önbellekteki sonuçlar göreli resim URL'leriyle duruyor, serileştirilirken tamamlanıyor
+    let mut headers = HeaderMap::new();
+    headers.insert(X_TOTAL_COUNT, HeaderValue::from(total));
+    Ok((
+        headers,
+        Json(WithStaticUrl::new(
+            shared_state.urls.static_url.clone(),
+            foods,
+        )),
+    ))
+}
+
+// Ek parametresi olmayan aramalar popüler arama önbelleğinden geçiyor, aynı sorgu için eşzamanlı istekler de tek bir
+// veritabanı aramasını bekliyor. Sayfalı, bulanık veya alerjen filtreli aramalar seyrek, doğrudan veritabanına gidiyor
+async fn search_with_hot_cache(
+    shared_state: &SharedState,
+    params: &SearchParams,
+    mode: &str,
+    query: &str,
+    allergen_free: &[&str],
+    diet_flags: &[&str],
+    limit: u64,
+) -> Result<(Vec<Food>, u64), APIError> {
+    let hot_key = (params.offset.unwrap_or(0) == 0
+        && !params.fuzzy.unwrap_or(false)
+        && params.op.is_none()
+        && allergen_free.is_empty()
+        && diet_flags.is_empty()
+        && params.min_completeness.is_none()
+        && params.include_branded.unwrap_or(true)
+        && shared_state.search_hot_cache.policy().max_capacity() != Some(0))
+    .then(|| hot_search::key(mode, query, limit))
+    .flatten();
+    match hot_key {
+        Some(key) => {
+            let entry = shared_state
+                .search_hot_cache
+                .entry(key)
+                .or_try_insert_with(async {
+                    let (foods, total) = search_foods(
+                        shared_state,
+                        params,
+                        mode,
+                        query,
+                        allergen_free,
+                        diet_flags,
+                        limit,
+                    )
+                    .await?;
+                    Ok::<_, APIError>(Arc::new(HotSearchResult { foods, total }))
+                })
+                .await
+                .map_err(Arc::unwrap_or_clone)?;
+            let counters = &shared_state.metrics.search_hot_cache;
+            if entry.is_fresh() {
+                counters.misses.fetch_add(1, Ordering::Relaxed);
+                counters.inserts.fetch_add(1, Ordering::Relaxed);
+            } else {
+                counters.hits.fetch_add(1, Ordering::Relaxed);
+            }
+            let result = entry.into_value();
+            Ok((result.foods.clone(), result.total))
+        }
+        None => {
+            shared_state
+                .metrics
+                .search_hot_cache
+                .bypasses
+                .fetch_add(1, Ordering::Relaxed);
+            search_foods(
+                shared_state,
+                params,
+                mode,
+                query,
+                allergen_free,
+                diet_flags,
+                limit,
+            )
+            .await
+        }
+    }
+}
+
+// diet_flags=vegan,gluten_free parametresi. Sadece config'de kuralı olan bayraklar kabul ediliyor, yazım hatası yapılan bir bayrak
+// sessizce yok sayılmasın. Tekrarlar atılıyor, SQL'de bayrak sayısı karşılaştırılıyor
+fn parse_diet_flags<'a>(
+    diet_flags: &'a str,
+    rules: &BTreeMap<String, DietFlagRule>,
+) -> Result<Vec<&'a str>, APIError> {
+    let mut parsed: Vec<&str> = Vec::new();
+    for flag in diet_flags
+        .split(',')
+        .map(str::trim)
+        .filter(|flag| !flag.is_empty())
+    {
+        if !rules.contains_key(flag) {
+            return Err(APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::InvalidParameters,
+                &format!(
+                    "Bilinmeyen diyet bayrağı: {}, bayraklar: {}",
+                    flag,
+                    rules.keys().cloned().collect::<Vec<_>>().join(", ")
+                ),
+            ));
+        }
+        if !parsed.contains(&flag) {
+            parsed.push(flag);
+        }
+    }
+    Ok(parsed)
+}
+
+// Göz atma filtreleri: etiketlerin hepsi, besin aralıkları ve diyetlerin eşikleri birlikte uygulanıyor. En az bir filtre gerekli,
+// yoksa tüm yemekleri döndürmek yerine eskisi gibi boş sorgu hatası veriyoruz; tüm liste için /foods/list var
+fn browse_filter(
+    params: &SearchParams,
+    raw_params: &BTreeMap<String, String>,
+    tag: &str,
+    allergen_free: &[&str],
+    diet_flags: &[&str],
+) -> Result<BrowseFilter, APIError> {
+    let invalid = |message: &str| {
+        APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            message,
+        )
+    };
+
+    let mut filter = BrowseFilter {
+        tags: tag
+            .split(',')
+            .map(|tag| str::to_search_key(tag.trim()))
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+        ranges: browse::parse_nutrient_ranges(raw_params).map_err(|e| invalid(&e))?,
+        allergen_free: allergen_free
+            .iter()
+            .map(|allergen| (*allergen).to_owned())
+            .collect(),
+        diet_flags: diet_flags.iter().map(|flag| (*flag).to_owned()).collect(),
+        nutri_scores: browse::parse_nutri_scores(params.nutri_score.as_deref().unwrap_or_default())
+            .map_err(|e| invalid(&e))?,
+        nova_groups: browse::parse_nova_groups(params.nova.as_deref().unwrap_or_default())
+            .map_err(|e| invalid(&e))?,
+        exclude_branded: !params.include_branded.unwrap_or(true),
+    };
+    if filter.tags.len() > MAX_SEARCH_TAGS {
+        return Err(invalid(&format!(
+            "En fazla {} etiket ile filtrelenebilir",
+            MAX_SEARCH_TAGS
+        )));
+    }
+    for diet in params
+        .diet
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|diet| !diet.is_empty())
+    {
+        let diet =
+            Diet::parse(diet).ok_or_else(|| invalid(&format!("Bilinmeyen diyet: {}", diet)))?;
+        filter.ranges.extend(diet.nutrient_range());
+        filter
+            .allergen_free
+            .extend(diet.excluded_allergen().map(str::to_owned));
+    }
+
+    // Tamlık da besin değerleri gibi foods tablosunda bir kolon, aralık olarak filtrelenebiliyor
+    if let Some(min) = params.min_completeness.filter(|min| *min > 0.0) {
+        filter.ranges.push(NutrientRange {
+            nutrient: "completeness",
+            min: Some(min),
+            max: None,
+        });
+    }
+
+    if filter.is_empty() {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidQueryLength,
+            "q verilmediğinde en az bir filtre (tag, diet, diet_flags, nutri_score, nova, allergen_free, min_completeness veya min_/max_ besin değeri) gerekli",
+        ));
+    }
+    Ok(filter)
+}
 
-    let mut foods = match mode.as_str() {
+// Moda göre veritabanında arıyor, sıralayıp istenen sayfayı ve sayfalamadan önceki toplamı döndürüyor
+async fn search_foods(
+    shared_state: &SharedState,
+    params: &SearchParams,
+    mode: &str,
+    query: &str,
+    allergen_free: &[&str],
+    diet_flags: &[&str],
+    limit: u64,
+) -> Result<(Vec<Food>, u64), APIError> {
+    let offset = params.offset.unwrap_or(0);
+    let min_completeness = params.min_completeness.unwrap_or(0.0);
+    let exclude_branded = !params.include_branded.unwrap_or(true);
+    let max_candidates = shared_state.config.load().search.max_candidates;
+    let fields = match mode {
+        "description" | "name" => Some(IndexFields::Names),
+        "all" => Some(IndexFields::All),
+        _ => None,
+    };
+    if let Some(fields) = fields
+        && let Some(index) = shared_state.search_index.snapshot().await
+    {
+        let filter = IndexFilter::new(allergen_free, min_completeness, exclude_branded, diet_flags);
+        return search_in_index(shared_state, params, &index, fields, query, &filter, limit).await;
+    }
+    // Etiket aramasında sıralama ve sayfalama SQL'de yapılıyor, o zaman yemeklerle birlikte toplam da dönüyor
+    let (mut foods, paged_total) = match mode {
         // İsim ile aratmada ayrıca sıralıyoruz benzerliğine göre
         "description" | "name" => {
-            let db = &*shared_state.api_db.lock().await;
-            let mut foods = database::search_foods_by_description_wild(db, &params.q)
+            let parsed = parse_search_query(query)?;
+            let db = &shared_state.api_db;
+            let ranking = shared_state.config.load().search.ranking;
+            let mut foods = Vec::new();
+            for term in search_terms(db, &parsed).await {
+                let mut found = database::search_foods_by_description_wild(
+                    db,
+                    &term.terms,
+                    allergen_free,
+                    min_completeness,
+                    exclude_branded,
+                    diet_flags,
+                    max_candidates,
+                )
                 .await
-                .map_err(|_| {
-                    APIError::new(
-                        StatusCode::NOT_FOUND,
-                        "Veritabanına yemek sorgusu atılırken bir hata oluştu",
-                    )
+                .map_err(|e| {
+                    error!("Açıklamada yemek aranırken hata oluştu: {:?}", e);
+                    APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
                 })?;
 
-            // Yemeklerin alakasına göre sıralıyoruz, örneğin query=Elm için 1. Elma, 2. Fuji Elma ... gibi
-            sort_foods_by_query(&mut foods, &params.q).await;
+                // Yemeklerin alakasına göre sıralıyoruz, örneğin query=Elm için 1. Elma, 2. Fuji Elma ... gibi
+                sort_foods_by_query(&mut found, &term.text(), ranking).await;
+                append_unique(&mut foods, found);
+            }
+
+            // Bulanık eşleşmeler tam eşleşmelerin arkasına ekleniyor, "elmaa" ve "protakal" da Elma ve Portakal'ı bulsun
+            if params.fuzzy.unwrap_or(false) {
+                let max_distance = shared_state.config.load().search.fuzzy_max_distance;
+                let candidates = database::select_verified_foods(
+                    db,
+                    allergen_free,
+                    min_completeness,
+                    exclude_branded,
+                    diet_flags,
+                )
+                .await
+                .map_err(|e| {
+                    error!(
+                        "Bulanık arama için yemekler sorgulanırken hata oluştu: {:?}",
+                        e
+                    );
+                    APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+                })?;
+                let fuzzy_matches =
+                    fuzzy_match_foods(candidates, &foods, &parsed.text(), max_distance);
+                foods.extend(fuzzy_matches);
+            }
 
-            Ok(foods)
+            Ok((foods, None))
         }
 
         "tag" => {
-            let db = &*shared_state.api_db.lock().await;
-            let foods = database::search_foods_by_tag_wild(db, &params.q)
+            // q=meyve,yaz&op=and ile birden fazla etikete göre aranabiliyor
+            let tags: Vec<&str> = query
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .collect();
+            if tags.is_empty() {
+                return Err(APIError::new(
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidQueryLength,
+                    "En az bir etiket girilmeli",
+                ));
+            }
+            if tags.len() > MAX_SEARCH_TAGS {
+                return Err(APIError::new(
+                    StatusCode::BAD_REQUEST,
+                    ErrorCode::InvalidParameters,
+                    &format!("En fazla {} etiket ile arama yapılabilir", MAX_SEARCH_TAGS),
+                ));
+            }
+            let match_all = params.op.unwrap_or_default() == TagOperator::And;
+
+            let db = &shared_state.api_db;
+            let (foods, total) = database::search_foods_by_tag_wild(
+                db,
+                &tags,
+                match_all,
+                allergen_free,
+                min_completeness,
+                exclude_branded,
+                diet_flags,
+                limit,
+                offset,
+            )
+            .await
+            .map_err(|e| {
+                error!("Etiket ile yemek aranırken hata oluştu: {:?}", e);
+                APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+            })?;
+
+            Ok((foods, Some(total)))
+        }
+
+        // Sorgu açıklama, etiket ve kaynak adlarında aranıyor, istemcinin tek bir alan seçmesi gerekmiyor
+        "all" => {
+            let parsed = parse_search_query(query)?;
+            let db = &shared_state.api_db;
+            let weights = shared_state.config.load().search.field_weights;
+            let mut foods = Vec::new();
+            for term in search_terms(db, &parsed).await {
+                let mut found = database::search_foods_by_any_field(
+                    db,
+                    &term.terms,
+                    allergen_free,
+                    min_completeness,
+                    exclude_branded,
+                    diet_flags,
+                    max_candidates,
+                )
                 .await
-                .map_err(|_| {
-                    APIError::new(
-                        StatusCode::NOT_FOUND,
-                        "Etiket ile yemek ararken sonuç bulunamadı",
-                    )
+                .map_err(|e| {
+                    error!("Tüm alanlarda yemek aranırken hata oluştu: {:?}", e);
+                    APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
                 })?;
 
-            Ok(foods)
+                sort_foods_by_fields(&mut found, &term, weights);
+                append_unique(&mut foods, found);
+            }
+            Ok((foods, None))
         }
 
-        _ => Err(APIError::new(StatusCode::BAD_REQUEST, "Geçersiz sorgu!")),
+        _ => Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidSearchMode,
+            "Geçersiz sorgu!",
+        )),
     }?;
 
-    // Onaylanmamış yemekleri döndürmüyoruz
-    foods.retain(|food| food.verified.unwrap_or(false));
-    // Sadece limit kadar yemeğe ihtiyacımız var, gerisini siliyoruz
+    // Sıralama bittikten sonra sadece istenen sayfayı bırakıyoruz, toplamı da header'da döndürüyoruz.
+    // Uygulamada sıralanan modlarda toplam en fazla max_candidates (ve eş anlamlıların adayları) kadar
+    let total = match paged_total {
+        Some(total) => total,
+        None => paginate(&mut foods, offset, limit) as u64,
+    };
+    Ok((foods, total))
+}
+
+// search_foods'un açıklama ve tüm alan aramaları, adaylar bellekteki indeksten seçilip sıralanıyor ve veritabanından sadece
+// istenen sayfadaki yemekler okunuyor. Sıralama ve toplam veritabanı aramasıyla aynı
+async fn search_in_index(
+    shared_state: &SharedState,
+    params: &SearchParams,
+    index: &[IndexEntry],
+    fields: IndexFields,
+    query: &str,
+    filter: &IndexFilter<'_>,
+    limit: u64,
+) -> Result<(Vec<Food>, u64), APIError> {
+    let parsed = parse_search_query(query)?;
+    let (ranking, weights, max_candidates, max_distance) = {
+        let config_guard = shared_state.config.load();
+        (
+            config_guard.search.ranking,
+            config_guard.search.field_weights,
+            config_guard.search.max_candidates,
+            config_guard.search.fuzzy_max_distance,
+        )
+    };
+    let db = &shared_state.api_db;
+
+    let mut ranked: Vec<&IndexEntry> = Vec::new();
+    for term in search_terms(db, &parsed).await {
+        let mut found =
+            search_index::candidates(index, &term.terms, fields, filter, max_candidates);
+        match fields {
+            IndexFields::Names => sort_foods_by_query(&mut found, &term.text(), ranking).await,
+            IndexFields::All => sort_foods_by_fields(&mut found, &term, weights),
+        }
+        append_unique(&mut ranked, found);
+    }
+    if fields == IndexFields::Names && params.fuzzy.unwrap_or(false) {
+        let candidates: Vec<&IndexEntry> =
+            index.iter().filter(|entry| filter.matches(entry)).collect();
+        let fuzzy_matches = fuzzy_match_foods(candidates, &ranked, &parsed.text(), max_distance);
+        ranked.extend(fuzzy_matches);
+    }
+
+    let total = paginate(&mut ranked, params.offset.unwrap_or(0), limit);
+    let slugs: Vec<String> = ranked.iter().map(|entry| entry.slug.clone()).collect();
+    let mut foods = database::select_verified_foods_by_slugs(db, &slugs)
+        .await
+        .map_err(|e| {
+            error!(
+                "Arama sonuçları veritabanından okunurken hata oluştu: {:?}",
+                e
+            );
+            APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+        })?;
+    // Sorgu sırayı korumuyor, indeksteki sıraya diziliyor. İndeks yüklendikten sonra onayı kaldırılan yemekler sonuçta yok
+    foods.sort_by_key(|food| {
+        slugs
+            .iter()
+            .position(|slug| food.slug.as_ref() == Some(slug))
+    });
+    Ok((foods, total as u64))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SuggestParams {
+    q: String,
+    limit: Option<u64>,
+}
+
+// Sonuç bulunamayan aramalardan sonra "Bunu mu demek istediniz?" önerileri, yazım hatasına en yakın yemek isimleri
+#[instrument(skip_all)]
+pub(crate) async fn foods_suggest(
+    Query(params): Query<SuggestParams>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<Json<Vec<Suggestion>>, APIError> {
+    let query = match validate_input(&params.q, MAX_INPUT_CHARS) {
+        Ok(query) => query,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+    let (max_distance, max_limit) = {
+        let config_guard = shared_state.config.load();
+        (
+            config_guard.search.fuzzy_max_distance,
+            config_guard.api.search_max_limit,
+        )
+    };
+    let limit = params.limit.unwrap_or(5);
+    if limit > max_limit {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::SearchLimitExceeded,
+            "Arama limitini geçtiniz!",
+        ));
+    }
+
+    Ok(Json(
+        shared_state
+            .suggestions
+            .suggest(&query, max_distance, limit as usize)
+            .await,
+    ))
+}
+
+// "tavuk göğsü" tırnaklıysa ifade olarak, değilse tavuk ve göğsü kelimeleri ayrı ayrı aranıyor
+fn parse_search_query(query: &str) -> Result<SearchQuery, APIError> {
+    let parsed = SearchQuery::parse(query);
+    if parsed.terms.is_empty() {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidQueryLength,
+            "Sorgu en az bir kelime içermeli",
+        ));
+    }
+    if parsed.terms.len() > MAX_QUERY_TERMS {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            &format!(
+                "Sorgu en fazla {} kelime veya ifade içerebilir",
+                MAX_QUERY_TERMS
+            ),
+        ));
+    }
+    Ok(parsed)
+}
+
+// Sorgu ve admin endpoint'lerinden tanımlanan eş anlamlıları (patates -> potato), önce sorgunun kendisi.
+// Eş anlamlılar tüm sorguyla eşleşiyor ve bölünmeden aranıyor. Okunamazlarsa arama sadece sorguyla devam ediyor
+async fn search_terms(db: &SqlitePool, query: &SearchQuery) -> Vec<SearchQuery> {
+    let mut terms = vec![query.clone()];
+    match database::select_synonyms(db, &query.text()).await {
+        Ok(synonyms) => terms.extend(synonyms.iter().map(|synonym| SearchQuery::phrase(synonym))),
+        Err(e) => error!("Eş anlamlılar okunurken hata oluştu: {:?}", e),
+    }
+    terms
+}
+
+// Eş anlamlılarla bulunan yemekler kendi aralarında sıralanıp önceki terimlerin sonuçlarının arkasına ekleniyor
+fn append_unique<T: Rankable>(foods: &mut Vec<T>, found: Vec<T>) {
+    for food in found {
+        if !foods.iter().any(|existing| existing.id() == food.id()) {
+            foods.push(food);
+        }
+    }
+}
+
+// Sıralanmış sonuçlardan offset kadarını atlayıp limit kadarını bırakıyor, sayfalamadan önceki toplamı döndürüyor
+fn paginate<T>(foods: &mut Vec<T>, offset: u64, limit: u64) -> usize {
+    let total = foods.len();
+    foods.drain(..(offset.min(total as u64) as usize));
     foods.truncate(limit as usize);
-    // Kalan yemeklerin de resim URL'lerini düzeltiyoruz
-    fix_image_urls(&State(shared_state), &mut foods).await;
+    total
+}
 
-    debug!(
-        "GET /foods/search: mod={}, limit={}, sorgu=\"{}\", ({} yemek), {}",
-        mode.as_str(),
-        limit,
-        &params.q,
-        foods.len(),
-        parse_client_ip(&addr, &headers)
-    );
-    Ok(Json(foods))
-}
-
-fn sanitize_input(s: &str) -> Result<(), APIError> {
-    // Normal bir yemek isminde olmaması gereken karakterler var mı diye de bakalım.
-    // Bu karakterler kullanılsa dahi sorun olmaması lazım, yine de önlemimizi alalım.
-    if s.contains("..")
-        || s.contains("/")
-        || s.contains("\\")
-        || s.contains("\0")
-        || s.contains(";")
-        || s.contains("*")
-        || s.contains("--")
-        || s.contains("/*")
-        || s.contains("*/")
-        || s.contains("'")
-        || s.contains("\"")
-        || s.contains("\\")
-        || s.trim().is_empty()
+// Sorgular veritabanına parametre olarak gidiyor, yani SQL için karakter engellemeye gerek yok; "Anne'nin Köftesi" gibi isimler de aranabilmeli.
+// Burada sadece uzunluğu, kontrol karakterlerini ve görünmez karakterleri denetliyoruz, girdiyi de NFC'ye normalize edip döndürüyoruz
+pub(crate) fn validate_input(s: &str, max_chars: usize) -> Result<String, APIError> {
+    let normalized = str::normalize_input(s);
+
+    if normalized.is_empty() || normalized.chars().count() > max_chars {
+        return Err(APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidQueryLength,
+            &format!(
+                "Sorgu en az 1, en fazla {} karakterden oluşabilir",
+                max_chars
+            ),
+        ));
+    }
+
+    if normalized
+        .chars()
+        .any(|c| c.is_control() || str::is_invisible_char(c))
     {
         return Err(APIError::new(
             StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidQueryCharacters,
             "Sorgu geçersiz karakterler içeriyor",
         ));
     }
 
-    Ok(())
+    Ok(normalized)
 }
 
-async fn fix_image_urls(State(shared_state): &State<SharedState>, foods: &mut Vec<Food>) {
-    // Eğer bir yemeğin resim URL'si / ile başlıyorsa, örneğin /images/muz.webp gibi, https://api.besinveri.com/images/muz.webp formatına getirilmeli
-    let base_url = &shared_state.config.lock().await.api.static_url;
-    foods
-        .iter_mut()
-        .filter(|food| food.image_url.starts_with("/"))
-        .for_each(|food| food.image_url = format!("{}{}", base_url, food.image_url));
+pub(crate) async fn record_offense(shared_state: &SharedState, real_ip: &RealIp, offense: Offense) {
+    shared_state
+        .metrics
+        .suspicious
+        .record(real_ip.ip(), offense)
+        .await;
 }
 
-async fn fix_image_url(State(shared_state): &State<SharedState>, food: &mut Food) {
-    // Eğer yemeğin resim URL'si / ile başlıyorsa, örneğin /images/muz.webp gibi, https://api.besinveri.com/images/muz.webp formatına getirilmeli
-    if food.image_url.starts_with("/") {
-        let base_url = &shared_state.config.lock().await.api.static_url;
-        food.image_url = format!("{}{}", base_url, food.image_url);
+// Sıralama fonksiyonlarının kullandığı alanlar; veritabanından okunan yemekler de arama indeksindeki satırlar da sıralanabiliyor.
+// Alanlar to_search_key ile normalize edilmiş dönüyor, indeksteki satırlar zaten normalize olduğu için kopyalanmıyor
+pub(crate) trait Rankable {
+    fn id(&self) -> Option<i64>;
+    // Açıklama ve diğer adlar
+    fn name_keys(&self) -> impl Iterator<Item = Cow<'_, str>>;
+    fn tag_keys(&self) -> impl Iterator<Item = Cow<'_, str>>;
+    fn source_key(&self) -> Cow<'_, str>;
+}
+
+impl Rankable for Food {
+    fn id(&self) -> Option<i64> {
+        self.id
+    }
+
+    fn name_keys(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.names()
+            .map(|name| Cow::Owned(str::to_search_key(name)))
+    }
+
+    fn tag_keys(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.tags
+            .iter()
+            .map(|tag| Cow::Owned(str::to_search_key(tag)))
+    }
+
+    fn source_key(&self) -> Cow<'_, str> {
+        Cow::Owned(str::to_search_key(&self.source))
     }
 }
 
-async fn sort_foods_by_query(foods: &mut Vec<Food>, query: &str) {
-    let query = query.to_lowercase();
+impl<T: Rankable> Rankable for &T {
+    fn id(&self) -> Option<i64> {
+        T::id(self)
+    }
 
-    // (original_index, yemek ref, skor)
-    let mut scored: Vec<(usize, Food, u64)> = foods
-        .drain(..)
-        .enumerate()
-        .filter_map(|(idx, food)| {
-            // Öncelikle sıralarken prefix şeklinde eşleşenlere öncelik vereceğiz
-            // Örneğin ka diye aratıldığında 0: K*ar*puz, 1: Porta*ka*l şeklinde sıralamak istiyoruz
-            // Bunun için basit bir puanlama sistemi yapıp bu puanlara göre sort edeceğiz, her eşleşen karakter için 1 puan ekleyeceğiz
-            let desc_lower = food.description.to_lowercase();
-            if desc_lower.starts_with(&query) {
-                return Some((idx, food, 20u64));
-            }
+    fn name_keys(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        T::name_keys(self)
+    }
 
-            // Prefix kontrolünü hiç geçemeyen yemekler için, örneğin ka diye arattığımızda Porta*ka*l ve Ma*ka*rna makarnanın öncelikli olmasını istiyoruz
-            // Başa ne kadar yakınsa o kadar yüksek puan olacak yani, pozisyona göre puan vereceğiz
-            if let Some(pos) = desc_lower.find(&query) {
-                let len = desc_lower.len();
-                let score = 10 * (len.saturating_sub(pos)) / len.max(1);
-                return Some((idx, food, score as u64));
-            }
+    fn tag_keys(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        T::tag_keys(self)
+    }
 
-            // Eğer hiçbir kontrole uymuyorsa buraya gelmiş olması mantıksız (SQL LIKE'da bir sorun yoksa), en kötü ihtimalle find'da bulunması gerek, yine de düşük bir skorla döndürelim.
-            return Some((idx, food, 0 as u64));
-        })
-        .collect();
+    fn source_key(&self) -> Cow<'_, str> {
+        T::source_key(self)
+    }
+}
+
+pub(crate) async fn sort_foods_by_query<T: Rankable>(
+    foods: &mut [T],
+    query: &str,
+    ranking: SearchRanking,
+) {
+    // Veritabanı araması gibi Türkçe karakter duyarsız karşılaştırıyoruz, "sut" araması Süt'ü başa almalı
+    let query = str::to_search_key(query);
+    match ranking {
+        SearchRanking::Trigram => sort_foods_by_similarity(foods, &query),
+        SearchRanking::Positional => sort_foods_by_position(foods, &query),
+    }
+}
+
+// Yemekleri puanlarına göre büyükten küçüğe yerinde sıralıyor, puanı aynı olanlar eski sıralarını koruyor.
+// Puanlar yemeklerle aynı sırada ayrı bir listeye hesaplanıyor, sıralanan da yemeklerin indeksleri; yemekler en sonda bir kez yer değiştiriyor
+fn sort_by_score<T>(foods: &mut [T], mut score: impl FnMut(&T) -> f64) {
+    let scores: Vec<f64> = foods.iter().map(&mut score).collect();
+    let mut order: Vec<usize> = (0..foods.len()).collect();
+    // sort_by stabil
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+    reorder(foods, order);
+}
+
+// order[i], i. sıraya gelecek yemeğin şimdiki indeksi. Permütasyonun döngüleri takip edilip yemekler swap ile yerine konuyor,
+// yerine konan indeksler order'da kendisini gösterecek şekilde işaretleniyor
+fn reorder<T>(foods: &mut [T], mut order: Vec<usize>) {
+    for start in 0..order.len() {
+        let mut current = start;
+        while order[current] != start {
+            let next = order[current];
+            foods.swap(current, next);
+            order[current] = current;
+            current = next;
+        }
+        order[current] = current;
+    }
+}
+
+// En benzer yemek önce, yemeğin açıklaması ve diğer adlarından en benzeri sayılıyor; benzerliği aynı olanlar veritabanı sırasını koruyor
+fn sort_foods_by_similarity<T: Rankable>(foods: &mut [T], query: &str) {
+    let query = str::Trigrams::new(query);
+    // Her ad için yeni bir liste ayırmamak için üçlüler aynı listeye yazılıyor
+    let mut name_trigrams = str::Trigrams::default();
+    sort_by_score(foods, |food| {
+        food.name_keys()
+            .map(|name| {
+                name_trigrams.set(&name);
+                query.similarity(&name_trigrams)
+            })
+            .fold(0.0, f64::max)
+    });
+}
+
+// mode=all sıralaması: alan sorgunun tüm parçalarını içeriyorsa puanı 0.5'ten başlayıp benzerliğe göre 1.0'a kadar çıkıyor,
+// parçaların bir kısmını içeriyorsa bu oranda azalıyor. Etiketlerden ve açıklamayla diğer adlardan en iyi eşleşeni sayılıyor, alan
+// puanları ağırlıklarıyla toplanıyor.
+// Puanı aynı olanlar veritabanı sırasını koruyor
+fn sort_foods_by_fields<T: Rankable>(
+    foods: &mut [T],
+    query: &SearchQuery,
+    weights: SearchFieldWeights,
+) {
+    let query_trigrams = str::Trigrams::new(&query.text());
+    let mut field_trigrams = str::Trigrams::default();
+    let mut field_score = |field: &str| {
+        let matched = query
+            .terms
+            .iter()
+            .filter(|term| field.contains(term.as_str()))
+            .count();
+        if matched == 0 {
+            return 0.0;
+        }
+        field_trigrams.set(field);
+        matched as f64 / query.terms.len() as f64
+            * (0.5 + 0.5 * query_trigrams.similarity(&field_trigrams))
+    };
+
+    sort_by_score(foods, |food| {
+        let tags = food
+            .tag_keys()
+            .map(|tag| field_score(&tag))
+            .fold(0.0, f64::max);
+        let description = food
+            .name_keys()
+            .map(|name| field_score(&name))
+            .fold(0.0, f64::max);
+        weights.description * description
+            + weights.tags * tags
+            + weights.source * field_score(&food.source_key())
+    });
+}
+
+fn sort_foods_by_position<T: Rankable>(foods: &mut [T], query: &str) {
+    // Öncelikle sıralarken prefix şeklinde eşleşenlere öncelik vereceğiz
+    // Örneğin ka diye aratıldığında 0: K*ar*puz, 1: Porta*ka*l şeklinde sıralamak istiyoruz
+    // Bunun için basit bir puanlama sistemi yapıp bu puanlara göre sort edeceğiz, her eşleşen karakter için 1 puan ekleyeceğiz
+    // Diğer adlarla bulunan yemekler için en iyi puanı alan adı sayıyoruz
+    sort_by_score(foods, |food| {
+        food.name_keys()
+            .map(|name| {
+                if name.starts_with(query) {
+                    return 20u64;
+                }
+
+                // Prefix kontrolünü hiç geçemeyen yemekler için, örneğin ka diye arattığımızda Porta*ka*l ve Ma*ka*rna makarnanın öncelikli olmasını istiyoruz
+                // Başa ne kadar yakınsa o kadar yüksek puan olacak yani, pozisyona göre puan vereceğiz
+                if let Some(pos) = name.find(query) {
+                    let len = name.len();
+                    return (10 * (len.saturating_sub(pos)) / len.max(1)) as u64;
+                }
+
+                // Eğer hiçbir kontrole uymuyorsa buraya gelmiş olması mantıksız (SQL LIKE'da bir sorun yoksa), en kötü ihtimalle find'da bulunması gerek, yine de düşük bir skorla döndürelim.
+                0_u64
+            })
+            .max()
+            .unwrap_or(0) as f64
+    });
+}
 
-    // Puanlara göre yüksekten düşüğe sıralıyoruz
-    scored.sort_unstable_by(|a, b| b.2.cmp(&a.2));
+// Tam eşleşmelerde olmayan yemeklerden sorguya en fazla izin verilen harf farkı kadar uzak olanlar, en yakın olan önce.
+// Sorgu, açıklamanın aynı sayıda kelimeden oluşan her parçasıyla karşılaştırılıyor ki "portakl" "Portakal Suyu"nu da bulsun
+fn fuzzy_match_foods<T: Rankable>(
+    candidates: Vec<T>,
+    exact_matches: &[T],
+    query: &str,
+    max_distance: usize,
+) -> Vec<T> {
+    let query = str::to_search_key(query);
+    let max_distance = max_distance.min(query.chars().count() / 3);
+    if max_distance == 0 {
+        return Vec::new();
+    }
 
-    // Sıralanmış yemekleri de birleştirip güncelliyoruz
-    *foods = scored.into_iter().map(|(_, food, _)| food).collect();
+    let mut matches: Vec<(usize, T)> = candidates
+        .into_iter()
+        .filter(|food| !exact_matches.iter().any(|exact| exact.id() == food.id()))
+        .filter_map(|food| {
+            let distance = food
+                .name_keys()
+                .map(|name| str::fuzzy_distance(&query, &name))
+                .min()?;
+            (distance <= max_distance).then_some((distance, food))
+        })
+        .collect();
+    // sort_by_key stabil, aynı uzaklıktaki yemekler veritabanı sırasını koruyor
+    matches.sort_by_key(|(distance, _)| *distance);
+    matches.into_iter().map(|(_, food)| food).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Instant;
+    use crate::core::food::{Allergen, FoodImage};
 
     // Test verisi oluşturan helper fonksiyonlar
     fn create_test_foods() -> Vec<Food> {
@@ -349,7 +1320,7 @@ mod tests {
                 slug: Some("karpuz".to_string()),
                 description: "Karpuz yaz meyvesi olarak bilinir".to_string(),
                 verified: Some(true),
-                image_url: "http://example.com/karpuz.jpg".to_string(),
+                image: FoodImage::new("http://example.com/karpuz.jpg"),
                 source: "Wikipedia".to_string(),
                 tags: vec!["meyve".to_string(), "yaz".to_string()],
                 allergens: vec![],
@@ -362,6 +1333,8 @@ mod tests {
                 saturated_fat: 0.0,
                 trans_fat: 0.0,
                 sugar: 6.2,
+                added_sugar: 0.0,
+                polyols: 0.0,
                 fiber: 0.4,
                 cholesterol: 0.0,
                 sodium: 1.0,
@@ -378,6 +1351,27 @@ mod tests {
                 vitamin_d: 0.0,
                 vitamin_e: 0.05,
                 vitamin_k: 0.1,
+                omega_3: 0.0,
+                omega_6: 0.0,
+                folate: 0.0,
+                niacin: 0.0,
+                phosphorus: 0.0,
+                selenium: 0.0,
+                copper: 0.0,
+                seasonality: Vec::new(),
+                variant_group: None,
+                brand: None,
+                generic_food: None,
+                gtin: None,
+                basis: Basis::Per100g,
+                completeness: 0.0,
+                diet_flags: BTreeMap::new(),
+                aliases: Vec::new(),
+                verification: None,
+                additives: Vec::new(),
+                fruit_vegetable_percent: None,
+                nutri_score: None,
+                nova_group: None,
             },
             // Contains "kar" in middle
             Food {
@@ -385,7 +1379,7 @@ mod tests {
                 slug: Some("portakal".to_string()),
                 description: "Portakal C vitamini açısından zengindir".to_string(),
                 verified: Some(true),
-                image_url: "http://example.com/portakal.jpg".to_string(),
+                image: FoodImage::new("http://example.com/portakal.jpg"),
                 source: "Wikipedia".to_string(),
                 tags: vec!["meyve".to_string(), "narenciye".to_string()],
                 allergens: vec![],
@@ -398,6 +1392,8 @@ mod tests {
                 saturated_fat: 0.0,
                 trans_fat: 0.0,
                 sugar: 9.35,
+                added_sugar: 0.0,
+                polyols: 0.0,
                 fiber: 2.4,
                 cholesterol: 0.0,
                 sodium: 0.0,
@@ -414,6 +1410,27 @@ mod tests {
                 vitamin_d: 0.0,
                 vitamin_e: 0.18,
                 vitamin_k: 0.0,
+                omega_3: 0.0,
+                omega_6: 0.0,
+                folate: 0.0,
+                niacin: 0.0,
+                phosphorus: 0.0,
+                selenium: 0.0,
+                copper: 0.0,
+                seasonality: Vec::new(),
+                variant_group: None,
+                brand: None,
+                generic_food: None,
+                gtin: None,
+                basis: Basis::Per100g,
+                completeness: 0.0,
+                diet_flags: BTreeMap::new(),
+                aliases: Vec::new(),
+                verification: None,
+                additives: Vec::new(),
+                fruit_vegetable_percent: None,
+                nutri_score: None,
+                nova_group: None,
             },
             // Contains "kar" at end
             Food {
@@ -421,10 +1438,10 @@ mod tests {
                 slug: Some("makarna".to_string()),
                 description: "Makarna İtalyan mutfağının temelidir".to_string(),
                 verified: Some(true),
-                image_url: "http://example.com/makarna.jpg".to_string(),
+                image: FoodImage::new("http://example.com/makarna.jpg"),
                 source: "Wikipedia".to_string(),
                 tags: vec!["makarna".to_string(), "italyan".to_string()],
-                allergens: vec!["gluten".to_string()],
+                allergens: vec![Allergen::contains("gluten")],
                 servings: servings.clone(),
                 glycemic_index: 50.0,
                 energy: 371.0,
@@ -434,6 +1451,8 @@ mod tests {
                 saturated_fat: 0.3,
                 trans_fat: 0.0,
                 sugar: 2.7,
+                added_sugar: 0.0,
+                polyols: 0.0,
                 fiber: 2.5,
                 cholesterol: 0.0,
                 sodium: 6.0,
@@ -450,6 +1469,27 @@ mod tests {
                 vitamin_d: 0.0,
                 vitamin_e: 0.11,
                 vitamin_k: 0.0,
+                omega_3: 0.0,
+                omega_6: 0.0,
+                folate: 0.0,
+                niacin: 0.0,
+                phosphorus: 0.0,
+                selenium: 0.0,
+                copper: 0.0,
+                seasonality: Vec::new(),
+                variant_group: None,
+                brand: None,
+                generic_food: None,
+                gtin: None,
+                basis: Basis::Per100g,
+                completeness: 0.0,
+                diet_flags: BTreeMap::new(),
+                aliases: Vec::new(),
+                verification: None,
+                additives: Vec::new(),
+                fruit_vegetable_percent: None,
+                nutri_score: None,
+                nova_group: None,
             },
             // No match
             Food {
@@ -457,7 +1497,7 @@ mod tests {
                 slug: Some("elma".to_string()),
                 description: "Elma güneydoğu Asya kökenlidir".to_string(),
                 verified: Some(false),
-                image_url: "http://example.com/elma.jpg".to_string(),
+                image: FoodImage::new("http://example.com/elma.jpg"),
                 source: "Wikipedia".to_string(),
                 tags: vec!["meyve".to_string()],
                 allergens: vec![],
@@ -470,6 +1510,8 @@ mod tests {
                 saturated_fat: 0.0,
                 trans_fat: 0.0,
                 sugar: 10.39,
+                added_sugar: 0.0,
+                polyols: 0.0,
                 fiber: 2.4,
                 cholesterol: 0.0,
                 sodium: 1.0,
@@ -486,137 +1528,36 @@ mod tests {
                 vitamin_d: 0.0,
                 vitamin_e: 0.18,
                 vitamin_k: 2.2,
+                omega_3: 0.0,
+                omega_6: 0.0,
+                folate: 0.0,
+                niacin: 0.0,
+                phosphorus: 0.0,
+                selenium: 0.0,
+                copper: 0.0,
+                seasonality: Vec::new(),
+                variant_group: None,
+                brand: None,
+                generic_food: None,
+                gtin: None,
+                basis: Basis::Per100g,
+                completeness: 0.0,
+                diet_flags: BTreeMap::new(),
+                aliases: Vec::new(),
+                verification: None,
+                additives: Vec::new(),
+                fruit_vegetable_percent: None,
+                nutri_score: None,
+                nova_group: None,
             },
         ]
     }
 
-    fn generate_large_food_dataset(size: usize) -> Vec<Food> {
-        let mut foods = Vec::with_capacity(size);
-        let mut servings = BTreeMap::new();
-        servings.insert("portion".to_string(), 100.0);
-
-        for i in 0..size {
-            let description = match i % 5 {
-                0 => format!("Karpuz {} yaz meyvesi olarak bilinir", i),
-                1 => format!("Portakal {} C vitamini açısından zengindir", i),
-                2 => format!("Makarna {} İtalyan mutfağının temelidir", i),
-                3 => format!("Elma {} güneydoğu Asya kökenlidir", i),
-                _ => format!("Meyve {} farklı türde bulunur", i),
-            };
-
-            foods.push(Food {
-                id: Some(i as i64),
-                slug: Some(format!("food-{}", i)),
-                description,
-                verified: Some(true),
-                image_url: format!("http://example.com/food-{}.jpg", i),
-                source: "Test Data".to_string(),
-                tags: vec![format!("tag-{}", i % 3)],
-                allergens: vec![],
-                servings: servings.clone(),
-                glycemic_index: 50.0 + (i as f64 % 50.0), // 50-100 arası rastgele
-                energy: 100.0 + (i as f64 % 400.0),       // 100-500 arası
-                carbohydrate: 20.0 + (i as f64 % 60.0),   // 20-80 arası
-                protein: 5.0 + (i as f64 % 20.0),         // 5-25 arası
-                fat: 3.0 + (i as f64 % 15.0),             // 3-18 arası
-                saturated_fat: 1.0 + (i as f64 % 5.0),    // 1-6 arası
-                trans_fat: 0.0,
-                sugar: 10.0 + (i as f64 % 30.0), // 10-40 arası
-                fiber: 2.0 + (i as f64 % 8.0),   // 2-10 arası
-                cholesterol: 0.0,
-                sodium: 50.0 + (i as f64 % 100.0), // 50-150 arası
-                potassium: 200.0 + (i as f64 % 300.0), // 200-500 arası
-                water: 80.0 + (i as f64 % 20.0),   // 80-100 arası
-                iron: 1.0 + (i as f64 % 2.0),      // 1-3 arası
-                magnesium: 20.0 + (i as f64 % 40.0), // 20-60 arası
-                calcium: 30.0 + (i as f64 % 70.0), // 30-100 arası
-                zinc: 0.5 + (i as f64 % 1.5),      // 0.5-2 arası
-                vitamin_a: 100.0 + (i as f64 % 200.0), // 100-300 arası
-                vitamin_b6: 0.1 + (i as f64 % 0.2), // 0.1-0.3 arası
-                vitamin_b12: 0.0,
-                vitamin_c: 50.0 + (i as f64 % 100.0), // 50-150 arası
-                vitamin_d: 5.0 + (i as f64 % 10.0),   // 5-15 arası
-                vitamin_e: 2.0 + (i as f64 % 3.0),    // 2-5 arası
-                vitamin_k: 10.0 + (i as f64 % 20.0),  // 10-30 arası
-            });
-        }
-
-        foods
-    }
-
-    // Testleri async yap
-    #[tokio::test]
-    async fn performance_test_small_dataset() {
-        let mut foods = generate_large_food_dataset(100);
-        let query = "kar";
-        let start = Instant::now();
-        sort_foods_by_query(&mut foods, query).await; // .await ekle
-        let duration = start.elapsed();
-
-        println!("100 foods: {:?}", duration);
-        assert!(duration.as_millis() < 10);
-        assert_eq!(foods.len(), 100);
-    }
-
-    #[tokio::test]
-    async fn performance_test_medium_dataset() {
-        let mut foods = generate_large_food_dataset(1000);
-        let query = "kar";
-        let start = Instant::now();
-        sort_foods_by_query(&mut foods, query).await;
-        let duration = start.elapsed();
-
-        println!("1000 foods: {:?}", duration);
-        assert!(duration.as_millis() < 50);
-        assert_eq!(foods.len(), 1000);
-    }
-
-    #[tokio::test]
-    async fn performance_test_large_dataset() {
-        let mut foods = generate_large_food_dataset(5000);
-        let query = "kar";
-        let start = Instant::now();
-        sort_foods_by_query(&mut foods, query).await;
-        let duration = start.elapsed();
-
-        println!("5000 foods: {:?}", duration);
-        assert!(duration.as_millis() < 250);
-        assert_eq!(foods.len(), 5000);
-    }
-
-    #[tokio::test]
-    async fn performance_regression_test() {
-        let sizes = [(100usize, 10u64), (1000usize, 50u64), (5000usize, 250u64)];
-        let query = "kar";
-
-        for &(size, max_ms) in &sizes {
-            println!("\nTesting {} foods (max {}ms)", size, max_ms);
-
-            let mut foods = generate_large_food_dataset(size);
-            let start = Instant::now();
-            sort_foods_by_query(&mut foods, query).await; // ✅ .await
-            let duration = start.elapsed();
-
-            let ms = duration.as_millis();
-
-            println!("Duration: {}ms", ms);
-
-            assert!(
-                ms < max_ms as u128,
-                "{} foods için {}ms (max: {}ms)",
-                size,
-                ms,
-                max_ms
-            );
-            assert_eq!(foods.len(), size);
-        }
-    }
-
     // Diğer unit testleri de güncelle
     #[tokio::test]
     async fn test_sort_by_query_prefix_match() {
         let mut foods = create_test_foods();
-        sort_foods_by_query(&mut foods, "kar").await; // ✅ .await
+        sort_foods_by_query(&mut foods, "kar", SearchRanking::Positional).await; // ✅ .await
 
         assert_eq!(foods[0].slug, Some("karpuz".to_string()));
         assert_eq!(foods[1].slug, Some("makarna".to_string()));
@@ -633,7 +1574,7 @@ mod tests {
                 slug: Some("baslangic".to_string()),
                 description: "Kaşar peyniri başlangıçta kullanılır".to_string(),
                 verified: Some(true),
-                image_url: "".to_string(),
+                image: FoodImage::new(""),
                 source: "".to_string(),
                 tags: vec![],
                 allergens: vec![],
@@ -646,7 +1587,7 @@ mod tests {
                 slug: Some("orta".to_string()),
                 description: "Peynir kaşar peyniri ortada erir".to_string(),
                 verified: Some(true),
-                image_url: "".to_string(),
+                image: FoodImage::new(""),
                 source: "".to_string(),
                 tags: vec![],
                 allergens: vec![],
@@ -659,7 +1600,7 @@ mod tests {
                 slug: Some("son".to_string()),
                 description: "Peynir ortada kaşar peyniri sonda".to_string(),
                 verified: Some(true),
-                image_url: "".to_string(),
+                image: FoodImage::new(""),
                 source: "".to_string(),
                 tags: vec![],
                 allergens: vec![],
@@ -668,7 +1609,7 @@ mod tests {
             },
         ];
 
-        sort_foods_by_query(&mut foods, "kaşar").await;
+        sort_foods_by_query(&mut foods, "kaşar", SearchRanking::Positional).await;
 
         // Başlangıçta olan en yüksek skor almalı (20 puan)
         assert_eq!(foods[0].slug, Some("baslangic".to_string()));
@@ -683,7 +1624,7 @@ mod tests {
         let mut foods = create_test_foods();
         let original_order = foods.clone();
 
-        sort_foods_by_query(&mut foods, "xyz").await; // Hiçbir şeyle eşleşmez
+        sort_foods_by_query(&mut foods, "xyz", SearchRanking::Positional).await; // Hiçbir şeyle eşleşmez
 
         // Sıralama değişmemeli (hepsi 0 skor)
         assert_eq!(foods, original_order);
@@ -694,7 +1635,7 @@ mod tests {
         let mut foods = create_test_foods();
         let original_order = foods.clone();
 
-        sort_foods_by_query(&mut foods, "").await;
+        sort_foods_by_query(&mut foods, "", SearchRanking::Positional).await;
 
         // Boş query ile sıralama değişmemeli
         assert_eq!(foods, original_order);
@@ -705,7 +1646,7 @@ mod tests {
         let mut foods: Vec<Food> = vec![];
         let original = foods.clone();
 
-        sort_foods_by_query(&mut foods, "test").await;
+        sort_foods_by_query(&mut foods, "test", SearchRanking::Positional).await;
 
         assert_eq!(foods, original);
     }
@@ -714,7 +1655,7 @@ mod tests {
     async fn test_sort_by_query_case_insensitive() {
         let mut foods = create_test_foods();
 
-        sort_foods_by_query(&mut foods, "KaR").await;
+        sort_foods_by_query(&mut foods, "KaR", SearchRanking::Positional).await;
 
         // Büyük/küçük harf duyarlılığı olmamalı
         assert_eq!(foods[0].slug, Some("karpuz".to_string()));
@@ -729,7 +1670,7 @@ mod tests {
                 slug: Some("karpuz".to_string()),
                 description: "Karpuz".to_string(),
                 verified: Some(true),
-                image_url: "".to_string(),
+                image: FoodImage::new(""),
                 source: "".to_string(),
                 tags: vec![],
                 allergens: vec![],
@@ -741,7 +1682,7 @@ mod tests {
                 slug: Some("portakal".to_string()),
                 description: "Portakal".to_string(),
                 verified: Some(true),
-                image_url: "".to_string(),
+                image: FoodImage::new(""),
                 source: "".to_string(),
                 tags: vec![],
                 allergens: vec![],
@@ -751,9 +1692,169 @@ mod tests {
         ];
 
         let original_order = foods.clone();
-        sort_foods_by_query(&mut foods, "ka").await;
+        sort_foods_by_query(&mut foods, "ka", SearchRanking::Positional).await;
 
         // Aynı skorlu elementler orijinal sıralarını korumalı
         assert_eq!(foods, original_order);
     }
+
+    #[tokio::test]
+    async fn test_sort_by_similarity() {
+        let mut foods = create_test_foods();
+        sort_foods_by_query(&mut foods, "kar", SearchRanking::Trigram).await;
+        assert_eq!(foods[0].slug, Some("karpuz".to_string()));
+
+        // Kelime sırası farklı olsa da tam eşleşen açıklama, sorguyu içeren daha uzun açıklamanın önüne geçiyor
+        let food = |slug: &str, description: &str| Food {
+            slug: Some(slug.to_string()),
+            description: description.to_string(),
+            ..Default::default()
+        };
+        let mut foods = vec![
+            food("elma-turtasi", "Elma Turtası"),
+            food("fuji-elma", "Fuji Elma"),
+            food("muz", "Muz"),
+        ];
+        sort_foods_by_query(&mut foods, "elma fuji", SearchRanking::Trigram).await;
+        let slugs: Vec<_> = foods.into_iter().filter_map(|food| food.slug).collect();
+        assert_eq!(slugs, vec!["fuji-elma", "elma-turtasi", "muz"]);
+    }
+
+    #[test]
+    fn test_fuzzy_match_foods() {
+        let slugs = |foods: Vec<Food>| -> Vec<String> {
+            foods.into_iter().filter_map(|food| food.slug).collect()
+        };
+
+        // Yer değiştiren iki harf tek fark sayılıyor
+        assert_eq!(
+            slugs(fuzzy_match_foods(create_test_foods(), &[], "protakal", 2)),
+            vec!["portakal"]
+        );
+        assert_eq!(
+            slugs(fuzzy_match_foods(create_test_foods(), &[], "elmaa", 2)),
+            vec!["elma"]
+        );
+
+        // Tam eşleşmelerde olan yemekler tekrar eklenmiyor
+        let exact = vec![create_test_foods().remove(3)];
+        assert!(fuzzy_match_foods(create_test_foods(), &exact, "elmaa", 2).is_empty());
+
+        // 3 fark, 2'den fazla; 3 harften kısa sorgularda ise bulanık arama kapalı
+        assert!(fuzzy_match_foods(create_test_foods(), &[], "elmaaaa", 2).is_empty());
+        assert!(fuzzy_match_foods(create_test_foods(), &[], "el", 2).is_empty());
+        assert!(fuzzy_match_foods(create_test_foods(), &[], "protakal", 0).is_empty());
+
+        // Diğer adlar da karşılaştırılıyor
+        let mut foods = create_test_foods();
+        foods[0].aliases = vec!["Watermelon".to_owned()];
+        assert_eq!(
+            slugs(fuzzy_match_foods(foods, &[], "watermelom", 2)),
+            vec!["karpuz"]
+        );
+    }
+
+    #[test]
+    fn test_validate_input() {
+        // Kesme işareti ve yıldız gibi karakterler artık geçerli, sorgular parametreli gidiyor
+        assert_eq!(
+            validate_input("Anne'nin Köftesi", MAX_INPUT_CHARS).unwrap(),
+            "Anne'nin Köftesi"
+        );
+        assert_eq!(
+            validate_input("5* otel", MAX_INPUT_CHARS).unwrap(),
+            "5* otel"
+        );
+        assert_eq!(
+            validate_input(" Çiğ Köfte ", MAX_INPUT_CHARS).unwrap(),
+            "Çiğ Köfte"
+        );
+        assert_eq!(
+            validate_input("s\u{0327}algam", MAX_INPUT_CHARS).unwrap(),
+            "şalgam"
+        );
+
+        // Limit bayt değil karakter üzerinden, Türkçe karakterler iki bayt olsa da tek karakter sayılmalı
+        assert!(validate_input(&"ğ".repeat(MAX_INPUT_CHARS), MAX_INPUT_CHARS).is_ok());
+        assert!(validate_input(&"ğ".repeat(MAX_INPUT_CHARS + 1), MAX_INPUT_CHARS).is_err());
+
+        assert!(validate_input("", MAX_INPUT_CHARS).is_err());
+        assert!(validate_input("   ", MAX_INPUT_CHARS).is_err());
+        assert!(validate_input("elma\0", MAX_INPUT_CHARS).is_err());
+        assert!(validate_input("el\nma", MAX_INPUT_CHARS).is_err());
+        assert!(validate_input("el\u{200B}ma", MAX_INPUT_CHARS).is_err());
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut items = vec!['a', 'b', 'c', 'd', 'e'];
+        reorder(&mut items, vec![3, 0, 4, 1, 2]);
+        assert_eq!(items, vec!['d', 'a', 'e', 'b', 'c']);
+
+        let mut items = vec!['a', 'b', 'c'];
+        reorder(&mut items, vec![0, 1, 2]);
+        assert_eq!(items, vec!['a', 'b', 'c']);
+    }
+
+    #[test]
+    fn test_paginate() {
+        let mut foods = create_test_foods();
+        let total = foods.len();
+        let expected: Vec<String> = foods[2..4].iter().map(|f| f.description.clone()).collect();
+
+        assert_eq!(paginate(&mut foods, 2, 2), total);
+        assert_eq!(
+            foods
+                .iter()
+                .map(|f| f.description.clone())
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        // Sonuçların dışına taşan offset boş sayfa döndürüyor ama toplamı değiştirmiyor
+        let mut foods = create_test_foods();
+        assert_eq!(paginate(&mut foods, u64::MAX, 5), total);
+        assert!(foods.is_empty());
+    }
+
+    #[test]
+    fn test_parse_diet_flags() {
+        let rules = crate::core::diet_flags::default_rules();
+        assert_eq!(
+            parse_diet_flags(" vegan,gluten_free,,vegan", &rules).ok(),
+            Some(vec!["vegan", "gluten_free"])
+        );
+        assert_eq!(parse_diet_flags("", &rules).ok(), Some(vec![]));
+        assert!(parse_diet_flags("vegan,keto", &rules).is_err());
+    }
+
+    #[test]
+    fn test_sort_by_fields() {
+        let food = |description: &str, tags: &[&str], source: &str| Food {
+            description: description.to_owned(),
+            tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            source: source.to_owned(),
+            ..Default::default()
+        };
+        let mut foods = vec![
+            food("Ayran", &["içecek"], "Süt Üreticileri"),
+            food("Sütlaç", &["tatlı"], "karahanbuhan"),
+            food("Süt", &["süt ürünü", "içecek"], "karahanbuhan"),
+        ];
+
+        let weights = SearchFieldWeights::default();
+        sort_foods_by_fields(&mut foods, &SearchQuery::parse("sut"), weights);
+        let descriptions: Vec<&str> = foods.iter().map(|f| f.description.as_str()).collect();
+        // Süt hem açıklamada hem etikette tam eşleşiyor, Ayran sadece kaynakta
+        assert_eq!(descriptions, vec!["Süt", "Sütlaç", "Ayran"]);
+
+        // Kaynak ağırlığı yüksekse kaynağı eşleşen yemek öne geçiyor
+        let weights = SearchFieldWeights {
+            description: 1.0,
+            tags: 0.0,
+            source: 10.0,
+        };
+        sort_foods_by_fields(&mut foods, &SearchQuery::parse("sut"), weights);
+        assert_eq!(foods[0].description, "Ayran");
+    }
 }
@@ -1,20 +1,22 @@
 use std::{
-    net::{IpAddr, Ipv4Addr, SocketAddr},
-    time::Duration,
+    net::{IpAddr, Ipv4Addr},
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
 };
 
-use axum::{
-    Json,
-    extract::{ConnectInfo, State},
-    http::HeaderMap,
-};
-use chrono::{FixedOffset, Utc};
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::Utc;
 use reqwest::ClientBuilder;
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
-use tracing::debug;
 
-use crate::{SharedState, api::parse_client_ip};
+use crate::{
+    SharedState,
+    api::{
+        database::{DATABASE_DIR, migrations_applied},
+        metrics::DATABASE_QUERIES,
+    },
+};
 
 #[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct ServerHealth {
@@ -29,66 +31,120 @@ pub(crate) struct ServerHealth {
 
 #[derive(Serialize, Deserialize, Clone)]
 pub(crate) struct ServerHealthDetails {
-    internet_connection: bool,
+    // Kontrol config'de kapalıysa veya ilk kontrol henüz bitmediyse null
+    internet_connection: Option<bool>,
     database_functionality: bool,
+    // Ölçülemeyen değerler (ör: desteklenmeyen platformlarda bellek kullanımı) null dönüyor
+    database_latency_ms: Option<f64>,
+    disk_free_bytes: Option<u64>,
+    memory_rss_bytes: Option<u64>,
+    cache_entries: u64,
+    cache_size_bytes: u64,
+    uptime_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct Liveness {
+    status: &'static str,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct Readiness {
+    status: &'static str,
+    database_functionality: bool,
+    // Son sorgular tekrar denemelere rağmen kilit hatasıyla bittiyse true, o sırada istemciler 503 alıyor
+    database_locked: bool,
+    migrations_applied: bool,
+    cache_warmed_up: bool,
 }
 
 // Cargo bize environment üzerinden sürümü sağlıyor, manuel girmeye gerek yok
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-pub(crate) async fn health(
-    State(shared_state): State<SharedState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    headers: HeaderMap,
-) -> Json<ServerHealth> {
-    let timestamp = {
-        let utc_time = Utc::now();
-        let turkish_offset = FixedOffset::east_opt(3 * 3600).unwrap(); // +3 saat
-        utc_time.with_timezone(&turkish_offset).to_rfc3339() // ör: 2025-09-13T21:42:35.785219+03:00 (ISO 8601)
-    };
+pub(crate) async fn health(State(shared_state): State<SharedState>) -> Json<ServerHealth> {
+    // Saat dilimi config'den geliyor, ör: Europe/Istanbul için 2025-09-13T21:42:35.785219+03:00 (ISO 8601)
+    let timezone = shared_state.config.load().core.timezone;
+    let timestamp = Utc::now().with_timezone(&timezone).to_rfc3339();
 
-    // URL'lere klon atmadan yaparsak Mutex'i serbest bırakmadığımız için config'i blokluyor, yani diğer bağlantıları bloklamaması için urlleri klonluyoruz
-    // Zaten bir URL'ye ping atmak birkaç yüz ms sürdüğü için buradaki klon ne RAM ne de hız olarak önemli bir etkiye sebep olacak
-    let urls = &shared_state
-        .config
-        .lock()
-        .await
-        .api
-        .health_internet_check_urls
-        .clone();
-    let is_connected_to_internet = check_internet(urls).await;
-    let is_database_functional = check_database(&*shared_state.api_db.lock().await).await;
+    // Dış bağlantı kontrolü arka planda periyodik olarak yapılıyor, burada sadece son sonucu okuyoruz
+    let internet_connection = *shared_state
+        .internet_connection
+        .read()
+        .expect("İnternet durumu kilidi zehirlendi");
+    let pool = shared_state.api_db.clone();
+    let database_latency = ping_database(&pool).await;
+    let is_database_functional = database_latency.is_some();
 
     let health = ServerHealth {
         name: "besinveri-api",
         version: VERSION,
-        status: if is_connected_to_internet && is_database_functional {
+        status: if internet_connection != Some(false) && is_database_functional {
             "healthy"
         } else {
             "unhealthy"
         },
         details: ServerHealthDetails {
-            internet_connection: is_connected_to_internet,
+            internet_connection,
             database_functionality: is_database_functional,
+            database_latency_ms: database_latency
+                .map(|latency| (latency.as_secs_f64() * 1000.0 * 100.0).round() / 100.0),
+            disk_free_bytes: fs4::available_space(DATABASE_DIR).ok(),
+            memory_rss_bytes: memory_stats::memory_stats().map(|stats| stats.physical_mem as u64),
+            cache_entries: shared_state.cache.entry_count(),
+            cache_size_bytes: shared_state.cache.weighted_size(),
+            uptime_seconds: shared_state.started_at.elapsed().as_secs(),
         },
         documentation: "https://github.com/karahanbuhan/besinveri-api",
         source_code: "https://github.com/karahanbuhan/besinveri-api",
         last_updated: timestamp,
     };
-
-    debug!(
-        "GET /health: ({}), {}",
-        health.status,
-        parse_client_ip(&addr, &headers)
-    );
     Json(health)
 }
 
+// Liveness probe'u: süreç ayakta ve istek işleyebiliyor mu, dışarıya veya veritabanına hiç gitmiyoruz ki probe anında dönsün
+pub(crate) async fn live() -> Json<Liveness> {
+    Json(Liveness { status: "alive" })
+}
+
+// Readiness probe'u: veritabanı erişilebilir, migration'lar uygulanmış ve önbellek yüklenmiş mi.
+// İnternet kontrolü yapmıyoruz, dış bağlantının kopması bu sunucuya trafik gönderilmemesi için bir sebep değil
+pub(crate) async fn ready(
+    State(shared_state): State<SharedState>,
+) -> (StatusCode, Json<Readiness>) {
+    let pool = shared_state.api_db.clone();
+    let is_database_functional = check_database(&pool).await;
+    let are_migrations_applied = is_database_functional && migrations_applied(&pool).await;
+    let is_warmed_up = shared_state.warmed_up.load(Ordering::Relaxed);
+    let is_database_locked = DATABASE_QUERIES.is_locked();
+
+    let is_ready =
+        is_database_functional && !is_database_locked && are_migrations_applied && is_warmed_up;
+    let readiness = Readiness {
+        status: if is_ready { "ready" } else { "not_ready" },
+        database_functionality: is_database_functional,
+        database_locked: is_database_locked,
+        migrations_applied: are_migrations_applied,
+        cache_warmed_up: is_warmed_up,
+    };
+
+    if is_ready {
+        (StatusCode::OK, Json(readiness))
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, Json(readiness))
+    }
+}
+
 async fn check_database(pool: &SqlitePool) -> bool {
     sqlx::query("SELECT 1").fetch_one(pool).await.is_ok()
 }
 
-async fn check_internet(urls: &Vec<String>) -> bool {
+// Veritabanına basit bir sorgu atıp yanıt süresini ölçüyoruz, sorgu başarısızsa None
+async fn ping_database(pool: &SqlitePool) -> Option<Duration> {
+    let start = Instant::now();
+    check_database(pool).await.then(|| start.elapsed())
+}
+
+pub(crate) async fn check_internet(urls: &[String]) -> bool {
     let client = match ClientBuilder::new()
         .timeout(Duration::from_secs(3)) // 3 saniyeden fazla beklemiyoruz, bu kadar uzun bir bağlantı süresi zaten bağlantıda bir sorun olduğuna işarettir
         .local_address(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))) // IPv4 ile bağlanmak istiyoruz, gelecekte güncellenebilir
@@ -0,0 +1,73 @@
+use std::{sync::Arc, time::Duration};
+
+use moka::{future::Cache, policy::EvictionPolicy};
+
+use crate::{
+    api::metrics::Metrics,
+    core::{config::SearchConfig, food::Food, query::SearchQuery, str::to_search_key},
+};
+
+// Çok aranan sorguların ("elma", "tavuk") sonuçları, HTTP yanıt önbelleğinden ayrı. O önbellek URL'ye göre tuttuğu için
+// "Elma", "elma " ve "elma&limit=5" ayrı kayıtlar; burada anahtar normalize edilmiş sorgu, mod ve limit. TinyLFU politikası
+// tek seferlik aramaları içeri almıyor, sık arananları tutuyor. Böylece trafik patlamalarında popüler aramalar SQLite'a gitmiyor
+pub(crate) type HotSearchCache = Cache<String, Arc<HotSearchResult>>;
+
+// Resim URL'leri düzeltilmeden önceki sayfa ve sayfalamadan önceki toplam
+pub(crate) struct HotSearchResult {
+    pub(crate) foods: Vec<Food>,
+    pub(crate) total: u64,
+}
+
+pub(crate) fn build(config: &SearchConfig, metrics: Arc<Metrics>) -> HotSearchCache {
+    Cache::builder()
+        .max_capacity(config.hot_cache_entries)
+        .time_to_live(Duration::from_secs(config.hot_cache_ttl_seconds))
+        .eviction_policy(EvictionPolicy::tiny_lfu())
+        .eviction_listener(move |_key, _value, cause| {
+            metrics.search_hot_cache.record_removal(cause)
+        })
+        .build()
+}
+
+// Aynı sonucu döndüren yazımlar aynı anahtara düşüyor: büyük/küçük harf, Türkçe karakterler, fazla boşluk ve kelimelerin
+// tekrarı fark etmiyor. Bilinmeyen modlar için None, o aramalar zaten hata dönüyor
+pub(crate) fn key(mode: &str, query: &str, limit: u64) -> Option<String> {
+    let (mode, terms) = match mode {
+        "description" | "name" => ("description", SearchQuery::parse(query).terms),
+        "all" => ("all", SearchQuery::parse(query).terms),
+        "tag" => (
+            "tag",
+            query
+                .split(',')
+                .map(|tag| to_search_key(tag.trim()))
+                .filter(|tag| !tag.is_empty())
+                .collect(),
+        ),
+        _ => return None,
+    };
+    // Tırnaklı ifadeler boşluk içerebiliyor, terimleri boşlukla birleştirmek "tavuk göğsü" ile tavuk göğsü'nü karıştırırdı
+    Some(format!("{}:{}:{}", mode, limit, terms.join("\u{1f}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_normalization() {
+        assert_eq!(key("description", "Elma", 5), key("name", "  elma ", 5));
+        assert_eq!(key("all", "SÜT süt", 5), key("all", "sut", 5));
+        assert_eq!(key("tag", "Meyve, yaz", 5), key("tag", "meyve,YAZ,", 5));
+        // Mod, limit ve tırnaklar sonucu değiştiriyor
+        assert_ne!(key("description", "elma", 5), key("all", "elma", 5));
+        assert_ne!(
+            key("description", "elma", 5),
+            key("description", "elma", 10)
+        );
+        assert_ne!(
+            key("description", "tavuk göğsü", 5),
+            key("description", "\"tavuk göğsü\"", 5)
+        );
+        assert_eq!(key("nutrient", "elma", 5), None);
+    }
+}
@@ -0,0 +1,59 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::{
+    SharedState,
+    api::{
+        error::{APIError, ErrorCode},
+        rate_limit::is_exempt_path,
+    },
+};
+
+// Bakımdayken de orkestratörler sağlık durumunu görebilmeli, adminler de bakım modunu kapatabilmeli
+const MAINTENANCE_EXEMPT_PATHS: [&str; 2] = ["/health", "/admin"];
+
+// İçe aktarma veya migration sırasında yarım kalmış veriyi sunmak yerine tüm istekleri 503 ile reddediyoruz.
+// Önbellekteki yanıtlar da dönmesin diye bu katman önbellekten önce çalışıyor
+pub(crate) async fn maintenance_middleware(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, APIError> {
+    let maintenance = shared_state.config.load().maintenance.clone();
+    if !maintenance.enabled || is_exempt_path(&MAINTENANCE_EXEMPT_PATHS, request.uri().path()) {
+        return Ok(next.run(request).await);
+    }
+
+    Err(under_maintenance(maintenance.retry_after_seconds))
+}
+
+fn under_maintenance(retry_after: u64) -> APIError {
+    APIError::new(
+        StatusCode::SERVICE_UNAVAILABLE, ErrorCode::UnderMaintenance,
+        "BesinVeri API şu anda bakımda, lütfen daha sonra tekrar deneyin / BesinVeri API is under maintenance, please retry later",
+    )
+    .with_retry_after(retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_maintenance_exempt_paths() {
+        assert!(is_exempt_path(&MAINTENANCE_EXEMPT_PATHS, "/health"));
+        assert!(is_exempt_path(&MAINTENANCE_EXEMPT_PATHS, "/health/ready"));
+        assert!(is_exempt_path(
+            &MAINTENANCE_EXEMPT_PATHS,
+            "/admin/maintenance"
+        ));
+
+        assert!(!is_exempt_path(&MAINTENANCE_EXEMPT_PATHS, "/foods/search"));
+        assert!(!is_exempt_path(&MAINTENANCE_EXEMPT_PATHS, "/administrator"));
+    }
+}
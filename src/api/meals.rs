@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, instrument};
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+        foods::{MAX_INPUT_CHARS, validate_input},
+        reference_intakes::find_reference_intake,
+    },
+    core::{
+        daily_values,
+        food::Food,
+        nutrition::{Nutrients, NutritionSum, round},
+    },
+};
+
+// Bir öğündeki en fazla yemek ve yemek başına en fazla gram, tarif limitleriyle aynı
+const MAX_MEAL_ITEMS: usize = 50;
+const MAX_ITEM_GRAMS: f64 = 10_000.0;
+
+// Fazlası uyarı verilen, sınırlanması önerilen besinler
+const LIMITED_NUTRIENTS: [&str; 3] = ["sodium", "sugar", "saturated_fat"];
+// Eksikleri aranan vitamin ve mineraller
+const MICRONUTRIENTS: [&str; 17] = [
+    "potassium",
+    "iron",
+    "magnesium",
+    "calcium",
+    "zinc",
+    "vitamin_a",
+    "vitamin_b6",
+    "vitamin_b12",
+    "vitamin_c",
+    "vitamin_d",
+    "vitamin_e",
+    "vitamin_k",
+    "folate",
+    "niacin",
+    "phosphorus",
+    "selenium",
+    "copper",
+];
+
+#[derive(Deserialize)]
+pub(crate) struct MealItem {
+    slug: String,
+    grams: f64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct AnalyzeParams {
+    // Referans alım profilinin slug'ı, verilmezse config'deki meals.default_profile
+    profile: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MealAnalysis {
+    profile: String,
+    total_grams: f64,
+    items: Vec<AnalyzedItem>,
+    nutrients: Nutrients,
+    // Günlük değeri olan besinler için profile göre yüzdeler
+    percent_daily_values: BTreeMap<&'static str, f64>,
+    warnings: Vec<MealWarning>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct AnalyzedItem {
+    slug: String,
+    description: String,
+    grams: f64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MealGaps {
+    profile: String,
+    total_grams: f64,
+    items: Vec<AnalyzedItem>,
+    gaps: Vec<NutrientGap>,
+    suggestions: Vec<GapSuggestion>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct NutrientGap {
+    nutrient: &'static str,
+    amount: f64,
+    daily_value: f64,
+    percent: f64,
+    // Günlük değere ulaşmak için gereken miktar
+    missing: f64,
+}
+
+impl NutrientGap {
+    fn rounded(&self) -> Self {
+        Self {
+            nutrient: self.nutrient,
+            amount: round(self.amount),
+            daily_value: round(self.daily_value),
+            percent: round(self.percent),
+            missing: round(self.missing),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub(crate) struct GapSuggestion {
+    slug: String,
+    description: String,
+    score: f64,
+    // Yemeğin 100 gramının kapattığı eksiklerin yüzdeleri
+    covers: BTreeMap<&'static str, f64>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct MealWarning {
+    nutrient: &'static str,
+    amount: f64,
+    daily_value: f64,
+    percent: f64,
+    message: String,
+}
+
+// Kalori takip uygulamaları öğünün toplamını her seferinde kendileri hesaplıyordu, yuvarlamaları da farklı olduğu için
+// sonuçlar uygulamadan uygulamaya değişiyordu. Toplamlar yuvarlanmadan hesaplanıyor, sadece yanıtta bir kere yuvarlanıyor
+#[instrument(skip_all)]
+pub(crate) async fn analyze_meal(
+    Query(params): Query<AnalyzeParams>,
+    State(shared_state): State<SharedState>,
+    Json(items): Json<Vec<MealItem>>,
+) -> Result<Json<MealAnalysis>, APIError> {
+    let (sum, analyzed) = sum_items(&shared_state, &items).await?;
+    let (profile, daily_values) = find_daily_values(&shared_state, params.profile).await?;
+    let warning_percent = shared_state.config.load().meals.warning_percent;
+    let nutrients = sum.nutrients();
+    Ok(Json(MealAnalysis {
+        profile,
+        total_grams: round(sum.grams()),
+        items: analyzed,
+        percent_daily_values: daily_values::percent_of_daily_values(&nutrients, &daily_values),
+        warnings: warnings(&nutrients, &daily_values, warning_percent),
+        nutrients: nutrients.rounded(),
+    }))
+}
+
+// Bir günün yemeklerinde profile göre eksik kalan vitamin ve mineraller, eksikleri en iyi kapatan onaylı yemeklerle birlikte
+#[instrument(skip_all)]
+pub(crate) async fn meal_gaps(
+    Query(params): Query<AnalyzeParams>,
+    State(shared_state): State<SharedState>,
+    Json(items): Json<Vec<MealItem>>,
+) -> Result<Json<MealGaps>, APIError> {
+    let (sum, analyzed) = sum_items(&shared_state, &items).await?;
+    let (profile, daily_values) = find_daily_values(&shared_state, params.profile).await?;
+    let (gap_percent, max_suggestions) = {
+        let config = shared_state.config.load();
+        (config.meals.gap_percent, config.meals.gap_suggestions)
+    };
+
+    let gaps = gaps(&sum.nutrients(), &daily_values, gap_percent);
+    let suggestions = if gaps.is_empty() {
+        Vec::new()
+    } else {
+        let foods = database::select_verified_foods(&shared_state.api_db, &[], 0.0, false, &[])
+            .await
+            .map_err(|e| {
+                error!(
+                    "Eksikler için önerilecek yemekler sorgulanırken hata oluştu: {:?}",
+                    e
+                );
+                APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+            })?;
+        // Günün yemeklerinde zaten olan yemekler önerilmiyor
+        let candidates: Vec<Food> = foods
+            .into_iter()
+            .filter(|food| {
+                !analyzed
+                    .iter()
+                    .any(|item| food.slug.as_deref() == Some(&item.slug))
+            })
+            .collect();
+        suggestions(&candidates, &gaps, max_suggestions)
+    };
+
+    Ok(Json(MealGaps {
+        profile,
+        total_grams: round(sum.grams()),
+        items: analyzed,
+        gaps: gaps.iter().map(NutrientGap::rounded).collect(),
+        suggestions,
+    }))
+}
+
+// Yemekleri doğrulayıp veritabanından okuyor ve gramlarına göre topluyor
+async fn sum_items(
+    shared_state: &SharedState,
+    items: &[MealItem],
+) -> Result<(NutritionSum, Vec<AnalyzedItem>), APIError> {
+    let invalid = |message: &str| {
+        APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            message,
+        )
+    };
+    if !(1..=MAX_MEAL_ITEMS).contains(&items.len()) {
+        return Err(invalid(&format!(
+            "Bir öğün en az 1, en fazla {} yemekten oluşabilir",
+            MAX_MEAL_ITEMS
+        )));
+    }
+    if items
+        .iter()
+        .any(|item| !(item.grams.is_finite() && item.grams > 0.0 && item.grams <= MAX_ITEM_GRAMS))
+    {
+        return Err(invalid(&format!(
+            "Yemek gramları 0'dan büyük, en fazla {} olmalı",
+            MAX_ITEM_GRAMS
+        )));
+    }
+
+    let slugs = items
+        .iter()
+        .map(|item| validate_input(&item.slug, MAX_INPUT_CHARS))
+        .collect::<Result<Vec<String>, APIError>>()?;
+    // Aynı yemek öğünde birden fazla kez geçebilir, veritabanına bir kere soruluyor
+    let mut unique_slugs = slugs.clone();
+    unique_slugs.sort();
+    unique_slugs.dedup();
+    let foods = database::select_verified_foods_by_slugs(&shared_state.api_db, &unique_slugs)
+        .await
+        .map_err(|e| {
+            error!("Öğündeki yemekler sorgulanırken hata oluştu: {:?}", e);
+            APIError::database(&e, "Veritabanına yemek sorgusu atılırken bir hata oluştu")
+        })?;
+
+    let mut sum = NutritionSum::default();
+    let mut analyzed = Vec::new();
+    for (slug, item) in slugs.into_iter().zip(items) {
+        let Some(food) = foods
+            .iter()
+            .find(|food| food.slug.as_deref() == Some(&slug))
+        else {
+            return Err(APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::FoodNotFound,
+                &format!("{} yemeği bulunamadı veya henüz onaylanmadı", slug),
+            ));
+        };
+        sum.add(food, item.grams);
+        analyzed.push(AnalyzedItem {
+            slug,
+            description: food.description.clone(),
+            grams: item.grams,
+        });
+    }
+    Ok((sum, analyzed))
+}
+
+// Profilin slug'ı ve günlük değerleri, profil verilmezse config'deki meals.default_profile
+async fn find_daily_values(
+    shared_state: &SharedState,
+    profile: Option<String>,
+) -> Result<(String, Nutrients), APIError> {
+    let profile = match profile {
+        Some(profile) => validate_input(&profile, MAX_INPUT_CHARS)?,
+        None => shared_state.config.load().meals.default_profile.clone(),
+    };
+    let daily_values = find_reference_intake(shared_state, &profile)
+        .await?
+        .ok_or_else(|| {
+            APIError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::ReferenceIntakeNotFound,
+                &format!("{} referans alım profili bulunamadı", profile),
+            )
+        })?
+        .daily_values();
+    Ok((profile, daily_values))
+}
+
+fn warnings(
+    nutrients: &Nutrients,
+    daily_values: &Nutrients,
+    warning_percent: f64,
+) -> Vec<MealWarning> {
+    nutrients
+        .values()
+        .into_iter()
+        .zip(daily_values.values())
+        // Profilde referansı olmayan besin için uyarı verilemiyor
+        .filter(|((nutrient, _), (_, daily_value))| {
+            LIMITED_NUTRIENTS.contains(nutrient) && *daily_value > 0.0
+        })
+        .filter_map(|((nutrient, amount), (_, daily_value))| {
+            let percent = amount / daily_value * 100.0;
+            (percent >= warning_percent).then(|| MealWarning {
+                nutrient,
+                amount: round(amount),
+                daily_value: round(daily_value),
+                percent: round(percent),
+                message: format!(
+                    "Bu öğün günlük {} değerinin %{:.0} kadarını karşılıyor",
+                    nutrient, percent
+                ),
+            })
+        })
+        .collect()
+}
+
+// Günlük değerin gap_percent yüzdesinin altında kalan vitamin ve mineraller, profilde referansı olmayanlar sayılmıyor
+fn gaps(nutrients: &Nutrients, daily_values: &Nutrients, gap_percent: f64) -> Vec<NutrientGap> {
+    nutrients
+        .values()
+        .into_iter()
+        .zip(daily_values.values())
+        .filter(|((nutrient, _), (_, daily_value))| {
+            MICRONUTRIENTS.contains(nutrient) && *daily_value > 0.0
+        })
+        .filter_map(|((nutrient, amount), (_, daily_value))| {
+            let percent = amount / daily_value * 100.0;
+            (percent < gap_percent).then_some(NutrientGap {
+                nutrient,
+                amount,
+                daily_value,
+                percent,
+                missing: daily_value - amount,
+            })
+        })
+        .collect()
+}
+
+// Yemekler 100 gramlarının eksikleri ne kadar kapattığına göre puanlanıyor. Her eksik en fazla 1 puan getiriyor, böylece tek
+// bir besinde çok zengin olan yemek, birkaç eksiği birden kapatan yemeğin önüne geçemiyor
+fn suggestions(foods: &[Food], gaps: &[NutrientGap], max_suggestions: usize) -> Vec<GapSuggestion> {
+    let mut suggestions: Vec<GapSuggestion> = foods
+        .iter()
+        .filter_map(|food| {
+            let values = Nutrients::of(food, 100.0).values();
+            let covers: BTreeMap<&'static str, f64> = gaps
+                .iter()
+                .filter_map(|gap| {
+                    let (_, value) = values
+                        .iter()
+                        .find(|(nutrient, _)| *nutrient == gap.nutrient)?;
+                    let covered = (value / gap.missing).min(1.0);
+                    (covered > 0.0).then_some((gap.nutrient, covered))
+                })
+                .collect();
+            if covers.is_empty() {
+                return None;
+            }
+            Some(GapSuggestion {
+                slug: food.slug.clone().unwrap_or_default(),
+                description: food.description.clone(),
+                score: covers.values().sum(),
+                covers,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.slug.cmp(&b.slug))
+    });
+    suggestions.truncate(max_suggestions);
+    for suggestion in &mut suggestions {
+        suggestion.score = round(suggestion.score);
+        for covered in suggestion.covers.values_mut() {
+            *covered = round(*covered * 100.0);
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warnings() {
+        let daily_values = Nutrients {
+            sodium: 2400.0,
+            sugar: 90.0,
+            saturated_fat: 20.0,
+            protein: 50.0,
+            ..Default::default()
+        };
+        let nutrients = Nutrients {
+            sodium: 1200.0,
+            sugar: 10.0,
+            saturated_fat: 8.0,
+            // Sınırlanan besinlerden değil
+            protein: 49.0,
+            ..Default::default()
+        };
+        let warnings = warnings(&nutrients, &daily_values, 40.0);
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|warning| (warning.nutrient, warning.percent))
+                .collect::<Vec<_>>(),
+            vec![("saturated_fat", 40.0), ("sodium", 50.0)]
+        );
+        assert!(super::warnings(&nutrients, &daily_values, 60.0).is_empty());
+        // Referansı olmayan besin
+        let no_sodium = Nutrients {
+            sodium: 0.0,
+            ..daily_values
+        };
+        assert_eq!(super::warnings(&nutrients, &no_sodium, 40.0).len(), 1);
+    }
+
+    #[test]
+    fn test_gaps_and_suggestions() {
+        let daily_values = Nutrients {
+            iron: 18.0,
+            vitamin_c: 90.0,
+            calcium: 1000.0,
+            sodium: 2400.0,
+            ..Default::default()
+        };
+        let nutrients = Nutrients {
+            iron: 9.0,
+            vitamin_c: 120.0,
+            calcium: 400.0,
+            // Vitamin ve mineral değil
+            sodium: 100.0,
+            // Profilde referansı yok
+            zinc: 0.0,
+            ..Default::default()
+        };
+        let gaps = gaps(&nutrients, &daily_values, 100.0);
+        assert_eq!(
+            gaps.iter()
+                .map(|gap| (gap.nutrient, gap.percent, gap.missing))
+                .collect::<Vec<_>>(),
+            vec![("iron", 50.0, 9.0), ("calcium", 40.0, 600.0)]
+        );
+        assert_eq!(super::gaps(&nutrients, &daily_values, 45.0).len(), 1);
+
+        let food = |slug: &str, iron: f64, calcium: f64| Food {
+            slug: Some(slug.to_owned()),
+            description: slug.to_owned(),
+            iron,
+            calcium,
+            ..Default::default()
+        };
+        let foods = vec![
+            // Demirde çok zengin ama kalsiyumu yok
+            food("ciger", 30.0, 5.0),
+            food("ispanak", 2.7, 99.0),
+            food("peynir", 0.5, 700.0),
+            food("su", 0.0, 0.0),
+        ];
+        let suggestions = suggestions(&foods, &gaps, 2);
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|suggestion| (suggestion.slug.as_str(), suggestion.score))
+                .collect::<Vec<_>>(),
+            vec![("peynir", 1.06), ("ciger", 1.01)]
+        );
+        assert_eq!(suggestions[1].covers["iron"], 100.0);
+        assert_eq!(suggestions[1].covers["calcium"], 0.83);
+    }
+}
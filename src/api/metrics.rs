@@ -0,0 +1,762 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        Arc, LazyLock, RwLock,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, OriginalUri, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use moka::{future::Cache, notification::RemovalCause};
+use serde::Serialize;
+
+use crate::{SharedState, api::runtime::StallDetector};
+
+// Basit sayaçlar tutuyoruz, harici bir metrik sistemine ihtiyaç duymadan neler olup bittiğini görebilmek için
+// AtomicU64 kullandığımız için Mutex'e gerek yok, Ordering::Relaxed yeterli çünkü sadece sayıyoruz
+#[derive(Default)]
+pub(crate) struct Metrics {
+    pub(crate) cache: CacheCounters,
+    pub(crate) negative_cache: CacheCounters,
+    pub(crate) search_hot_cache: CacheCounters,
+    pub(crate) suspicious: SuspiciousTraffic,
+    pub(crate) latencies: RouteLatencies,
+    pub(crate) responses: ResponseCounters,
+    pub(crate) stalls: StallDetector,
+}
+
+// Route'ların döndürdüğü yanıtlar, hata oranı alarmları bunlardan hesaplanıyor
+#[derive(Default)]
+pub(crate) struct ResponseCounters {
+    pub(crate) total: AtomicU64,
+    // 503'ler hariç 5xx yanıtlar, yük atma ve bakım modu bilerek verilen yanıtlar
+    pub(crate) server_errors: AtomicU64,
+}
+
+impl ResponseCounters {
+    pub(crate) fn record(&self, status: StatusCode) {
+        self.total.fetch_add(1, Ordering::Relaxed);
+        if status.is_server_error() && status != StatusCode::SERVICE_UNAVAILABLE {
+            self.server_errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// Önbellek sayaçları, cache_capacity_bytes ve TTL'lerin doğru ayarlanıp ayarlanmadığını görmek için.
+// Silinme sayaçları moka'nın eviction listener'ından geliyor, moka bunları arka plan işlerinde bildirdiği için biraz gecikebiliyor
+#[derive(Default)]
+pub(crate) struct CacheCounters {
+    pub(crate) hits: AtomicU64,
+    pub(crate) misses: AtomicU64,
+    // Önbelleğe hiç bakılmayan (GET olmayan, /health/ altındaki) veya hata döndüğü için kaydedilmeyen istekler
+    pub(crate) bypasses: AtomicU64,
+    pub(crate) inserts: AtomicU64,
+    // Aynı anahtarı o sırada çalışan başka bir isteğin sonucunu bekleyerek alanlar, handler'ı hiç çalıştırmadılar
+    pub(crate) coalesced: AtomicU64,
+    // Kapasite dolduğu için çıkarılanlar, bu sayı yüksekse cache_capacity_bytes küçük kalıyor
+    evictions: AtomicU64,
+    // TTL'i dolduğu için silinenler
+    expirations: AtomicU64,
+    // /admin/cache/purge ile silinenler
+    invalidations: AtomicU64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct CacheStats {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) hit_ratio: f64,
+    pub(crate) bypasses: u64,
+    pub(crate) inserts: u64,
+    pub(crate) coalesced: u64,
+    pub(crate) evictions: u64,
+    pub(crate) expirations: u64,
+    pub(crate) invalidations: u64,
+    pub(crate) entries: u64,
+    pub(crate) size_bytes: u64,
+    pub(crate) capacity_bytes: Option<u64>,
+}
+
+impl CacheCounters {
+    pub(crate) fn record_removal(&self, cause: RemovalCause) {
+        let counter = match cause {
+            RemovalCause::Size => &self.evictions,
+            RemovalCause::Expired => &self.expirations,
+            RemovalCause::Explicit => &self.invalidations,
+            // Aynı anahtara yeni değer yazıldı, kayıt aslında önbellekten çıkmadı
+            RemovalCause::Replaced => return,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats<V>(&self, cache: &Cache<String, V>) -> CacheStats
+    where
+        V: Clone + Send + Sync + 'static,
+    {
+        self.stats_with(
+            cache.entry_count(),
+            cache.weighted_size(),
+            cache.policy().max_capacity(),
+        )
+    }
+
+    // Birden fazla önbelleğe bölünmüş yanıt önbelleği için toplam kayıt, boyut ve kapasiteyle
+    pub(crate) fn stats_with(
+        &self,
+        entries: u64,
+        size_bytes: u64,
+        capacity_bytes: Option<u64>,
+    ) -> CacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        CacheStats {
+            hits,
+            misses,
+            hit_ratio: if hits + misses == 0 {
+                0.0
+            } else {
+                hits as f64 / (hits + misses) as f64
+            },
+            bypasses: self.bypasses.load(Ordering::Relaxed),
+            inserts: self.inserts.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            expirations: self.expirations.load(Ordering::Relaxed),
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            entries,
+            size_bytes,
+            capacity_bytes,
+        }
+    }
+}
+
+// Şüpheli trafik türleri, limitleri ayarlarken hangi IP'lerin neye takıldığını görmek için
+#[derive(Clone, Copy)]
+pub(crate) enum Offense {
+    // Doğrulamadan geçemeyen slug veya sorgular
+    InvalidInput,
+    // Boyut limitini aşan arama sorguları
+    OversizedQuery,
+    RateLimited,
+}
+
+#[derive(Default)]
+struct OffenseCounts {
+    invalid_inputs: AtomicU64,
+    oversized_queries: AtomicU64,
+    rate_limited: AtomicU64,
+}
+
+// IP -> sayaçlar. Saldırı altında sınırsız büyümesin diye kapasitesi sınırlı, bir gün boyunca sessiz kalan IP'ler de siliniyor
+pub(crate) struct SuspiciousTraffic {
+    offenders: Cache<IpAddr, Arc<OffenseCounts>>,
+}
+
+impl Default for SuspiciousTraffic {
+    fn default() -> Self {
+        Self {
+            offenders: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_idle(Duration::from_secs(24 * 60 * 60))
+                .build(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct Offender {
+    pub(crate) ip: IpAddr,
+    pub(crate) invalid_inputs: u64,
+    pub(crate) oversized_queries: u64,
+    pub(crate) rate_limited: u64,
+    pub(crate) total: u64,
+}
+
+impl SuspiciousTraffic {
+    pub(crate) async fn record(&self, ip: IpAddr, offense: Offense) {
+        let counts = self
+            .offenders
+            .get_with(ip.to_canonical(), async { Arc::default() })
+            .await;
+        let counter = match offense {
+            Offense::InvalidInput => &counts.invalid_inputs,
+            Offense::OversizedQuery => &counts.oversized_queries,
+            Offense::RateLimited => &counts.rate_limited,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // entry_count moka'nın arka plan işleri çalışana kadar güncel olmayabiliyor, sayarak buluyoruz
+    pub(crate) fn tracked_ips(&self) -> usize {
+        self.offenders.iter().count()
+    }
+
+    // Toplam sayısı en yüksek olan IP'ler önce
+    pub(crate) fn top_offenders(&self, limit: usize) -> Vec<Offender> {
+        let mut offenders: Vec<Offender> = self
+            .offenders
+            .iter()
+            .map(|(ip, counts)| {
+                let invalid_inputs = counts.invalid_inputs.load(Ordering::Relaxed);
+                let oversized_queries = counts.oversized_queries.load(Ordering::Relaxed);
+                let rate_limited = counts.rate_limited.load(Ordering::Relaxed);
+                Offender {
+                    ip: *ip,
+                    invalid_inputs,
+                    oversized_queries,
+                    rate_limited,
+                    total: invalid_inputs + oversized_queries + rate_limited,
+                }
+            })
+            .collect();
+        offenders.sort_unstable_by_key(|offender| std::cmp::Reverse(offender.total));
+        offenders.truncate(limit);
+        offenders
+    }
+}
+
+// Histogram kovaları 100µs'den başlayıp 1.25 katı büyüyor, 64 kova ~2 dakikaya kadar çıkıyor.
+// Yüzdelikler kovanın üst sınırı olarak raporlandığı için en fazla %25 yukarı yuvarlanmış oluyor, eğilimleri görmek için yeterli
+const LATENCY_BUCKETS: usize = 64;
+const FIRST_BUCKET_MICROS: f64 = 100.0;
+const BUCKET_GROWTH: f64 = 1.25;
+
+fn bucket_upper_bound_micros(bucket: usize) -> f64 {
+    FIRST_BUCKET_MICROS * BUCKET_GROWTH.powi(bucket as i32)
+}
+
+fn bucket_for(latency: Duration) -> usize {
+    let micros = latency.as_micros() as f64;
+    if micros <= FIRST_BUCKET_MICROS {
+        return 0;
+    }
+    ((micros / FIRST_BUCKET_MICROS).log(BUCKET_GROWTH).ceil() as usize).min(LATENCY_BUCKETS - 1)
+}
+
+// Kilitsiz bir gecikme histogramı, her istek sadece bir kovayı artırıyor
+pub(crate) struct LatencyHistogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    pub(crate) fn record(&self, latency: Duration) {
+        self.buckets[bucket_for(latency)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    // q: 0.0-1.0 arası, ör: p95 için 0.95. Sonuç milisaniye cinsinden
+    fn quantile_ms(&self, counts: &[u64], total: u64, q: f64) -> f64 {
+        let target = ((total as f64) * q).ceil().max(1.0) as u64;
+        let mut cumulative = 0;
+        for (bucket, count) in counts.iter().enumerate() {
+            cumulative += count;
+            if cumulative >= target {
+                return round_ms(bucket_upper_bound_micros(bucket) / 1000.0);
+            }
+        }
+        round_ms(bucket_upper_bound_micros(LATENCY_BUCKETS - 1) / 1000.0)
+    }
+
+    // Eşiği aşan istek sayısı; eşik bir kovanın içine düşüyorsa o kova eşiği aşmamış sayılıyor
+    fn slower_than(&self, counts: &[u64], threshold: Duration) -> u64 {
+        let threshold_bucket = bucket_for(threshold);
+        counts[threshold_bucket + 1..].iter().sum()
+    }
+
+    fn counts(&self) -> Vec<u64> {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect()
+    }
+
+    fn mean_ms(&self, total: u64) -> f64 {
+        if total == 0 {
+            0.0
+        } else {
+            round_ms(self.sum_micros.load(Ordering::Relaxed) as f64 / total as f64 / 1000.0)
+        }
+    }
+
+    pub(crate) fn summary(&self, route: String, slo: &SloTarget) -> RouteLatencySummary {
+        let counts = self.counts();
+        let requests: u64 = counts.iter().sum();
+        let slow_requests = self.slower_than(&counts, slo.threshold);
+
+        // Hata bütçesi yakma oranı: 1.0 bütçenin tam sınırında olduğumuzu, üstü SLO'yu ihlal ettiğimizi gösteriyor
+        let slow_ratio = if requests == 0 {
+            0.0
+        } else {
+            slow_requests as f64 / requests as f64
+        };
+        let error_budget = 1.0 - slo.objective;
+        let burn_rate = if error_budget > 0.0 {
+            slow_ratio / error_budget
+        } else if slow_requests > 0 {
+            f64::INFINITY
+        } else {
+            0.0
+        };
+
+        RouteLatencySummary {
+            route,
+            requests,
+            mean_ms: self.mean_ms(requests),
+            p50_ms: self.quantile_ms(&counts, requests, 0.50),
+            p95_ms: self.quantile_ms(&counts, requests, 0.95),
+            p99_ms: self.quantile_ms(&counts, requests, 0.99),
+            threshold_ms: slo.threshold.as_millis() as u64,
+            objective: slo.objective,
+            slow_requests,
+            compliance: 1.0 - slow_ratio,
+            burn_rate,
+        }
+    }
+}
+
+fn round_ms(ms: f64) -> f64 {
+    (ms * 100.0).round() / 100.0
+}
+
+pub(crate) struct SloTarget {
+    pub(crate) threshold: Duration,
+    // İsteklerin bu oranı eşiğin altında kalmalı, ör: 0.99
+    pub(crate) objective: f64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RouteLatencySummary {
+    pub(crate) route: String,
+    pub(crate) requests: u64,
+    pub(crate) mean_ms: f64,
+    pub(crate) p50_ms: f64,
+    pub(crate) p95_ms: f64,
+    pub(crate) p99_ms: f64,
+    pub(crate) threshold_ms: u64,
+    pub(crate) objective: f64,
+    pub(crate) slow_requests: u64,
+    pub(crate) compliance: f64,
+    pub(crate) burn_rate: f64,
+}
+
+// Route şablonu (ör: /food/{slug}) -> histogram. Route sayısı sabit olduğu için sınırsız büyümüyor
+#[derive(Default)]
+pub(crate) struct RouteLatencies {
+    routes: RwLock<HashMap<String, Arc<LatencyHistogram>>>,
+}
+
+impl RouteLatencies {
+    pub(crate) fn record(&self, route: &str, latency: Duration) {
+        let histogram = self
+            .routes
+            .read()
+            .expect("Gecikme metrikleri kilidi zehirlendi")
+            .get(route)
+            .cloned();
+        let histogram = match histogram {
+            Some(histogram) => histogram,
+            None => self
+                .routes
+                .write()
+                .expect("Gecikme metrikleri kilidi zehirlendi")
+                .entry(route.to_owned())
+                .or_default()
+                .clone(),
+        };
+        histogram.record(latency);
+    }
+
+    pub(crate) fn histograms(&self) -> Vec<(String, Arc<LatencyHistogram>)> {
+        let mut histograms: Vec<(String, Arc<LatencyHistogram>)> = self
+            .routes
+            .read()
+            .expect("Gecikme metrikleri kilidi zehirlendi")
+            .iter()
+            .map(|(route, histogram)| (route.clone(), histogram.clone()))
+            .collect();
+        histograms.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        histograms
+    }
+}
+
+// Tekrar denemeler arasındaki bekleme bundan uzun olmuyor, istek zaten en fazla birkaç deneme kadar bekliyor
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// Veritabanı fonksiyonları SharedState'i görmüyor, her birine metrik parametresi eklemek yerine süreler süreç genelindeki bu kayda yazılıyor
+pub(crate) static DATABASE_QUERIES: LazyLock<QueryMetrics> = LazyLock::new(QueryMetrics::default);
+
+#[derive(Default)]
+struct QueryStats {
+    histogram: LatencyHistogram,
+    failures: AtomicU64,
+}
+
+// Sorgu adı (veritabanı fonksiyonunun adı) -> süre histogramı ve hata sayısı. Sorgu adları sabit olduğu için sınırsız büyümüyor
+#[derive(Default)]
+pub(crate) struct QueryMetrics {
+    queries: RwLock<HashMap<&'static str, Arc<QueryStats>>>,
+    // 0 ise yavaş sorgu logu kapalı, açılışta config'den ayarlanıyor
+    slow_threshold_micros: AtomicU64,
+    // Havuzdan bağlantı alınamadığı için zaman aşımına uğrayan sorgular
+    pool_timeouts: AtomicU64,
+    // Kilit hatalarında (SQLITE_BUSY/SQLITE_LOCKED) sorgunun en fazla kaç kez daha deneneceği ve ilk bekleme, açılışta config'den ayarlanıyor
+    max_retries: AtomicU32,
+    retry_base_delay_micros: AtomicU64,
+    retries: AtomicU64,
+    // Son kilit hatası tüm denemelere rağmen geçmediyse ve o zamandan beri hiçbir sorgu başarılı olmadıysa true
+    locked: AtomicBool,
+}
+
+#[derive(Serialize)]
+pub(crate) struct QueryLatencySummary {
+    pub(crate) query: &'static str,
+    pub(crate) calls: u64,
+    pub(crate) failures: u64,
+    pub(crate) mean_ms: f64,
+    pub(crate) p50_ms: f64,
+    pub(crate) p95_ms: f64,
+    pub(crate) p99_ms: f64,
+    pub(crate) total_ms: f64,
+    pub(crate) slow_threshold_ms: u64,
+    pub(crate) slow_calls: u64,
+}
+
+impl QueryMetrics {
+    pub(crate) fn set_slow_threshold(&self, threshold: Duration) {
+        self.slow_threshold_micros
+            .store(threshold.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn slow_threshold(&self) -> Option<Duration> {
+        match self.slow_threshold_micros.load(Ordering::Relaxed) {
+            0 => None,
+            micros => Some(Duration::from_micros(micros)),
+        }
+    }
+
+    pub(crate) fn record(&self, query: &'static str, latency: Duration, failed: bool) {
+        let stats = self
+            .queries
+            .read()
+            .expect("Sorgu metrikleri kilidi zehirlendi")
+            .get(query)
+            .cloned();
+        let stats = match stats {
+            Some(stats) => stats,
+            None => self
+                .queries
+                .write()
+                .expect("Sorgu metrikleri kilidi zehirlendi")
+                .entry(query)
+                .or_default()
+                .clone(),
+        };
+        stats.histogram.record(latency);
+        if failed {
+            stats.failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn set_retry_policy(&self, max_retries: u32, base_delay: Duration) {
+        self.max_retries.store(max_retries, Ordering::Relaxed);
+        self.retry_base_delay_micros
+            .store(base_delay.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    // attempt'inci tekrar denemeden önceki bekleme, her denemede iki katına çıkıyor ve en fazla 1 saniye.
+    // Denemeler bittiyse None
+    pub(crate) fn retry_delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.max_retries.load(Ordering::Relaxed) {
+            return None;
+        }
+        let base_delay = self.retry_base_delay_micros.load(Ordering::Relaxed);
+        Some(
+            Duration::from_micros(base_delay.saturating_mul(1 << attempt.min(16)))
+                .min(MAX_RETRY_DELAY),
+        )
+    }
+
+    pub(crate) fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn retries(&self) -> u64 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_locked(&self, locked: bool) {
+        // Her başarılı sorguda aynı değeri tekrar yazıp cache satırını kirletmemek için
+        if self.locked.load(Ordering::Relaxed) != locked {
+            self.locked.store(locked, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_pool_timeout(&self) {
+        self.pool_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn pool_timeouts(&self) -> u64 {
+        self.pool_timeouts.load(Ordering::Relaxed)
+    }
+
+    // Süreç başladığından beri tüm sorguların (çağrı, başarısız çağrı) toplamı
+    pub(crate) fn totals(&self) -> (u64, u64) {
+        self.queries
+            .read()
+            .expect("Sorgu metrikleri kilidi zehirlendi")
+            .values()
+            .fold((0, 0), |(calls, failures), stats| {
+                (
+                    calls + stats.histogram.counts().iter().sum::<u64>(),
+                    failures + stats.failures.load(Ordering::Relaxed),
+                )
+            })
+    }
+
+    // Toplam süresi en yüksek olan sorgular önce, optimize edilince en çok kazandıracak olanlar bunlar
+    pub(crate) fn summaries(&self) -> Vec<QueryLatencySummary> {
+        let slow_threshold = self.slow_threshold();
+        let mut summaries: Vec<QueryLatencySummary> = self
+            .queries
+            .read()
+            .expect("Sorgu metrikleri kilidi zehirlendi")
+            .iter()
+            .map(|(query, stats)| {
+                let histogram = &stats.histogram;
+                let counts = histogram.counts();
+                let calls: u64 = counts.iter().sum();
+                QueryLatencySummary {
+                    query,
+                    calls,
+                    failures: stats.failures.load(Ordering::Relaxed),
+                    mean_ms: histogram.mean_ms(calls),
+                    p50_ms: histogram.quantile_ms(&counts, calls, 0.50),
+                    p95_ms: histogram.quantile_ms(&counts, calls, 0.95),
+                    p99_ms: histogram.quantile_ms(&counts, calls, 0.99),
+                    total_ms: round_ms(
+                        histogram.sum_micros.load(Ordering::Relaxed) as f64 / 1000.0,
+                    ),
+                    slow_threshold_ms: slow_threshold
+                        .map_or(0, |threshold| threshold.as_millis() as u64),
+                    slow_calls: slow_threshold
+                        .map_or(0, |threshold| histogram.slower_than(&counts, threshold)),
+                }
+            })
+            .collect();
+        summaries.sort_unstable_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        summaries
+    }
+}
+
+// Route'ların gecikmesini önbellek isabetleri dahil ölçüyoruz, istemcinin gördüğü süre bu. Yanıt durumları da hata oranı alarmları için burada sayılıyor
+// route_layer olarak eklendiği için sadece eşleşen route'lar sayılıyor, rastgele 404 path'leri metrikleri şişiremiyor
+pub(crate) async fn latency_middleware(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(matched_path) = request.extensions().get::<MatchedPath>() else {
+        return next.run(request).await;
+    };
+    // MatchedPath nest edilen API prefix'ini de içeriyor (ör: /api/foods/search); config'deki eşikler base_url'den
+    // bağımsız olsun diye prefix'i, orijinal path ile router'ın gördüğü path arasındaki farktan bulup atıyoruz
+    let path = request.uri().path();
+    let api_prefix = request
+        .extensions()
+        .get::<OriginalUri>()
+        .and_then(|original| {
+            // API kökünde (/api) router'ın gördüğü path "/" oluyor
+            original
+                .path()
+                .strip_suffix(path)
+                .or_else(|| (path == "/").then(|| original.path()))
+        })
+        .unwrap_or("");
+    let route = match matched_path.as_str().strip_prefix(api_prefix) {
+        Some("") => "/".to_owned(),
+        Some(route) if route.starts_with('/') => route.to_owned(),
+        _ => matched_path.as_str().to_owned(),
+    };
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    shared_state
+        .metrics
+        .latencies
+        .record(&route, start.elapsed());
+    shared_state.metrics.responses.record(response.status());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_summary() {
+        let histogram = LatencyHistogram::default();
+        for _ in 0..90 {
+            histogram.record(Duration::from_millis(2));
+        }
+        for _ in 0..10 {
+            histogram.record(Duration::from_millis(800));
+        }
+
+        let slo = SloTarget {
+            threshold: Duration::from_millis(100),
+            objective: 0.95,
+        };
+        let summary = histogram.summary("/foods/search".to_owned(), &slo);
+        assert_eq!(summary.requests, 100);
+        assert_eq!(summary.slow_requests, 10);
+        // Yüzdelikler kova üst sınırı olduğu için gerçek değerden en fazla %25 büyük
+        assert!((2.0..=2.5).contains(&summary.p50_ms), "{}", summary.p50_ms);
+        assert!(
+            (800.0..=1000.0).contains(&summary.p99_ms),
+            "{}",
+            summary.p99_ms
+        );
+        assert!((summary.compliance - 0.90).abs() < 1e-9);
+        // %10 yavaş istek, %5'lik bütçenin iki katı
+        assert!((summary.burn_rate - 2.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_cache_stats() {
+        let counters = CacheCounters::default();
+        let cache: Cache<String, String> = Cache::builder().max_capacity(10).build();
+        counters.hits.fetch_add(3, Ordering::Relaxed);
+        counters.misses.fetch_add(1, Ordering::Relaxed);
+        counters.record_removal(RemovalCause::Size);
+        counters.record_removal(RemovalCause::Expired);
+        counters.record_removal(RemovalCause::Replaced);
+
+        let stats = counters.stats(&cache);
+        assert!((stats.hit_ratio - 0.75).abs() < 1e-9);
+        assert_eq!(
+            (stats.evictions, stats.expirations, stats.invalidations),
+            (1, 1, 0)
+        );
+        assert_eq!(stats.capacity_bytes, Some(10));
+        assert_eq!(CacheCounters::default().stats(&cache).hit_ratio, 0.0);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let queries = QueryMetrics::default();
+        // Politika ayarlanmadan tekrar deneme yok
+        assert_eq!(queries.retry_delay(0), None);
+
+        queries.set_retry_policy(3, Duration::from_millis(300));
+        assert_eq!(queries.retry_delay(0), Some(Duration::from_millis(300)));
+        assert_eq!(queries.retry_delay(1), Some(Duration::from_millis(600)));
+        // 1 saniyeyle sınırlı
+        assert_eq!(queries.retry_delay(2), Some(Duration::from_secs(1)));
+        assert_eq!(queries.retry_delay(3), None);
+    }
+
+    #[test]
+    fn test_query_summaries() {
+        let queries = QueryMetrics::default();
+        queries.set_slow_threshold(Duration::from_millis(50));
+        for _ in 0..3 {
+            queries.record("select_food_by_slug", Duration::from_millis(1), false);
+        }
+        queries.record(
+            "search_foods_by_tag_wild",
+            Duration::from_millis(200),
+            false,
+        );
+        queries.record("search_foods_by_tag_wild", Duration::from_millis(2), true);
+
+        let summaries = queries.summaries();
+        assert_eq!(summaries.len(), 2);
+        // Toplam süreye göre sıralı
+        assert_eq!(summaries[0].query, "search_foods_by_tag_wild");
+        assert_eq!(
+            (
+                summaries[0].calls,
+                summaries[0].failures,
+                summaries[0].slow_calls
+            ),
+            (2, 1, 1)
+        );
+        assert_eq!(summaries[1].calls, 3);
+        assert_eq!(summaries[1].slow_calls, 0);
+
+        queries.set_slow_threshold(Duration::ZERO);
+        assert_eq!(queries.slow_threshold(), None);
+    }
+
+    #[test]
+    fn test_bucket_for() {
+        assert_eq!(bucket_for(Duration::ZERO), 0);
+        assert_eq!(bucket_for(Duration::from_micros(100)), 0);
+        assert_eq!(bucket_for(Duration::from_micros(101)), 1);
+        assert_eq!(bucket_for(Duration::from_secs(3600)), LATENCY_BUCKETS - 1);
+        for millis in [1, 7, 150, 2500] {
+            let latency = Duration::from_millis(millis);
+            assert!(bucket_upper_bound_micros(bucket_for(latency)) >= latency.as_micros() as f64);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_top_offenders() {
+        let suspicious = SuspiciousTraffic::default();
+        let a: IpAddr = "203.0.113.1".parse().unwrap();
+        let b: IpAddr = "203.0.113.2".parse().unwrap();
+
+        suspicious.record(a, Offense::InvalidInput).await;
+        for _ in 0..3 {
+            suspicious.record(b, Offense::RateLimited).await;
+        }
+        suspicious.record(b, Offense::OversizedQuery).await;
+        // IPv4-mapped IPv6 adresleri aynı istemci sayılmalı
+        suspicious
+            .record("::ffff:203.0.113.1".parse().unwrap(), Offense::InvalidInput)
+            .await;
+
+        let offenders = suspicious.top_offenders(10);
+        assert_eq!(offenders.len(), 2);
+        assert_eq!(offenders[0].ip, b);
+        assert_eq!(
+            (
+                offenders[0].rate_limited,
+                offenders[0].oversized_queries,
+                offenders[0].total
+            ),
+            (3, 1, 4)
+        );
+        assert_eq!(offenders[1].ip, a);
+        assert_eq!(offenders[1].invalid_inputs, 2);
+
+        assert_eq!(suspicious.top_offenders(1).len(), 1);
+        assert_eq!(suspicious.tracked_ips(), 2);
+    }
+}
@@ -1,19 +1,32 @@
-use std::net::SocketAddr;
-
-use axum::http::HeaderMap;
-
+pub(crate) mod access_log;
+pub(crate) mod additives;
+pub(crate) mod admin;
+pub(crate) mod alerting;
+pub(crate) mod analytics;
 pub(crate) mod cache;
+pub(crate) mod calc;
+pub(crate) mod config_audit;
 pub(crate) mod database;
 pub(crate) mod endpoints;
 pub(crate) mod error;
+pub(crate) mod export;
 pub(crate) mod foods;
 pub(crate) mod health;
-
-fn parse_client_ip(proxy_addr: &SocketAddr, headers: &HeaderMap) -> String {
-    headers
-        .get("x-forwarded-for")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|s| s.split(",").next())
-        .map(|s| s.trim().to_string())
-        .unwrap_or_else(|| format!("proxy: {}", proxy_addr.ip()))
-}
+pub(crate) mod hot_search;
+pub(crate) mod maintenance;
+pub(crate) mod meals;
+pub(crate) mod metrics;
+pub(crate) mod open_food_facts;
+pub(crate) mod portions;
+pub(crate) mod quota;
+pub(crate) mod rate_limit;
+pub(crate) mod recipes;
+pub(crate) mod reference_intakes;
+pub(crate) mod request_id;
+pub(crate) mod runtime;
+pub(crate) mod search_index;
+pub(crate) mod security;
+pub(crate) mod server;
+pub(crate) mod signing;
+pub(crate) mod suggest;
+pub(crate) mod units;
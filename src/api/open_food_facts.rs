@@ -0,0 +1,237 @@
+use std::time::Duration;
+
+use chrono::{SecondsFormat, Utc};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tracing::{info, warn};
+
+use crate::{api::database, core::config::OpenFoodFactsConfig};
+
+pub(crate) const SOURCE: &str = "Open Food Facts";
+// Open Food Facts veritabanı Open Database License ile paylaşılıyor, kullanırken kaynak gösterilmesi gerekiyor
+pub(crate) const LICENSE: &str = "ODbL-1.0";
+
+// Sadece kullandığımız alanları istiyoruz, ürünün tamamı onlarca KB olabiliyor
+const PRODUCT_FIELDS: &str = "image_url,ingredients_text_tr,ingredients_text,nutriscore_grade";
+
+// Bir yemek için dış kaynaktan çekilen bilgiler, /food/{slug} yanıtında kaynağı ve lisansıyla birlikte gösteriliyor
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct Enrichment {
+    pub(crate) source: String,
+    // Ürünün kaynaktaki sayfası
+    pub(crate) url: String,
+    pub(crate) license: String,
+    // Ürün kaynakta bulunamadıysa false, bu kayıtlar yanıtta gösterilmiyor
+    #[serde(skip)]
+    pub(crate) found: bool,
+    pub(crate) image_url: Option<String>,
+    pub(crate) ingredients: Option<String>,
+    // a'dan e'ye, küçük harf
+    pub(crate) nutri_score: Option<String>,
+    pub(crate) fetched_at: String,
+}
+
+#[derive(Deserialize)]
+struct ProductResponse {
+    // Ürün bulunduysa 1, bulunamadıysa 0
+    status: i64,
+    product: Option<Product>,
+}
+
+#[derive(Deserialize)]
+struct Product {
+    image_url: Option<String>,
+    ingredients_text_tr: Option<String>,
+    ingredients_text: Option<String>,
+    nutriscore_grade: Option<String>,
+}
+
+// Yanıt gelmediyse (bulunamadıysa) boş bir kayıt döndürüyor, bir sonraki kontrol refresh_after_days sonra
+fn enrichment_from_response(
+    base_url: &str,
+    gtin: &str,
+    response: Option<ProductResponse>,
+    fetched_at: String,
+) -> Enrichment {
+    let product = response
+        .filter(|response| response.status == 1)
+        .and_then(|response| response.product);
+    let non_empty = |value: Option<String>| {
+        value
+            .map(|value| value.trim().to_owned())
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut enrichment = Enrichment {
+        source: SOURCE.to_owned(),
+        url: format!("{}/product/{}", base_url.trim_end_matches('/'), gtin),
+        license: LICENSE.to_owned(),
+        found: product.is_some(),
+        image_url: None,
+        ingredients: None,
+        nutri_score: None,
+        fetched_at,
+    };
+    if let Some(product) = product {
+        enrichment.image_url = non_empty(product.image_url);
+        // Türkçe içindekiler yoksa ürünün ana dilindeki metin
+        enrichment.ingredients =
+            non_empty(product.ingredients_text_tr).or(non_empty(product.ingredients_text));
+        // Hesaplanamayan ürünlerde "unknown" veya "not-applicable" geliyor
+        enrichment.nutri_score = non_empty(product.nutriscore_grade)
+            .map(|grade| grade.to_lowercase())
+            .filter(|grade| matches!(grade.as_str(), "a" | "b" | "c" | "d" | "e"));
+    }
+    enrichment
+}
+
+async fn fetch_product(
+    client: &Client,
+    config: &OpenFoodFactsConfig,
+    gtin: &str,
+) -> Result<Enrichment, reqwest::Error> {
+    let response = client
+        .get(format!(
+            "{}/api/v2/product/{}.json",
+            config.base_url.trim_end_matches('/'),
+            gtin
+        ))
+        .query(&[("fields", PRODUCT_FIELDS)])
+        .send()
+        .await?;
+    let fetched_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+
+    // Bulunamayan ürünlerde 404 ile birlikte status 0 dönüyor
+    let body = if response.status() == StatusCode::NOT_FOUND {
+        None
+    } else {
+        Some(
+            response
+                .error_for_status()?
+                .json::<ProductResponse>()
+                .await?,
+        )
+    };
+    Ok(enrichment_from_response(
+        &config.base_url,
+        gtin,
+        body,
+        fetched_at,
+    ))
+}
+
+// GTIN'i olan, hiç çekilmemiş veya refresh_after_days'ten eski yemekleri sırayla Open Food Facts'e soruyor. Ağ hatalarında kayıt
+// yazılmıyor, yemek bir sonraki turda tekrar deneniyor
+pub(crate) async fn run_enrichment(pool: Pool<Sqlite>, config: OpenFoodFactsConfig) {
+    let client = match Client::builder()
+        .user_agent(&config.user_agent)
+        .timeout(Duration::from_secs(config.timeout_seconds))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Open Food Facts istemcisi oluşturulamadı: {}", e);
+            return;
+        }
+    };
+    info!("GTIN'i olan yemekler Open Food Facts'ten zenginleştirilecek");
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(config.interval_seconds));
+    loop {
+        ticker.tick().await;
+        let stale_before = (Utc::now() - chrono::Duration::days(config.refresh_after_days as i64))
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        let foods = match database::select_foods_to_enrich(&pool, &stale_before).await {
+            Ok(foods) => foods,
+            Err(e) => {
+                warn!("Zenginleştirilecek yemekler sorgulanamadı: {}", e);
+                continue;
+            }
+        };
+
+        let (mut found, mut failed) = (0, 0);
+        for (food_id, gtin) in &foods {
+            match fetch_product(&client, &config, gtin).await {
+                Ok(enrichment) => {
+                    found += enrichment.found as usize;
+                    if let Err(e) =
+                        database::save_food_enrichment(&pool, *food_id, &enrichment).await
+                    {
+                        warn!("{} GTIN'li ürünün bilgileri kaydedilemedi: {}", gtin, e);
+                    }
+                }
+                Err(e) => {
+                    failed += 1;
+                    warn!(
+                        "{} GTIN'li ürün Open Food Facts'ten çekilemedi: {}",
+                        gtin, e
+                    );
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(config.request_delay_ms)).await;
+        }
+        if !foods.is_empty() {
+            info!(
+                "Open Food Facts: {} ürün soruldu, {} tanesi bulundu, {} tanesinde hata oluştu",
+                foods.len(),
+                found,
+                failed
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrichment_from_response() {
+        let response: ProductResponse = serde_json::from_str(
+            r#"{"status": 1, "product": {"image_url": "https://images.openfoodfacts.org/1.jpg",
+                "ingredients_text": "Oats", "ingredients_text_tr": " ", "nutriscore_grade": "A"}}"#,
+        )
+        .unwrap();
+        let enrichment = enrichment_from_response(
+            "https://world.openfoodfacts.org/",
+            "8690504001010",
+            Some(response),
+            "2026-10-17T00:00:00Z".to_owned(),
+        );
+        assert!(enrichment.found);
+        assert_eq!(
+            enrichment.url,
+            "https://world.openfoodfacts.org/product/8690504001010"
+        );
+        // Boş Türkçe metin yerine ana dildeki metin
+        assert_eq!(enrichment.ingredients.as_deref(), Some("Oats"));
+        assert_eq!(enrichment.nutri_score.as_deref(), Some("a"));
+
+        let response: ProductResponse = serde_json::from_str(
+            r#"{"status": 1, "product": {"nutriscore_grade": "not-applicable"}}"#,
+        )
+        .unwrap();
+        let enrichment = enrichment_from_response(
+            "https://world.openfoodfacts.org",
+            "8690504001010",
+            Some(response),
+            "2026-10-17T00:00:00Z".to_owned(),
+        );
+        assert!(enrichment.found);
+        assert_eq!(enrichment.nutri_score, None);
+
+        let response: ProductResponse =
+            serde_json::from_str(r#"{"status": 0, "status_verbose": "product not found"}"#)
+                .unwrap();
+        assert!(
+            !enrichment_from_response(
+                "https://world.openfoodfacts.org",
+                "8690504001010",
+                Some(response),
+                "2026-10-17T00:00:00Z".to_owned(),
+            )
+            .found
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use real::RealIp;
+use serde::Serialize;
+use tracing::{error, instrument};
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+        foods::{MAX_INPUT_CHARS, record_offense, validate_input},
+        metrics::Offense,
+    },
+    core::{portions::StandardPortion, str::to_search_key},
+};
+
+#[derive(Serialize)]
+pub(crate) struct FoodPortionsResponse {
+    slug: String,
+    description: String,
+    // Yemeğe özel porsiyonlar, /food/{slug}'daki servings ile aynı
+    servings: BTreeMap<String, f64>,
+    // Yemeğin etiketlerine göre ev ölçüleri. Yemeğin kendi porsiyonlarında aynı adla olanlar daha doğru olduğu için burada yok
+    standard_portions: Vec<StandardPortion>,
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn food_portions(
+    Path(slug): Path<String>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<Json<FoodPortionsResponse>, APIError> {
+    let slug = match validate_input(&slug, MAX_INPUT_CHARS) {
+        Ok(slug) => slug,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+
+    let pool = shared_state.api_db.clone();
+    let food = database::select_food_by_slug(&pool, &slug)
+        .await
+        .map_err(|e| {
+            error!("Veritabanı yemek bilgisi sorgularken hata oluştu: {:?}", e);
+            APIError::new(
+                StatusCode::NOT_FOUND,
+                ErrorCode::FoodNotFound,
+                "Bu yemekle ilgili veriye ulaşılamadı",
+            )
+        })?;
+    if !food.verified.is_some_and(|verified| verified) {
+        return Err(APIError::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::FoodNotVerified,
+            "Bu yemek henüz onaylanmadığı için gösterilemiyor",
+        ));
+    }
+
+    let standard_portions = match food.id {
+        Some(food_id) => database::select_standard_portions(&pool, food_id)
+            .await
+            .map_err(|e| {
+                error!("Standart ölçüler sorgulanırken hata oluştu: {:?}", e);
+                APIError::database(&e, "Veritabanına ölçü sorgusu atılırken bir hata oluştu")
+            })?,
+        None => Vec::new(),
+    };
+    Ok(Json(FoodPortionsResponse {
+        standard_portions: without_food_servings(standard_portions, &food.servings),
+        slug: food.slug.unwrap_or(slug),
+        description: food.description,
+        servings: food.servings,
+    }))
+}
+
+// Porsiyon adları büyük/küçük harf ve Türkçe karakter farkı gözetmeden karşılaştırılıyor, ?basis=serving: ile aynı
+fn without_food_servings(
+    standard_portions: Vec<StandardPortion>,
+    servings: &BTreeMap<String, f64>,
+) -> Vec<StandardPortion> {
+    let servings: BTreeSet<String> = servings
+        .keys()
+        .map(|serving| to_search_key(serving))
+        .collect();
+    standard_portions
+        .into_iter()
+        .filter(|portion| !servings.contains(&to_search_key(&portion.description)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_food_servings() {
+        let portion = |description: &str| StandardPortion {
+            description: description.to_owned(),
+            weight: 100.0,
+            category: "meyve".to_owned(),
+        };
+        let servings = BTreeMap::from([("1 Adet (Orta)".to_owned(), 180.0)]);
+        assert_eq!(
+            without_food_servings(vec![portion("1 adet (orta)"), portion("1 avuç")], &servings),
+            vec![portion("1 avuç")]
+        );
+    }
+}
@@ -0,0 +1,374 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Error;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use sqlx::SqlitePool;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+    },
+};
+
+// Sayaçlar her istekte veritabanına yazılmıyor, bu aralıkla toplu olarak yazılıyor
+pub(crate) const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+pub(crate) const X_QUOTA_DAILY_LIMIT: &str = "x-quota-daily-limit";
+pub(crate) const X_QUOTA_DAILY_REMAINING: &str = "x-quota-daily-remaining";
+pub(crate) const X_QUOTA_MONTHLY_LIMIT: &str = "x-quota-monthly-limit";
+pub(crate) const X_QUOTA_MONTHLY_REMAINING: &str = "x-quota-monthly-remaining";
+
+// Rate limiter API anahtarını doğruladıktan sonra anahtarın ismini isteğe extension olarak ekliyor
+#[derive(Clone)]
+pub(crate) struct AuthenticatedKey(pub(crate) String);
+
+// Anahtar ismi -> kullanım, günlük ve aylık kotaları kontrol etmek için her istekte veritabanına gitmek istemiyoruz
+#[derive(Default)]
+pub(crate) struct UsageTracker {
+    usages: Mutex<HashMap<String, KeyUsage>>,
+}
+
+struct KeyUsage {
+    // Sayaçların ait olduğu gün, YYYY-MM-DD; gün değişince sayaçları veritabanından yeniden yüklüyoruz
+    day: String,
+    daily: u64,
+    monthly: u64,
+    // Gün -> henüz veritabanına yazılmamış istek sayısı, gün dönümünde bir önceki günün istekleri kaybolmasın diye gün ile tutuyoruz
+    pending: HashMap<String, u64>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct QuotaStatus {
+    pub(crate) daily: u64,
+    pub(crate) monthly: u64,
+}
+
+impl UsageTracker {
+    // Kota aşılmadıysa isteği sayıp güncel kullanımı döndürüyor, aşıldıysa saymadan mevcut kullanımı hata olarak döndürüyor
+    pub(crate) async fn record(
+        &self,
+        pool: &SqlitePool,
+        key_name: &str,
+        today: &str,
+        daily_quota: Option<u64>,
+        monthly_quota: Option<u64>,
+    ) -> Result<QuotaStatus, QuotaStatus> {
+        // Gün değiştiyse kayıtlı kullanım kilit dışında okunuyor, veritabanı beklenirken diğer anahtarların istekleri bekletilmiyor.
+        // Okuma sırasında başka bir istek kaydı yüklediyse onunki kullanılıyor
+        let mut stored = None;
+        let mut usages = loop {
+            let mut usages = self.usages.lock().await;
+            if usages.get(key_name).is_some_and(|usage| usage.day == today) {
+                break usages;
+            }
+            let Some(stored) = stored.take() else {
+                drop(usages);
+                stored = Some(StoredUsage::load(pool, key_name, today).await);
+                continue;
+            };
+            let pending = usages
+                .remove(key_name)
+                .map(|usage| usage.pending)
+                .unwrap_or_default();
+            usages.insert(key_name.to_owned(), KeyUsage::new(today, stored, pending));
+            break usages;
+        };
+        let Some(usage) = usages.get_mut(key_name) else {
+            unreachable!("Kullanım kaydı az önce kontrol edildi");
+        };
+
+        let status = QuotaStatus {
+            daily: usage.daily,
+            monthly: usage.monthly,
+        };
+        if daily_quota.is_some_and(|quota| usage.daily >= quota)
+            || monthly_quota.is_some_and(|quota| usage.monthly >= quota)
+        {
+            return Err(status);
+        }
+
+        usage.daily += 1;
+        usage.monthly += 1;
+        *usage.pending.entry(today.to_owned()).or_default() += 1;
+        Ok(QuotaStatus {
+            daily: usage.daily,
+            monthly: usage.monthly,
+        })
+    }
+
+    // Bekleyen sayaçları veritabanına yazıyor, yazılamayanlar bir sonraki sefer için geri ekleniyor
+    pub(crate) async fn flush(&self, pool: &SqlitePool) {
+        let pending: Vec<(String, HashMap<String, u64>)> = {
+            let mut usages = self.usages.lock().await;
+            usages
+                .iter_mut()
+                .filter(|(_, usage)| !usage.pending.is_empty())
+                .map(|(name, usage)| (name.clone(), std::mem::take(&mut usage.pending)))
+                .collect()
+        };
+
+        for (key_name, days) in pending {
+            for (day, requests) in days {
+                if let Err(e) = write_usage(pool, &key_name, &day, requests).await {
+                    warn!(
+                        "{} anahtarının kullanımı veritabanına yazılamadı: {}",
+                        key_name, e
+                    );
+                    if let Some(usage) = self.usages.lock().await.get_mut(&key_name) {
+                        *usage.pending.entry(day).or_default() += requests;
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Veritabanına yazılmış günlük ve aylık kullanım
+struct StoredUsage {
+    daily: u64,
+    monthly: u64,
+}
+
+impl StoredUsage {
+    async fn load(pool: &SqlitePool, key_name: &str, today: &str) -> Self {
+        Self {
+            daily: stored_usage(pool, key_name, today).await,
+            monthly: stored_usage(pool, key_name, month_of(today)).await,
+        }
+    }
+}
+
+impl KeyUsage {
+    // Henüz yazılmamış istekler de kayıtlı kullanıma ekleniyor
+    fn new(today: &str, stored: StoredUsage, pending: HashMap<String, u64>) -> Self {
+        let month = month_of(today);
+        let unflushed = |prefix: &str| -> u64 {
+            pending
+                .iter()
+                .filter(|(day, _)| day.starts_with(prefix))
+                .map(|(_, requests)| requests)
+                .sum()
+        };
+
+        Self {
+            day: today.to_owned(),
+            daily: stored.daily + unflushed(today),
+            monthly: stored.monthly + unflushed(month),
+            pending,
+        }
+    }
+}
+
+// Veritabanına ulaşamazsak API'yi kilitlemek yerine sıfırdan saymaya devam ediyoruz
+async fn stored_usage(pool: &SqlitePool, key_name: &str, period: &str) -> u64 {
+    database::select_api_key_usage(pool, key_name, period)
+        .await
+        .unwrap_or_else(|e| {
+            warn!("{} anahtarının kullanımı okunamadı: {}", key_name, e);
+            0
+        })
+}
+
+// Aynı istek sayısı hem günlük hem de aylık kayda tek transaction'da ekleniyor
+async fn write_usage(
+    pool: &SqlitePool,
+    key_name: &str,
+    day: &str,
+    requests: u64,
+) -> Result<(), Error> {
+    database::add_api_key_usage(pool, key_name, &[day, month_of(day)], requests).await
+}
+
+fn month_of(day: &str) -> &str {
+    day.get(..7).unwrap_or(day)
+}
+
+// Saniyelik rate limit'in yanında API anahtarlarına günlük ve aylık toplam istek kotası uyguluyoruz.
+// Rate limiter'dan sonra çalışmalı, sadece AuthenticatedKey eklenmiş ve kotası tanımlanmış anahtarlar sayılıyor
+pub(crate) async fn quota_middleware(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(AuthenticatedKey(key_name)) = request.extensions().get::<AuthenticatedKey>().cloned()
+    else {
+        return next.run(request).await;
+    };
+
+    let (daily_quota, monthly_quota) = {
+        let config_guard = shared_state.config.load();
+        config_guard
+            .api_keys
+            .iter()
+            .find(|api_key| api_key.name == key_name)
+            .map(|api_key| (api_key.daily_quota, api_key.monthly_quota))
+            .unwrap_or_default()
+    };
+    if daily_quota.is_none() && monthly_quota.is_none() {
+        return next.run(request).await;
+    }
+
+    let now = Utc::now();
+    let today = now.format("%Y-%m-%d").to_string();
+    let pool = shared_state.api_db.clone();
+
+    match shared_state
+        .usage
+        .record(&pool, &key_name, &today, daily_quota, monthly_quota)
+        .await
+    {
+        Ok(status) => {
+            let mut response = next.run(request).await;
+            insert_quota_headers(response.headers_mut(), status, daily_quota, monthly_quota);
+            response
+        }
+        Err(status) => {
+            debug!("{} anahtarının kotası doldu", key_name);
+            // Aylık kota dolduysa gün dönümü işe yaramıyor, ay başına kadar beklemek gerekiyor
+            let retry_after = if monthly_quota.is_some_and(|quota| status.monthly >= quota) {
+                seconds_until_next_month(now)
+            } else {
+                seconds_until_next_day(now)
+            };
+            let mut response = APIError::new(
+                StatusCode::TOO_MANY_REQUESTS, ErrorCode::QuotaExceeded,
+                "API anahtarınızın istek kotası doldu / Your API key has exceeded its request quota",
+            )
+            .with_retry_after(retry_after)
+            .into_response();
+            insert_quota_headers(response.headers_mut(), status, daily_quota, monthly_quota);
+            response
+        }
+    }
+}
+
+fn insert_quota_headers(
+    headers: &mut HeaderMap,
+    status: QuotaStatus,
+    daily_quota: Option<u64>,
+    monthly_quota: Option<u64>,
+) {
+    if let Some(quota) = daily_quota {
+        headers.insert(X_QUOTA_DAILY_LIMIT, HeaderValue::from(quota));
+        headers.insert(
+            X_QUOTA_DAILY_REMAINING,
+            HeaderValue::from(quota.saturating_sub(status.daily)),
+        );
+    }
+    if let Some(quota) = monthly_quota {
+        headers.insert(X_QUOTA_MONTHLY_LIMIT, HeaderValue::from(quota));
+        headers.insert(
+            X_QUOTA_MONTHLY_REMAINING,
+            HeaderValue::from(quota.saturating_sub(status.monthly)),
+        );
+    }
+}
+
+// Kotalar UTC gün ve ay başlarında sıfırlanıyor
+fn seconds_until_next_day(now: DateTime<Utc>) -> u64 {
+    let next_day = now
+        .date_naive()
+        .succ_opt()
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|day| day.and_utc());
+    seconds_until(now, next_day)
+}
+
+fn seconds_until_next_month(now: DateTime<Utc>) -> u64 {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    let next_month = NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|day| day.and_utc());
+    seconds_until(now, next_month)
+}
+
+fn seconds_until(now: DateTime<Utc>, then: Option<DateTime<Utc>>) -> u64 {
+    then.map(|then| (then - now).num_seconds().max(1) as u64)
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_usage_tracker_quotas() -> Result<(), Error> {
+        let pool = database::memory_pool().await?;
+        // Ay içinde daha önceden yapılmış istekler aylık kotaya dahil olmalı
+        database::add_api_key_usage(&pool, "partner", &["2025-09"], 5).await?;
+
+        let tracker = UsageTracker::default();
+        for _ in 0..2 {
+            assert!(
+                tracker
+                    .record(&pool, "partner", "2025-09-08", Some(2), Some(8))
+                    .await
+                    .is_ok()
+            );
+        }
+        // Günlük kota doldu
+        assert!(
+            tracker
+                .record(&pool, "partner", "2025-09-08", Some(2), Some(8))
+                .await
+                .is_err()
+        );
+
+        // Ertesi gün günlük sayaç sıfırlanıyor ama aylık sayaç devam ediyor: 5 + 2 + 1 = 8
+        let status = tracker
+            .record(&pool, "partner", "2025-09-09", Some(2), Some(8))
+            .await
+            .ok()
+            .unwrap();
+        assert_eq!((status.daily, status.monthly), (1, 8));
+        assert!(
+            tracker
+                .record(&pool, "partner", "2025-09-09", Some(2), Some(8))
+                .await
+                .is_err()
+        );
+
+        // Bekleyen sayaçlar hem günlük hem aylık kayda yazılmalı
+        tracker.flush(&pool).await;
+        assert_eq!(
+            database::select_api_key_usage(&pool, "partner", "2025-09-08").await?,
+            2
+        );
+        assert_eq!(
+            database::select_api_key_usage(&pool, "partner", "2025-09-09").await?,
+            1
+        );
+        assert_eq!(
+            database::select_api_key_usage(&pool, "partner", "2025-09").await?,
+            8
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seconds_until_reset() {
+        let now = "2025-12-31T23:59:30Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(seconds_until_next_day(now), 30);
+        assert_eq!(seconds_until_next_month(now), 30);
+
+        let now = "2025-09-08T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert_eq!(seconds_until_next_day(now), 12 * 60 * 60);
+        assert_eq!(seconds_until_next_month(now), (22 * 24 + 12) * 60 * 60);
+    }
+}
@@ -0,0 +1,214 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use real::RealIp;
+use tracing::warn;
+
+use crate::{
+    SharedState,
+    api::{
+        error::{APIError, ErrorCode},
+        metrics::Offense,
+        quota::AuthenticatedKey,
+        security,
+    },
+    core::config::APIKeyConfig,
+};
+
+pub(crate) const API_KEY_HEADER: &str = "x-api-key";
+
+// Önceki bir middleware bu isteği rate limit'ten muaf tuttuysa extension olarak bunu ekliyor
+#[derive(Clone)]
+pub(crate) struct RateLimitExempt;
+
+// Lazy-limit kuralları path'e göre tutuyor, API anahtarı kademeleri için de sahte bir path üzerinden kural tanımlıyoruz
+// Bu sayede IP kuralları ile kademe kuralları birbirine karışmıyor
+pub(crate) fn tier_route(tier: &str) -> String {
+    format!("__tier__{}", tier)
+}
+
+// Önceden axum-governor kullanıyorduk, ama o sadece IP'ye göre limitleme yapabiliyor
+// X-API-Key gönderen istemciler kendi kademelerinin limitlerine tabi, anahtarsız istemciler ise eskisi gibi IP'ye göre limitleniyor
+pub(crate) async fn rate_limit_middleware(
+    State(shared_state): State<SharedState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Result<Response, APIError> {
+    if request.extensions().get::<RateLimitExempt>().is_some()
+        || is_exempt_path(
+            &shared_state.config.load().rate_limit.exempt_paths,
+            request.uri().path(),
+        )
+    {
+        return Ok(next.run(request).await);
+    }
+
+    if let Some(key) = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+    {
+        // (isim, kademe, kademenin kuralının aralığı)
+        let api_key = {
+            let config_guard = shared_state.config.load();
+            find_api_key(&config_guard.api_keys, key).map(|api_key| {
+                (
+                    api_key.name.clone(),
+                    api_key.tier.clone(),
+                    config_guard
+                        .rate_limit
+                        .tiers
+                        .get(&api_key.tier)
+                        .map(|rule| rule.interval_seconds),
+                )
+            })
+        };
+
+        let Some((name, tier, tier_interval)) = api_key else {
+            return Err(APIError::new(
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidApiKey,
+                "Geçersiz API anahtarı",
+            ));
+        };
+
+        // Kademe için kural tanımlanmamışsa override modu hiçbir limit uygulamaz, bunu istemiyoruz; genel kurala düşüyoruz
+        let who = format!("key:{}", name);
+        let allowed = if tier_interval.is_some() {
+            lazy_limit::limit_override!(&who, &tier_route(&tier)).await
+        } else {
+            lazy_limit::limit!(&who, request.uri().path()).await
+        };
+
+        return if allowed {
+            // Kota middleware'i anahtarı tekrar doğrulamasın diye ismini isteğe ekliyoruz
+            request.extensions_mut().insert(AuthenticatedKey(name));
+            Ok(next.run(request).await)
+        } else {
+            // Anahtarla gelen istekler de IP'ye göre sayılıyor, sızmış bir anahtarı kimin kullandığını görebilmek için
+            if let Some(ip) = request.extensions().get::<RealIp>().map(|ip| ip.ip()) {
+                shared_state
+                    .metrics
+                    .suspicious
+                    .record(ip, Offense::RateLimited)
+                    .await;
+            }
+            let retry_after = match tier_interval {
+                Some(interval) => interval,
+                None => retry_interval(&shared_state, request.uri().path()).await,
+            };
+            Err(too_many_requests(retry_after))
+        };
+    }
+
+    // Adres client_ip_middleware'in bağlantıdan veya güvenilen proxy'nin header'ından bulduğu adres. İstemcinin kendi gönderdiği
+    // X-Real-IP'ye göre sayılsaydık her istekte başka bir adres yazarak limitten kurtulabilirdi
+    let Some(ip) = request.extensions().get::<RealIp>().map(|ip| ip.ip()) else {
+        warn!("RealIp bulunamadı, client_ip_middleware rate limiter'dan önce kurulmalı");
+        return Err(APIError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::InternalError,
+            "Rate limiter yanlış yapılandırılmış",
+        ));
+    };
+
+    if lazy_limit::limit!(&ip.to_string(), request.uri().path()).await {
+        Ok(next.run(request).await)
+    } else {
+        shared_state
+            .metrics
+            .suspicious
+            .record(ip, Offense::RateLimited)
+            .await;
+        Err(too_many_requests(
+            retry_interval(&shared_state, request.uri().path()).await,
+        ))
+    }
+}
+
+// Genel kural ve path'e özel kural birlikte uygulandığı için istemcinin en uzun aralık kadar beklemesi gerekebilir
+async fn retry_interval(shared_state: &SharedState, path: &str) -> u64 {
+    let config_guard = shared_state.config.load();
+    let rate_limit = &config_guard.rate_limit;
+    rate_limit
+        .routes
+        .iter()
+        .filter(|route| route.path == path)
+        .map(|route| route.interval_seconds)
+        .fold(rate_limit.default.interval_seconds, u64::max)
+}
+
+// Gelen anahtar her tanımlı anahtarla sabit zamanda karşılaştırılıyor ve ilk eşleşmede durulmuyor, yanıt süresinden anahtarın
+// ne kadarının tuttuğu veya listede kaçıncı sırada olduğu anlaşılamıyor
+pub(crate) fn find_api_key<'a>(
+    api_keys: &'a [APIKeyConfig],
+    key: &str,
+) -> Option<&'a APIKeyConfig> {
+    api_keys.iter().fold(None, |found, api_key| {
+        let matches = security::secrets_match(key, &api_key.key);
+        found.or(matches.then_some(api_key))
+    })
+}
+
+// /health muafsa /health/live gibi alt path'ler de muaf, ama /healthz gibi benzer isimli path'ler değil
+pub(crate) fn is_exempt_path(exempt_paths: &[impl AsRef<str>], path: &str) -> bool {
+    exempt_paths.iter().any(|exempt| {
+        let exempt = exempt.as_ref().trim_end_matches('/');
+        path == exempt
+            || path
+                .strip_prefix(exempt)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+pub(crate) fn too_many_requests(retry_after: u64) -> APIError {
+    APIError::new(
+        StatusCode::TOO_MANY_REQUESTS, ErrorCode::RateLimited,
+        &format!(
+            "Çok fazla istek gönderdiniz, lütfen {} saniye sonra tekrar deneyin / Too many requests, please retry after {} seconds",
+            retry_after, retry_after
+        ),
+    )
+    .with_retry_after(retry_after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_exempt_path() {
+        let exempt_paths = vec!["/health".to_owned()];
+
+        assert!(is_exempt_path(&exempt_paths, "/health"));
+        assert!(is_exempt_path(&exempt_paths, "/health/live"));
+        assert!(!is_exempt_path(&exempt_paths, "/healthz"));
+        assert!(!is_exempt_path(&exempt_paths, "/foods/search"));
+        assert!(!is_exempt_path(&[] as &[String], "/health"));
+    }
+
+    #[test]
+    fn test_find_api_key() {
+        let api_key = |name: &str, key: &str| APIKeyConfig {
+            name: name.to_owned(),
+            key: key.to_owned(),
+            tier: "partner".to_owned(),
+            secret: None,
+            daily_quota: None,
+            monthly_quota: None,
+        };
+        let api_keys = vec![api_key("mobil", "anahtar-1"), api_key("web", "anahtar-2")];
+
+        assert_eq!(
+            find_api_key(&api_keys, "anahtar-2").map(|api_key| api_key.name.as_str()),
+            Some("web")
+        );
+        assert!(find_api_key(&api_keys, "anahtar-").is_none());
+        assert!(find_api_key(&api_keys, "").is_none());
+        assert!(find_api_key(&[], "anahtar-1").is_none());
+    }
+}
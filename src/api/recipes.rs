@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use real::RealIp;
+use serde::Serialize;
+use tracing::{error, instrument};
+
+use crate::{
+    SharedState,
+    api::{
+        database::{self, RecipeRecord},
+        error::{APIError, ErrorCode},
+        foods::{MAX_INPUT_CHARS, record_offense, validate_input},
+        metrics::Offense,
+    },
+    core::nutrition::{Nutrients, NutritionSum},
+};
+
+// Tarifin besin değerleri saklanmıyor, malzemelerin güncel değerlerinden hesaplanıyor. Bir yemeğin değerleri düzeltildiğinde
+// onu kullanan tarifler de düzeliyor
+#[derive(Serialize)]
+pub(crate) struct Recipe {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) servings: u32,
+    pub(crate) total_grams: f64,
+    pub(crate) ingredients: Vec<RecipeIngredient>,
+    // Tarifin tamamının değerleri
+    pub(crate) total: Nutrients,
+    pub(crate) per_serving: Nutrients,
+}
+
+#[derive(Serialize)]
+pub(crate) struct RecipeIngredient {
+    pub(crate) slug: Option<String>,
+    pub(crate) description: String,
+    pub(crate) grams: f64,
+}
+
+impl From<RecipeRecord> for Recipe {
+    fn from(record: RecipeRecord) -> Self {
+        let mut sum = NutritionSum::default();
+        for (food, grams) in &record.ingredients {
+            sum.add(food, *grams);
+        }
+        let total = sum.nutrients();
+        Self {
+            slug: record.slug,
+            description: record.description,
+            servings: record.servings,
+            total_grams: sum.grams(),
+            ingredients: record
+                .ingredients
+                .into_iter()
+                .map(|(food, grams)| RecipeIngredient {
+                    slug: food.slug,
+                    description: food.description,
+                    grams,
+                })
+                .collect(),
+            total: total.rounded(),
+            per_serving: total.scaled(1.0 / record.servings as f64).rounded(),
+        }
+    }
+}
+
+// /foods/list gibi slug -> tarif URL'si
+pub(crate) async fn recipes(
+    State(shared_state): State<SharedState>,
+) -> Result<Json<BTreeMap<String, String>>, APIError> {
+    let recipes = database::select_recipe_names(&shared_state.api_db)
+        .await
+        .map_err(|e| {
+            error!("Tarifler sorgulanırken hata oluştu: {:?}", e);
+            APIError::database(&e, "Veritabanı tarif sorgusu yapılırken hata oluştu")
+        })?;
+
+    let api_base_url = &shared_state.urls.base_url;
+    Ok(Json(
+        recipes
+            .into_iter()
+            .map(|(slug, _)| (slug.clone(), format!("{}/recipe/{}", api_base_url, slug)))
+            .collect(),
+    ))
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn recipe(
+    Path(slug): Path<String>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<Json<Recipe>, APIError> {
+    let slug = match validate_input(&slug, MAX_INPUT_CHARS) {
+        Ok(slug) => slug,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+
+    let record = database::select_recipe_by_slug(&shared_state.api_db, &slug)
+        .await
+        .map_err(|e| {
+            error!("Tarif sorgulanırken hata oluştu: {:?}", e);
+            APIError::database(&e, "Veritabanı tarif sorgusu yapılırken hata oluştu")
+        })?
+        .ok_or_else(|| recipe_not_found(&slug))?;
+    Ok(Json(Recipe::from(record)))
+}
+
+pub(crate) fn recipe_not_found(slug: &str) -> APIError {
+    APIError::new(
+        StatusCode::NOT_FOUND,
+        ErrorCode::RecipeNotFound,
+        &format!("{} tarifi bulunamadı", slug),
+    )
+}
@@ -0,0 +1,74 @@
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use real::RealIp;
+use tracing::{error, instrument};
+
+use crate::{
+    SharedState,
+    api::{
+        database,
+        error::{APIError, ErrorCode},
+        foods::{MAX_INPUT_CHARS, record_offense, validate_input},
+        metrics::Offense,
+    },
+    core::daily_values::ReferenceIntake,
+};
+
+// Öğün analizinde ?profile= ile seçilebilen profiller, değerleriyle birlikte. Az sayıda oldukları için liste de tam dönüyor
+pub(crate) async fn reference_intakes(
+    State(shared_state): State<SharedState>,
+) -> Result<Json<Vec<ReferenceIntake>>, APIError> {
+    let profiles = database::select_reference_intakes(&shared_state.api_db)
+        .await
+        .map_err(|e| {
+            error!(
+                "Referans alım profilleri sorgulanırken hata oluştu: {:?}",
+                e
+            );
+            APIError::database(&e, "Veritabanı profil sorgusu yapılırken hata oluştu")
+        })?;
+    Ok(Json(profiles))
+}
+
+#[instrument(skip_all)]
+pub(crate) async fn reference_intake(
+    Path(slug): Path<String>,
+    State(shared_state): State<SharedState>,
+    real_ip: RealIp,
+) -> Result<Json<ReferenceIntake>, APIError> {
+    let slug = match validate_input(&slug, MAX_INPUT_CHARS) {
+        Ok(slug) => slug,
+        Err(e) => {
+            record_offense(&shared_state, &real_ip, Offense::InvalidInput).await;
+            return Err(e);
+        }
+    };
+
+    let profile = find_reference_intake(&shared_state, &slug)
+        .await?
+        .ok_or_else(|| reference_intake_not_found(&slug))?;
+    Ok(Json(profile))
+}
+
+pub(crate) async fn find_reference_intake(
+    shared_state: &SharedState,
+    slug: &str,
+) -> Result<Option<ReferenceIntake>, APIError> {
+    database::select_reference_intake(&shared_state.api_db, slug)
+        .await
+        .map_err(|e| {
+            error!("Referans alım profili sorgulanırken hata oluştu: {:?}", e);
+            APIError::database(&e, "Veritabanı profil sorgusu yapılırken hata oluştu")
+        })
+}
+
+pub(crate) fn reference_intake_not_found(slug: &str) -> APIError {
+    APIError::new(
+        StatusCode::NOT_FOUND,
+        ErrorCode::ReferenceIntakeNotFound,
+        &format!("{} referans alım profili bulunamadı", slug),
+    )
+}
@@ -0,0 +1,130 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::Request,
+    http::{HeaderValue, header::CONTENT_LENGTH, header::CONTENT_TYPE},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{Instrument, info_span, warn};
+use uuid::Uuid;
+
+use crate::api::error::APIError;
+
+pub(crate) const X_REQUEST_ID: &str = "x-request-id";
+
+// İstemcinin gönderdiği ID'yi aynen kullanıyoruz ama loglara ve header'lara keyfi veri girmesin diye sınırlıyoruz
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+// Hata gövdeleri küçük, bundan büyük bir gövde APIError olamaz
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+// Her isteğe bir ID veriyoruz, istemci X-Request-Id gönderdiyse onu kullanıyoruz (ör: reverse proxy'nin ürettiği ID).
+// ID isteğin tüm loglarında span alanı olarak görünüyor, yanıt header'ında ve hata gövdelerinde de dönüyor; kullanıcılar hataları bu ID ile bildirebiliyor
+pub(crate) async fn request_id_middleware(mut request: Request<Body>, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| is_valid_request_id(value))
+        .map(|value| value.to_owned())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let header_value = HeaderValue::from_str(&request_id)
+        .expect("Request ID'ler sadece görünür ASCII karakterlerden oluşuyor");
+    request
+        .headers_mut()
+        .insert(X_REQUEST_ID, header_value.clone());
+
+    let span = info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+    crate::core::error_reporting::set_request_id(&request_id);
+    // Reverse proxy veya istemci traceparent gönderdiyse span'ımız onun trace'ine bağlanıyor
+    crate::core::telemetry::set_parent_from_headers(&span, request.headers());
+    let mut response = next.run(request).instrument(span).await;
+
+    // Hata gövdesini burada güncelliyoruz, önbellekteki hata gövdelerinde eski isteğin ID'si kalmasın diye
+    if response.status().is_client_error() || response.status().is_server_error() {
+        response = with_request_id_in_body(response, &request_id).await;
+    }
+    response.headers_mut().insert(X_REQUEST_ID, header_value);
+    response
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.bytes().all(|b| b.is_ascii_graphic())
+}
+
+async fn with_request_id_in_body(response: Response, request_id: &str) -> Response {
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Hata gövdesi okunamadı: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let Ok(mut error) = serde_json::from_slice::<APIError>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    error.request_id = Some(request_id.to_owned());
+    let Ok(body) = serde_json::to_vec(&error) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    // Gövde uzadığı için eski Content-Length artık geçersiz
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_request_id() {
+        assert!(is_valid_request_id("3f2c9a1e-7b1d-4c55-9d8e-0a6f2b1c4d3e"));
+        assert!(is_valid_request_id("caddy-12345"));
+
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("içinde boşluk var"));
+        assert!(!is_valid_request_id("satır\nsonu"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LEN + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_added_to_error_body() {
+        use axum::{http::StatusCode, response::IntoResponse};
+
+        let response = APIError::new(
+            StatusCode::NOT_FOUND,
+            crate::api::error::ErrorCode::FoodNotFound,
+            "Bulunamadı",
+        )
+        .into_response();
+        let response = with_request_id_in_body(response, "istek-1").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let body = to_bytes(response.into_body(), MAX_ERROR_BODY_BYTES)
+            .await
+            .unwrap();
+        let error: APIError = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error.request_id.as_deref(), Some("istek-1"));
+        assert_eq!(error.message, "Bulunamadı");
+        assert_eq!(error.error, crate::api::error::ErrorCode::FoodNotFound);
+    }
+}
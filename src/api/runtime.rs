@@ -0,0 +1,196 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::runtime::Handle;
+use tracing::warn;
+
+use crate::{SharedState, api::metrics::DATABASE_QUERIES};
+
+// Bekçi görevi bu aralıklarla uyanıyor, uyanması bundan fazla gecikirse worker'lar bloklanmış demektir
+const WATCHDOG_INTERVAL: Duration = Duration::from_millis(100);
+const STALL_THRESHOLD: Duration = Duration::from_millis(100);
+
+// Async kodda bloklayan bir çağrı veya uzun süre tutulan bir kilit, worker'ları meşgul edip tüm istekleri bekletiyor.
+// Bekçi görevinin ne kadar geç uyandığını ölçerek bu tıkanmaları yakalıyoruz
+#[derive(Default)]
+pub(crate) struct StallDetector {
+    stalls: AtomicU64,
+    max_lag_micros: AtomicU64,
+    last_stall: Mutex<Option<Instant>>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct StallStats {
+    threshold_ms: u64,
+    stalls: u64,
+    max_lag_ms: f64,
+    last_stall_seconds_ago: Option<u64>,
+}
+
+impl StallDetector {
+    fn record(&self, lag: Duration) {
+        self.max_lag_micros
+            .fetch_max(lag.as_micros() as u64, Ordering::Relaxed);
+        if lag < STALL_THRESHOLD {
+            return;
+        }
+        self.stalls.fetch_add(1, Ordering::Relaxed);
+        *self.last_stall.lock().expect("Tıkanma kilidi zehirlendi") = Some(Instant::now());
+        warn!(
+            "Tokio çalışma zamanı {}ms tıkandı, bir worker'da bloklayan bir çağrı veya uzun süre tutulan bir kilit olabilir",
+            lag.as_millis()
+        );
+    }
+
+    pub(crate) fn stats(&self) -> StallStats {
+        StallStats {
+            threshold_ms: STALL_THRESHOLD.as_millis() as u64,
+            stalls: self.stalls.load(Ordering::Relaxed),
+            max_lag_ms: round_ms(Duration::from_micros(
+                self.max_lag_micros.load(Ordering::Relaxed),
+            )),
+            last_stall_seconds_ago: self
+                .last_stall
+                .lock()
+                .expect("Tıkanma kilidi zehirlendi")
+                .map(|last_stall| last_stall.elapsed().as_secs()),
+        }
+    }
+
+    // Tek bir worker bloklandığında bekçi başka bir worker'da çalışabiliyor, bu yüzden en çok tüm worker'ların tıkandığı durumları yakalıyor
+    pub(crate) async fn watch(&self) {
+        loop {
+            let start = Instant::now();
+            tokio::time::sleep(WATCHDOG_INTERVAL).await;
+            self.record(start.elapsed().saturating_sub(WATCHDOG_INTERVAL));
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(crate) struct RuntimeStats {
+    uptime_seconds: u64,
+    workers: usize,
+    alive_tasks: usize,
+    // Worker'lara dağıtılmayı bekleyen görevler, sürekli yüksekse worker'lar yetişemiyor
+    global_queue_depth: usize,
+    worker_stats: Vec<WorkerStats>,
+    stalls: StallStats,
+    database_pool: PoolStats,
+    memory: MemoryStats,
+}
+
+#[derive(Serialize)]
+pub(crate) struct WorkerStats {
+    worker: usize,
+    busy_ms: u64,
+    // Süreç başladığından beri meşgul geçen sürenin oranı
+    busy_ratio: f64,
+    park_count: u64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct PoolStats {
+    size: u32,
+    idle: usize,
+    in_use: usize,
+    max_connections: u32,
+    // Kullanılan bağlantıların en fazla bağlantıya oranı, 1'e yakınsa istekler bağlantı bekliyor olabilir
+    utilization: f64,
+    acquire_timeout_ms: u64,
+    // Süreç başladığından beri havuzdan bağlantı alınamadığı için zaman aşımına uğrayan sorgular
+    acquire_timeouts: u64,
+    // Kilit hatası yüzünden tekrar denenen sorgu sayısı, sürekli artıyorsa yazmalar okumaları bekletiyor
+    lock_retries: u64,
+}
+
+#[derive(Serialize)]
+pub(crate) struct MemoryStats {
+    rss_bytes: Option<u64>,
+    virtual_bytes: Option<u64>,
+    cache_bytes: u64,
+    negative_cache_bytes: u64,
+}
+
+pub(crate) async fn runtime_stats(shared_state: &SharedState) -> RuntimeStats {
+    let uptime = shared_state.started_at.elapsed();
+    let metrics = Handle::current().metrics();
+    let worker_stats = (0..metrics.num_workers())
+        .map(|worker| {
+            let busy = metrics.worker_total_busy_duration(worker);
+            WorkerStats {
+                worker,
+                busy_ms: busy.as_millis() as u64,
+                busy_ratio: (busy.as_secs_f64() / uptime.as_secs_f64().max(f64::EPSILON) * 1000.0)
+                    .round()
+                    / 1000.0,
+                park_count: metrics.worker_park_count(worker),
+            }
+        })
+        .collect();
+
+    let memory = memory_stats::memory_stats();
+    RuntimeStats {
+        uptime_seconds: uptime.as_secs(),
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        worker_stats,
+        stalls: shared_state.metrics.stalls.stats(),
+        database_pool: pool_stats(&shared_state.api_db),
+        memory: MemoryStats {
+            rss_bytes: memory.map(|stats| stats.physical_mem as u64),
+            virtual_bytes: memory.map(|stats| stats.virtual_mem as u64),
+            cache_bytes: shared_state.cache.weighted_size(),
+            negative_cache_bytes: shared_state.negative_cache.weighted_size(),
+        },
+    }
+}
+
+fn round_ms(duration: Duration) -> f64 {
+    (duration.as_secs_f64() * 1000.0 * 1000.0).round() / 1000.0
+}
+
+pub(crate) fn pool_stats(pool: &SqlitePool) -> PoolStats {
+    let size = pool.size();
+    let idle = pool.num_idle();
+    let in_use = (size as usize).saturating_sub(idle);
+    let max_connections = pool.options().get_max_connections();
+    PoolStats {
+        size,
+        idle,
+        in_use,
+        max_connections,
+        utilization: (in_use as f64 / max_connections.max(1) as f64 * 1000.0).round() / 1000.0,
+        acquire_timeout_ms: pool.options().get_acquire_timeout().as_millis() as u64,
+        acquire_timeouts: DATABASE_QUERIES.pool_timeouts(),
+        lock_retries: DATABASE_QUERIES.retries(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_detector() {
+        let detector = StallDetector::default();
+        detector.record(Duration::from_millis(3));
+        assert_eq!(detector.stats().stalls, 0);
+        assert!(detector.stats().last_stall_seconds_ago.is_none());
+
+        detector.record(Duration::from_millis(450));
+        detector.record(Duration::from_millis(120));
+        let stats = detector.stats();
+        assert_eq!(stats.stalls, 2);
+        assert_eq!(stats.max_lag_ms, 450.0);
+        assert_eq!(stats.last_stall_seconds_ago, Some(0));
+    }
+}
@@ -0,0 +1,278 @@
+use std::{borrow::Cow, sync::Arc};
+
+use anyhow::Error;
+use sqlx::SqlitePool;
+use tokio::sync::RwLock;
+
+use crate::{
+    api::{database, foods::Rankable},
+    core::str,
+};
+
+// Açıklama ve tüm alan aramaları her tuşta LIKE ile tabloyu taramasın diye onaylanmış yemeklerin normalize edilmiş adları, etiketleri
+// ve filtrelerde kullanılan alanları bellekte tutuluyor. Adaylar bellekte seçilip sıralanıyor, veritabanından sadece istenen sayfadaki
+// yemekler okunuyor. Açılışta ve yemekler değiştiğinde (doğrulama, diğer adlar, /admin/cache/purge) yeniden yükleniyor; yüklenemediyse
+// arama eskisi gibi veritabanında yapılıyor
+#[derive(Default)]
+pub(crate) struct SearchIndex {
+    // Aramalar kilidi sıralama boyunca tutmasın diye yüklenen liste Arc içinde, yeniden yükleme yeni bir liste koyuyor
+    entries: RwLock<Option<Arc<Vec<IndexEntry>>>>,
+}
+
+// Metinler veritabanındaki search_description kolonları gibi to_search_key ile normalize edilmiş
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IndexEntry {
+    pub(crate) id: i64,
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) tags: Vec<String>,
+    pub(crate) source: String,
+    // Filtreler için: tüm seviyelerdeki alerjenler, tamlık, markalı ürün mü ve uyduğu diyet bayrakları
+    pub(crate) allergens: Vec<String>,
+    pub(crate) completeness: f64,
+    pub(crate) branded: bool,
+    pub(crate) diet_flags: Vec<String>,
+}
+
+// Hangi alanlarda aranacağı, foods_search'ün modlarına karşılık geliyor
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum IndexFields {
+    // mode=description: sorgunun tüm parçaları açıklamada ya da diğer adlardan tek birinde geçmeli
+    Names,
+    // mode=all: her parça açıklamada, diğer adlarda, etiketlerde veya kaynak adında geçmeli
+    All,
+}
+
+// Veritabanı aramalarındaki food_filters_sql ile aynı filtreler
+pub(crate) struct IndexFilter<'a> {
+    allergen_free: Vec<String>,
+    min_completeness: f64,
+    exclude_branded: bool,
+    diet_flags: &'a [&'a str],
+}
+
+impl<'a> IndexFilter<'a> {
+    pub(crate) fn new(
+        allergen_free: &[&str],
+        min_completeness: f64,
+        exclude_branded: bool,
+        diet_flags: &'a [&'a str],
+    ) -> Self {
+        Self {
+            allergen_free: allergen_free
+                .iter()
+                .map(|allergen| str::to_search_key(allergen))
+                .collect(),
+            min_completeness,
+            exclude_branded,
+            diet_flags,
+        }
+    }
+
+    pub(crate) fn matches(&self, entry: &IndexEntry) -> bool {
+        !entry
+            .allergens
+            .iter()
+            .any(|allergen| self.allergen_free.contains(allergen))
+            && entry.completeness >= self.min_completeness
+            && !(self.exclude_branded && entry.branded)
+            && self
+                .diet_flags
+                .iter()
+                .all(|flag| entry.diet_flags.iter().any(|entry_flag| entry_flag == flag))
+    }
+}
+
+impl SearchIndex {
+    pub(crate) async fn reload(&self, pool: &SqlitePool) -> Result<usize, Error> {
+        let entries = database::select_search_index_entries(pool).await?;
+        let loaded = entries.len();
+        *self.entries.write().await = Some(Arc::new(entries));
+        Ok(loaded)
+    }
+
+    pub(crate) async fn snapshot(&self) -> Option<Arc<Vec<IndexEntry>>> {
+        self.entries.read().await.clone()
+    }
+}
+
+impl IndexEntry {
+    fn matches_terms(&self, terms: &[String], fields: IndexFields) -> bool {
+        let all_in = |field: &str| terms.iter().all(|term| field.contains(term.as_str()));
+        match fields {
+            IndexFields::Names => {
+                all_in(&self.description) || self.aliases.iter().any(|alias| all_in(alias))
+            }
+            IndexFields::All => terms.iter().all(|term| {
+                let term = term.as_str();
+                self.description.contains(term)
+                    || self.source.contains(term)
+                    || self.tags.iter().any(|tag| tag.contains(term))
+                    || self.aliases.iter().any(|alias| alias.contains(term))
+            }),
+        }
+    }
+
+    // description_relevance_order_sql ile aynı: açıklama sorgunun kendisi, sorguyla başlayan, bir kelimesi sorguyla başlayan,
+    // sorguyu sadece içeren (veya diğer adlarla bulunan) sırasıyla; sonra kısa açıklamalar ve eklenme sırası
+    fn relevance(&self, search_key: &str) -> (u8, usize, i64) {
+        let group = if self.description == search_key {
+            0
+        } else if self.description.starts_with(search_key) {
+            1
+        } else if self.description.contains(&format!(" {}", search_key)) {
+            2
+        } else {
+            3
+        };
+        (group, self.description.chars().count(), self.id)
+    }
+}
+
+// Sorgunun parçalarına uyan ve filtrelerden geçen yemeklerden alakaya göre ilk max_candidates yemek, veritabanı aramalarıyla
+// aynı adaylar. Asıl sıralama foods'taki sıralama fonksiyonlarıyla yapılıyor
+pub(crate) fn candidates<'a>(
+    entries: &'a [IndexEntry],
+    terms: &[String],
+    fields: IndexFields,
+    filter: &IndexFilter,
+    max_candidates: u32,
+) -> Vec<&'a IndexEntry> {
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let search_key = terms.join(" ");
+    let mut found: Vec<&IndexEntry> = entries
+        .iter()
+        .filter(|entry| entry.matches_terms(terms, fields) && filter.matches(entry))
+        .collect();
+    found.sort_by_cached_key(|entry| entry.relevance(&search_key));
+    found.truncate(max_candidates as usize);
+    found
+}
+
+impl Rankable for IndexEntry {
+    fn id(&self) -> Option<i64> {
+        Some(self.id)
+    }
+
+    fn name_keys(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        std::iter::once(Cow::Borrowed(self.description.as_str())).chain(
+            self.aliases
+                .iter()
+                .map(|alias| Cow::Borrowed(alias.as_str())),
+        )
+    }
+
+    fn tag_keys(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.tags.iter().map(|tag| Cow::Borrowed(tag.as_str()))
+    }
+
+    fn source_key(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i64, description: &str, aliases: &[&str], tags: &[&str]) -> IndexEntry {
+        IndexEntry {
+            id,
+            slug: str::to_lower_en_kebab_case(description),
+            description: str::to_search_key(description),
+            aliases: aliases
+                .iter()
+                .map(|alias| str::to_search_key(alias))
+                .collect(),
+            tags: tags.iter().map(|tag| str::to_search_key(tag)).collect(),
+            source: "turkomp".to_owned(),
+            allergens: Vec::new(),
+            completeness: 1.0,
+            branded: false,
+            diet_flags: Vec::new(),
+        }
+    }
+
+    fn slugs(found: Vec<&IndexEntry>) -> Vec<&str> {
+        found.into_iter().map(|entry| entry.slug.as_str()).collect()
+    }
+
+    #[test]
+    fn test_candidates() {
+        let entries = vec![
+            entry(1, "Elma Turtası", &[], &["tatlı"]),
+            entry(2, "Fuji Elma", &[], &["meyve"]),
+            entry(3, "Elma", &["apple"], &["meyve"]),
+            entry(4, "Patlıcan", &["aubergine"], &["sebze"]),
+            entry(5, "Portakal", &[], &["meyve"]),
+        ];
+        let no_filter = IndexFilter::new(&[], 0.0, false, &[]);
+        let terms = |query: &str| vec![str::to_search_key(query)];
+
+        // Tam eşleşme, sonra sorguyla başlayanlar, sonra kelimesi sorguyla başlayanlar
+        assert_eq!(
+            slugs(candidates(
+                &entries,
+                &terms("elma"),
+                IndexFields::Names,
+                &no_filter,
+                10
+            )),
+            vec!["elma", "elma-turtasi", "fuji-elma"]
+        );
+        assert_eq!(
+            slugs(candidates(
+                &entries,
+                &terms("elma"),
+                IndexFields::Names,
+                &no_filter,
+                2
+            )),
+            vec!["elma", "elma-turtasi"]
+        );
+        assert_eq!(
+            slugs(candidates(
+                &entries,
+                &terms("aubergine"),
+                IndexFields::Names,
+                &no_filter,
+                10
+            )),
+            vec!["patlican"]
+        );
+        // Etiketler sadece tüm alanlarda aranıyor
+        assert!(
+            candidates(
+                &entries,
+                &terms("meyve"),
+                IndexFields::Names,
+                &no_filter,
+                10
+            )
+            .is_empty()
+        );
+        assert_eq!(
+            candidates(&entries, &terms("meyve"), IndexFields::All, &no_filter, 10).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut entry = entry(1, "Sütlaç", &[], &[]);
+        entry.allergens = vec!["sut".to_owned()];
+        entry.diet_flags = vec!["vegetarian".to_owned()];
+        entry.completeness = 0.5;
+
+        assert!(IndexFilter::new(&[], 0.5, true, &["vegetarian"]).matches(&entry));
+        assert!(!IndexFilter::new(&["Süt"], 0.0, false, &[]).matches(&entry));
+        assert!(!IndexFilter::new(&[], 0.8, false, &[]).matches(&entry));
+        assert!(!IndexFilter::new(&[], 0.0, false, &["vegan"]).matches(&entry));
+        entry.branded = true;
+        assert!(!IndexFilter::new(&[], 0.0, true, &[]).matches(&entry));
+    }
+}
@@ -0,0 +1,269 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use ipnet::IpNet;
+use real::RealIp;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use tracing::debug;
+
+use crate::{
+    SharedState,
+    api::{
+        error::{APIError, ErrorCode},
+        rate_limit::RateLimitExempt,
+    },
+};
+
+// İstemci adresi bağlantının karşı ucu. X-Forwarded-For ve X-Real-IP'yi herkes gönderebildiği için sadece karşı uç
+// security.trusted_proxies'teki bir reverse proxy ise okunuyor. Sonraki middleware'ler ve handler'lar adresi RealIp olarak buluyor
+pub(crate) async fn client_ip_middleware(
+    State(shared_state): State<SharedState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    if let Some(ConnectInfo(peer)) = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .copied()
+    {
+        let ip = client_ip(
+            &shared_state.config.load().security.trusted_proxies,
+            peer.ip(),
+            request.headers(),
+        );
+        request.extensions_mut().insert(RealIp(ip));
+    }
+    next.run(request).await
+}
+
+// Her proxy X-Forwarded-For'un sonuna kendinden öncekinin adresini ekliyor. Sağdan başlayıp güvenilen proxy'ler atlanınca kalan
+// ilk adres istemcinin; daha soldaki değerleri istemci kendisi yazmış olabilir. Okunamayan bir değerde son bilinen adreste kalınıyor
+fn client_ip(trusted_proxies: &[IpNet], peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+    if !contains_ip(trusted_proxies, peer) {
+        return peer;
+    }
+
+    let forwarded_for: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+    if forwarded_for.is_empty() {
+        return headers
+            .get("x-real-ip")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(peer);
+    }
+
+    let mut ip = peer;
+    for hop in forwarded_for.iter().rev() {
+        let Ok(hop) = hop.parse::<IpAddr>() else {
+            break;
+        };
+        ip = hop;
+        if !contains_ip(trusted_proxies, hop) {
+            break;
+        }
+    }
+    ip
+}
+
+// Config'deki IP listelerini rate limiter'dan önce uyguluyoruz, reverse proxy'de değişiklik yapmadan istemci engelleyebilmek için
+// Engelli listesindeki istemciler 403 alıyor, izinli listesindekiler ise rate limit'ten muaf tutuluyor
+pub(crate) async fn ip_filter_middleware(
+    State(shared_state): State<SharedState>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Result<Response, APIError> {
+    let Some(ip) = request.extensions().get::<RealIp>().map(|ip| ip.ip()) else {
+        // IP'yi bilmiyorsak filtreleyemeyiz, rate limiter zaten bu durumu ele alıyor
+        return Ok(next.run(request).await);
+    };
+
+    let (denied, allowed) = {
+        let config_guard = shared_state.config.load();
+        (
+            contains_ip(&config_guard.security.ip_denylist, ip),
+            contains_ip(&config_guard.security.ip_allowlist, ip),
+        )
+    };
+
+    if denied {
+        debug!("Engelli listesindeki IP'den istek reddedildi: {}", ip);
+        return Err(APIError::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::IpNotAllowed,
+            "Bu IP adresinden gelen isteklere izin verilmiyor",
+        ));
+    }
+
+    if allowed {
+        request.extensions_mut().insert(RateLimitExempt);
+    }
+
+    Ok(next.run(request).await)
+}
+
+// Token ve anahtarlar sabit zamanda karşılaştırılıyor, yanıt süresinden kaç karakterin tuttuğu anlaşılamasın diye. Önce hash'leri
+// alınıyor, böylece uzunluk farkı da erken dönüşe yol açmıyor
+pub(crate) fn secrets_match(provided: &str, expected: &str) -> bool {
+    Sha256::digest(provided.as_bytes())
+        .ct_eq(&Sha256::digest(expected.as_bytes()))
+        .into()
+}
+
+fn contains_ip(ranges: &[IpNet], ip: IpAddr) -> bool {
+    // IPv4 istemciler bazen IPv6'ya eşlenmiş şekilde (::ffff:1.2.3.4) gelebiliyor, onları da IPv4 olarak kontrol ediyoruz
+    let ip = ip.to_canonical();
+    ranges.iter().any(|range| range.contains(&ip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_ip() {
+        let ranges: Vec<IpNet> = vec![
+            "10.0.0.0/8".parse().unwrap(),
+            "2001:db8::/32".parse().unwrap(),
+        ];
+
+        assert!(contains_ip(&ranges, "10.1.2.3".parse().unwrap()));
+        assert!(contains_ip(&ranges, "2001:db8::1".parse().unwrap()));
+        assert!(contains_ip(&ranges, "::ffff:10.1.2.3".parse().unwrap()));
+        assert!(!contains_ip(&ranges, "192.168.1.1".parse().unwrap()));
+        assert!(!contains_ip(&[], "10.1.2.3".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_client_ip_trusts_headers_only_from_proxies() {
+        let proxies: Vec<IpNet> = vec![
+            "127.0.0.1/32".parse().unwrap(),
+            "10.0.0.0/8".parse().unwrap(),
+        ];
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.append(*name, value.parse().unwrap());
+            }
+            headers
+        };
+        let ip = |value: &str| value.parse::<IpAddr>().unwrap();
+
+        // Doğrudan bağlanan istemcinin header'ları yok sayılıyor
+        let spoofed = headers(&[("x-real-ip", "10.0.0.1"), ("x-forwarded-for", "10.0.0.1")]);
+        assert_eq!(
+            client_ip(&proxies, ip("203.0.113.7"), &spoofed),
+            ip("203.0.113.7")
+        );
+
+        // Proxy arkasında zincirin sağındaki ilk güvenilmeyen adres, istemcinin sola eklediği değer değil
+        let chain = headers(&[("x-forwarded-for", "1.2.3.4, 198.51.100.9, 10.0.0.5")]);
+        assert_eq!(
+            client_ip(&proxies, ip("127.0.0.1"), &chain),
+            ip("198.51.100.9")
+        );
+        let chain = headers(&[
+            ("x-forwarded-for", "1.2.3.4"),
+            ("x-forwarded-for", "198.51.100.9"),
+        ]);
+        assert_eq!(
+            client_ip(&proxies, ip("127.0.0.1"), &chain),
+            ip("198.51.100.9")
+        );
+        assert_eq!(
+            client_ip(
+                &proxies,
+                ip("127.0.0.1"),
+                &headers(&[("x-real-ip", "198.51.100.9")])
+            ),
+            ip("198.51.100.9")
+        );
+        assert_eq!(
+            client_ip(
+                &proxies,
+                ip("127.0.0.1"),
+                &headers(&[("x-forwarded-for", "bilinmiyor")])
+            ),
+            ip("127.0.0.1")
+        );
+        assert_eq!(
+            client_ip(&proxies, ip("127.0.0.1"), &HeaderMap::new()),
+            ip("127.0.0.1")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_header_gets_no_exemption() -> Result<(), anyhow::Error> {
+        use axum::{Extension, Router, middleware, routing::get};
+        use tower::ServiceExt;
+
+        let mut config = crate::core::config::get_default_config();
+        config.security.ip_allowlist = vec!["10.0.0.0/8".parse()?];
+        config.security.ip_denylist = vec!["203.0.113.0/24".parse()?];
+        let state = SharedState::with_pool(config, crate::api::database::memory_pool().await?);
+        let router = Router::new()
+            .route(
+                "/",
+                get(|exempt: Option<Extension<RateLimitExempt>>| async move {
+                    if exempt.is_some() { "muaf" } else { "limitli" }
+                }),
+            )
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                ip_filter_middleware,
+            ))
+            .layer(middleware::from_fn_with_state(state, client_ip_middleware));
+        let request = |peer: [u8; 4], real_ip: &str| {
+            let mut request = Request::get("/")
+                .header("x-real-ip", real_ip)
+                .body(Body::empty())
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from((peer, 40000))));
+            request
+        };
+        let body = |response: Response| async move {
+            axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap()
+        };
+
+        // Güvenilmeyen istemci izinli listedeki bir adresi yazarak muafiyet alamıyor, engellenen istemci de başka bir adresle geçemiyor
+        let response = router
+            .clone()
+            .oneshot(request([198, 51, 100, 9], "10.0.0.1"))
+            .await?;
+        assert_eq!(&body(response).await[..], b"limitli");
+        let response = router
+            .clone()
+            .oneshot(request([203, 0, 113, 7], "198.51.100.9"))
+            .await?;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Aynı header yerel reverse proxy'den gelince kullanılıyor
+        let response = router.oneshot(request([127, 0, 0, 1], "10.0.0.1")).await?;
+        assert_eq!(&body(response).await[..], b"muaf");
+        Ok(())
+    }
+
+    #[test]
+    fn test_secrets_match() {
+        assert!(secrets_match("gizli-token", "gizli-token"));
+        assert!(!secrets_match("gizli-tokeN", "gizli-token"));
+        assert!(!secrets_match("gizli", "gizli-token"));
+        assert!(!secrets_match("", "gizli-token"));
+    }
+}
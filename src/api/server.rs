@@ -0,0 +1,236 @@
+use std::{convert::Infallible, str::FromStr, sync::Arc, time::Duration};
+
+use anyhow::{Context, Error};
+use axum::{
+    Extension, Router,
+    extract::{ConnectInfo, Request, State},
+    http::{StatusCode, header::HOST, uri::Authority},
+    response::{IntoResponse, Redirect, Response},
+};
+use hyper::body::Incoming;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+    server::{conn::auto::Builder, graceful::GracefulShutdown},
+    service::TowerToHyperService,
+};
+use tokio::net::TcpListener;
+use tokio_io_timeout::TimeoutStream;
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        self,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    },
+};
+use tower::{Layer, Service};
+use tracing::{debug, warn};
+
+use crate::{
+    api::error::{APIError, ErrorCode},
+    core::config::{ServerConfig, TlsConfig},
+};
+
+// Kapanış sinyalinden sonra açık bağlantıların bitmesi için beklenecek en uzun süre
+const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// axum::serve zaman aşımı ayarlarına izin vermediği için bağlantıları hyper ile kendimiz kabul ediyoruz.
+// Header'ını hiç bitirmeyen ya da soketi boşta tutan (slowloris gibi) istemciler böylece bağlantıyı sonsuza kadar tutamıyor
+// TLS acceptor verilirse bağlantılar TLS el sıkışmasından sonra sunuluyor, Caddy gibi bir reverse proxy olmadan da HTTPS sunabiliyoruz
+pub(crate) async fn serve<S>(
+    listener: TcpListener,
+    service: S,
+    server_config: &ServerConfig,
+    tls_acceptor: Option<TlsAcceptor>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), Error>
+where
+    S: Service<Request<Incoming>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    let header_read_timeout = Duration::from_secs(server_config.header_read_timeout_seconds);
+    let mut builder = Builder::new(TokioExecutor::new());
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .header_read_timeout(header_read_timeout)
+        .keep_alive(server_config.keep_alive);
+    if server_config.http2 {
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .max_concurrent_streams(server_config.http2_max_concurrent_streams)
+            .keep_alive_interval(
+                (server_config.http2_keep_alive_interval_seconds > 0)
+                    .then(|| Duration::from_secs(server_config.http2_keep_alive_interval_seconds)),
+            )
+            .keep_alive_timeout(Duration::from_secs(
+                server_config.http2_keep_alive_timeout_seconds,
+            ));
+    } else {
+        builder = builder.http1_only();
+    }
+
+    let graceful = GracefulShutdown::new();
+    let mut shutdown = std::pin::pin!(shutdown);
+
+    loop {
+        let (stream, remote_addr) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // Dosya tanımlayıcısı tükenmesi gibi hatalarda döngüyü hemen tekrar çalıştırıp CPU'yu yakmamak için kısa bir süre bekliyoruz
+                    warn!("Bağlantı kabul edilemedi: {}", e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            _ = shutdown.as_mut() => break,
+        };
+
+        // Boşta kalma süresi soketten hiç veri okunamadığı süre, yazma süresi de istemcinin yanıtı almadığı süre
+        let mut stream = TimeoutStream::new(stream);
+        stream.set_read_timeout(Some(Duration::from_secs(
+            server_config.idle_timeout_seconds,
+        )));
+        stream.set_write_timeout(Some(Duration::from_secs(
+            server_config.write_timeout_seconds,
+        )));
+        let stream = Box::pin(stream);
+
+        // Rate limiter ve loglar istemci adresini ConnectInfo üzerinden okuyor
+        let service =
+            TowerToHyperService::new(Extension(ConnectInfo(remote_addr)).layer(service.clone()));
+        let builder = builder.clone();
+        let watcher = graceful.watcher();
+        let tls_acceptor = tls_acceptor.clone();
+
+        tokio::spawn(async move {
+            let result = match tls_acceptor {
+                // El sıkışması da header okuma süresiyle sınırlı, yarım bırakılan el sıkışmaları soket tutmasın
+                Some(tls_acceptor) => {
+                    match tokio::time::timeout(header_read_timeout, tls_acceptor.accept(stream))
+                        .await
+                    {
+                        Ok(Ok(stream)) => {
+                            let connection = builder
+                                .serve_connection(TokioIo::new(stream), service)
+                                .into_owned();
+                            watcher.watch(connection).await
+                        }
+                        Ok(Err(e)) => {
+                            debug!("TLS el sıkışması başarısız oldu ({}): {}", remote_addr, e);
+                            return;
+                        }
+                        Err(_) => {
+                            debug!("TLS el sıkışması zaman aşımına uğradı ({})", remote_addr);
+                            return;
+                        }
+                    }
+                }
+                None => {
+                    let connection = builder
+                        .serve_connection(TokioIo::new(stream), service)
+                        .into_owned();
+                    watcher.watch(connection).await
+                }
+            };
+
+            if let Err(e) = result {
+                debug!("Bağlantı kapandı ({}): {}", remote_addr, e);
+            }
+        });
+    }
+
+    // Yeni bağlantı kabul etmeyi bıraktık, mevcut isteklerin tamamlanmasını bekliyoruz
+    tokio::select! {
+        _ = graceful.shutdown() => {},
+        _ = tokio::time::sleep(GRACEFUL_SHUTDOWN_TIMEOUT) => {
+            warn!("Bazı bağlantılar {} saniye içinde kapanmadı, bekleme sonlandırılıyor", GRACEFUL_SHUTDOWN_TIMEOUT.as_secs());
+        },
+    }
+
+    Ok(())
+}
+
+// Sertifika ve anahtar PEM formatında okunuyor, Let's Encrypt'in fullchain.pem ve privkey.pem dosyaları doğrudan kullanılabilir
+pub(crate) fn tls_acceptor(tls_config: &TlsConfig, http2: bool) -> Result<TlsAcceptor, Error> {
+    let certs = CertificateDer::pem_file_iter(&tls_config.cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .with_context(|| format!("TLS sertifikası okunamadı: {}", tls_config.cert_path))?;
+    let key = PrivateKeyDer::from_pem_file(&tls_config.key_path)
+        .with_context(|| format!("TLS anahtarı okunamadı: {}", tls_config.key_path))?;
+
+    let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .context("TLS sertifikası ve anahtarı uyumsuz")?;
+    // HTTP/2'yi de ALPN ile sunuyoruz, hyper bağlantıda hangisinin seçildiğini kendisi anlıyor
+    config.alpn_protocols = if http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+// Düz HTTP portuna gelen istekleri aynı path ile HTTPS portuna kalıcı olarak yönlendiriyoruz
+pub(crate) fn https_redirect_router(https_port: u16) -> Router {
+    Router::new()
+        .fallback(redirect_to_https)
+        .with_state(https_port)
+}
+
+async fn redirect_to_https(State(https_port): State<u16>, request: Request) -> Response {
+    let Some(host) = request
+        .headers()
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Authority::from_str(value).ok())
+    else {
+        return APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidHostHeader,
+            "Host header'ı eksik veya geçersiz",
+        )
+        .into_response();
+    };
+
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+    Redirect::permanent(&https_url(host.host(), https_port, path)).into_response()
+}
+
+fn https_url(host: &str, https_port: u16, path: &str) -> String {
+    if https_port == 443 {
+        format!("https://{}{}", host, path)
+    } else {
+        format!("https://{}:{}{}", host, https_port, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_https_url() {
+        assert_eq!(
+            https_url("api.besinveri.com", 443, "/api/food/muz?x=1"),
+            "https://api.besinveri.com/api/food/muz?x=1"
+        );
+        assert_eq!(https_url("localhost", 8099, "/"), "https://localhost:8099/");
+        // Host header'ındaki port Authority::host ile atılıyor, IPv6 köşeli parantezleri korunuyor
+        let authority = Authority::from_str("[::1]:80").unwrap();
+        assert_eq!(
+            https_url(authority.host(), 8443, "/health"),
+            "https://[::1]:8443/health"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::debug;
+
+use crate::{
+    SharedState,
+    api::{
+        error::{APIError, ErrorCode},
+        rate_limit::{API_KEY_HEADER, find_api_key},
+    },
+};
+
+pub(crate) const SIGNATURE_HEADER: &str = "x-signature";
+pub(crate) const TIMESTAMP_HEADER: &str = "x-timestamp";
+
+// Config'de secret tanımlanmış API anahtarları için her isteğin imzalı olmasını istiyoruz.
+// İmza, anahtarın secret'ı ile "METHOD\nPATH?QUERY\nTIMESTAMP" metninin HMAC-SHA256'sı, hex olarak X-Signature header'ında gönderiliyor.
+// Anahtar sızsa bile secret olmadan istek üretilemiyor, kullanılmış imzaları da saklayarak aynı isteğin tekrar gönderilmesini engelliyoruz
+pub(crate) async fn signature_middleware(
+    State(shared_state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, APIError> {
+    let Some(key) = header_value(&request, API_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+
+    // Bilinmeyen anahtarları rate limiter zaten reddediyor, secret'ı olmayan anahtarlar da imzasız kullanılabiliyor
+    let (name, secret, max_clock_skew) = {
+        let config_guard = shared_state.config.load();
+        let api_key = find_api_key(&config_guard.api_keys, &key)
+            .and_then(|api_key| Some((api_key.name.clone(), api_key.secret.clone()?)));
+        match api_key {
+            Some((name, secret)) => (name, secret, config_guard.signing.max_clock_skew_seconds),
+            None => {
+                drop(config_guard);
+                return Ok(next.run(request).await);
+            }
+        }
+    };
+
+    let (Some(timestamp), Some(signature)) = (
+        header_value(&request, TIMESTAMP_HEADER).and_then(|value| value.parse::<i64>().ok()),
+        header_value(&request, SIGNATURE_HEADER),
+    ) else {
+        return Err(unauthorized(
+            ErrorCode::SignatureRequired,
+            "Bu API anahtarı için X-Timestamp ve X-Signature header'ları gerekli",
+        ));
+    };
+
+    // Saat farkı toleransı dışındaki istekler eski bir isteğin tekrarı olabilir
+    if chrono::Utc::now().timestamp().abs_diff(timestamp) > max_clock_skew {
+        return Err(unauthorized(
+            ErrorCode::SignatureExpired,
+            "İstek zaman damgası geçersiz veya süresi dolmuş",
+        ));
+    }
+
+    // Nest edilmiş router'da path'in başındaki API prefix'i siliniyor, istemcinin imzaladığı tam path'i kullanmalıyız
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| &uri.0)
+        .unwrap_or(request.uri())
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or("/");
+
+    if !verify_signature(
+        &secret,
+        request.method().as_str(),
+        path,
+        timestamp,
+        &signature,
+    ) {
+        debug!("Geçersiz imza ile istek reddedildi: {}", name);
+        return Err(unauthorized(
+            ErrorCode::SignatureInvalid,
+            "İstek imzası geçersiz",
+        ));
+    }
+
+    // Entry API ile kontrol ve ekleme tek adımda yapılıyor, aynı imzayla eşzamanlı gelen iki istekten yalnızca biri geçebiliyor
+    let is_fresh = shared_state
+        .used_signatures
+        .entry(format!("{}:{}", name, signature.to_lowercase()))
+        .or_insert(())
+        .await
+        .is_fresh();
+    if !is_fresh {
+        debug!("Tekrar gönderilen imzalı istek reddedildi: {}", name);
+        return Err(unauthorized(
+            ErrorCode::SignatureReused,
+            "Bu istek imzası daha önce kullanıldı",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+fn verify_signature(
+    secret: &str,
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    signature: &str,
+) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(format!("{}\n{}\n{}", method, path, timestamp).as_bytes());
+    // verify_slice karşılaştırmayı sabit zamanda yapıyor, zamanlama saldırılarına karşı
+    mac.verify_slice(&signature).is_ok()
+}
+
+// Await noktalarında request'e referans tutmamak için header'ları kopyalıyoruz, aksi halde future Send olmuyor
+fn header_value(request: &Request<Body>, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_owned())
+}
+
+fn unauthorized(code: ErrorCode, message: &str) -> APIError {
+    APIError::new(StatusCode::UNAUTHORIZED, code, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, message: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(message.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        let signature = sign("gizli", "GET\n/api/foods/search?q=elma\n1700000000");
+
+        assert!(verify_signature(
+            "gizli",
+            "GET",
+            "/api/foods/search?q=elma",
+            1700000000,
+            &signature
+        ));
+        assert!(verify_signature(
+            "gizli",
+            "GET",
+            "/api/foods/search?q=elma",
+            1700000000,
+            &signature.to_uppercase()
+        ));
+        // Secret, path veya zaman damgası değişirse imza tutmamalı
+        assert!(!verify_signature(
+            "başka",
+            "GET",
+            "/api/foods/search?q=elma",
+            1700000000,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "gizli",
+            "GET",
+            "/api/foods/search?q=armut",
+            1700000000,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "gizli",
+            "GET",
+            "/api/foods/search?q=elma",
+            1700000001,
+            &signature
+        ));
+        assert!(!verify_signature(
+            "gizli",
+            "GET",
+            "/api/foods/search?q=elma",
+            1700000000,
+            "hex-değil"
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::Error;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::sync::RwLock;
+
+use crate::{api::database, core::str};
+
+// "Bunu mu demek istediniz?" önerileri her istekte veritabanına gitmesin diye onaylanmış yemeklerin slug ve açıklamaları bellekte tutuluyor.
+// Yemekler sadece açılışta içe aktarılıyor, veritabanında elle değişiklik yapıldığında /admin/cache/purge ile yeniden yükleniyor
+#[derive(Default)]
+pub(crate) struct SuggestionCorpus {
+    entries: RwLock<Vec<CorpusEntry>>,
+}
+
+struct CorpusEntry {
+    slug: String,
+    description: String,
+    // to_search_key ile normalize edilmiş açıklama ve kelimeleri boşlukla ayrılmış slug
+    description_key: String,
+    slug_key: String,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct Suggestion {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    // Sorgu ile açıklama arasındaki harf farkı
+    pub(crate) distance: usize,
+}
+
+impl SuggestionCorpus {
+    pub(crate) async fn reload(&self, pool: &SqlitePool) -> Result<usize, Error> {
+        let entries: Vec<CorpusEntry> = database::select_verified_food_names(pool)
+            .await?
+            .into_iter()
+            .map(|(slug, description)| CorpusEntry {
+                slug_key: slug.replace('-', " "),
+                description_key: str::to_search_key(&description),
+                slug,
+                description,
+            })
+            .collect();
+        let loaded = entries.len();
+        *self.entries.write().await = entries;
+        Ok(loaded)
+    }
+
+    // Sorguya en fazla max_distance harf farkıyla uyan yemekler, en yakın önce; uzaklığı aynı olanlardan kısa açıklamalı olan önce.
+    // Bulanık aramadaki gibi kısa sorgularda her şey eşleşmesin diye sorgunun her 3 harfi için en fazla 1 fark kabul ediliyor
+    pub(crate) async fn suggest(
+        &self,
+        query: &str,
+        max_distance: usize,
+        limit: usize,
+    ) -> Vec<Suggestion> {
+        let query = str::to_search_key(query);
+        let max_distance = max_distance.min(query.chars().count() / 3);
+        if max_distance == 0 {
+            return Vec::new();
+        }
+
+        let entries = self.entries.read().await;
+        let mut suggestions: Vec<Suggestion> = entries
+            .iter()
+            .filter_map(|entry| {
+                let distance = str::fuzzy_distance(&query, &entry.description_key)
+                    .min(str::fuzzy_distance(&query, &entry.slug_key));
+                (distance <= max_distance).then(|| Suggestion {
+                    slug: entry.slug.clone(),
+                    description: entry.description.clone(),
+                    distance,
+                })
+            })
+            .collect();
+        suggestions.sort_by_key(|suggestion| {
+            (suggestion.distance, suggestion.description.chars().count())
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn corpus(names: &[(&str, &str)]) -> SuggestionCorpus {
+        let corpus = SuggestionCorpus::default();
+        *corpus.entries.write().await = names
+            .iter()
+            .map(|(slug, description)| CorpusEntry {
+                slug: (*slug).to_owned(),
+                description: (*description).to_owned(),
+                description_key: str::to_search_key(description),
+                slug_key: slug.replace('-', " "),
+            })
+            .collect();
+        corpus
+    }
+
+    #[tokio::test]
+    async fn test_suggest() {
+        let corpus = corpus(&[
+            ("portakal-suyu", "Portakal Suyu"),
+            ("portakal", "Portakal"),
+            ("tavuk-gogsu-cig", "Tavuk Göğsü (Çiğ)"),
+            ("muz", "Muz"),
+        ])
+        .await;
+
+        let slugs = |suggestions: Vec<Suggestion>| -> Vec<String> {
+            suggestions.into_iter().map(|s| s.slug).collect()
+        };
+        // Aynı uzaklıkta kısa açıklama önce
+        assert_eq!(
+            slugs(corpus.suggest("protakal", 2, 5).await),
+            vec!["portakal", "portakal-suyu"]
+        );
+        assert_eq!(
+            slugs(corpus.suggest("protakal", 2, 1).await),
+            vec!["portakal"]
+        );
+        // Türkçe karakterler ve büyük/küçük harf fark sayılmıyor
+        assert_eq!(
+            corpus.suggest("TAVUK GÖSÜ", 2, 5).await,
+            vec![Suggestion {
+                slug: "tavuk-gogsu-cig".to_owned(),
+                description: "Tavuk Göğsü (Çiğ)".to_owned(),
+                distance: 1,
+            }]
+        );
+        // Kısa sorgularda öneri yok, yoksa "mz" her şeye benzer
+        assert!(corpus.suggest("mz", 2, 5).await.is_empty());
+        assert!(corpus.suggest("karnabahar", 2, 5).await.is_empty());
+    }
+}
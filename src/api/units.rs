@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+
+use axum::{Json, extract::Query, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    api::error::{APIError, ErrorCode},
+    core::{
+        food::Food,
+        nutrition::Nutrients,
+        units::{self, Unit},
+    },
+};
+
+#[derive(Deserialize)]
+pub(crate) struct ConvertParams {
+    nutrient: Option<String>,
+    value: Option<f64>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Conversion {
+    nutrient: String,
+    value: f64,
+    from: Unit,
+    to: Unit,
+    result: f64,
+}
+
+// Yemek yanıtına eklenen, istenen birimdeki değer
+#[derive(Serialize, Debug, PartialEq)]
+pub(crate) struct ConvertedValue {
+    value: f64,
+    unit: Unit,
+}
+
+// Etiketlerde A ve D vitaminleri hâlâ çoğunlukla IU ile yazılıyor, istemciler dönüşüm katsayılarını kendileri tutmasın
+pub(crate) async fn convert(
+    Query(params): Query<ConvertParams>,
+) -> Result<Json<Conversion>, APIError> {
+    let invalid = |message: &str| {
+        APIError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidParameters,
+            message,
+        )
+    };
+    let (Some(nutrient), Some(value), Some(from), Some(to)) =
+        (params.nutrient, params.value, params.from, params.to)
+    else {
+        return Err(invalid("nutrient, value, from ve to parametreleri gerekli"));
+    };
+    if !value.is_finite() || value < 0.0 {
+        return Err(invalid("value negatif olmayan bir sayı olmalı"));
+    }
+    let (Some(from), Some(to)) = (Unit::parse(&from), Unit::parse(&to)) else {
+        return Err(invalid("Birim g, mg, mcg, iu, rae, kcal veya kj olmalı"));
+    };
+
+    let nutrient = nutrient.trim().to_owned();
+    let result = units::convert(&nutrient, value, from, to).map_err(|e| invalid(&e))?;
+    Ok(Json(Conversion {
+        nutrient,
+        value,
+        from,
+        to,
+        result,
+    }))
+}
+
+// Yemeğin değerleri her zaman veritabanındaki birimleriyle dönüyor, istenen birimler ayrıca ekleniyor
+pub(crate) fn convert_food(
+    food: &Food,
+    requests: &[(&'static str, Unit)],
+) -> BTreeMap<&'static str, ConvertedValue> {
+    let values = Nutrients::of(food, 100.0).values();
+    requests
+        .iter()
+        .filter_map(|(nutrient, unit)| {
+            let (_, value) = values.iter().find(|(name, _)| name == nutrient)?;
+            let stored = units::stored_unit(nutrient)?;
+            let value = units::convert(nutrient, *value, stored, *unit).ok()?;
+            Some((*nutrient, ConvertedValue { value, unit: *unit }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_food() {
+        let food = Food {
+            energy: 47.0,
+            vitamin_a: 0.01,
+            vitamin_d: 1.0,
+            ..Default::default()
+        };
+        let requests = units::parse_unit_requests("vitamin_a:iu,vitamin_d:iu,energy:kj").unwrap();
+        let converted = convert_food(&food, &requests);
+        assert_eq!(
+            converted["vitamin_a"],
+            ConvertedValue {
+                value: 33.333333,
+                unit: Unit::Iu,
+            }
+        );
+        assert_eq!(converted["vitamin_d"].value, 40.0);
+        assert_eq!(converted["energy"].value, 196.648);
+    }
+}
@@ -0,0 +1,253 @@
+// benches/ altındaki Criterion ölçümlerinin ve yük testinin (src/bin/loadtest.rs) kullandığı, ölçülen fonksiyonları saran
+// yardımcılar. Hazırlık (yemek listesi, veritabanı, router) ölçümün dışında kalsın diye ayrı yapılıyor; dönen değerler
+// black_box'a verilebilsin diye basit tipler
+use std::sync::atomic::Ordering;
+
+use anyhow::Error;
+use axum::{
+    Json, Router,
+    body::{Body, to_bytes},
+    http::Request,
+    middleware,
+    routing::get,
+};
+use sqlx::SqlitePool;
+use tower::ServiceExt;
+
+use crate::{
+    SharedState,
+    api::{cache, database, foods, search_index::IndexEntry},
+    core::{
+        browse::{BrowseFilter, BrowseSort, NutrientRange},
+        config::{self, Config, SearchRanking},
+        food::Food,
+        query::SearchQuery,
+        str::{to_lower_en_kebab_case, to_search_key},
+    },
+};
+
+const WORDS: [&str; 12] = [
+    "Elma", "Armut", "Karpuz", "Tavuk", "Süt", "Peynir", "Ekmek", "Mercimek", "Domates", "Yoğurt",
+    "Şeftali", "Kıyma",
+];
+const TAGS: [&str; 6] = ["meyve", "sebze", "et", "süt ürünü", "tahıl", "baklagil"];
+
+// Açıklamaları ve etiketleri birbirinden farklı, aramalarda kısmen eşleşen yemekler
+fn generate_foods(count: usize) -> Vec<Food> {
+    (0..count)
+        .map(|i| {
+            let description = format!(
+                "{} {} {}",
+                WORDS[i % WORDS.len()],
+                WORDS[(i / WORDS.len()) % WORDS.len()],
+                i
+            );
+            Food {
+                source: "Benchmark".to_owned(),
+                tags: vec![
+                    TAGS[i % TAGS.len()].to_owned(),
+                    TAGS[(i / TAGS.len()) % TAGS.len()].to_owned(),
+                ],
+                energy: 50.0 + (i % 500) as f64,
+                protein: (i % 30) as f64,
+                fat: (i % 20) as f64,
+                sugar: (i % 40) as f64,
+                ..database::test_food(&description, true)
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+pub struct FoodList(Vec<Food>);
+
+impl FoodList {
+    pub fn generate(count: usize) -> Self {
+        Self(generate_foods(count))
+    }
+}
+
+// trigram false ise konuma göre sıralama (SearchRanking::Positional)
+pub async fn sort_foods_by_query(foods: &mut FoodList, query: &str, trigram: bool) {
+    let ranking = if trigram {
+        SearchRanking::Trigram
+    } else {
+        SearchRanking::Positional
+    };
+    foods::sort_foods_by_query(&mut foods.0, query, ranking).await;
+}
+
+// Arama indeksindeki satırlar, FoodList'teki yemeklerle aynı adlar ve etiketlerle
+pub struct IndexList(Vec<IndexEntry>);
+
+impl IndexList {
+    pub fn generate(count: usize) -> Self {
+        Self(
+            generate_foods(count)
+                .into_iter()
+                .zip(0..)
+                .map(|(food, id)| IndexEntry {
+                    id,
+                    slug: to_lower_en_kebab_case(&food.description),
+                    description: to_search_key(&food.description),
+                    aliases: Vec::new(),
+                    tags: food.tags.iter().map(|tag| to_search_key(tag)).collect(),
+                    source: to_search_key(&food.source),
+                    allergens: Vec::new(),
+                    completeness: 1.0,
+                    branded: false,
+                    diet_flags: Vec::new(),
+                })
+                .collect(),
+        )
+    }
+}
+
+// Aramada indeksten seçilen adaylar gibi satırların referansları sıralanıyor, satırlar kopyalanmıyor. En üstteki satırın id'si
+pub async fn sort_index_by_query(entries: &IndexList, query: &str, trigram: bool) -> i64 {
+    let ranking = if trigram {
+        SearchRanking::Trigram
+    } else {
+        SearchRanking::Positional
+    };
+    let mut candidates: Vec<&IndexEntry> = entries.0.iter().collect();
+    foods::sort_foods_by_query(&mut candidates, query, ranking).await;
+    candidates.first().map_or(0, |entry| entry.id)
+}
+
+pub fn slug(description: &str) -> String {
+    to_lower_en_kebab_case(description)
+}
+
+// Migration'ları uygulanmış, bellekte tutulan ve verilen sayıda yemekle doldurulmuş bir veritabanı
+pub struct SearchDatabase(SqlitePool);
+
+impl SearchDatabase {
+    pub async fn seed(count: usize) -> Result<Self, Error> {
+        let pool = database::memory_pool().await?;
+        for food in generate_foods(count) {
+            database::insert_food(&pool, food).await?;
+        }
+        Ok(Self(pool))
+    }
+
+    // /foods/search?q= aramasının veritabanı kısmı, bulunan aday sayısı
+    pub async fn search_description(&self, query: &str) -> Result<usize, Error> {
+        let terms = SearchQuery::parse(query).terms;
+        Ok(
+            database::search_foods_by_description_wild(&self.0, &terms, &[], 0.0, false, &[], 200)
+                .await?
+                .len(),
+        )
+    }
+
+    // /foods/search?tag= aramasının ilk sayfası, toplam sonuç sayısı
+    pub async fn search_tags(&self, tags: &[&str], match_all: bool) -> Result<u64, Error> {
+        let (_, total) = database::search_foods_by_tag_wild(
+            &self.0,
+            tags,
+            match_all,
+            &[],
+            0.0,
+            false,
+            &[],
+            10,
+            0,
+        )
+        .await?;
+        Ok(total)
+    }
+
+    // q verilmeyen, aralık filtreli ve besin değerine göre sıralı göz atma
+    pub async fn browse(&self) -> Result<u64, Error> {
+        let filter = BrowseFilter {
+            ranges: vec![NutrientRange {
+                nutrient: "protein",
+                min: Some(10.0),
+                max: None,
+            }],
+            ..Default::default()
+        };
+        let sort = BrowseSort {
+            column: "energy",
+            descending: true,
+        };
+        let (_, total) = database::browse_foods(&self.0, &filter, sort, 10, 0).await?;
+        Ok(total)
+    }
+}
+
+// Aynı JSON'u dönen iki router, biri önbellek middleware'i ile. Aradaki fark middleware'in isabet ve ıska maliyeti
+pub struct CacheRouters {
+    cached: Router,
+    uncached: Router,
+}
+
+impl CacheRouters {
+    // body_size baytlık bir JSON dönüyorlar
+    pub async fn new(body_size: usize) -> Result<Self, Error> {
+        let pool = database::memory_pool().await?;
+        let shared_state = SharedState::with_pool(config::get_default_config(), pool);
+        let body = "a".repeat(body_size.saturating_sub(2));
+        let uncached = Router::new().route(
+            "/foods/list",
+            get(move || {
+                let body = body.clone();
+                async move { Json(body) }
+            }),
+        );
+        let cached = uncached.clone().route_layer(middleware::from_fn_with_state(
+            shared_state,
+            cache::cache_middleware,
+        ));
+        Ok(Self { cached, uncached })
+    }
+
+    // Yanıt gövdesinin uzunluğu. İlk istekten sonra önbellekli router'ın yanıtları önbellekten geliyor
+    pub async fn request(&self, cached: bool, uri: &str) -> Result<usize, Error> {
+        let router = if cached { &self.cached } else { &self.uncached };
+        let response = router
+            .clone()
+            .oneshot(Request::get(uri).body(Body::empty())?)
+            .await?;
+        Ok(to_bytes(response.into_body(), usize::MAX).await?.len())
+    }
+}
+
+// Yük testinin istek gönderdiği, tüm middleware'leri kurulu API router'ı ve veritabanındaki yemeklerin slug'ları
+pub struct LoadTestApp {
+    pub router: Router,
+    pub slugs: Vec<String>,
+}
+
+impl LoadTestApp {
+    // Veritabanı bellekte, verilen sayıda yemekle dolduruluyor. cache false ise önbellek kapasitesi 0, her istek veritabanına gidiyor
+    pub async fn new(food_count: usize, cache: bool) -> Result<Self, Error> {
+        let mut config = config::get_default_config();
+        if !cache {
+            config.core.cache_capacity_bytes = 0;
+        }
+        let pool = SearchDatabase::seed(food_count).await?.0;
+        Ok(Self {
+            slugs: database::select_all_foods_slugs(&pool).await?,
+            router: crate::api_router(app_state(config, pool).await?),
+        })
+    }
+}
+
+// Yemekleri eklenmiş veritabanıyla sunucunun açılıştaki durumu: puanlar ve diyet bayrakları hesaplanıyor, öneri ve arama indeksleri
+// yükleniyor. Yük testi ve uçtan uca testler (TestApp) bununla kuruluyor; rate limiter süreç genelinde bir kez kuruluyor, istekler
+// limite takılmasın diye genel kuralın limiti en yüksek değerde
+pub(crate) async fn app_state(mut config: Config, pool: SqlitePool) -> Result<SharedState, Error> {
+    database::refresh_diet_flags(&pool, &config.diet_flags).await?;
+    database::refresh_food_scores(&pool).await?;
+
+    config.rate_limit.default.max_requests = u32::MAX;
+    crate::init_rate_limiter_once(&config.rate_limit).await;
+
+    let state = SharedState::with_pool(config, pool.clone());
+    state.suggestions.reload(&pool).await?;
+    state.search_index.reload(&pool).await?;
+    state.warmed_up.store(true, Ordering::Relaxed);
+    Ok(state)
+}
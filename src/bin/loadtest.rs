@@ -0,0 +1,296 @@
+// Router'ı süreç içinde kurup gerçekçi istek karışımlarıyla yükleyen yük testi: cargo run --release --bin loadtest -- --mix search
+// İstekler ağa çıkmadan doğrudan router'a veriliyor, ölçülen süre middleware'ler, handler'lar ve veritabanı. Veritabanı
+// Mutex'inin kaldırılması gibi değişikliklerin etkisini sayılarla görmek için değişiklikten önce ve sonra aynı parametrelerle
+// çalıştırılıp sonuçlar karşılaştırılmalı
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Error;
+use axum::{body::Body, extract::ConnectInfo, http::Request};
+use besinveri_api::bench::LoadTestApp;
+use clap::{Parser, ValueEnum};
+use tower::ServiceExt;
+
+/// BesinVeri API yük testi, sonuçta saniyedeki istek sayısı ve gecikme yüzdelikleri raporlanır
+#[derive(Parser)]
+struct Args {
+    /// İstek karışımı
+    #[arg(long, value_enum, default_value = "search")]
+    mix: Mix,
+    /// Aynı anda istek gönderen istemci sayısı
+    #[arg(long, default_value_t = 32)]
+    concurrency: usize,
+    /// Ölçüm süresi, saniye
+    #[arg(long, default_value_t = 10)]
+    duration: u64,
+    /// Ölçümden önce sonuçlara katılmadan gönderilen isteklerin süresi, saniye. Önbellek ve hazır ifadeler ısınıyor
+    #[arg(long, default_value_t = 2)]
+    warmup: u64,
+    /// Bellekteki veritabanına eklenen yemek sayısı
+    #[arg(long, default_value_t = 2000)]
+    foods: usize,
+    /// Yanıt önbelleğini kapatır, her istek veritabanına gider
+    #[arg(long)]
+    no_cache: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mix {
+    /// Çoğunlukla açıklama ve etiket aramaları, yanında yemek detayları ve öneriler
+    Search,
+    /// Çoğunlukla yemek ve etiket listeleri ile göz atma
+    List,
+}
+
+const QUERIES: [&str; 8] = [
+    "elma",
+    "sut",
+    "tavuk",
+    "peynir ekmek",
+    "mercimek",
+    "yogurt",
+    "seftali",
+    "kiyma",
+];
+const TAGS: [&str; 4] = ["meyve", "sebze", "et", "baklagil"];
+
+impl Mix {
+    // (ağırlık, istek türü)
+    fn weights(self) -> &'static [(u32, Kind)] {
+        match self {
+            Mix::Search => &[
+                (50, Kind::Search),
+                (15, Kind::TagSearch),
+                (20, Kind::Food),
+                (10, Kind::Suggest),
+                (5, Kind::List),
+            ],
+            Mix::List => &[
+                (35, Kind::List),
+                (20, Kind::Tags),
+                (25, Kind::Browse),
+                (10, Kind::Food),
+                (10, Kind::Search),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Search,
+    TagSearch,
+    Browse,
+    Food,
+    Suggest,
+    List,
+    Tags,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Search => "/foods/search?q=",
+            Kind::TagSearch => "/foods/search?tag=",
+            Kind::Browse => "/foods/search (göz atma)",
+            Kind::Food => "/food/{slug}",
+            Kind::Suggest => "/foods/suggest",
+            Kind::List => "/foods/list",
+            Kind::Tags => "/tags",
+        }
+    }
+}
+
+// Tekrarlanabilir sonuçlar için her istemcinin kendi tohumuyla basit bir xorshift üreteci
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[(self.next() % items.len() as u64) as usize]
+    }
+}
+
+fn request_uri(kind: Kind, rng: &mut Rng, slugs: &[String]) -> String {
+    match kind {
+        Kind::Search => format!(
+            "/foods/search?q={}&limit={}",
+            rng.pick(&QUERIES).replace(' ', "+"),
+            rng.next() % 10 + 1
+        ),
+        Kind::TagSearch => format!("/foods/search?tag={}&limit=10", rng.pick(&TAGS)),
+        Kind::Browse => format!(
+            "/foods/search?min_protein={}&sort=energy&order=desc&limit=10",
+            rng.next() % 20
+        ),
+        Kind::Food => format!("/food/{}", rng.pick(slugs)),
+        Kind::Suggest => format!("/foods/suggest?q={}", &rng.pick(&QUERIES)[..3]),
+        Kind::List => "/foods/list".to_owned(),
+        Kind::Tags => "/tags".to_owned(),
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    // İstek türüne göre gecikmeler, mikrosaniye
+    latencies: BTreeMap<&'static str, Vec<u64>>,
+    statuses: BTreeMap<u16, u64>,
+}
+
+impl Stats {
+    fn merge(&mut self, other: Stats) {
+        for (kind, latencies) in other.latencies {
+            self.latencies.entry(kind).or_default().extend(latencies);
+        }
+        for (status, count) in other.statuses {
+            *self.statuses.entry(status).or_default() += count;
+        }
+    }
+}
+
+// Sıralı listede yüzdelik, mikrosaniye
+fn percentile(sorted: &[u64], percent: f64) -> u64 {
+    let index = ((sorted.len() as f64 * percent / 100.0).ceil() as usize).clamp(1, sorted.len());
+    sorted[index - 1]
+}
+
+fn format_latency(micros: u64) -> String {
+    format!("{:.2}ms", micros as f64 / 1000.0)
+}
+
+async fn run_client(
+    app: Arc<LoadTestApp>,
+    mix: Mix,
+    seed: u64,
+    measure_from: Instant,
+    until: Instant,
+) -> Result<Stats, Error> {
+    let weights = mix.weights();
+    let total_weight: u32 = weights.iter().map(|(weight, _)| weight).sum();
+    let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1);
+    // Her istemci ayrı bir IP'den bağlanıyormuş gibi, rate limiter ve erişim kayıtları gerçek trafikteki gibi çalışıyor
+    let client_addr =
+        SocketAddr::from(([10, 0, (seed / 250) as u8, (seed % 250 + 1) as u8], 40000));
+    let mut stats = Stats::default();
+
+    while Instant::now() < until {
+        let mut choice = (rng.next() % total_weight as u64) as u32;
+        let kind = weights
+            .iter()
+            .find(|(weight, _)| {
+                if choice < *weight {
+                    true
+                } else {
+                    choice -= weight;
+                    false
+                }
+            })
+            .map(|(_, kind)| *kind)
+            .expect("Ağırlıkların toplamından küçük bir seçim her zaman bir türe düşüyor");
+        let mut request =
+            Request::get(request_uri(kind, &mut rng, &app.slugs)).body(Body::empty())?;
+        request.extensions_mut().insert(ConnectInfo(client_addr));
+
+        let start = Instant::now();
+        let response = app.router.clone().oneshot(request).await?;
+        let status = response.status().as_u16();
+        // Gövde de ölçüme dahil, önbellekten dönmeyen yanıtlar gövde okunurken oluşturulabiliyor
+        axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let elapsed = start.elapsed();
+
+        if start >= measure_from {
+            stats
+                .latencies
+                .entry(kind.name())
+                .or_default()
+                .push(elapsed.as_micros() as u64);
+            *stats.statuses.entry(status).or_default() += 1;
+        }
+    }
+    Ok(stats)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    if args.concurrency == 0 || args.duration == 0 {
+        anyhow::bail!("concurrency ve duration 0'dan büyük olmalı");
+    }
+
+    println!("{} yemekle veritabanı hazırlanıyor...", args.foods);
+    let app = Arc::new(LoadTestApp::new(args.foods, !args.no_cache).await?);
+
+    let start = Instant::now();
+    let measure_from = start + Duration::from_secs(args.warmup);
+    let until = measure_from + Duration::from_secs(args.duration);
+    println!(
+        "{} istemci, {} sn ısınma + {} sn ölçüm{}",
+        args.concurrency,
+        args.warmup,
+        args.duration,
+        if args.no_cache {
+            ", önbellek kapalı"
+        } else {
+            ""
+        }
+    );
+
+    let clients: Vec<_> = (0..args.concurrency as u64)
+        .map(|seed| tokio::spawn(run_client(app.clone(), args.mix, seed, measure_from, until)))
+        .collect();
+    let mut stats = Stats::default();
+    for client in clients {
+        stats.merge(client.await??);
+    }
+
+    let mut all: Vec<u64> = stats.latencies.values().flatten().copied().collect();
+    if all.is_empty() {
+        anyhow::bail!("Ölçüm süresinde hiç istek tamamlanmadı");
+    }
+    all.sort_unstable();
+    println!(
+        "\n{} istek, {:.0} istek/sn",
+        all.len(),
+        all.len() as f64 / args.duration as f64
+    );
+    println!(
+        "{:<28} {:>8} {:>9} {:>9} {:>9} {:>9}",
+        "", "istek", "p50", "p90", "p99", "max"
+    );
+    let mut rows: Vec<(&str, Vec<u64>)> = stats.latencies.into_iter().collect();
+    for (_, latencies) in &mut rows {
+        latencies.sort_unstable();
+    }
+    rows.push(("toplam", all));
+    for (name, latencies) in &rows {
+        println!(
+            "{:<28} {:>8} {:>9} {:>9} {:>9} {:>9}",
+            name,
+            latencies.len(),
+            format_latency(percentile(latencies, 50.0)),
+            format_latency(percentile(latencies, 90.0)),
+            format_latency(percentile(latencies, 99.0)),
+            format_latency(*latencies.last().expect("Boş türler listeye eklenmiyor")),
+        );
+    }
+    println!(
+        "Durum kodları: {}",
+        stats
+            .statuses
+            .iter()
+            .map(|(status, count)| format!("{}: {}", status, count))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(())
+}
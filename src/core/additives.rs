@@ -0,0 +1,158 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::core::str::to_search_key;
+
+// Bir gıda katkı maddesi, E numarasıyla. Referans liste db/additives/default.json'da, yemeklerin JSON'larında sadece E numarası
+// yazılıyor ("additives": ["E330"]), adı ve sınıfı veritabanından okunurken dolduruluyor
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct Additive {
+    pub(crate) e_number: String,
+    pub(crate) name: String,
+    // Kullanım amacı: "koruyucu", "renklendirici", "emülgatör" gibi, etiketler gibi küçük harfle tutuluyor
+    pub(crate) category: String,
+    pub(crate) notes: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for Additive {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            ENumber(String),
+            Detailed {
+                e_number: String,
+                #[serde(default)]
+                name: String,
+                #[serde(default)]
+                category: String,
+                #[serde(default)]
+                notes: Option<String>,
+            },
+        }
+
+        Ok(match Entry::deserialize(deserializer)? {
+            Entry::ENumber(e_number) => Additive {
+                e_number,
+                name: String::new(),
+                category: String::new(),
+                notes: None,
+            },
+            Entry::Detailed {
+                e_number,
+                name,
+                category,
+                notes,
+            } => Additive {
+                e_number,
+                name,
+                category,
+                notes,
+            },
+        })
+    }
+}
+
+impl Additive {
+    // Referans listeye eklenecek katkı maddesi, E numarası standart yazılışına çevriliyor ve boşluklar kırpılıyor
+    pub(crate) fn normalized(self) -> Result<Self, String> {
+        let e_number = normalize_e_number(&self.e_number)
+            .ok_or_else(|| format!("Geçersiz E numarası: {}", self.e_number))?;
+        let name = self.name.trim().to_owned();
+        if name.is_empty() {
+            return Err(format!("{} katkı maddesinin adı boş olamaz", e_number));
+        }
+        let category = self.category.trim().to_lowercase();
+        if category.is_empty() {
+            return Err(format!("{} katkı maddesinin sınıfı boş olamaz", e_number));
+        }
+        Ok(Additive {
+            e_number,
+            name,
+            category,
+            notes: self
+                .notes
+                .map(|notes| notes.trim().to_owned())
+                .filter(|notes| !notes.is_empty()),
+        })
+    }
+
+    // ?category= filtresi için, etiketlerdeki gibi Türkçe karakter ve büyük/küçük harf duyarsız
+    pub(crate) fn in_category(&self, category: &str) -> bool {
+        to_search_key(&self.category) == to_search_key(category)
+    }
+}
+
+// "e 330", "E-160A", "e160a(ii)" gibi yazılışları "E330", "E160a", "E160a(ii)" yapıyor. E'den sonra 3 veya 4 rakam, isteğe bağlı
+// tek harf ve parantez içinde Romen rakamıyla alt tür olabiliyor, başka bir şey varsa None
+pub(crate) fn normalize_e_number(e_number: &str) -> Option<String> {
+    let compact: String = e_number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let rest = compact.strip_prefix('e')?;
+
+    let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+    if !(3..=4).contains(&digits) {
+        return None;
+    }
+    let (number, mut rest) = rest.split_at(digits);
+    let mut normalized = format!("E{}", number);
+
+    if let Some(letter) = rest.chars().next().filter(char::is_ascii_lowercase) {
+        normalized.push(letter);
+        rest = &rest[1..];
+    }
+    if !rest.is_empty() {
+        let roman = rest.strip_prefix('(')?.strip_suffix(')')?;
+        if !matches!(roman, "i" | "ii" | "iii" | "iv" | "v" | "vi") {
+            return None;
+        }
+        normalized.push_str(&format!("({})", roman));
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_e_number() {
+        assert_eq!(normalize_e_number("E330").as_deref(), Some("E330"));
+        assert_eq!(normalize_e_number(" e 330 ").as_deref(), Some("E330"));
+        assert_eq!(normalize_e_number("E-160A").as_deref(), Some("E160a"));
+        assert_eq!(normalize_e_number("e1422").as_deref(), Some("E1422"));
+        assert_eq!(
+            normalize_e_number("E160a (ii)").as_deref(),
+            Some("E160a(ii)")
+        );
+        assert_eq!(normalize_e_number("330"), None);
+        assert_eq!(normalize_e_number("E33"), None);
+        assert_eq!(normalize_e_number("E33000"), None);
+        assert_eq!(normalize_e_number("E330x1"), None);
+        assert_eq!(normalize_e_number("E160a(x)"), None);
+    }
+
+    #[test]
+    fn test_deserialize_and_normalize() {
+        let additives: Vec<Additive> = serde_json::from_str(
+            r#"["e 330", {"e_number": "E211", "name": " Sodyum benzoat ", "category": "Koruyucu", "notes": ""}]"#,
+        )
+        .unwrap();
+        assert_eq!(additives[0].e_number, "e 330");
+        assert!(additives[0].clone().normalized().is_err());
+
+        let benzoate = additives[1].clone().normalized().unwrap();
+        assert_eq!(
+            benzoate,
+            Additive {
+                e_number: "E211".to_owned(),
+                name: "Sodyum benzoat".to_owned(),
+                category: "koruyucu".to_owned(),
+                notes: None,
+            }
+        );
+        assert!(benzoate.in_category("KORUYUCU"));
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::BTreeMap;
+
+use crate::core::{nova::NOVA_GROUPS, nutri_score::Grade};
+
+// foods tablosunun 100 gram başına besin değeri kolonları, Food'daki alanlarla aynı isimde. Sıralama kolon adını SQL'e doğrudan
+// yazdığı için sadece bu listedeki isimler kabul ediliyor; aralık filtreleri için api::database'deki browse_filter_sql'de de her
+// kolonun bir satırı olmalı
+pub(crate) const NUTRIENTS: [&str; 33] = [
+    "glycemic_index",
+    "energy",
+    "carbohydrate",
+    "protein",
+    "fat",
+    "saturated_fat",
+    "trans_fat",
+    "sugar",
+    "added_sugar",
+    "polyols",
+    "fiber",
+    "cholesterol",
+    "sodium",
+    "potassium",
+    "water",
+    "iron",
+    "magnesium",
+    "calcium",
+    "zinc",
+    "vitamin_a",
+    "vitamin_b6",
+    "vitamin_b12",
+    "vitamin_c",
+    "vitamin_d",
+    "vitamin_e",
+    "vitamin_k",
+    "omega_3",
+    "omega_6",
+    "folate",
+    "niacin",
+    "phosphorus",
+    "selenium",
+    "copper",
+];
+
+// min_protein=20&max_energy=200 gibi parametrelerden gelen, sınırları dahil bir aralık
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct NutrientRange {
+    pub(crate) nutrient: &'static str,
+    pub(crate) min: Option<f64>,
+    pub(crate) max: Option<f64>,
+}
+
+// Sorgu parametreleri arasındaki min_<besin> ve max_<besin> parametrelerini aralıklara çeviriyor, diğer parametreleri atlıyor.
+// Bilinmeyen besinler ve sayı olmayan değerler hata, istemci yazım hatası yaptığında filtre sessizce yok sayılmasın
+pub(crate) fn parse_nutrient_ranges(
+    params: &BTreeMap<String, String>,
+) -> Result<Vec<NutrientRange>, String> {
+    let mut ranges: Vec<NutrientRange> = Vec::new();
+    for (key, value) in params {
+        let (name, is_min) = match (key.strip_prefix("min_"), key.strip_prefix("max_")) {
+            (Some(name), _) => (name, true),
+            (_, Some(name)) => (name, false),
+            _ => continue,
+        };
+        // Besin değeri değil, aramada da geçerli olduğu için SearchParams'ta ayrıca okunuyor
+        if key == "min_completeness" {
+            continue;
+        }
+        let Some(nutrient) = NUTRIENTS.iter().find(|nutrient| **nutrient == name) else {
+            return Err(format!("Bilinmeyen besin değeri: {}", name));
+        };
+        let bound = match value.trim().parse::<f64>() {
+            Ok(bound) if bound.is_finite() && bound >= 0.0 => bound,
+            _ => return Err(format!("{} negatif olmayan bir sayı olmalı", key)),
+        };
+
+        let range = match ranges.iter_mut().find(|range| range.nutrient == *nutrient) {
+            Some(range) => range,
+            None => {
+                ranges.push(NutrientRange {
+                    nutrient,
+                    min: None,
+                    max: None,
+                });
+                ranges.last_mut().expect("Aralık az önce eklendi")
+            }
+        };
+        if is_min {
+            range.min = Some(bound);
+        } else {
+            range.max = Some(bound);
+        }
+        if let (Some(min), Some(max)) = (range.min, range.max)
+            && min > max
+        {
+            return Err(format!("min_{} max_{}'dan büyük olamaz", name, name));
+        }
+    }
+    Ok(ranges)
+}
+
+// Hazır beslenme filtreleri. Veritabanında diyet bilgisi tutulmuyor, besin değerlerinden ve alerjenlerden çıkarılıyor.
+// Eşikler 100 gram için; yağ, şeker, sodyum ve lif AB beslenme beyanı (1924/2006) eşikleri. AB protein için enerjinin %20'sini
+// şart koşuyor, tek bir kolonla ifade edilebilsin diye sabit bir eşik kullanıyoruz
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Diet {
+    LowFat,
+    LowSugar,
+    LowSodium,
+    HighProtein,
+    HighFiber,
+    GlutenFree,
+}
+
+impl Diet {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "low_fat" => Some(Diet::LowFat),
+            "low_sugar" => Some(Diet::LowSugar),
+            "low_sodium" => Some(Diet::LowSodium),
+            "high_protein" => Some(Diet::HighProtein),
+            "high_fiber" => Some(Diet::HighFiber),
+            "gluten_free" => Some(Diet::GlutenFree),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn nutrient_range(self) -> Option<NutrientRange> {
+        let (nutrient, min, max) = match self {
+            Diet::LowFat => ("fat", None, Some(3.0)),
+            Diet::LowSugar => ("sugar", None, Some(5.0)),
+            Diet::LowSodium => ("sodium", None, Some(120.0)),
+            Diet::HighProtein => ("protein", Some(12.0), None),
+            Diet::HighFiber => ("fiber", Some(6.0), None),
+            Diet::GlutenFree => return None,
+        };
+        Some(NutrientRange { nutrient, min, max })
+    }
+
+    pub(crate) fn excluded_allergen(self) -> Option<&'static str> {
+        match self {
+            Diet::GlutenFree => Some("gluten"),
+            _ => None,
+        }
+    }
+}
+
+// Göz atma sonuçlarının sıralaması, sort=protein&order=desc. Varsayılan açıklamaya göre alfabetik
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BrowseSort {
+    // Türkçe karakterlerden arındırılmış açıklama, tamlık veya NUTRIENTS'taki bir kolon
+    pub(crate) column: &'static str,
+    pub(crate) descending: bool,
+}
+
+impl Default for BrowseSort {
+    fn default() -> Self {
+        Self {
+            column: "search_description",
+            descending: false,
+        }
+    }
+}
+
+impl BrowseSort {
+    pub(crate) fn parse(sort: Option<&str>, order: Option<&str>) -> Result<Self, String> {
+        let column = match sort.map(str::trim) {
+            None | Some("description") => "search_description",
+            // En çok ölçülmüş değeri olan yemekler önce gelsin diye, order=desc ile
+            Some("completeness") => "completeness",
+            // Düşük puan daha iyi, order=asc ile en sağlıklılar önce. Sınıfı olmayanlar her iki yönde de sonda
+            Some("nutri_score") => "nutri_score_points",
+            Some("nova_group") => "nova_group",
+            Some(sort) => *NUTRIENTS
+                .iter()
+                .find(|nutrient| **nutrient == sort)
+                .ok_or_else(|| format!("Bu alana göre sıralanamaz: {}", sort))?,
+        };
+        let descending = match order.map(str::trim) {
+            None | Some("asc") => false,
+            Some("desc") => true,
+            Some(order) => return Err(format!("order asc veya desc olmalı: {}", order)),
+        };
+        Ok(Self { column, descending })
+    }
+}
+
+// q verilmeden /foods/search'e gelen göz atma isteğinin filtreleri, yemeğin hepsine uyması gerekiyor
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct BrowseFilter {
+    // Etiketler tam eşleşmeyle aranıyor, to_search_key ile normalize edilmiş
+    pub(crate) tags: Vec<String>,
+    pub(crate) ranges: Vec<NutrientRange>,
+    pub(crate) allergen_free: Vec<String>,
+    // diet_flags=vegan,gluten_free, config'deki kurallardan hesaplanan bayraklar (core::diet_flags)
+    pub(crate) diet_flags: Vec<String>,
+    // nutri_score=A,B ve nova=1,2: yemeğin sınıfı bunlardan biri olmalı, sınıfı olmayanlar dışlanıyor
+    pub(crate) nutri_scores: Vec<Grade>,
+    pub(crate) nova_groups: Vec<u8>,
+    // include_branded=false, tek başına filtre sayılmıyor
+    pub(crate) exclude_branded: bool,
+}
+
+// nutri_score=a,b parametresi, tekrarlar atılıyor
+pub(crate) fn parse_nutri_scores(s: &str) -> Result<Vec<Grade>, String> {
+    let mut grades: Vec<Grade> = Vec::new();
+    for grade in s
+        .split(',')
+        .map(str::trim)
+        .filter(|grade| !grade.is_empty())
+    {
+        let grade = Grade::parse(grade)
+            .ok_or_else(|| format!("Nutri-Score A, B, C, D veya E olmalı: {}", grade))?;
+        if !grades.contains(&grade) {
+            grades.push(grade);
+        }
+    }
+    Ok(grades)
+}
+
+// nova=1,2 parametresi, tekrarlar atılıyor
+pub(crate) fn parse_nova_groups(s: &str) -> Result<Vec<u8>, String> {
+    let mut groups: Vec<u8> = Vec::new();
+    for group in s
+        .split(',')
+        .map(str::trim)
+        .filter(|group| !group.is_empty())
+    {
+        let group = group
+            .parse::<u8>()
+            .ok()
+            .filter(|group| NOVA_GROUPS.contains(group))
+            .ok_or_else(|| format!("NOVA sınıfı 1, 2, 3 veya 4 olmalı: {}", group))?;
+        if !groups.contains(&group) {
+            groups.push(group);
+        }
+    }
+    Ok(groups)
+}
+
+impl BrowseFilter {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.ranges.is_empty()
+            && self.allergen_free.is_empty()
+            && self.diet_flags.is_empty()
+            && self.nutri_scores.is_empty()
+            && self.nova_groups.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_nutrient_ranges() {
+        assert_eq!(
+            parse_nutrient_ranges(&params(&[
+                ("q", "elma"),
+                ("min_completeness", "0.8"),
+                ("min_protein", "20"),
+                ("max_energy", "200.5"),
+                ("max_protein", "30"),
+            ])),
+            Ok(vec![
+                NutrientRange {
+                    nutrient: "energy",
+                    min: None,
+                    max: Some(200.5),
+                },
+                NutrientRange {
+                    nutrient: "protein",
+                    min: Some(20.0),
+                    max: Some(30.0),
+                },
+            ])
+        );
+        assert!(parse_nutrient_ranges(&params(&[("min_kafein", "1")])).is_err());
+        assert!(parse_nutrient_ranges(&params(&[("min_fat", "az")])).is_err());
+        assert!(parse_nutrient_ranges(&params(&[("min_fat", "-1")])).is_err());
+        assert!(parse_nutrient_ranges(&params(&[("min_fat", "5"), ("max_fat", "2")])).is_err());
+    }
+
+    #[test]
+    fn test_parse_diet_and_sort() {
+        assert_eq!(Diet::parse("high_protein"), Some(Diet::HighProtein));
+        assert_eq!(Diet::parse("vegan"), None);
+        assert_eq!(Diet::GlutenFree.excluded_allergen(), Some("gluten"));
+
+        assert_eq!(BrowseSort::parse(None, None), Ok(BrowseSort::default()));
+        assert_eq!(
+            BrowseSort::parse(Some("protein"), Some("desc")),
+            Ok(BrowseSort {
+                column: "protein",
+                descending: true,
+            })
+        );
+        assert_eq!(
+            BrowseSort::parse(Some("completeness"), Some("desc")).map(|sort| sort.column),
+            Ok("completeness")
+        );
+        assert_eq!(
+            BrowseSort::parse(Some("nutri_score"), None).map(|sort| sort.column),
+            Ok("nutri_score_points")
+        );
+        assert!(BrowseSort::parse(Some("slug; DROP TABLE foods"), None).is_err());
+        assert!(BrowseSort::parse(None, Some("yukari")).is_err());
+    }
+
+    #[test]
+    fn test_parse_nutri_scores_and_nova_groups() {
+        assert_eq!(parse_nutri_scores("a, B,a"), Ok(vec![Grade::A, Grade::B]));
+        assert!(parse_nutri_scores("A,F").is_err());
+        assert_eq!(parse_nova_groups("1,2,,1"), Ok(vec![1, 2]));
+        assert!(parse_nova_groups("5").is_err());
+        assert!(parse_nova_groups("bir").is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Serialize;
+
+// Enerji gereksinimi hesapları. Formüller yetişkinler için geliştirildiği için yaş 18'den küçük olamaz, değerler kcal/gün
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Sex {
+    Male,
+    Female,
+}
+
+impl Sex {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "m" | "male" | "e" | "erkek" => Some(Sex::Male),
+            "f" | "female" | "k" | "kadın" => Some(Sex::Female),
+            _ => None,
+        }
+    }
+}
+
+// Fiziksel aktivite düzeyi, bazal metabolizma hızı bu katsayıyla çarpılarak günlük toplam harcama bulunuyor
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ActivityLevel {
+    // Masa başı, egzersiz yok
+    Sedentary,
+    // Haftada 1-3 gün hafif egzersiz
+    Light,
+    // Haftada 3-5 gün orta egzersiz
+    Moderate,
+    // Haftada 6-7 gün yoğun egzersiz
+    Active,
+    // Günde iki antrenman veya ağır bedensel iş
+    VeryActive,
+}
+
+impl ActivityLevel {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "sedentary" => Some(ActivityLevel::Sedentary),
+            "light" => Some(ActivityLevel::Light),
+            "moderate" => Some(ActivityLevel::Moderate),
+            "active" => Some(ActivityLevel::Active),
+            "very_active" => Some(ActivityLevel::VeryActive),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn factor(self) -> f64 {
+        match self {
+            ActivityLevel::Sedentary => 1.2,
+            ActivityLevel::Light => 1.375,
+            ActivityLevel::Moderate => 1.55,
+            ActivityLevel::Active => 1.725,
+            ActivityLevel::VeryActive => 1.9,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Formula {
+    // Mifflin-St Jeor (1990), varsayılan. Güncel çalışmalarda en isabetlisi
+    MifflinStJeor,
+    // Roza ve Shizgal'ın (1984) düzelttiği Harris-Benedict
+    HarrisBenedict,
+    // Katch-McArdle, yağsız vücut kütlesinden hesaplıyor, vücut yağ oranı gerekli
+    KatchMcArdle,
+}
+
+impl Formula {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "mifflin_st_jeor" | "mifflin" => Some(Formula::MifflinStJeor),
+            "harris_benedict" => Some(Formula::HarrisBenedict),
+            "katch_mcardle" => Some(Formula::KatchMcArdle),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Person {
+    pub(crate) age: f64,
+    pub(crate) sex: Sex,
+    // kg
+    pub(crate) weight: f64,
+    // cm
+    pub(crate) height: f64,
+    // Yüzde, sadece Katch-McArdle için
+    pub(crate) body_fat: Option<f64>,
+}
+
+impl Person {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let in_range =
+            |value: f64, min: f64, max: f64| value.is_finite() && (min..=max).contains(&value);
+        if !in_range(self.age, 18.0, 120.0) {
+            return Err("age 18 ile 120 arasında olmalı".to_owned());
+        }
+        if !in_range(self.weight, 20.0, 400.0) {
+            return Err("weight 20 ile 400 kg arasında olmalı".to_owned());
+        }
+        if !in_range(self.height, 100.0, 250.0) {
+            return Err("height 100 ile 250 cm arasında olmalı".to_owned());
+        }
+        if self
+            .body_fat
+            .is_some_and(|body_fat| !in_range(body_fat, 2.0, 70.0))
+        {
+            return Err("body_fat yüzde 2 ile 70 arasında olmalı".to_owned());
+        }
+        Ok(())
+    }
+}
+
+// Bazal metabolizma hızı, kcal/gün
+pub(crate) fn bmr(formula: Formula, person: &Person) -> Result<f64, String> {
+    person.validate()?;
+    let Person {
+        age,
+        sex,
+        weight,
+        height,
+        body_fat,
+    } = *person;
+    Ok(match formula {
+        Formula::MifflinStJeor => {
+            let base = 10.0 * weight + 6.25 * height - 5.0 * age;
+            match sex {
+                Sex::Male => base + 5.0,
+                Sex::Female => base - 161.0,
+            }
+        }
+        Formula::HarrisBenedict => match sex {
+            Sex::Male => 88.362 + 13.397 * weight + 4.799 * height - 5.677 * age,
+            Sex::Female => 447.593 + 9.247 * weight + 3.098 * height - 4.330 * age,
+        },
+        Formula::KatchMcArdle => {
+            let body_fat = body_fat.ok_or("katch_mcardle için body_fat gerekli")?;
+            370.0 + 21.6 * weight * (1.0 - body_fat / 100.0)
+        }
+    })
+}
+
+// Günlük toplam enerji harcaması, kcal/gün
+pub(crate) fn tdee(bmr: f64, activity: ActivityLevel) -> f64 {
+    bmr * activity.factor()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(sex: Sex) -> Person {
+        Person {
+            age: 30.0,
+            sex,
+            weight: 80.0,
+            height: 180.0,
+            body_fat: None,
+        }
+    }
+
+    #[test]
+    fn test_bmr() {
+        let male = person(Sex::Male);
+        assert_eq!(bmr(Formula::MifflinStJeor, &male), Ok(1780.0));
+        assert_eq!(
+            bmr(Formula::MifflinStJeor, &person(Sex::Female)),
+            Ok(1614.0)
+        );
+        let harris_benedict = bmr(Formula::HarrisBenedict, &male).unwrap();
+        assert!((harris_benedict - 1853.632).abs() < 1e-9);
+
+        assert!(bmr(Formula::KatchMcArdle, &male).is_err());
+        let lean = Person {
+            body_fat: Some(20.0),
+            ..male
+        };
+        assert!((bmr(Formula::KatchMcArdle, &lean).unwrap() - 1752.4).abs() < 1e-9);
+
+        assert!((tdee(1780.0, ActivityLevel::Moderate) - 2759.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_validate_and_parse() {
+        let invalid = |person: Person| bmr(Formula::MifflinStJeor, &person).is_err();
+        let male = person(Sex::Male);
+        assert!(invalid(Person { age: 12.0, ..male }));
+        assert!(invalid(Person {
+            weight: f64::NAN,
+            ..male
+        }));
+        assert!(invalid(Person {
+            height: 1.8,
+            ..male
+        }));
+        assert!(invalid(Person {
+            body_fat: Some(90.0),
+            ..male
+        }));
+
+        assert_eq!(Sex::parse("M"), Some(Sex::Male));
+        assert_eq!(Sex::parse("kadın"), Some(Sex::Female));
+        assert_eq!(Sex::parse("x"), None);
+        assert_eq!(
+            ActivityLevel::parse("very_active"),
+            Some(ActivityLevel::VeryActive)
+        );
+        assert_eq!(Formula::parse("mifflin"), Some(Formula::MifflinStJeor));
+    }
+}
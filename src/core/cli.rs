@@ -0,0 +1,96 @@
+use clap::{Parser, Subcommand};
+
+/// Türkiye'deki gıdaların kalori, makro, mineral ve vitamin değerlerini sunan açık ve ücretsiz bir API.
+#[derive(Parser)]
+#[command(version, about)]
+pub(crate) struct Cli {
+    /// Kullanılacak config dosyası, yoksa varsayılan değerlerle oluşturulur
+    #[arg(
+        long,
+        short,
+        global = true,
+        env = "BESINVERI_CONFIG",
+        default_value = "config.toml"
+    )]
+    pub(crate) config: String,
+
+    /// Config profili (dev, staging, prod...), config.toml'un üzerine config.<profil>.toml birleştirilir
+    #[arg(long, short, global = true, env = "BESINVERI_ENV")]
+    pub(crate) env: Option<String>,
+
+    /// Dinlenecek port, config'deki server.port değerini ezer
+    #[arg(long, short, global = true, env = "BESINVERI_PORT")]
+    pub(crate) port: Option<u16>,
+
+    /// Sürecin PID'inin yazılacağı dosya, kapanışta silinir (runit, OpenRC gibi init sistemleri için). Süreç her zaman ön planda
+    /// çalışır, arka plana almak init sisteminin işidir
+    #[arg(long, global = true, env = "BESINVERI_PID_FILE")]
+    pub(crate) pid_file: Option<String>,
+
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+}
+
+// Alt komut verilmezse serve çalışıyor
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// API sunucusunu başlatır (varsayılan)
+    Serve,
+
+    /// TürKomp'tan (Ulusal Gıda Kompozisyon Veri Tabanı) dışa aktarılan CSV dosyasını db/foods'a yüklenecek bir JSON dosyasına çevirir
+    ImportTurkomp {
+        /// TürKomp CSV dosyası, her satırda bir yemeğin bir bileşeni
+        file: String,
+
+        /// Yazılacak JSON dosyası, sunucu bir sonraki açılışta yemekleri veritabanına ekler
+        #[arg(long, short, default_value = "db/foods/turkomp.json")]
+        output: String,
+
+        /// Yemeklerin kaynak adı
+        #[arg(long, default_value = "TürKomp")]
+        source: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_parsing() {
+        let cli = Cli::try_parse_from(["besinveri-api"]).unwrap();
+        assert_eq!(cli.config, "config.toml");
+        assert_eq!(cli.port, None);
+        assert_eq!(cli.env, None);
+        assert_eq!(cli.pid_file, None);
+        assert!(cli.command.is_none());
+
+        let cli = Cli::try_parse_from([
+            "besinveri-api",
+            "serve",
+            "--config",
+            "/etc/besinveri/config.toml",
+            "-p",
+            "8080",
+            "--env",
+            "prod",
+            "--pid-file",
+            "/run/besinveri.pid",
+        ])
+        .unwrap();
+        assert_eq!(cli.config, "/etc/besinveri/config.toml");
+        assert_eq!(cli.port, Some(8080));
+        assert_eq!(cli.env.as_deref(), Some("prod"));
+        assert_eq!(cli.pid_file.as_deref(), Some("/run/besinveri.pid"));
+        assert!(matches!(cli.command, Some(Command::Serve)));
+
+        assert!(Cli::try_parse_from(["besinveri-api", "--port", "port-değil"]).is_err());
+
+        let cli = Cli::try_parse_from(["besinveri-api", "import-turkomp", "turkomp.csv"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::ImportTurkomp { file, output, source })
+                if file == "turkomp.csv" && output == "db/foods/turkomp.json" && source == "TürKomp"
+        ));
+    }
+}
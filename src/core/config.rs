@@ -1,42 +1,669 @@
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
     io::Write,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
 };
 
-use anyhow::Error;
+use anyhow::{Context, Error, anyhow};
+use chrono_tz::Tz;
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 
-#[derive(Serialize, Deserialize)]
+use crate::core::diet_flags::{self, DietFlagRule};
+
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct Config {
     pub(crate) core: CoreConfig,
     pub(crate) api: APIConfig,
+    // Eski config dosyalarında bu bölüm olmayabilir, o yüzden varsayılan değerlerle dolduruyoruz
+    #[serde(default)]
+    pub(crate) admin: AdminConfig,
+    #[serde(default)]
+    pub(crate) rate_limit: RateLimitConfig,
+    // X-API-Key ile tanınan istemciler, anonim IP'lerden farklı limitlere tabi
+    #[serde(default)]
+    pub(crate) api_keys: Vec<APIKeyConfig>,
+    #[serde(default)]
+    pub(crate) security: SecurityConfig,
+    #[serde(default)]
+    pub(crate) server: ServerConfig,
+    #[serde(default)]
+    pub(crate) signing: SigningConfig,
+    #[serde(default)]
+    pub(crate) maintenance: MaintenanceConfig,
+    #[serde(default)]
+    pub(crate) database: DatabaseConfig,
+    #[serde(default)]
+    pub(crate) telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub(crate) sentry: SentryConfig,
+    #[serde(default)]
+    pub(crate) alerting: AlertingConfig,
+    #[serde(default)]
+    pub(crate) slo: SloConfig,
+    #[serde(default)]
+    pub(crate) access_log: AccessLogConfig,
+    #[serde(default)]
+    pub(crate) search: SearchConfig,
+    #[serde(default)]
+    pub(crate) response_cache: ResponseCacheConfig,
+    #[serde(default)]
+    pub(crate) meals: MealsConfig,
+    #[serde(default)]
+    pub(crate) open_food_facts: OpenFoodFactsConfig,
+    // Yemeklerin vegan, gluten_free gibi diyet bayraklarının kuralları, bayrak adı -> kural. Bölüm yoksa varsayılan kurallar kullanılıyor
+    #[serde(default = "diet_flags::default_rules")]
+    pub(crate) diet_flags: BTreeMap<String, DietFlagRule>,
+    // Tanımlanırsa sunucu TLS'i kendisi sonlandırıyor, reverse proxy arkasında çalışırken gerek yok
+    #[serde(default)]
+    pub(crate) tls: Option<TlsConfig>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct CoreConfig {
-    pub(crate) cache_capacity: u64,
+    // Bayt cinsinden yanıt önbelleği bütçesi
+    #[serde(default = "default_cache_capacity_bytes")]
+    pub(crate) cache_capacity_bytes: u64,
+    // Eski config'lerdeki kayıt sayısı cinsinden kapasite. Birim bayta geçtiğinde anahtarın adı değişti, eski değerler bayt
+    // sanılıp sessizce kullanılmasın veya yok sayılmasın diye okunup validate'te reddediliyor
+    #[serde(default, rename = "cache_capacity", skip_serializing)]
+    pub(crate) legacy_cache_capacity: Option<u64>,
     pub(crate) tracing_level: String,
+    // Belirtilirse yanıt önbelleği kapanışta bu dosyaya yazılıp açılışta geri yüklenir
+    pub(crate) cache_snapshot_path: Option<String>,
+    // İnsanlara gösterilen zaman damgalarının saat dilimi (IANA adı, ör: "Europe/Istanbul"). Kota dönemleri gibi hesaplamalar her zaman UTC
+    #[serde(default = "default_timezone")]
+    pub(crate) timezone: Tz,
+}
+
+fn default_cache_capacity_bytes() -> u64 {
+    32 * 1024 * 1024 // 32MB
 }
 
-#[derive(Serialize, Deserialize)]
+fn default_timezone() -> Tz {
+    Tz::UTC
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct APIConfig {
     pub(crate) base_url: String,
     pub(crate) static_url: String,
     pub(crate) search_max_limit: u64,
     pub(crate) health_internet_check_urls: Vec<String>,
+    // İnternet kontrolü /health isteklerinde değil arka planda bu aralıkla yapılıyor, kapatılırsa /health'te null döner
+    #[serde(default = "default_health_internet_check_enabled")]
+    pub(crate) health_internet_check_enabled: bool,
+    #[serde(default = "default_health_internet_check_interval_seconds")]
+    pub(crate) health_internet_check_interval_seconds: u64,
+}
+
+impl APIConfig {
+    pub(crate) fn urls(&self) -> APIUrls {
+        let base_url = self.base_url.trim_end_matches('/');
+        // http(s)://alanadi.com/API/NEST/PATH -> Bu şekilde girildiğinde /API/NEST/PATH'i kullanacağız nest için
+        let api_path = base_url
+            .replace("://", "") // Kesme işaretlerini istemiyoruz başlangıçtaki
+            .split_once('/')
+            .map(|(_before, after)| format!("/{}", after))
+            .unwrap_or("/".to_owned());
+        APIUrls {
+            base_url: Arc::from(base_url),
+            static_url: Arc::from(self.static_url.trim_end_matches('/')),
+            api_path,
+        }
+    }
+}
+
+// base_url ve static_url'den açılışta bir kez türetilen değerler, handler'lar her istekte config'i okuyup kopyalamıyor.
+// İkisi de sonunda / olmadan tutuluyor
+pub(crate) struct APIUrls {
+    pub(crate) base_url: Arc<str>,
+    pub(crate) static_url: Arc<str>,
+    // Route'ların bağlandığı path, base_url'de path yoksa "/"
+    pub(crate) api_path: String,
+}
+
+fn default_health_internet_check_enabled() -> bool {
+    true
+}
+
+fn default_health_internet_check_interval_seconds() -> u64 {
+    60
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub(crate) struct AdminConfig {
+    // Admin endpoint'leri için Bearer token, belirtilmezse admin endpoint'leri tamamen kapalı olur
+    pub(crate) token: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RateLimitConfig {
+    // Rate limiter'ın kullanabileceği maksimum bellek (bayt)
+    pub(crate) max_memory: usize,
+    // Tüm istemcilere uygulanan genel kural
+    pub(crate) default: RateLimitRule,
+    // Belirli path'ler için ek kurallar. Genel kural da her zaman uygulandığı için bu kurallar sadece limiti sıkılaştırabilir
+    // Path'ler API köküne göre ve birebir eşleşiyor, ör: /foods/search
+    #[serde(default)]
+    pub(crate) routes: Vec<RouteRateLimitRule>,
+    // API anahtarı kademeleri (ör: free, partner) ve limitleri. Anahtarlı istekler genel kurala değil sadece kademelerinin kuralına tabi
+    #[serde(default)]
+    pub(crate) tiers: BTreeMap<String, RateLimitRule>,
+    // Bu path'ler (ve altındaki path'ler) rate limit'e hiç takılmaz, ör: izleme sistemlerinin sık sık yokladığı /health
+    // Kaynak IP aralığına göre muafiyet için security.ip_allowlist kullanılmalı
+    #[serde(default)]
+    pub(crate) exempt_paths: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RateLimitRule {
+    pub(crate) interval_seconds: u64,
+    pub(crate) max_requests: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct RouteRateLimitRule {
+    pub(crate) path: String,
+    pub(crate) interval_seconds: u64,
+    pub(crate) max_requests: u32,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct APIKeyConfig {
+    // Loglarda ve limit kayıtlarında anahtarın kendisi yerine bu isim kullanılıyor
+    pub(crate) name: String,
+    pub(crate) key: String,
+    pub(crate) tier: String,
+    // Tanımlanırsa bu anahtarla gelen istekler X-Signature ile HMAC imzalı olmak zorunda, sunucu tarafı entegrasyonlar için
+    #[serde(default)]
+    pub(crate) secret: Option<String>,
+    // UTC gün ve ay başında sıfırlanan toplam istek kotaları, tanımlanmazsa sınırsız
+    #[serde(default)]
+    pub(crate) daily_quota: Option<u64>,
+    #[serde(default)]
+    pub(crate) monthly_quota: Option<u64>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SecurityConfig {
+    // Bu aralıklardaki istemciler rate limit'e takılmaz, ör: iç izleme sistemleri. CIDR formatında: "10.0.0.0/8", "1.2.3.4/32"
+    pub(crate) ip_allowlist: Vec<IpNet>,
+    // Bu aralıklardaki istemcilerin istekleri doğrudan reddedilir, ör: kötüye kullanan tarayıcı botlar
+    pub(crate) ip_denylist: Vec<IpNet>,
+    // Sadece bu aralıklardan gelen bağlantılar reverse proxy sayılıyor ve istemci adresi X-Forwarded-For/X-Real-IP'den okunuyor.
+    // Diğer bağlantılarda bu header'lar yok sayılıyor, herkes gönderebildiği için aksi halde listeler ve rate limit atlatılabilirdi
+    pub(crate) trusted_proxies: Vec<IpNet>,
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            ip_allowlist: Vec::new(),
+            ip_denylist: Vec::new(),
+            // Aynı makinedeki Caddy/Nginx
+            trusted_proxies: vec!["127.0.0.1/32".parse().unwrap(), "::1/128".parse().unwrap()],
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct TlsConfig {
+    // PEM formatında sertifika zinciri ve özel anahtar dosyaları
+    pub(crate) cert_path: String,
+    pub(crate) key_path: String,
+    // Belirtilirse bu portta düz HTTP dinlenip tüm istekler HTTPS'e yönlendirilir, ör: 80
+    #[serde(default)]
+    pub(crate) http_redirect_port: Option<u16>,
+    // Yönlendirmede kullanılacak HTTPS portu, istemcilerin gördüğü port dinlenen porttan farklıysa (ör: NAT ile 443 -> 8099) ayarlanmalı
+    #[serde(default)]
+    pub(crate) https_port: Option<u16>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SigningConfig {
+    // İmzalı isteklerin zaman damgası sunucu saatinden en fazla bu kadar saniye farklı olabilir
+    pub(crate) max_clock_skew_seconds: u64,
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        Self {
+            max_clock_skew_seconds: 300,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AccessLogFormat {
+    // Apache/Nginx "combined" formatı, mevcut log analiz araçlarıyla okunabiliyor
+    Combined,
+    // Satır başına bir JSON nesnesi, log toplama sistemleri için
+    Json,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct AccessLogConfig {
+    // Açıkken her istek için bir satır "access_log" hedefiyle INFO seviyesinde loglanır
+    pub(crate) enabled: bool,
+    pub(crate) format: AccessLogFormat,
+}
+
+impl Default for AccessLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: AccessLogFormat::Combined,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SloConfig {
+    // İsteklerin objective oranı bu sürenin altında tamamlanmalı, /admin/slo'da route bazında raporlanıyor
+    pub(crate) default_threshold_ms: u64,
+    pub(crate) objective: f64,
+    // Route şablonuna özel eşikler, ör: { "/foods/search" = 300 }
+    pub(crate) route_thresholds_ms: BTreeMap<String, u64>,
+}
+
+impl Default for SloConfig {
+    fn default() -> Self {
+        Self {
+            default_threshold_ms: 250,
+            objective: 0.99,
+            route_thresholds_ms: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SearchConfig {
+    // fuzzy=true aramalarında kabul edilen en fazla harf farkı (ekleme, silme, değiştirme veya yer değiştirme).
+    // Kısa sorgularda neredeyse her şey eşleşmesin diye sorgunun her 3 harfi için en fazla 1 fark kabul ediliyor
+    pub(crate) fuzzy_max_distance: usize,
+    pub(crate) ranking: SearchRanking,
+    // Açıklama ve mode=all aramalarında uygulamada sıralanmak üzere veritabanından çekilen en fazla yemek.
+    // SQL bunları basit bir alaka sırasıyla seçiyor, "a" gibi geniş sorgular tüm tabloyu çekmiyor
+    pub(crate) max_candidates: u32,
+    // mode=all aramasında alanların sıralamadaki ağırlıkları
+    pub(crate) field_weights: SearchFieldWeights,
+    // Popüler aramaların sonuçlarını tutan önbellekteki en fazla sorgu, 0 verilirse kapalı
+    pub(crate) hot_cache_entries: u64,
+    // Veritabanında elle yapılan değişikliklerin purge edilmeden de en geç bu süre sonra aramalara yansıması için
+    pub(crate) hot_cache_ttl_seconds: u64,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            fuzzy_max_distance: 2,
+            ranking: SearchRanking::Trigram,
+            max_candidates: 200,
+            field_weights: SearchFieldWeights::default(),
+            hot_cache_entries: 256,
+            hot_cache_ttl_seconds: 60,
+        }
+    }
+}
+
+// Yanıt önbelleği endpoint sınıflarına göre ayrı önbelleklere bölünüyor, her birinin core.cache_capacity_bytes'tan payı ve TTL'i ayrı.
+// Tekil arama sorgularından oluşan bir dalga sadece arama payını dolduruyor, /foods/list ve /tags gibi sürekli istenen kayıtları atamıyor
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ResponseCacheConfig {
+    // Tek bir kayda ait yanıtlar: /food/{slug}, /recipe/{slug}, /reference-intake/{slug}
+    pub(crate) detail: CacheShardConfig,
+    // Sorgu parametresi az olan listeler: /foods/list, /tags, /foods, /additives...
+    pub(crate) list: CacheShardConfig,
+    // Anahtarı sorguyla değişen, sayısı sınırsız yanıtlar: /foods/search, /foods/suggest, /foods/summaries, /convert, /calc/energy
+    pub(crate) search: CacheShardConfig,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct CacheShardConfig {
+    // core.cache_capacity_bytes'ın bu sınıfa ayrılan payı, payların toplamı 1'i geçmemeli
+    pub(crate) share: f64,
+    pub(crate) ttl_seconds: u64,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            detail: CacheShardConfig {
+                share: 0.45,
+                ttl_seconds: 600,
+            },
+            list: CacheShardConfig {
+                share: 0.25,
+                ttl_seconds: 600,
+            },
+            search: CacheShardConfig {
+                share: 0.30,
+                ttl_seconds: 300,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct MealsConfig {
+    // POST /meals/analyze'da tek bir öğün sodyum, şeker veya doymuş yağın günlük değerinin bu yüzdesini geçince uyarı veriliyor
+    pub(crate) warning_percent: f64,
+    // ?profile= verilmediğinde %DV için kullanılan referans alım profili, GET /reference-intakes'teki slug'lardan biri
+    pub(crate) default_profile: String,
+    // POST /meals/gaps'te günlük değerin bu yüzdesinin altında kalan vitamin ve mineraller eksik sayılıyor
+    pub(crate) gap_percent: f64,
+    // POST /meals/gaps'in eksikleri kapatmak için önerdiği en fazla yemek sayısı
+    pub(crate) gap_suggestions: usize,
+}
+
+impl Default for MealsConfig {
+    fn default() -> Self {
+        Self {
+            warning_percent: 40.0,
+            default_profile: "adult".to_owned(),
+            gap_percent: 100.0,
+            gap_suggestions: 5,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct OpenFoodFactsConfig {
+    // GTIN'i olan yemeklerin resim, içindekiler ve Nutri-Score'u Open Food Facts'ten arka planda çekiliyor. Kapalıyken
+    // dışarıya hiç istek atılmıyor, daha önce çekilmiş veriler gösterilmeye devam ediyor
+    pub(crate) enabled: bool,
+    pub(crate) base_url: String,
+    // Open Food Facts istek atan uygulamanın "Uygulama/Sürüm (iletişim)" biçiminde kendini tanıtmasını istiyor
+    pub(crate) user_agent: String,
+    // Çekilmemiş veya eskimiş yemekler bu aralıkla kontrol ediliyor
+    pub(crate) interval_seconds: u64,
+    pub(crate) refresh_after_days: u64,
+    // Open Food Facts'in istek limitini aşmamak için iki ürün isteği arasında beklenen süre
+    pub(crate) request_delay_ms: u64,
+    pub(crate) timeout_seconds: u64,
+}
+
+impl Default for OpenFoodFactsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: "https://world.openfoodfacts.org".to_owned(),
+            user_agent: "BesinVeri/0.1.0 (https://github.com/karahanbuhan/besinveri-api)"
+                .to_owned(),
+            interval_seconds: 86400,
+            refresh_after_days: 30,
+            request_delay_ms: 1000,
+            timeout_seconds: 10,
+        }
+    }
+}
+
+// Sorgu birden fazla alanda geçiyorsa puanlar toplanıyor, 0 verilen alan sıralamayı etkilemiyor ama aramaya dahil
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SearchFieldWeights {
+    pub(crate) description: f64,
+    pub(crate) tags: f64,
+    pub(crate) source: f64,
+}
+
+impl Default for SearchFieldWeights {
+    fn default() -> Self {
+        Self {
+            description: 3.0,
+            tags: 2.0,
+            source: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SearchRanking {
+    // Sorgu ile açıklamanın üçlü harf gruplarının benzerliği (Jaccard), kelime sırası ve kelime parçalarıyla iyi çalışıyor
+    Trigram,
+    // Eski sıralama: önce sorguyla başlayanlar, sonra sorgunun açıklamada başa ne kadar yakın geçtiği. Karşılaştırma için duruyor
+    Positional,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct DatabaseConfig {
+    // Bundan uzun süren sorgular parametreleriyle birlikte loglanır, 0 ise kapalı
+    pub(crate) slow_query_threshold_ms: u64,
+    // Havuzdaki en fazla bağlantı. SQLite'ta yazmalar zaten sıraya giriyor, okuma ağırlıklı yüklerde çekirdek sayısının birkaç katı yeterli
+    pub(crate) max_connections: u32,
+    // Havuzda boş bağlantı yoksa sorgunun bu kadar bekledikten sonra hata vermesi, istekler uzun süre askıda kalmasın
+    pub(crate) acquire_timeout_seconds: u64,
+    // Bundan uzun süre boşta kalan bağlantılar kapatılıyor, 0 ise kapatılmıyor
+    pub(crate) idle_timeout_seconds: u64,
+    // Her bağlantının hazırlanmış (prepared) ifade önbelleğinin boyutu. Sorgular sabit metinli olduğu için aynı ifade tekrar
+    // derlenmeden kullanılıyor, sorgu çeşidinden küçük olursa en eski kullanılanlar atılıyor
+    pub(crate) statement_cache_capacity: usize,
+    // Başka bir bağlantı yazarken alınan SQLITE_BUSY/SQLITE_LOCKED hatalarında sorgunun en fazla kaç kez daha deneneceği, 0 ise denenmez
+    pub(crate) max_retries: u32,
+    // İlk tekrar denemeden önceki bekleme, her denemede iki katına çıkıyor
+    pub(crate) retry_base_delay_ms: u64,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            slow_query_threshold_ms: 100,
+            max_connections: 10,
+            acquire_timeout_seconds: 5,
+            idle_timeout_seconds: 600,
+            statement_cache_capacity: 128,
+            max_retries: 3,
+            retry_base_delay_ms: 20,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TelemetryConfig {
+    // OTLP/HTTP trace endpoint'i, ör: http://localhost:4318/v1/traces. Belirtilmezse span'lar dışarı aktarılmaz
+    pub(crate) otlp_endpoint: Option<String>,
+    pub(crate) service_name: String,
+    // Üst trace'i olmayan isteklerin ne kadarının örnekleneceği, 0.0 ile 1.0 arası
+    pub(crate) sample_ratio: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "besinveri-api".to_owned(),
+            sample_ratio: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct SentryConfig {
+    // Sentry projesinin DSN'i. Belirtilmezse veya sunucu `sentry` özelliği olmadan derlendiyse hatalar raporlanmaz
+    pub(crate) dsn: Option<String>,
+    // Ör: production, staging. Belirtilmezse Sentry debug derlemelerde development, diğerlerinde production kullanıyor
+    pub(crate) environment: Option<String>,
+    // Raporlanan hataların ne kadarının gönderileceği, 0.0 ile 1.0 arası
+    pub(crate) sample_rate: f32,
+}
+
+impl Default for SentryConfig {
+    fn default() -> Self {
+        Self {
+            dsn: None,
+            environment: None,
+            sample_rate: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct AlertingConfig {
+    // Slack veya Discord gelen webhook URL'si. Belirtilmezse hata oranları izlenmiyor
+    pub(crate) webhook_url: Option<String>,
+    // Oranlar bu aralıklarla, son aralıktaki istek ve sorgular üzerinden hesaplanıyor
+    pub(crate) check_interval_seconds: u64,
+    // 5xx yanıtların ve başarısız veritabanı sorgularının oranı, 0.0 ile 1.0 arası
+    pub(crate) server_error_rate_threshold: f64,
+    pub(crate) database_failure_rate_threshold: f64,
+    // Aralıkta bundan az istek/sorgu varsa oran hesaplanmıyor, düşük trafikte tek bir hata alarm çaldırmasın
+    pub(crate) min_samples: u64,
+    // Süren bir sorun için alarm en fazla bu sıklıkla tekrarlanıyor
+    pub(crate) repeat_after_seconds: u64,
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            check_interval_seconds: 60,
+            server_error_rate_threshold: 0.05,
+            database_failure_rate_threshold: 0.05,
+            min_samples: 20,
+            repeat_after_seconds: 3600,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct MaintenanceConfig {
+    // Açıkken /health ve /admin dışındaki tüm istekler 503 alır, admin endpoint'inden çalışırken de açılıp kapatılabilir
+    pub(crate) enabled: bool,
+    // İstemcilere Retry-After olarak bildirilecek tahmini bakım süresi
+    pub(crate) retry_after_seconds: u64,
 }
 
-pub(crate) fn load_config_with_defaults() -> Result<Config, Error> {
-    let path = "config.toml";
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retry_after_seconds: 300,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct ServerConfig {
+    // 0.0.0.0 üzerinde dinlenecek port, --port ile ezilebilir
+    pub(crate) port: u16,
+    // Aynı anda işlenebilecek maksimum istek sayısı, bu sayı aşılınca yeni istekler kuyruğa alınmadan 503 ile reddedilir
+    pub(crate) max_concurrent_requests: usize,
+    // İstemcinin HTTP header'larını göndermeyi bitirmesi gereken süre, slowloris saldırılarına karşı
+    pub(crate) header_read_timeout_seconds: u64,
+    // Soketten hiç veri gelmediğinde bağlantının kapatılacağı süre, keep-alive bağlantılar da buna dahil
+    pub(crate) idle_timeout_seconds: u64,
+    // Yanıt istemciye bu süre boyunca hiç yazılamazsa bağlantı kapatılır
+    pub(crate) write_timeout_seconds: u64,
+    pub(crate) keep_alive: bool,
+    // TLS açıkken HTTP/2 ALPN ile sunuluyor, kapatılırsa bağlantılar sadece HTTP/1.1. TLS yokken HTTP/2 sadece ön bilgiyle (h2c) kullanılabiliyor
+    pub(crate) http2: bool,
+    // Tek bir HTTP/2 bağlantısında aynı anda işlenebilecek en fazla istek, mobil istemciler paralel isteklerini tek bağlantıda gönderiyor
+    pub(crate) http2_max_concurrent_streams: u32,
+    // Boştaki HTTP/2 bağlantılarına bu aralıkla ping gönderiliyor, mobil ağlarda NAT'ın sessiz bağlantıları düşürmesini önlüyor (0: kapalı)
+    pub(crate) http2_keep_alive_interval_seconds: u64,
+    // Ping'e bu süre içinde yanıt gelmezse bağlantı kapatılıyor
+    pub(crate) http2_keep_alive_timeout_seconds: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            port: 8099,
+            max_concurrent_requests: 512,
+            header_read_timeout_seconds: 10,
+            idle_timeout_seconds: 60,
+            write_timeout_seconds: 30,
+            keep_alive: true,
+            http2: true,
+            http2_max_concurrent_streams: 200,
+            http2_keep_alive_interval_seconds: 0,
+            http2_keep_alive_timeout_seconds: 20,
+        }
+    }
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        // Önceden koddaki sabit değerler: 1 saniyede maksimum 5 istek, 64MB bellek
+        Self {
+            max_memory: 64 * 1024 * 1024,
+            default: RateLimitRule {
+                interval_seconds: 1,
+                max_requests: 5,
+            },
+            routes: Vec::new(),
+            tiers: BTreeMap::from([
+                (
+                    "free".to_owned(),
+                    RateLimitRule {
+                        interval_seconds: 1,
+                        max_requests: 10,
+                    },
+                ),
+                (
+                    "partner".to_owned(),
+                    RateLimitRule {
+                        interval_seconds: 1,
+                        max_requests: 50,
+                    },
+                ),
+            ]),
+            exempt_paths: Vec::new(),
+        }
+    }
+}
 
+// Profil verilirse config.toml'un üzerine aynı dizindeki config.<profil>.toml birleştiriliyor (ör: config.prod.toml).
+// Profil dosyasında sadece ortama göre değişen alanları yazmak yeterli, geri kalanı temel config'den geliyor
+pub(crate) fn load_config_with_defaults(
+    path: &str,
+    profile: Option<&str>,
+) -> Result<Config, Error> {
     if !fs::exists(path)? {
         create_default_config_file(path)?;
-        return Ok(get_default_config());
     }
 
-    let file: Vec<u8> = fs::read(path)?;
-    let mut config: Config = toml::from_slice(&file.as_slice())?;
+    let mut value = read_toml(path)?;
+    if let Some(profile) = profile {
+        let profile_path = profile_config_path(path, profile);
+        // Yanlış yazılmış bir profil adıyla sessizce temel config'le çalışmak prod'da tehlikeli, o yüzden hata veriyoruz
+        if !fs::exists(&profile_path)? {
+            return Err(anyhow!(
+                "{} profili için {} bulunamadı",
+                profile,
+                profile_path
+            ));
+        }
+        info!("{} profili yükleniyor: {}", profile, profile_path);
+        merge_toml(&mut value, read_toml(&profile_path)?);
+    }
+
+    expand_env_vars(&mut value, "")?;
+
+    let mut config: Config = value
+        .try_into()
+        .with_context(|| format!("{} geçerli bir config dosyası değil", path))?;
 
     // Remove the trailing slashes in case there are
     config.api.base_url = config.api.base_url.trim_end_matches("/").to_owned();
@@ -45,11 +672,444 @@ pub(crate) fn load_config_with_defaults() -> Result<Config, Error> {
     Ok(config)
 }
 
-fn get_default_config() -> Config {
+fn read_toml(path: &str) -> Result<toml::Table, Error> {
+    let file = fs::read_to_string(path).with_context(|| format!("{} okunamadı", path))?;
+    toml::from_str(&file).with_context(|| format!("{} geçerli bir TOML dosyası değil", path))
+}
+
+// config.toml -> config.prod.toml, uzantısı olmayan dosyalarda profil adı sona ekleniyor
+fn profile_config_path(path: &str, profile: &str) -> String {
+    let path = Path::new(path);
+    let file_name = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => format!(
+            "{}.{}.{}",
+            stem.to_string_lossy(),
+            profile,
+            extension.to_string_lossy()
+        ),
+        _ => format!("{}.{}", path.to_string_lossy(), profile),
+    };
+    path.with_file_name(file_name)
+        .to_string_lossy()
+        .into_owned()
+}
+
+// Config'deki "${DEGISKEN}" ifadeleri ortam değişkenlerinin (ve .env'in) değerleriyle değiştiriliyor,
+// ör: token = "${BESINVERI_ADMIN_TOKEN}". Tanımsız bir değişken hangi alanda kullanıldığıyla birlikte hata veriyor
+fn expand_env_vars(table: &mut toml::Table, path: &str) -> Result<(), Error> {
+    for (key, value) in table.iter_mut() {
+        let field = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", path, key)
+        };
+        expand_env_vars_in_value(value, &field)?;
+    }
+    Ok(())
+}
+
+fn expand_env_vars_in_value(value: &mut toml::Value, field: &str) -> Result<(), Error> {
+    match value {
+        toml::Value::String(string) => *string = expand_env_vars_in_str(string, field)?,
+        toml::Value::Table(table) => expand_env_vars(table, field)?,
+        toml::Value::Array(array) => {
+            for (i, value) in array.iter_mut().enumerate() {
+                expand_env_vars_in_value(value, &format!("{}[{}]", field, i))?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn expand_env_vars_in_str(string: &str, field: &str) -> Result<String, Error> {
+    let mut expanded = String::with_capacity(string.len());
+    let mut rest = string;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+        let value = std::env::var(name)
+            .with_context(|| format!("{}: {} ortam değişkeni tanımlı değil", field, name))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+// Tablolar alan alan birleştiriliyor, diğer değerler (diziler dahil) profildeki değerle değiştiriliyor.
+// Dizileri birleştirmiyoruz, aksi halde prod'da api_keys veya denylist listesini daraltmak mümkün olmazdı
+fn merge_toml(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_toml(base_table, overlay_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+impl Config {
+    // Yanlış bir değer yüzünden sunucu ilk istekte panic atmasın veya sessizce yanlış davranmasın diye açılışta kontrol ediyoruz.
+    // İlk hatada durmak yerine tüm hataları topluyoruz ki config'i tek seferde düzeltebilelim
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        let mut errors: Vec<String> = Vec::new();
+        let mut check = |ok: bool, field: &str, message: &str| {
+            if !ok {
+                errors.push(format!("{}: {}", field, message));
+            }
+        };
+
+        check(
+            self.core.legacy_cache_capacity.is_none(),
+            "core.cache_capacity",
+            "kaldırıldı, önbellek artık kayıt sayısıyla değil bayt cinsinden sınırlanıyor; yerine core.cache_capacity_bytes kullanılmalı",
+        );
+        check(
+            self.core.cache_capacity_bytes >= 1024 * 1024,
+            "core.cache_capacity_bytes",
+            "bayt cinsinden en az 1MB (1048576) olmalı",
+        );
+        let shards = [
+            ("detail", &self.response_cache.detail),
+            ("list", &self.response_cache.list),
+            ("search", &self.response_cache.search),
+        ];
+        for (name, shard) in shards {
+            check(
+                shard.share > 0.0 && shard.share <= 1.0,
+                &format!("response_cache.{}.share", name),
+                "0'dan büyük, en fazla 1 olmalı",
+            );
+            check(
+                (1..=86400).contains(&shard.ttl_seconds),
+                &format!("response_cache.{}.ttl_seconds", name),
+                "1 ile 86400 arasında olmalı",
+            );
+        }
+        check(
+            shards.iter().map(|(_, shard)| shard.share).sum::<f64>() <= 1.0 + 1e-9,
+            "response_cache",
+            "payların toplamı 1'i geçmemeli",
+        );
+        check(
+            tracing::Level::from_str(&self.core.tracing_level).is_ok(),
+            "core.tracing_level",
+            "TRACE, DEBUG, INFO, WARN veya ERROR olmalı",
+        );
+
+        check(
+            is_http_url(&self.api.base_url),
+            "api.base_url",
+            "http(s):// ile başlayan geçerli bir URL olmalı",
+        );
+        check(
+            is_http_url(&self.api.static_url),
+            "api.static_url",
+            "http(s):// ile başlayan geçerli bir URL olmalı",
+        );
+        check(
+            self.api.search_max_limit > 0,
+            "api.search_max_limit",
+            "0'dan büyük olmalı",
+        );
+        for (i, url) in self.api.health_internet_check_urls.iter().enumerate() {
+            check(
+                is_http_url(url),
+                &format!("api.health_internet_check_urls[{}]", i),
+                "http(s):// ile başlayan geçerli bir URL olmalı",
+            );
+        }
+        check(
+            !self.api.health_internet_check_enabled
+                || self.api.health_internet_check_interval_seconds > 0,
+            "api.health_internet_check_interval_seconds",
+            "0'dan büyük olmalı",
+        );
+
+        for (i, range) in self.security.trusted_proxies.iter().enumerate() {
+            check(
+                range.prefix_len() > 0,
+                &format!("security.trusted_proxies[{}]", i),
+                "tüm adresleri kapsamamalı, aksi halde her istemci kendi adresini header'la belirleyebilir",
+            );
+        }
+
+        let rate_limit = &self.rate_limit;
+        check(
+            rate_limit.default.interval_seconds > 0 && rate_limit.default.max_requests > 0,
+            "rate_limit.default",
+            "interval_seconds ve max_requests 0'dan büyük olmalı",
+        );
+        for (i, route) in rate_limit.routes.iter().enumerate() {
+            check(
+                route.path.starts_with('/'),
+                &format!("rate_limit.routes[{}].path", i),
+                "'/' ile başlamalı",
+            );
+            check(
+                route.interval_seconds > 0 && route.max_requests > 0,
+                &format!("rate_limit.routes[{}]", i),
+                "interval_seconds ve max_requests 0'dan büyük olmalı",
+            );
+        }
+        for (tier, rule) in &rate_limit.tiers {
+            check(
+                rule.interval_seconds > 0 && rule.max_requests > 0,
+                &format!("rate_limit.tiers.{}", tier),
+                "interval_seconds ve max_requests 0'dan büyük olmalı",
+            );
+        }
+
+        for (i, api_key) in self.api_keys.iter().enumerate() {
+            let field = format!("api_keys[{}]", i);
+            check(
+                !api_key.name.is_empty() && !api_key.key.is_empty(),
+                &field,
+                "name ve key boş olamaz",
+            );
+            check(
+                rate_limit.tiers.contains_key(&api_key.tier),
+                &format!("{}.tier", field),
+                &format!(
+                    "rate_limit.tiers içinde \"{}\" kademesi tanımlı değil",
+                    api_key.tier
+                ),
+            );
+            check(
+                self.api_keys[..i]
+                    .iter()
+                    .all(|other| other.name != api_key.name && other.key != api_key.key),
+                &field,
+                "name ve key diğer anahtarlarla aynı olamaz",
+            );
+        }
+
+        check(
+            self.server.max_concurrent_requests > 0,
+            "server.max_concurrent_requests",
+            "0'dan büyük olmalı",
+        );
+        check(
+            self.server.header_read_timeout_seconds > 0
+                && self.server.idle_timeout_seconds > 0
+                && self.server.write_timeout_seconds > 0,
+            "server",
+            "zaman aşımları 0'dan büyük olmalı",
+        );
+        check(
+            self.server.http2_max_concurrent_streams > 0,
+            "server.http2_max_concurrent_streams",
+            "0'dan büyük olmalı",
+        );
+        check(
+            self.server.http2_keep_alive_timeout_seconds > 0,
+            "server.http2_keep_alive_timeout_seconds",
+            "0'dan büyük olmalı",
+        );
+        // Ping'ler bağlantıyı boşta saymaz, aralık boşta kalma süresinden uzunsa bağlantı ping'ten önce kapanıyor
+        check(
+            self.server.http2_keep_alive_interval_seconds < self.server.idle_timeout_seconds,
+            "server.http2_keep_alive_interval_seconds",
+            "server.idle_timeout_seconds'tan küçük olmalı",
+        );
+        check(
+            (1..=256).contains(&self.database.max_connections),
+            "database.max_connections",
+            "1 ile 256 arasında olmalı",
+        );
+        check(
+            self.database.acquire_timeout_seconds > 0,
+            "database.acquire_timeout_seconds",
+            "0'dan büyük olmalı",
+        );
+        check(
+            self.database.statement_cache_capacity > 0,
+            "database.statement_cache_capacity",
+            "0'dan büyük olmalı",
+        );
+        check(
+            self.database.max_retries <= 10,
+            "database.max_retries",
+            "10'dan büyük olmamalı",
+        );
+        check(
+            self.signing.max_clock_skew_seconds > 0,
+            "signing.max_clock_skew_seconds",
+            "0'dan büyük olmalı",
+        );
+
+        if let Some(endpoint) = &self.telemetry.otlp_endpoint {
+            check(
+                is_http_url(endpoint),
+                "telemetry.otlp_endpoint",
+                "http(s):// ile başlayan geçerli bir URL olmalı",
+            );
+        }
+        check(
+            (0.0..=1.0).contains(&self.telemetry.sample_ratio),
+            "telemetry.sample_ratio",
+            "0.0 ile 1.0 arasında olmalı",
+        );
+        if let Some(dsn) = &self.sentry.dsn {
+            check(
+                is_http_url(dsn),
+                "sentry.dsn",
+                "http(s):// ile başlayan geçerli bir DSN olmalı",
+            );
+        }
+        check(
+            (0.0..=1.0).contains(&self.sentry.sample_rate),
+            "sentry.sample_rate",
+            "0.0 ile 1.0 arasında olmalı",
+        );
+
+        let alerting = &self.alerting;
+        if let Some(url) = &alerting.webhook_url {
+            check(
+                is_http_url(url),
+                "alerting.webhook_url",
+                "http(s):// ile başlayan geçerli bir URL olmalı",
+            );
+        }
+        check(
+            alerting.check_interval_seconds > 0,
+            "alerting.check_interval_seconds",
+            "0'dan büyük olmalı",
+        );
+        check(
+            alerting.server_error_rate_threshold > 0.0
+                && alerting.server_error_rate_threshold <= 1.0,
+            "alerting.server_error_rate_threshold",
+            "0.0'dan büyük, en fazla 1.0 olmalı",
+        );
+        check(
+            alerting.database_failure_rate_threshold > 0.0
+                && alerting.database_failure_rate_threshold <= 1.0,
+            "alerting.database_failure_rate_threshold",
+            "0.0'dan büyük, en fazla 1.0 olmalı",
+        );
+
+        check(
+            self.slo.default_threshold_ms > 0
+                && self.slo.route_thresholds_ms.values().all(|&ms| ms > 0),
+            "slo",
+            "eşikler 0'dan büyük olmalı",
+        );
+        check(
+            self.slo.objective > 0.0 && self.slo.objective < 1.0,
+            "slo.objective",
+            "0.0 ile 1.0 arasında olmalı (ör: 0.99)",
+        );
+
+        check(
+            self.search.max_candidates as u64 >= self.api.search_max_limit,
+            "search.max_candidates",
+            "api.search_max_limit'ten küçük olmamalı",
+        );
+        check(
+            self.meals.warning_percent > 0.0 && self.meals.warning_percent <= 100.0,
+            "meals.warning_percent",
+            "0'dan büyük, en fazla 100 olmalı",
+        );
+        check(
+            !self.meals.default_profile.trim().is_empty(),
+            "meals.default_profile",
+            "boş olmamalı",
+        );
+        check(
+            self.meals.gap_percent > 0.0 && self.meals.gap_percent <= 100.0,
+            "meals.gap_percent",
+            "0'dan büyük, en fazla 100 olmalı",
+        );
+        check(
+            (1..=20).contains(&self.meals.gap_suggestions),
+            "meals.gap_suggestions",
+            "1 ile 20 arasında olmalı",
+        );
+        check(
+            self.search.hot_cache_entries == 0 || self.search.hot_cache_ttl_seconds > 0,
+            "search.hot_cache_ttl_seconds",
+            "hot_cache_entries 0 değilse 0'dan büyük olmalı",
+        );
+        let weights = self.search.field_weights;
+        check(
+            [weights.description, weights.tags, weights.source]
+                .iter()
+                .all(|weight| weight.is_finite() && *weight >= 0.0)
+                && weights.description + weights.tags + weights.source > 0.0,
+            "search.field_weights",
+            "ağırlıklar negatif olmamalı ve en az biri 0'dan büyük olmalı",
+        );
+
+        let open_food_facts = &self.open_food_facts;
+        check(
+            is_http_url(&open_food_facts.base_url),
+            "open_food_facts.base_url",
+            "http(s):// ile başlayan geçerli bir URL olmalı",
+        );
+        check(
+            !open_food_facts.user_agent.trim().is_empty(),
+            "open_food_facts.user_agent",
+            "boş olmamalı",
+        );
+        check(
+            open_food_facts.interval_seconds > 0 && open_food_facts.timeout_seconds > 0,
+            "open_food_facts",
+            "interval_seconds ve timeout_seconds 0'dan büyük olmalı",
+        );
+        for (flag, rule) in &self.diet_flags {
+            let field = format!("diet_flags.{}", flag);
+            check(
+                diet_flags::is_valid_flag_name(flag),
+                &field,
+                "bayrak adı sadece küçük harf, rakam ve alt çizgiden oluşmalı",
+            );
+            if let Err(e) = rule.validate() {
+                check(false, &field, &e);
+            }
+        }
+
+        if let Some(tls) = &self.tls {
+            check(
+                fs::exists(&tls.cert_path).unwrap_or(false),
+                "tls.cert_path",
+                &format!("{} dosyası bulunamadı", tls.cert_path),
+            );
+            check(
+                fs::exists(&tls.key_path).unwrap_or(false),
+                "tls.key_path",
+                &format!("{} dosyası bulunamadı", tls.key_path),
+            );
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Config geçersiz:\n  - {}", errors.join("\n  - ")))
+        }
+    }
+}
+
+fn is_http_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+}
+
+pub(crate) fn get_default_config() -> Config {
     Config {
         core: CoreConfig {
-            cache_capacity: 100,
+            cache_capacity_bytes: default_cache_capacity_bytes(),
+            legacy_cache_capacity: None,
             tracing_level: "TRACE".to_owned(),
+            cache_snapshot_path: None,
+            timezone: default_timezone(),
         },
         api: APIConfig {
             base_url: "https://api.besinveri.com".to_owned(),
@@ -60,7 +1120,29 @@ fn get_default_config() -> Config {
                 "https://github.com".to_owned(),
                 "https://www.wikipedia.org".to_owned(),
             ],
+            health_internet_check_enabled: default_health_internet_check_enabled(),
+            health_internet_check_interval_seconds: default_health_internet_check_interval_seconds(
+            ),
         },
+        admin: AdminConfig::default(),
+        rate_limit: RateLimitConfig::default(),
+        api_keys: Vec::new(),
+        security: SecurityConfig::default(),
+        server: ServerConfig::default(),
+        signing: SigningConfig::default(),
+        maintenance: MaintenanceConfig::default(),
+        database: DatabaseConfig::default(),
+        telemetry: TelemetryConfig::default(),
+        sentry: SentryConfig::default(),
+        alerting: AlertingConfig::default(),
+        slo: SloConfig::default(),
+        access_log: AccessLogConfig::default(),
+        search: SearchConfig::default(),
+        response_cache: ResponseCacheConfig::default(),
+        meals: MealsConfig::default(),
+        open_food_facts: OpenFoodFactsConfig::default(),
+        diet_flags: diet_flags::default_rules(),
+        tls: None,
     }
 }
 
@@ -90,6 +1172,21 @@ mod tests {
         assert_eq!(config.api.base_url, "https://api.besinveri.com");
     }
 
+    #[test]
+    fn api_urls_are_normalized() {
+        let mut config = get_default_config();
+        let urls = config.api.urls();
+        assert_eq!(&*urls.base_url, "https://api.besinveri.com");
+        assert_eq!(urls.api_path, "/");
+
+        config.api.base_url = "https://besinveri.com/api/v1/".to_owned();
+        config.api.static_url = "https://besinveri.com/static/".to_owned();
+        let urls = config.api.urls();
+        assert_eq!(&*urls.base_url, "https://besinveri.com/api/v1");
+        assert_eq!(&*urls.static_url, "https://besinveri.com/static");
+        assert_eq!(urls.api_path, "/api/v1");
+    }
+
     #[test]
     fn default_config_is_valid() {
         // TOML serialize edilebiliyor mu test et
@@ -101,4 +1198,229 @@ mod tests {
         let deserialized: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(deserialized.api.base_url, "https://api.besinveri.com");
     }
+
+    #[test]
+    fn rate_limit_routes_are_parsed() {
+        let toml_str = r#"
+            [core]
+            cache_capacity_bytes = 1024
+            tracing_level = "INFO"
+
+            [api]
+            base_url = "https://api.besinveri.com"
+            static_url = "https://besinveri.com/static"
+            search_max_limit = 10
+            health_internet_check_urls = []
+
+            [rate_limit]
+            max_memory = 1048576
+            default = { interval_seconds = 1, max_requests = 10 }
+            exempt_paths = ["/health"]
+
+            [[rate_limit.routes]]
+            path = "/foods/search"
+            interval_seconds = 1
+            max_requests = 3
+
+            [rate_limit.tiers]
+            partner = { interval_seconds = 1, max_requests = 100 }
+
+            [security]
+            ip_allowlist = ["10.0.0.0/8"]
+            ip_denylist = ["203.0.113.7/32", "2001:db8::/32"]
+            trusted_proxies = ["172.16.0.0/12"]
+
+            [[api_keys]]
+            name = "ornek-partner"
+            key = "gizli-anahtar"
+            tier = "partner"
+        "#;
+
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.rate_limit.default.max_requests, 10);
+        assert_eq!(config.rate_limit.routes.len(), 1);
+        assert_eq!(config.rate_limit.routes[0].path, "/foods/search");
+        assert_eq!(config.rate_limit.tiers["partner"].max_requests, 100);
+        assert_eq!(config.rate_limit.exempt_paths, vec!["/health"]);
+        assert_eq!(config.api_keys[0].tier, "partner");
+        assert_eq!(config.security.ip_allowlist.len(), 1);
+        assert_eq!(config.security.ip_denylist.len(), 2);
+        assert_eq!(config.security.trusted_proxies.len(), 1);
+        // Belirtilmeyen bölümler varsayılan değerleri almalı
+        assert!(config.admin.token.is_none());
+    }
+
+    #[test]
+    fn default_config_passes_validation() {
+        assert!(get_default_config().validate().is_ok());
+    }
+
+    #[test]
+    fn validation_reports_every_invalid_field() {
+        let mut config = get_default_config();
+        config.core.cache_capacity_bytes = 1000;
+        config.core.legacy_cache_capacity = Some(100); // Eski config'lerdeki gibi kayıt sayısı
+        config.api.base_url = "api.besinveri.com".to_owned();
+        config.api.search_max_limit = 0;
+        config.api.health_internet_check_urls = vec!["ftp://example.com".to_owned()];
+        config.server.http2_keep_alive_interval_seconds = 90;
+        config.security.trusted_proxies = vec!["0.0.0.0/0".parse().unwrap()];
+        config.api_keys = vec![APIKeyConfig {
+            name: "partner".to_owned(),
+            key: "anahtar".to_owned(),
+            tier: "olmayan".to_owned(),
+            secret: None,
+            daily_quota: None,
+            monthly_quota: None,
+        }];
+
+        let message = config.validate().unwrap_err().to_string();
+        for field in [
+            "core.cache_capacity:",
+            "core.cache_capacity_bytes:",
+            "api.base_url",
+            "api.search_max_limit",
+            "api.health_internet_check_urls[0]",
+            "server.http2_keep_alive_interval_seconds",
+            "security.trusted_proxies[0]",
+            "api_keys[0].tier",
+        ] {
+            assert!(
+                message.contains(field),
+                "{} hatası eksik: {}",
+                field,
+                message
+            );
+        }
+        assert!(!message.contains("api.static_url"));
+    }
+
+    #[test]
+    fn profile_config_path_inserts_profile_before_extension() {
+        assert_eq!(
+            profile_config_path("config.toml", "prod"),
+            "config.prod.toml"
+        );
+        assert_eq!(
+            profile_config_path("/etc/besinveri/config.toml", "staging"),
+            "/etc/besinveri/config.staging.toml"
+        );
+        assert_eq!(profile_config_path("besinveri", "dev"), "besinveri.dev");
+    }
+
+    #[test]
+    fn profile_values_override_base_config() {
+        let mut base: toml::Table = toml::from_str(
+            r#"
+            [core]
+            tracing_level = "DEBUG"
+            cache_capacity_bytes = 67108864
+
+            [security]
+            denylist = ["10.0.0.0/8", "192.168.0.0/16"]
+            "#,
+        )
+        .unwrap();
+        let overlay: toml::Table = toml::from_str(
+            r#"
+            [core]
+            tracing_level = "INFO"
+
+            [security]
+            denylist = []
+            "#,
+        )
+        .unwrap();
+
+        merge_toml(&mut base, overlay);
+        assert_eq!(base["core"]["tracing_level"].as_str(), Some("INFO"));
+        assert_eq!(
+            base["core"]["cache_capacity_bytes"].as_integer(),
+            Some(67108864)
+        );
+        assert_eq!(
+            base["security"]["denylist"].as_array().map(|a| a.len()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn env_vars_are_expanded_in_strings() {
+        // Ortam değişkeni tanımlamak testler paralel çalıştığı için güvenli değil, her ortamda olan PATH'i kullanıyoruz
+        let path = std::env::var("PATH").unwrap();
+
+        let mut table: toml::Table = toml::from_str(
+            r#"
+            [admin]
+            token = "${PATH}"
+
+            [[api_keys]]
+            name = "partner"
+            key = "onek-${PATH}"
+            secret = "${BESINVERI_TEST_OLMAYAN}"
+            "#,
+        )
+        .unwrap();
+        let error = expand_env_vars(&mut table, "").unwrap_err().to_string();
+        assert!(error.contains("api_keys[0].secret"), "{}", error);
+        assert!(error.contains("BESINVERI_TEST_OLMAYAN"), "{}", error);
+
+        assert_eq!(
+            expand_env_vars_in_str("${PATH}", "admin.token").unwrap(),
+            path
+        );
+        assert_eq!(
+            expand_env_vars_in_str("onek-${PATH}-sonek", "x").unwrap(),
+            format!("onek-{}-sonek", path)
+        );
+        assert_eq!(
+            expand_env_vars_in_str("düz $ metin ${", "x").unwrap(),
+            "düz $ metin ${"
+        );
+    }
+
+    #[test]
+    fn legacy_cache_capacity_is_rejected() {
+        let core: CoreConfig =
+            toml::from_str("cache_capacity = 100\ntracing_level = \"INFO\"").unwrap();
+        assert_eq!(core.legacy_cache_capacity, Some(100));
+        assert_eq!(core.cache_capacity_bytes, default_cache_capacity_bytes());
+
+        let mut config = get_default_config();
+        config.core = core;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("core.cache_capacity_bytes kullanılmalı")
+        );
+        // Eski anahtar config geri yazılırken taşınmıyor
+        let written: toml::Table = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert!(written["core"].get("cache_capacity").is_none());
+    }
+
+    #[test]
+    fn timezone_is_parsed_from_iana_name() {
+        let core: CoreConfig = toml::from_str(
+            r#"
+            cache_capacity_bytes = 33554432
+            tracing_level = "INFO"
+            timezone = "Europe/Istanbul"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(core.timezone, chrono_tz::Europe::Istanbul);
+
+        let core: CoreConfig =
+            toml::from_str("cache_capacity_bytes = 33554432\ntracing_level = \"INFO\"").unwrap();
+        assert_eq!(core.timezone, Tz::UTC);
+
+        assert!(
+            toml::from_str::<CoreConfig>(
+                "cache_capacity_bytes = 33554432\ntracing_level = \"INFO\"\ntimezone = \"Mars/Olympus\""
+            )
+            .is_err()
+        );
+    }
 }
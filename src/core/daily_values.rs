@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::nutrition::{Nutrients, round};
+
+// Günlük referans alım (%DV) profili. Varsayılan profiller db/reference_intakes/default.json'da: AB'nin etiketlerde kullanılan
+// referans alımları (1169/2011 Ek XIII) ve EFSA'nın cinsiyet, yaş ve hamileliğe göre değerleri. Türkiye Beslenme Rehberi gibi
+// başka kaynakların değerleri aynı biçimde bir JSON dosyasıyla eklenebiliyor
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct ReferenceIntake {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    #[serde(default)]
+    pub(crate) source: String,
+    // Besin adı -> günlük değer, Food'daki birimlerle: A ve K vitaminleri mg; D, B12 vitaminleri, folat ve selenyum µg.
+    // Olmayan besinlerin bu profilde referansı yok
+    pub(crate) values: BTreeMap<String, f64>,
+}
+
+impl ReferenceIntake {
+    // JSON'dan gelen profiller veritabanına eklenmeden önce kontrol ediliyor, yazım hatalı bir besin adı %DV'den sessizce düşmesin
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.slug.is_empty()
+            || !self
+                .slug
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        {
+            return Err(format!(
+                "Profil adı küçük harf, rakam ve _ içermeli: {}",
+                self.slug
+            ));
+        }
+        if self.values.is_empty() {
+            return Err(format!("{} profilinin hiç değeri yok", self.slug));
+        }
+        let mut nutrients = Nutrients::default();
+        for (nutrient, amount) in &self.values {
+            if !nutrients.set(nutrient, *amount) {
+                return Err(format!("Bilinmeyen besin değeri: {}", nutrient));
+            }
+            if !amount.is_finite() || *amount <= 0.0 {
+                return Err(format!("{} için günlük değer pozitif olmalı", nutrient));
+            }
+        }
+        Ok(())
+    }
+
+    // Referansı olmayan besinler 0
+    pub(crate) fn daily_values(&self) -> Nutrients {
+        let mut nutrients = Nutrients::default();
+        for (nutrient, amount) in &self.values {
+            nutrients.set(nutrient, *amount);
+        }
+        nutrients
+    }
+}
+
+// Referansı olan besinlerin günlük değerin yüzde kaçını karşıladığı
+pub(crate) fn percent_of_daily_values(
+    nutrients: &Nutrients,
+    daily_values: &Nutrients,
+) -> BTreeMap<&'static str, f64> {
+    nutrients
+        .values()
+        .into_iter()
+        .zip(daily_values.values())
+        .filter(|(_, (_, daily_value))| *daily_value > 0.0)
+        .map(|((nutrient, value), (_, daily_value))| (nutrient, round(value / daily_value * 100.0)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(slug: &str, values: &[(&str, f64)]) -> ReferenceIntake {
+        ReferenceIntake {
+            slug: slug.to_owned(),
+            description: "Test".to_owned(),
+            source: String::new(),
+            values: values
+                .iter()
+                .map(|(nutrient, amount)| ((*nutrient).to_owned(), *amount))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_percent_of_daily_values() {
+        let nutrients = Nutrients {
+            energy: 500.0,
+            sodium: 1200.0,
+            vitamin_c: 20.0,
+            cholesterol: 300.0,
+            ..Default::default()
+        };
+        let adult = profile(
+            "adult",
+            &[("energy", 2000.0), ("sodium", 2400.0), ("vitamin_c", 80.0)],
+        );
+        let percents = percent_of_daily_values(&nutrients, &adult.daily_values());
+        assert_eq!(percents["energy"], 25.0);
+        assert_eq!(percents["sodium"], 50.0);
+        assert_eq!(percents["vitamin_c"], 25.0);
+        // Referansı olmayan besinler yok
+        assert!(!percents.contains_key("cholesterol"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            profile("adult_female", &[("iron", 16.0)])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            profile("Adult Female", &[("iron", 16.0)])
+                .validate()
+                .is_err()
+        );
+        assert!(profile("adult", &[]).validate().is_err());
+        assert!(profile("adult", &[("demir", 16.0)]).validate().is_err());
+        assert!(profile("adult", &[("iron", 0.0)]).validate().is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{food::Food, nutrition::Nutrients, str::to_search_key};
+
+// Bir diyet bayrağının (vegan, gluten_free gibi) kuralı. Yemek tüm koşulları sağlıyorsa bayrak true. Veritabanında diyet bilgisi
+// tutulmuyor, bayraklar açılışta etiketlerden, alerjenlerden ve 100 gramdaki besin değerlerinden hesaplanıyor. Etiket ve alerjen
+// adları büyük/küçük harf ve Türkçe karakter farkı gözetmeden karşılaştırılıyor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct DietFlagRule {
+    // Boş değilse yemeğin bu etiketlerden en az birine sahip olması gerekiyor
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) any_tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) excluded_tags: Vec<String>,
+    // allergen_free'deki gibi may_contain seviyesindeki alerjenler de dışlanıyor, bulaşma ihtimali olan ürün glutensiz sayılmamalı
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) excluded_allergens: Vec<String>,
+    // Besin adı -> 100 gramdaki en düşük/en yüksek değer, sınırlar dahil. Adlar Food'daki alanlarla aynı
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) min: BTreeMap<String, f64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) max: BTreeMap<String, f64>,
+}
+
+impl DietFlagRule {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.any_tags.is_empty()
+            && self.excluded_tags.is_empty()
+            && self.excluded_allergens.is_empty()
+            && self.min.is_empty()
+            && self.max.is_empty()
+        {
+            return Err("kuralın en az bir koşulu olmalı".to_owned());
+        }
+        for (nutrient, bound) in self.min.iter().chain(&self.max) {
+            if !Nutrients::default().set(nutrient, 0.0) {
+                return Err(format!("bilinmeyen besin değeri: {}", nutrient));
+            }
+            if !bound.is_finite() || *bound < 0.0 {
+                return Err(format!(
+                    "{} sınırı negatif olmayan bir sayı olmalı",
+                    nutrient
+                ));
+            }
+        }
+        for (nutrient, min) in &self.min {
+            if self.max.get(nutrient).is_some_and(|max| min > max) {
+                return Err(format!("{} için min max'tan büyük olamaz", nutrient));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn matches(&self, food: &Food) -> bool {
+        let tags: Vec<String> = food.tags.iter().map(|tag| to_search_key(tag)).collect();
+        let has_tag = |tag: &String| tags.contains(&to_search_key(tag));
+        if !self.any_tags.is_empty() && !self.any_tags.iter().any(has_tag) {
+            return false;
+        }
+        if self.excluded_tags.iter().any(has_tag) {
+            return false;
+        }
+        if food.allergens.iter().any(|allergen| {
+            self.excluded_allergens
+                .iter()
+                .any(|excluded| to_search_key(excluded) == to_search_key(&allergen.name))
+        }) {
+            return false;
+        }
+
+        let values: BTreeMap<&str, f64> = Nutrients::of(food, 100.0).values().into_iter().collect();
+        let value = |nutrient: &String| values.get(nutrient.as_str()).copied().unwrap_or(0.0);
+        self.min
+            .iter()
+            .all(|(nutrient, min)| value(nutrient) >= *min)
+            && self
+                .max
+                .iter()
+                .all(|(nutrient, max)| value(nutrient) <= *max)
+    }
+}
+
+// Yemeğin tüm bayrakları, kuralın adıyla
+pub(crate) fn evaluate(
+    rules: &BTreeMap<String, DietFlagRule>,
+    food: &Food,
+) -> BTreeMap<String, bool> {
+    rules
+        .iter()
+        .map(|(flag, rule)| (flag.clone(), rule.matches(food)))
+        .collect()
+}
+
+// Config'de diet_flags bölümü yoksa kullanılan kurallar. Hayvansal ürünler ve yumurta/süt gibi alerjenler etiketlerden ve
+// alerjenlerden çıkarılıyor, düşük sodyum eşiği core::browse::Diet::LowSodium ile aynı
+pub(crate) fn default_rules() -> BTreeMap<String, DietFlagRule> {
+    let strings = |values: &[&str]| values.iter().map(|value| (*value).to_owned()).collect();
+    let meat_tags = [
+        "et",
+        "kırmızı et",
+        "kümes hayvanı",
+        "tavuk",
+        "balık",
+        "deniz ürünü",
+        "sakatat",
+    ];
+    let seafood_allergens = ["balık", "kabuklu deniz ürünleri", "yumuşakçalar"];
+
+    BTreeMap::from([
+        (
+            "vegan".to_owned(),
+            DietFlagRule {
+                excluded_tags: strings(
+                    &[
+                        &meat_tags[..],
+                        &["hayvansal", "süt ürünleri", "yumurta", "bal"],
+                    ]
+                    .concat(),
+                ),
+                excluded_allergens: strings(
+                    &[&seafood_allergens[..], &["süt", "yumurta"]].concat(),
+                ),
+                ..Default::default()
+            },
+        ),
+        (
+            "vegetarian".to_owned(),
+            DietFlagRule {
+                excluded_tags: strings(&meat_tags),
+                excluded_allergens: strings(&seafood_allergens),
+                ..Default::default()
+            },
+        ),
+        (
+            "gluten_free".to_owned(),
+            DietFlagRule {
+                excluded_allergens: strings(&["gluten"]),
+                ..Default::default()
+            },
+        ),
+        (
+            "lactose_free".to_owned(),
+            DietFlagRule {
+                excluded_allergens: strings(&["süt", "laktoz"]),
+                ..Default::default()
+            },
+        ),
+        (
+            "low_sodium".to_owned(),
+            DietFlagRule {
+                max: BTreeMap::from([("sodium".to_owned(), 120.0)]),
+                ..Default::default()
+            },
+        ),
+    ])
+}
+
+// Bayrak adları sorgu parametresinde ve SQL'e bağlanan değerlerde kullanılıyor, sadece küçük harf, rakam ve alt çizgi
+pub(crate) fn is_valid_flag_name(flag: &str) -> bool {
+    !flag.is_empty()
+        && flag
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::food::{Allergen, ContaminationLevel};
+
+    #[test]
+    fn test_default_rules() {
+        let rules = default_rules();
+        assert!(rules.values().all(|rule| rule.validate().is_ok()));
+
+        let lentils = Food {
+            tags: vec!["Baklagil".to_owned()],
+            sodium: 6.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            evaluate(&rules, &lentils),
+            BTreeMap::from([
+                ("gluten_free".to_owned(), true),
+                ("lactose_free".to_owned(), true),
+                ("low_sodium".to_owned(), true),
+                ("vegan".to_owned(), true),
+                ("vegetarian".to_owned(), true),
+            ])
+        );
+
+        let cheese = Food {
+            tags: vec!["Süt Ürünleri".to_owned()],
+            allergens: vec![Allergen::contains("Süt")],
+            sodium: 1100.0,
+            ..Default::default()
+        };
+        let flags = evaluate(&rules, &cheese);
+        assert!(!flags["vegan"] && flags["vegetarian"] && !flags["lactose_free"]);
+        assert!(!flags["low_sodium"]);
+
+        // Eser miktarda gluten içerebilen ürün glutensiz sayılmıyor
+        let oats = Food {
+            allergens: vec![Allergen {
+                level: ContaminationLevel::MayContain,
+                ..Allergen::contains("gluten")
+            }],
+            ..Default::default()
+        };
+        assert!(!evaluate(&rules, &oats)["gluten_free"]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let rule = |min: f64, max: f64| DietFlagRule {
+            min: BTreeMap::from([("protein".to_owned(), min)]),
+            max: BTreeMap::from([("protein".to_owned(), max)]),
+            ..Default::default()
+        };
+        assert!(rule(10.0, 20.0).validate().is_ok());
+        assert!(rule(30.0, 20.0).validate().is_err());
+        assert!(DietFlagRule::default().validate().is_err());
+        assert!(
+            DietFlagRule {
+                max: BTreeMap::from([("kafein".to_owned(), 1.0)]),
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+
+        assert!(is_valid_flag_name("high_protein"));
+        assert!(!is_valid_flag_name("Vegan"));
+        assert!(!is_valid_flag_name(""));
+    }
+}
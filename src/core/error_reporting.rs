@@ -0,0 +1,74 @@
+use anyhow::Error;
+use axum::http::StatusCode;
+
+use crate::core::config::SentryConfig;
+
+// Sentry istemcisi bu değer düşürülene kadar açık kalıyor, düşürülürken kuyruktaki olaylar gönderiliyor.
+// Sunucu `sentry` özelliği olmadan derlendiyse boş bir değer
+pub(crate) struct ErrorReporting {
+    #[cfg(feature = "sentry")]
+    _guard: Option<sentry::ClientInitGuard>,
+}
+
+// 5xx hataları ve panic'ler yalnızca yerel loglarda kalmasın diye Sentry'ye raporlanıyor. Panic'ler Sentry'nin panic hook'u ile yakalanıyor,
+// istek bilgileri (method, URL, hassas olmayan header'lar, request ID) router'daki Sentry katmanları sayesinde her olaya ekleniyor
+pub(crate) fn init(config: &SentryConfig) -> Result<ErrorReporting, Error> {
+    let Some(dsn) = &config.dsn else {
+        return Ok(ErrorReporting {
+            #[cfg(feature = "sentry")]
+            _guard: None,
+        });
+    };
+
+    #[cfg(feature = "sentry")]
+    {
+        use anyhow::Context;
+
+        let dsn = dsn
+            .parse::<sentry::types::Dsn>()
+            .context("sentry.dsn geçersiz")?;
+        let guard = sentry::init(sentry::ClientOptions {
+            dsn: Some(dsn),
+            release: sentry::release_name!(),
+            environment: config.environment.clone().map(Into::into),
+            sample_rate: config.sample_rate,
+            ..Default::default()
+        });
+        tracing::info!("Hatalar Sentry'ye raporlanacak");
+        Ok(ErrorReporting {
+            _guard: Some(guard),
+        })
+    }
+
+    #[cfg(not(feature = "sentry"))]
+    {
+        let _ = dsn;
+        tracing::warn!(
+            "sentry.dsn ayarlanmış ama sunucu sentry özelliği olmadan derlenmiş, hatalar raporlanmayacak (cargo build --features sentry)"
+        );
+        Ok(ErrorReporting {})
+    }
+}
+
+// İsteğin hub'ındaki olaylara request ID'yi ekliyoruz, kullanıcının bildirdiği ID ile Sentry'de arama yapılabilsin
+pub(crate) fn set_request_id(request_id: &str) {
+    #[cfg(feature = "sentry")]
+    sentry::configure_scope(|scope| scope.set_tag("request_id", request_id));
+    #[cfg(not(feature = "sentry"))]
+    let _ = request_id;
+}
+
+// 503'leri raporlamıyoruz, yük atma ve bakım modu gibi bilerek verilen yanıtlar bunlar
+pub(crate) fn capture_server_error(status: StatusCode, message: &str) {
+    if !status.is_server_error() || status == StatusCode::SERVICE_UNAVAILABLE {
+        return;
+    }
+
+    #[cfg(feature = "sentry")]
+    sentry::with_scope(
+        |scope| scope.set_tag("status", status.as_u16()),
+        || sentry::capture_message(message, sentry::Level::Error),
+    );
+    #[cfg(not(feature = "sentry"))]
+    let _ = message;
+}
@@ -1,20 +1,55 @@
-use std::collections::{BTreeMap};
+use std::{cell::RefCell, collections::BTreeMap, sync::Arc};
 
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::{Error, FromRow, Row, sqlite::SqliteRow};
 
+use crate::core::{
+    additives::Additive,
+    nutri_score::{Grade, NutriScore},
+    nutrition,
+    str::to_search_key,
+};
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
 pub(crate) struct Food {
     // ID ve Verified değerleri JSON'dan yükleme yapılırken bulunmayabilir, okurken de bu struct'ı kullanacağımız için Option olarak kullanacağız
     pub(crate) id: Option<i64>,
     pub(crate) slug: Option<String>,
     pub(crate) description: String,
+    // Yemeğin yöresel veya başka dillerdeki adları ("aubergine"), açıklamayla birlikte aranıyor
+    #[serde(default)]
+    pub(crate) aliases: Vec<String>,
     pub(crate) verified: Option<bool>,
-    pub(crate) image_url: String,
+    // Verinin kim tarafından, ne zaman ve hangi yöntemle doğrulandığı; birden fazla doğrulama varsa en sonuncusu
+    #[serde(default)]
+    pub(crate) verification: Option<Verification>,
+    // Eski JSON dosyalarında resim sadece URL olarak image_url'de yazıyor
+    #[serde(alias = "image_url")]
+    pub(crate) image: FoodImage,
     pub(crate) source: String,
     pub(crate) tags: Vec<String>,
-    pub(crate) allergens: Vec<String>,
+    pub(crate) allergens: Vec<Allergen>,
+    // Paketli ürünlerdeki katkı maddeleri, JSON'da sadece E numaralarıyla yazılıyor
+    #[serde(default)]
+    pub(crate) additives: Vec<Additive>,
     pub(crate) servings: BTreeMap<String, f64>,
+    // Meyve ve sebzelerin mevsimi, diğer yemeklerde boş
+    #[serde(default)]
+    pub(crate) seasonality: Vec<Seasonality>,
+    // Aynı yemeğin farklı hallerini (çiğ, haşlanmış, kızartma) bağlayan grup, /food/{slug} aynı gruptakileri variants'ta döndürüyor.
+    // Eklenirken küçük harfli kebab-case'e çevriliyor, "Patates" ve "patates" aynı grup
+    #[serde(default)]
+    pub(crate) variant_group: Option<String>,
+    // Markalı ürünlerde marka ve ürünün markasız genel karşılığı, besin değerleri karşılaştırılabilsin diye. JSON'da genel
+    // yemeğin slug'ı ya da açıklaması yazılıyor, yemek eklenirken veritabanında olması gerekiyor
+    #[serde(default)]
+    pub(crate) brand: Option<String>,
+    #[serde(default)]
+    pub(crate) generic_food: Option<FoodLink>,
+    // Paketli ürünlerin barkodu, Open Food Facts'ten resim, içindekiler ve Nutri-Score çekilirken kullanılıyor
+    #[serde(default)]
+    pub(crate) gtin: Option<String>,
     pub(crate) glycemic_index: f64,
     pub(crate) energy: f64,
     pub(crate) carbohydrate: f64,
@@ -23,6 +58,11 @@ pub(crate) struct Food {
     pub(crate) saturated_fat: f64,
     pub(crate) trans_fat: f64,
     pub(crate) sugar: f64,
+    // Şekerin üretimde eklenen kısmı ve şekere dahil olmayan poliollar. Eski JSON'larda yoklar, 0 sayılıyor
+    #[serde(default)]
+    pub(crate) added_sugar: f64,
+    #[serde(default)]
+    pub(crate) polyols: f64,
     pub(crate) fiber: f64,
     pub(crate) cholesterol: f64,
     pub(crate) sodium: f64,
@@ -39,6 +79,401 @@ pub(crate) struct Food {
     pub(crate) vitamin_d: f64,
     pub(crate) vitamin_e: f64,
     pub(crate) vitamin_k: f64,
+    // Sonradan eklenen besin değerleri, eski JSON'larda yoklar ve 0 sayılıyor. Birimleri core::units::stored_unit'te
+    #[serde(default)]
+    pub(crate) omega_3: f64,
+    #[serde(default)]
+    pub(crate) omega_6: f64,
+    #[serde(default)]
+    pub(crate) folate: f64,
+    #[serde(default)]
+    pub(crate) niacin: f64,
+    #[serde(default)]
+    pub(crate) phosphorus: f64,
+    #[serde(default)]
+    pub(crate) selenium: f64,
+    #[serde(default)]
+    pub(crate) copper: f64,
+    // Besin değerlerinin neye göre verildiği, JSON'dan yüklenmiyor
+    #[serde(skip_deserializing)]
+    pub(crate) basis: Basis,
+    // Sıfır olmayan, yani ölçülmüş sayılan besin değerlerinin oranı (0-1). Veritabanında değerlerden hesaplanıyor, JSON'dan yüklenmiyor
+    #[serde(skip_deserializing)]
+    pub(crate) completeness: f64,
+    // Config'deki kurallardan hesaplanan diyet bayrakları (core::diet_flags), bayrak adı -> uyuyor mu. JSON'dan yüklenmiyor
+    #[serde(skip_deserializing)]
+    pub(crate) diet_flags: BTreeMap<String, bool>,
+    // Meyve, sebze, baklagil ve kuruyemiş oranı (yüzde), Nutri-Score için. Yazılmamışsa etiketlerden tahmin ediliyor
+    #[serde(default)]
+    pub(crate) fruit_vegetable_percent: Option<f64>,
+    // Değerlerden hesaplanan Nutri-Score (core::nutri_score), JSON'dan yüklenmiyor
+    #[serde(skip_deserializing)]
+    pub(crate) nutri_score: Option<NutriScore>,
+    // NOVA işlenme sınıfı (core::nova), 1-4. JSON'da yazılabiliyor, yanıtta yazılmamışsa tahmin edilen dönüyor
+    #[serde(default)]
+    pub(crate) nova_group: Option<u8>,
+}
+
+// Yemeğin bir doğrulaması. JSON dosyalarında da yazılabiliyor, sonradan POST /admin/foods/{slug}/verifications ile ekleniyor
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub(crate) struct Verification {
+    // Doğrulayan kişi veya kurum
+    pub(crate) verified_by: String,
+    // RFC 3339, veritabanına UTC olarak yazılıyor
+    pub(crate) verified_at: String,
+    pub(crate) method: VerificationMethod,
+    #[serde(default)]
+    pub(crate) note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum VerificationMethod {
+    // Laboratuvar analizi
+    LabData,
+    // Yayınlanmış bir kaynak veya veritabanı
+    Literature,
+    // Benzer yemeklerden veya tariften tahmin
+    Estimated,
+}
+
+impl VerificationMethod {
+    // verifications.method'da tutulan değer, JSON'dakiyle aynı
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            VerificationMethod::LabData => "lab_data",
+            VerificationMethod::Literature => "literature",
+            VerificationMethod::Estimated => "estimated",
+        }
+    }
+}
+
+impl Verification {
+    // Boşlukları kırpıyor ve tarihi UTC'ye çeviriyor ki doğrulamalar metin olarak sıralanabilsin
+    pub(crate) fn normalized(self) -> Result<Self, String> {
+        let verified_by = self.verified_by.trim().to_owned();
+        if verified_by.is_empty() {
+            return Err("Doğrulayan (verified_by) boş olamaz".to_owned());
+        }
+        let verified_at = DateTime::parse_from_rfc3339(self.verified_at.trim())
+            .map_err(|_| {
+                format!(
+                    "Doğrulama tarihi RFC 3339 biçiminde olmalı: {}",
+                    self.verified_at
+                )
+            })?
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        Ok(Verification {
+            verified_by,
+            verified_at,
+            method: self.method,
+            note: self
+                .note
+                .map(|note| note.trim().to_owned())
+                .filter(|note| !note.is_empty()),
+        })
+    }
+}
+
+// Yemekteki bir alerjen. JSON'da sadece adı da yazılabiliyor ("gluten"), o zaman yemeğin içinde olduğu varsayılıyor
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct Allergen {
+    pub(crate) name: String,
+    pub(crate) level: ContaminationLevel,
+    // "Aynı tesiste fındık işlenmektedir" gibi etiketteki açıklama
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) note: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ContaminationLevel {
+    // Alerjen yemeğin içeriğinde var
+    #[default]
+    Contains,
+    // İçeriğinde yok ama üretimde bulaşmış olabilir, etiketlerdeki "eser miktarda içerebilir"
+    MayContain,
+}
+
+impl ContaminationLevel {
+    // food_allergens.level'da tutulan değer, JSON'dakiyle aynı
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            ContaminationLevel::Contains => "contains",
+            ContaminationLevel::MayContain => "may_contain",
+        }
+    }
+}
+
+impl Allergen {
+    pub(crate) fn contains(name: &str) -> Self {
+        Allergen {
+            name: name.to_owned(),
+            level: ContaminationLevel::Contains,
+            note: None,
+        }
+    }
+}
+
+// Eski JSON dosyaları değişmeden yüklenebilsin diye alerjen hem ad hem de nesne olarak okunabiliyor
+impl<'de> Deserialize<'de> for Allergen {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Name(String),
+            Detailed {
+                name: String,
+                #[serde(default)]
+                level: ContaminationLevel,
+                #[serde(default)]
+                note: Option<String>,
+            },
+        }
+
+        Ok(match Entry::deserialize(deserializer)? {
+            Entry::Name(name) => Allergen::contains(&name),
+            Entry::Detailed { name, level, note } => Allergen {
+                name,
+                level,
+                note: note.filter(|note| !note.trim().is_empty()),
+            },
+        })
+    }
+}
+
+// Yemeğin resmi ve kullanım koşulları. Fotoğrafları atıfsız dağıtmak API kullanıcıları için hukuki bir sorun olduğundan
+// resim lisansıyla birlikte veriliyor
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub(crate) struct FoodImage {
+    // Veritabanında /images/muz.webp gibi göreli tutuluyor, yanıtlarda WithStaticUrl ile tamamlanıyor
+    #[serde(serialize_with = "serialize_image_url")]
+    pub(crate) url: String,
+    // Fotoğrafı çeken veya yayınlayan kişi/kurum, ör: "Ahmet Yılmaz / Wikimedia Commons"
+    pub(crate) attribution: Option<String>,
+    // Mümkünse SPDX kısaltması, ör: "CC-BY-SA-4.0"
+    pub(crate) license: Option<String>,
+    // Fotoğrafın alındığı orijinal sayfa
+    pub(crate) source_url: Option<String>,
+}
+
+impl FoodImage {
+    pub(crate) fn new(url: &str) -> Self {
+        FoodImage {
+            url: url.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+thread_local! {
+    // Serileştirme senkron olduğundan WithStaticUrl'in ayarladığı değer sadece o yanıtın serileştirilmesi boyunca görünüyor
+    static STATIC_URL: RefCell<Option<Arc<str>>> = const { RefCell::new(None) };
+}
+
+// Yanıttaki tüm resim URL'lerini serileştirirken static_url ile tamamlıyor: /images/muz.webp ->
+// https://api.besinveri.com/images/muz.webp. Yemekler değiştirilmediği için önbellekteki sonuçlar da olduğu gibi kullanılabiliyor
+pub(crate) struct WithStaticUrl<T> {
+    static_url: Arc<str>,
+    value: T,
+}
+
+impl<T> WithStaticUrl<T> {
+    pub(crate) fn new(static_url: Arc<str>, value: T) -> Self {
+        WithStaticUrl { static_url, value }
+    }
+}
+
+impl<T: Serialize> Serialize for WithStaticUrl<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Panic olsa da bir sonraki yanıta sızmasın diye önceki değer drop'ta geri yükleniyor
+        struct Restore(Option<Arc<str>>);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                STATIC_URL.with(|static_url| *static_url.borrow_mut() = self.0.take());
+            }
+        }
+
+        let _restore = Restore(
+            STATIC_URL.with(|static_url| static_url.borrow_mut().replace(self.static_url.clone())),
+        );
+        self.value.serialize(serializer)
+    }
+}
+
+fn serialize_image_url<S: Serializer>(url: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let static_url = STATIC_URL.with(|static_url| static_url.borrow().clone());
+    match static_url {
+        Some(static_url) if url.starts_with('/') => {
+            serializer.collect_str(&format_args!("{}{}", static_url, url))
+        }
+        _ => serializer.serialize_str(url),
+    }
+}
+
+// JSON'da resim sadece URL olarak da yazılabiliyor, o zaman atıf ve lisans bilgisi yok
+impl<'de> Deserialize<'de> for FoodImage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Url(String),
+            Detailed {
+                url: String,
+                #[serde(default)]
+                attribution: Option<String>,
+                #[serde(default)]
+                license: Option<String>,
+                #[serde(default)]
+                source_url: Option<String>,
+            },
+        }
+
+        let non_empty = |value: Option<String>| value.filter(|value| !value.trim().is_empty());
+        Ok(match Entry::deserialize(deserializer)? {
+            Entry::Url(url) => FoodImage::new(&url),
+            Entry::Detailed {
+                url,
+                attribution,
+                license,
+                source_url,
+            } => FoodImage {
+                url,
+                attribution: non_empty(attribution),
+                license: non_empty(license),
+                source_url: non_empty(source_url),
+            },
+        })
+    }
+}
+
+// Başka bir yemeğe bağlantı: aynı gruptaki başka bir hal veya markalı ürünün genel karşılığı
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct FoodLink {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+}
+
+// Listelerde yemeğin tamamı yerine dönen özet. Sadece foods tablosundan ve resimden okunuyor, etiket ve alerjen gibi alt
+// sorgular çalışmıyor
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub(crate) struct FoodSummary {
+    pub(crate) slug: String,
+    pub(crate) description: String,
+    pub(crate) image: FoodImage,
+}
+
+// JSON'da bağlantı sadece slug olarak da yazılabiliyor, açıklaması veritabanından okunurken dolduruluyor
+impl<'de> Deserialize<'de> for FoodLink {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Entry {
+            Slug(String),
+            Detailed {
+                slug: String,
+                #[serde(default)]
+                description: String,
+            },
+        }
+
+        Ok(match Entry::deserialize(deserializer)? {
+            Entry::Slug(slug) => FoodLink {
+                slug,
+                description: String::new(),
+            },
+            Entry::Detailed { slug, description } => FoodLink { slug, description },
+        })
+    }
+}
+
+// Yemeğin bir bölgede mevsiminde olduğu aylar, 1 (Ocak) ile 12 (Aralık) arası
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub(crate) struct Seasonality {
+    pub(crate) region: String,
+    pub(crate) months: Vec<u8>,
+}
+
+// Değerler veritabanında her zaman 100 gram için tutuluyor. İstemci bir porsiyon istediğinde değerler o porsiyonun gramına
+// ölçekleniyor, istemcinin hangisini aldığını tahmin etmesine gerek kalmasın diye yanıtta yazıyor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) enum Basis {
+    #[default]
+    Per100g,
+    // Yemeğin servings'indeki porsiyonun açıklaması
+    Serving(String),
+}
+
+impl Basis {
+    // ?basis= parametresi. Porsiyonun yemekte olup olmadığı yemek bulunduktan sonra set_basis'te kontrol ediliyor
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            "per_100g" => Some(Basis::Per100g),
+            s => s
+                .strip_prefix("serving:")
+                .map(str::trim)
+                .filter(|description| !description.is_empty())
+                .map(|description| Basis::Serving(description.to_owned())),
+        }
+    }
+}
+
+impl Food {
+    // Açıklama ve diğer adlar, aramada sıralanırken yemeğin sorguya en çok benzeyen adı kullanılıyor
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.description.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+
+    // Değerleri 100 gramdan istenen porsiyona ölçekliyor. Porsiyon açıklaması büyük/küçük harf ve Türkçe karakter farkı
+    // gözetmeden eşleşiyor, yanıtta yemekteki yazılışı kullanılıyor
+    pub(crate) fn set_basis(&mut self, basis: Basis) -> Result<(), String> {
+        let Basis::Serving(requested) = basis else {
+            return Ok(());
+        };
+        let Some((description, grams)) = self
+            .servings
+            .iter()
+            .find(|(description, _)| to_search_key(description) == to_search_key(&requested))
+            .map(|(description, grams)| (description.clone(), *grams))
+        else {
+            return Err(format!(
+                "Bu yemeğin {} porsiyonu yok, porsiyonlar: {}",
+                requested,
+                self.servings.keys().cloned().collect::<Vec<_>>().join(", ")
+            ));
+        };
+        nutrition::scale_food(self, grams / 100.0);
+        self.basis = Basis::Serving(description);
+        Ok(())
+    }
+}
+
+// Tek bir metin olarak yazılıyor, ?basis= parametresiyle aynı biçimde: per_100g veya serving:<açıklama>
+impl Serialize for Basis {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Basis::Per100g => serializer.serialize_str("per_100g"),
+            Basis::Serving(description) => {
+                serializer.serialize_str(&format!("serving:{}", description))
+            }
+        }
+    }
+}
+
+// GTIN-8, -12, -13 veya -14: sadece rakam ve son hane mod 10 kontrol hanesi. Sağdan başlayarak rakamlar sırayla 3 ve 1 ile
+// çarpılıyor, kontrol hanesiyle birlikte toplam 10'un katı olmalı
+pub(crate) fn is_valid_gtin(gtin: &str) -> bool {
+    if !matches!(gtin.len(), 8 | 12 | 13 | 14) || !gtin.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let sum: u32 = gtin
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| (b - b'0') as u32 * if i % 2 == 1 { 3 } else { 1 })
+        .sum();
+    sum.is_multiple_of(10)
 }
 
 impl<'r> FromRow<'r, SqliteRow> for Food {
@@ -57,20 +492,72 @@ impl<'r> FromRow<'r, SqliteRow> for Food {
         let allergens_str: String = row.try_get("allergens")?;
         let allergens = serde_json::from_str(&allergens_str).map_err(json_err)?;
 
+        let additives_str: String = row.try_get("additives")?;
+        let additives = serde_json::from_str(&additives_str).map_err(json_err)?;
+
         let servings_str: String = row.try_get("servings")?;
         let servings = serde_json::from_str(&servings_str).map_err(json_err)?;
 
+        // Doğrulaması olmayan yemeklerde alt sorgu NULL dönüyor
+        let verification = row
+            .try_get::<Option<String>, _>("verification")?
+            .map(|verification| serde_json::from_str(&verification))
+            .transpose()
+            .map_err(json_err)?;
+
+        let aliases_str: String = row.try_get("aliases")?;
+        let aliases = serde_json::from_str(&aliases_str).map_err(json_err)?;
+
+        let seasonality_str: String = row.try_get("seasonality")?;
+        let seasonality = serde_json::from_str(&seasonality_str).map_err(json_err)?;
+
+        let diet_flags_str: String = row.try_get("diet_flags")?;
+        let diet_flags = serde_json::from_str(&diet_flags_str).map_err(json_err)?;
+
+        // Hesaplanmamışsa (sınıfı bilinmeyen eski kayıtlar) ikisi de NULL
+        let nutri_score = match (
+            row.try_get::<Option<String>, _>("nutri_score")?
+                .as_deref()
+                .and_then(Grade::parse),
+            row.try_get::<Option<i64>, _>("nutri_score_points")?,
+        ) {
+            (Some(grade), Some(points)) => Some(NutriScore { grade, points }),
+            _ => None,
+        };
+
+        // Genel karşılık onaylanmamışsa JOIN'de eleniyor, kolonlar NULL geliyor
+        let generic_food = match (
+            row.try_get::<Option<String>, _>("generic_slug")?,
+            row.try_get::<Option<String>, _>("generic_description")?,
+        ) {
+            (Some(slug), Some(description)) => Some(FoodLink { slug, description }),
+            _ => None,
+        };
+
         // Son olarak struct'ımızı döndürüyoruz
         Ok(Food {
             id: Some(row.try_get("id")?),
             slug: row.try_get("slug")?,
             description: row.try_get("description")?,
-            verified, 
-            image_url: row.try_get("image_url")?,
+            aliases,
+            verified,
+            verification,
+            image: FoodImage {
+                url: row.try_get("image_url")?,
+                attribution: row.try_get("image_attribution")?,
+                license: row.try_get("image_license")?,
+                source_url: row.try_get("image_source_url")?,
+            },
             source: row.try_get("source_description")?,
             tags,
             allergens,
+            additives,
             servings,
+            seasonality,
+            variant_group: row.try_get("variant_group")?,
+            brand: row.try_get("brand")?,
+            generic_food,
+            gtin: row.try_get("gtin")?,
             glycemic_index: row.try_get("glycemic_index")?,
             energy: row.try_get("energy")?,
             carbohydrate: row.try_get("carbohydrate")?,
@@ -79,6 +566,8 @@ impl<'r> FromRow<'r, SqliteRow> for Food {
             saturated_fat: row.try_get("saturated_fat")?,
             trans_fat: row.try_get("trans_fat")?,
             sugar: row.try_get("sugar")?,
+            added_sugar: row.try_get("added_sugar")?,
+            polyols: row.try_get("polyols")?,
             fiber: row.try_get("fiber")?,
             water: row.try_get("water")?,
             cholesterol: row.try_get("cholesterol")?,
@@ -95,6 +584,160 @@ impl<'r> FromRow<'r, SqliteRow> for Food {
             vitamin_d: row.try_get("vitamin_d")?,
             vitamin_e: row.try_get("vitamin_e")?,
             vitamin_k: row.try_get("vitamin_k")?,
+            omega_3: row.try_get("omega_3")?,
+            omega_6: row.try_get("omega_6")?,
+            folate: row.try_get("folate")?,
+            niacin: row.try_get("niacin")?,
+            phosphorus: row.try_get("phosphorus")?,
+            selenium: row.try_get("selenium")?,
+            copper: row.try_get("copper")?,
+            basis: Basis::Per100g,
+            // Hesaplanan kolonun tipini sqlx sorgu planından INTEGER çıkarıyor, SQLite ise REAL döndürüyor; kontrolü atlıyoruz
+            completeness: row.try_get_unchecked("completeness")?,
+            diet_flags,
+            fruit_vegetable_percent: row.try_get("fruit_vegetable_percent")?,
+            nutri_score,
+            nova_group: row.try_get("nova_group")?,
         })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_normalized() {
+        let verification: Verification = serde_json::from_str(
+            r#"{"verified_by": "  Diyetisyen ", "verified_at": "2026-01-15T23:30:00-02:00", "method": "lab_data", "note": " "}"#,
+        )
+        .unwrap();
+        let verification = verification.normalized().unwrap();
+        assert_eq!(verification.verified_by, "Diyetisyen");
+        assert_eq!(verification.verified_at, "2026-01-16T01:30:00Z");
+        assert_eq!(verification.method, VerificationMethod::LabData);
+        assert_eq!(verification.note, None);
+
+        let invalid = |verified_by: &str, verified_at: &str| {
+            Verification {
+                verified_by: verified_by.to_owned(),
+                verified_at: verified_at.to_owned(),
+                method: VerificationMethod::Estimated,
+                note: None,
+            }
+            .normalized()
+            .is_err()
+        };
+        assert!(invalid(" ", "2026-01-15T00:00:00Z"));
+        assert!(invalid("Editör", "15.01.2026"));
+        assert!(serde_json::from_str::<VerificationMethod>(r#""guess""#).is_err());
+    }
+
+    #[test]
+    fn test_with_static_url() {
+        let images = vec![
+            FoodImage::new("/images/muz.webp"),
+            FoodImage::new("https://example.com/elma.webp"),
+        ];
+        let static_url: Arc<str> = Arc::from("https://api.besinveri.com");
+        assert_eq!(
+            serde_json::to_value(WithStaticUrl::new(static_url, &images)).unwrap()[0]["url"],
+            "https://api.besinveri.com/images/muz.webp"
+        );
+        // Sarmalayıcı dışında URL'ler olduğu gibi kalıyor, tam URL'lere de dokunulmuyor
+        let urls = serde_json::to_value(&images).unwrap();
+        assert_eq!(urls[0]["url"], "/images/muz.webp");
+        assert_eq!(urls[1]["url"], "https://example.com/elma.webp");
+    }
+
+    #[test]
+    fn test_set_basis() {
+        let mut food = Food {
+            servings: BTreeMap::from([("Adet (Orta)".to_owned(), 150.0)]),
+            glycemic_index: 40.0,
+            energy: 52.0,
+            vitamin_c: 4.6,
+            ..Default::default()
+        };
+        assert_eq!(Basis::parse("serving:"), None);
+        assert_eq!(Basis::parse("porsiyon"), None);
+        assert!(
+            food.set_basis(Basis::parse("serving:dilim").unwrap())
+                .is_err()
+        );
+
+        food.set_basis(Basis::parse("serving:adet (orta)").unwrap())
+            .unwrap();
+        assert_eq!(food.basis, Basis::Serving("Adet (Orta)".to_owned()));
+        assert_eq!(food.energy, 78.0);
+        assert_eq!(food.vitamin_c, 6.9);
+        // Glisemik indeks porsiyona göre değişmiyor
+        assert_eq!(food.glycemic_index, 40.0);
+        assert_eq!(
+            serde_json::to_value(&food).unwrap()["basis"],
+            "serving:Adet (Orta)"
+        );
+    }
+
+    #[test]
+    fn test_deserialize_image() {
+        let food: Food = serde_json::from_value(serde_json::json!({
+            "description": "Elma", "image_url": "/images/elma.webp", "source": "Test", "tags": [],
+            "allergens": [], "servings": {}, "glycemic_index": 0, "energy": 0, "carbohydrate": 0,
+            "protein": 0, "fat": 0, "saturated_fat": 0, "trans_fat": 0, "sugar": 0, "fiber": 0,
+            "cholesterol": 0, "sodium": 0, "potassium": 0, "water": 0, "iron": 0, "magnesium": 0,
+            "calcium": 0, "zinc": 0, "vitamin_a": 0, "vitamin_b6": 0, "vitamin_b12": 0, "vitamin_c": 0,
+            "vitamin_d": 0, "vitamin_e": 0, "vitamin_k": 0
+        }))
+        .unwrap();
+        // Eski JSON'lardaki image_url
+        assert_eq!(food.image, FoodImage::new("/images/elma.webp"));
+
+        let image: FoodImage = serde_json::from_str(
+            r#"{"url": "/images/elma.webp", "attribution": "Ahmet Yılmaz", "license": "CC-BY-4.0", "source_url": ""}"#,
+        )
+        .unwrap();
+        assert_eq!(image.attribution.as_deref(), Some("Ahmet Yılmaz"));
+        assert_eq!(image.source_url, None);
+        assert_eq!(
+            serde_json::to_value(&food).unwrap()["image"],
+            serde_json::json!({"url": "/images/elma.webp", "attribution": null, "license": null, "source_url": null})
+        );
+    }
+
+    #[test]
+    fn test_is_valid_gtin() {
+        assert!(is_valid_gtin("8690504001010"));
+        assert!(is_valid_gtin("96385074"));
+        assert!(is_valid_gtin("036000291452"));
+        assert!(!is_valid_gtin("8690504001019"));
+        assert!(!is_valid_gtin("869050400101"));
+        assert!(!is_valid_gtin("869050400101a"));
+        assert!(!is_valid_gtin(""));
+    }
+
+    #[test]
+    fn test_deserialize_allergens() {
+        let allergens: Vec<Allergen> = serde_json::from_str(
+            r#"["gluten", {"name": "fındık", "level": "may_contain", "note": "Aynı tesiste işlenir"}, {"name": "süt", "note": ""}]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            allergens,
+            vec![
+                Allergen::contains("gluten"),
+                Allergen {
+                    name: "fındık".to_owned(),
+                    level: ContaminationLevel::MayContain,
+                    note: Some("Aynı tesiste işlenir".to_owned()),
+                },
+                Allergen::contains("süt"),
+            ]
+        );
+        assert!(serde_json::from_str::<Allergen>(r#"{"name": "süt", "level": "iz"}"#).is_err());
+        assert_eq!(
+            serde_json::to_value(&allergens[0]).unwrap(),
+            serde_json::json!({"name": "gluten", "level": "contains"})
+        );
+    }
+}
@@ -1,3 +1,20 @@
+pub(crate) mod additives;
+pub(crate) mod browse;
+pub(crate) mod calc;
+pub(crate) mod cli;
+pub(crate) mod config;
+pub(crate) mod daily_values;
+pub(crate) mod diet_flags;
+pub(crate) mod error_reporting;
 pub(crate) mod food;
+pub(crate) mod nova;
+pub(crate) mod nutri_score;
+pub(crate) mod nutrition;
+pub(crate) mod portions;
+pub(crate) mod process;
+pub(crate) mod query;
 pub(crate) mod str;
-pub(crate) mod config;
\ No newline at end of file
+pub(crate) mod systemd;
+pub(crate) mod telemetry;
+pub(crate) mod turkomp;
+pub(crate) mod units;
@@ -0,0 +1,111 @@
+use crate::core::{food::Food, str::to_search_key};
+
+// NOVA işlenme sınıfı: 1 işlenmemiş veya az işlenmiş, 2 işlenmiş mutfak malzemesi, 3 işlenmiş, 4 ultra işlenmiş gıda. İçindekiler
+// listesi tutulmadığı için sınıf JSON'da nova_group ile yazılabiliyor, yazılmamışsa katkı maddelerinden ve etiketlerden
+// tahmin ediliyor. Tahmin edilemeyen yemeklerde sınıf yok
+pub(crate) const NOVA_GROUPS: [u8; 4] = [1, 2, 3, 4];
+
+// Ultra işlenmiş gıdalara özgü, sadece görünüş ve lezzet için eklenen katkı sınıfları (core::additives::Additive::category)
+const COSMETIC_ADDITIVE_CATEGORIES: [&str; 8] = [
+    "renklendirici",
+    "tatlandirici",
+    "aroma arttirici",
+    "emulgator",
+    "kivam arttirici",
+    "jellestirici",
+    "kopuk onleyici",
+    "modifiye nisasta",
+];
+const CULINARY_INGREDIENT_TAGS: [&str; 7] = [
+    "yag", "sivi yag", "tereyagi", "seker", "tuz", "bal", "sirke",
+];
+const WHOLE_FOOD_TAGS: [&str; 10] = [
+    "meyve",
+    "sebze",
+    "baklagil",
+    "kuruyemis",
+    "tahil",
+    "et",
+    "kumes hayvani",
+    "balik",
+    "yumurta",
+    "sut",
+];
+
+// declared JSON'da yazılan sınıf, foods.nova_group_declared
+pub(crate) fn classify(food: &Food, declared: Option<u8>) -> Option<u8> {
+    if let Some(declared) = declared.filter(|group| NOVA_GROUPS.contains(group)) {
+        return Some(declared);
+    }
+
+    let additive_categories: Vec<String> = food
+        .additives
+        .iter()
+        .map(|additive| to_search_key(&additive.category))
+        .collect();
+    if additive_categories
+        .iter()
+        .any(|category| COSMETIC_ADDITIVE_CATEGORIES.contains(&category.as_str()))
+    {
+        return Some(4);
+    }
+    if !additive_categories.is_empty() {
+        return Some(3);
+    }
+
+    let tags: Vec<String> = food.tags.iter().map(|tag| to_search_key(tag)).collect();
+    let has_any_tag =
+        |candidates: &[&str]| tags.iter().any(|tag| candidates.contains(&tag.as_str()));
+    if has_any_tag(&CULINARY_INGREDIENT_TAGS) {
+        Some(2)
+    } else if has_any_tag(&WHOLE_FOOD_TAGS) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::additives::Additive;
+
+    #[test]
+    fn test_classify() {
+        let additive = |category: &str| Additive {
+            e_number: "E000".to_owned(),
+            name: String::new(),
+            category: category.to_owned(),
+            notes: None,
+        };
+        let food = |tags: &[&str], additives: Vec<Additive>| Food {
+            tags: tags.iter().map(|tag| (*tag).to_owned()).collect(),
+            additives,
+            ..Default::default()
+        };
+
+        assert_eq!(classify(&food(&["Meyve"], Vec::new()), None), Some(1));
+        assert_eq!(classify(&food(&["Meyve"], Vec::new()), Some(3)), Some(3));
+        // Geçersiz yazılmış sınıf yok sayılıyor
+        assert_eq!(classify(&food(&["Meyve"], Vec::new()), Some(7)), Some(1));
+        assert_eq!(
+            classify(&food(&["sıvı yağ", "sebze"], Vec::new()), None),
+            Some(2)
+        );
+        assert_eq!(
+            classify(&food(&["meyve"], vec![additive("koruyucu")]), None),
+            Some(3)
+        );
+        assert_eq!(
+            classify(
+                &food(
+                    &["içecek"],
+                    vec![additive("koruyucu"), additive("Tatlandırıcı")]
+                ),
+                None
+            ),
+            Some(4)
+        );
+        assert_eq!(classify(&food(&["ana öğün"], Vec::new()), None), None);
+    }
+}
@@ -0,0 +1,299 @@
+use serde::Serialize;
+
+use crate::core::{food::Food, str::to_search_key};
+
+// Nutri-Score (2017 algoritması), 100 gram için besin değerlerinden A (en iyi) ile E arasında bir sınıf. Yağ, şeker, doymuş yağ
+// ve sodyum puan ekliyor; meyve/sebze oranı, lif ve protein puan düşürüyor. Sınıf yemeğin türüne göre farklı hesaplanıyor, tür
+// etiketlerden çıkarılıyor
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Grade {
+    A,
+    B,
+    C,
+    D,
+    E,
+}
+
+impl Grade {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_uppercase().as_str() {
+            "A" => Some(Grade::A),
+            "B" => Some(Grade::B),
+            "C" => Some(Grade::C),
+            "D" => Some(Grade::D),
+            "E" => Some(Grade::E),
+            _ => None,
+        }
+    }
+
+    // foods.nutri_score'da tutulan değer, JSON'dakiyle aynı
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Grade::A => "A",
+            Grade::B => "B",
+            Grade::C => "C",
+            Grade::D => "D",
+            Grade::E => "E",
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct NutriScore {
+    pub(crate) grade: Grade,
+    // Düşük puan daha iyi, aynı sınıftaki yemekleri sıralamak için
+    pub(crate) points: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Category {
+    General,
+    Cheese,
+    Fat,
+    Beverage,
+    Water,
+}
+
+const BEVERAGE_TAGS: [&str; 1] = ["icecek"];
+const CHEESE_TAGS: [&str; 1] = ["peynir"];
+const FAT_TAGS: [&str; 4] = ["yag", "sivi yag", "tereyagi", "margarin"];
+// JSON'da fruit_vegetable_percent yoksa bu etiketlerden biri olan yemekler tamamen meyve/sebze sayılıyor
+const FRUIT_VEGETABLE_TAGS: [&str; 4] = ["meyve", "sebze", "baklagil", "kuruyemis"];
+
+fn has_any_tag(food: &Food, tags: &[&str]) -> bool {
+    food.tags
+        .iter()
+        .any(|tag| tags.contains(&to_search_key(tag).as_str()))
+}
+
+fn category(food: &Food) -> Category {
+    if has_any_tag(food, &BEVERAGE_TAGS) {
+        if to_search_key(&food.description) == "su" {
+            Category::Water
+        } else {
+            Category::Beverage
+        }
+    } else if has_any_tag(food, &CHEESE_TAGS) {
+        Category::Cheese
+    } else if has_any_tag(food, &FAT_TAGS) {
+        Category::Fat
+    } else {
+        Category::General
+    }
+}
+
+// Meyve, sebze, baklagil ve kuruyemiş oranı, yüzde
+pub(crate) fn fruit_vegetable_percent(food: &Food) -> f64 {
+    food.fruit_vegetable_percent.unwrap_or_else(|| {
+        if has_any_tag(food, &FRUIT_VEGETABLE_TAGS) {
+            100.0
+        } else {
+            0.0
+        }
+    })
+}
+
+// Değerin aştığı eşik sayısı
+fn points(value: f64, thresholds: &[f64]) -> i64 {
+    thresholds
+        .iter()
+        .filter(|threshold| value > **threshold)
+        .count() as i64
+}
+
+pub(crate) fn compute(food: &Food) -> NutriScore {
+    let category = category(food);
+    if category == Category::Water {
+        return NutriScore {
+            grade: Grade::A,
+            points: 0,
+        };
+    }
+
+    let energy_kj = food.energy * 4.184;
+    let beverage = category == Category::Beverage;
+    let energy = if beverage {
+        points(
+            energy_kj,
+            &[
+                0.0, 30.0, 60.0, 90.0, 120.0, 150.0, 180.0, 210.0, 240.0, 270.0,
+            ],
+        )
+    } else {
+        points(
+            energy_kj,
+            &[
+                335.0, 670.0, 1005.0, 1340.0, 1675.0, 2010.0, 2345.0, 2680.0, 3015.0, 3350.0,
+            ],
+        )
+    };
+    let sugar = if beverage {
+        points(
+            food.sugar,
+            &[0.0, 1.5, 3.0, 4.5, 6.0, 7.5, 9.0, 10.5, 12.0, 13.5],
+        )
+    } else {
+        points(
+            food.sugar,
+            &[4.5, 9.0, 13.5, 18.0, 22.5, 27.0, 31.0, 36.0, 40.0, 45.0],
+        )
+    };
+    // Yağlarda doymuş yağın miktarı yerine toplam yağa oranı sayılıyor
+    let saturated_fat = if category == Category::Fat {
+        let ratio = if food.fat > 0.0 {
+            food.saturated_fat / food.fat * 100.0
+        } else {
+            0.0
+        };
+        // Oran eşiklerinde sınır değer üst puana dahil
+        [10.0, 16.0, 22.0, 28.0, 34.0, 40.0, 46.0, 52.0, 58.0, 64.0]
+            .iter()
+            .filter(|threshold| ratio >= **threshold)
+            .count() as i64
+    } else {
+        points(
+            food.saturated_fat,
+            &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0],
+        )
+    };
+    let sodium = points(
+        food.sodium,
+        &[
+            90.0, 180.0, 270.0, 360.0, 450.0, 540.0, 630.0, 720.0, 810.0, 900.0,
+        ],
+    );
+    let negative = energy + sugar + saturated_fat + sodium;
+
+    let percent = fruit_vegetable_percent(food);
+    let (fruit_vegetable, max_fruit_vegetable) = if beverage {
+        (
+            points(percent, &[40.0, 60.0]) * 2 + (percent > 80.0) as i64 * 6,
+            10,
+        )
+    } else {
+        (
+            points(percent, &[40.0, 60.0]) + (percent > 80.0) as i64 * 3,
+            5,
+        )
+    };
+    let fiber = points(food.fiber, &[0.9, 1.9, 2.8, 3.7, 4.7]);
+    let protein = points(food.protein, &[1.6, 3.2, 4.8, 6.4, 8.0]);
+    // Olumsuz puanı yüksek yemeklerde protein, meyve/sebze oranı tam puan almadıkça sayılmıyor; peynirlerde her zaman sayılıyor
+    let counts_protein =
+        negative < 11 || fruit_vegetable == max_fruit_vegetable || category == Category::Cheese;
+    let score = negative - fruit_vegetable - fiber - if counts_protein { protein } else { 0 };
+
+    let grade = if beverage {
+        match score {
+            ..=1 => Grade::B,
+            2..=5 => Grade::C,
+            6..=9 => Grade::D,
+            _ => Grade::E,
+        }
+    } else {
+        match score {
+            ..=-1 => Grade::A,
+            0..=2 => Grade::B,
+            3..=10 => Grade::C,
+            11..=18 => Grade::D,
+            _ => Grade::E,
+        }
+    };
+    NutriScore {
+        grade,
+        points: score,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        // 52 kcal, 10.4 g şeker: olumsuz 2 puan, meyve 5 ve lif 2 puan düşürüyor
+        let apple = Food {
+            description: "Elma".to_owned(),
+            tags: vec!["Meyve".to_owned()],
+            energy: 52.0,
+            sugar: 10.4,
+            fiber: 2.4,
+            protein: 0.3,
+            sodium: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            compute(&apple),
+            NutriScore {
+                grade: Grade::A,
+                points: -5,
+            }
+        );
+
+        // Olumsuz puanı 11'i geçtiği için protein sayılmıyor
+        let biscuit = Food {
+            description: "Bisküvi".to_owned(),
+            energy: 480.0,
+            sugar: 25.0,
+            saturated_fat: 9.5,
+            sodium: 400.0,
+            fiber: 2.0,
+            protein: 7.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            compute(&biscuit),
+            NutriScore {
+                grade: Grade::E,
+                points: 21,
+            }
+        );
+        // Peynirde sayılıyor
+        let cheese = Food {
+            tags: vec!["peynir".to_owned()],
+            ..biscuit.clone()
+        };
+        assert_eq!(compute(&cheese).points, 17);
+
+        let cola = Food {
+            description: "Kola".to_owned(),
+            tags: vec!["İçecek".to_owned()],
+            energy: 42.0,
+            sugar: 10.6,
+            ..Default::default()
+        };
+        assert_eq!(compute(&cola).grade, Grade::E);
+        let water = Food {
+            description: "Su".to_owned(),
+            ..cola
+        };
+        assert_eq!(compute(&water).grade, Grade::A);
+
+        // Zeytinyağında doymuş yağın yağa oranı %14
+        let olive_oil = Food {
+            description: "Zeytinyağı".to_owned(),
+            tags: vec!["yağ".to_owned()],
+            energy: 884.0,
+            fat: 100.0,
+            saturated_fat: 14.0,
+            sodium: 2.0,
+            fruit_vegetable_percent: Some(100.0),
+            ..Default::default()
+        };
+        assert_eq!(
+            compute(&olive_oil),
+            NutriScore {
+                grade: Grade::C,
+                points: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_grade() {
+        assert_eq!(Grade::parse("b"), Some(Grade::B));
+        assert_eq!(Grade::parse(" E "), Some(Grade::E));
+        assert_eq!(Grade::parse("F"), None);
+        assert!(Grade::A < Grade::E);
+    }
+}
@@ -0,0 +1,186 @@
+use serde::Serialize;
+
+use crate::core::food::Food;
+
+// Besin değerleri Food'da 100 gram için tutuluyor. Tarif ve öğün gibi birden fazla yemekten oluşan şeylerin değerleri,
+// her yemeğin kullanılan gramına göre ölçeklenip toplanarak bulunuyor. Glisemik indeks toplanamıyor, ayrıca hesaplanıyor
+macro_rules! nutrients {
+    ($($field:ident),* $(,)?) => {
+        #[derive(Serialize, Debug, Clone, Copy, Default, PartialEq)]
+        pub(crate) struct Nutrients {
+            pub(crate) glycemic_index: f64,
+            $(pub(crate) $field: f64,)*
+        }
+
+        impl Nutrients {
+            // Yemeğin verilen gramındaki değerler
+            pub(crate) fn of(food: &Food, grams: f64) -> Self {
+                Self {
+                    glycemic_index: food.glycemic_index,
+                    $($field: food.$field * grams / 100.0,)*
+                }
+            }
+
+            fn add(&mut self, other: &Nutrients) {
+                $(self.$field += other.$field;)*
+            }
+
+            pub(crate) fn scaled(&self, factor: f64) -> Self {
+                Self {
+                    glycemic_index: self.glycemic_index,
+                    $($field: self.$field * factor,)*
+                }
+            }
+
+            // Değerleri yemeğe yazıyor, glisemik indeks dahil
+            pub(crate) fn apply_to(&self, food: &mut Food) {
+                food.glycemic_index = self.glycemic_index;
+                $(food.$field = self.$field;)*
+            }
+
+            // Glisemik indeks hariç (besin adı, değer) çiftleri, Food'daki sırayla
+            pub(crate) fn values(&self) -> Vec<(&'static str, f64)> {
+                vec![$((stringify!($field), self.$field),)*]
+            }
+
+            // Adıyla bir değeri ayarlıyor, glisemik indeks ve bilinmeyen adlar için false
+            pub(crate) fn set(&mut self, nutrient: &str, value: f64) -> bool {
+                match nutrient {
+                    $(stringify!($field) => self.$field = value,)*
+                    _ => return false,
+                }
+                true
+            }
+
+            // Yuvarlama sadece yanıt verilirken bir kere yapılmalı, ara toplamları yuvarlamak hataları biriktiriyor
+            pub(crate) fn rounded(&self) -> Self {
+                Self {
+                    glycemic_index: round(self.glycemic_index),
+                    $($field: round(self.$field),)*
+                }
+            }
+        }
+
+        // Yemeğin kendi değerlerini yerinde ölçekliyor, glisemik indeks miktara göre değişmiyor
+        pub(crate) fn scale_food(food: &mut Food, factor: f64) {
+            $(food.$field = round(food.$field * factor);)*
+        }
+    };
+}
+
+nutrients!(
+    energy,
+    carbohydrate,
+    protein,
+    fat,
+    saturated_fat,
+    trans_fat,
+    sugar,
+    added_sugar,
+    polyols,
+    fiber,
+    cholesterol,
+    sodium,
+    potassium,
+    water,
+    iron,
+    magnesium,
+    calcium,
+    zinc,
+    vitamin_a,
+    vitamin_b6,
+    vitamin_b12,
+    vitamin_c,
+    vitamin_d,
+    vitamin_e,
+    vitamin_k,
+    omega_3,
+    omega_6,
+    folate,
+    niacin,
+    phosphorus,
+    selenium,
+    copper,
+);
+
+pub(crate) fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// Yemekler gramlarıyla eklendikçe toplam değerleri tutuyor
+#[derive(Default)]
+pub(crate) struct NutritionSum {
+    nutrients: Nutrients,
+    grams: f64,
+    // Glisemik indeks karbonhidrat ağırlıklı ortalama: her yemeğin indeksi, öğündeki karbonhidrata katkısı kadar sayılıyor
+    weighted_glycemic_index: f64,
+}
+
+impl NutritionSum {
+    pub(crate) fn add(&mut self, food: &Food, grams: f64) {
+        let nutrients = Nutrients::of(food, grams);
+        self.weighted_glycemic_index += food.glycemic_index * nutrients.carbohydrate;
+        self.nutrients.add(&nutrients);
+        self.grams += grams;
+    }
+
+    pub(crate) fn grams(&self) -> f64 {
+        self.grams
+    }
+
+    // Karbonhidrat yoksa glisemik indeks anlamsız, 0
+    pub(crate) fn nutrients(&self) -> Nutrients {
+        Nutrients {
+            glycemic_index: if self.nutrients.carbohydrate > 0.0 {
+                self.weighted_glycemic_index / self.nutrients.carbohydrate
+            } else {
+                0.0
+            },
+            ..self.nutrients
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nutrition_sum() {
+        let rice = Food {
+            glycemic_index: 70.0,
+            energy: 130.0,
+            carbohydrate: 28.0,
+            protein: 2.7,
+            ..Default::default()
+        };
+        let chicken = Food {
+            glycemic_index: 0.0,
+            energy: 165.0,
+            protein: 31.0,
+            ..Default::default()
+        };
+        let lentil = Food {
+            glycemic_index: 30.0,
+            energy: 116.0,
+            carbohydrate: 20.0,
+            protein: 9.0,
+            ..Default::default()
+        };
+
+        let mut sum = NutritionSum::default();
+        sum.add(&rice, 200.0);
+        sum.add(&chicken, 150.0);
+        sum.add(&lentil, 100.0);
+        let nutrients = sum.nutrients().rounded();
+        assert_eq!(sum.grams(), 450.0);
+        assert_eq!(nutrients.energy, 623.5);
+        assert_eq!(nutrients.protein, 60.9);
+        assert_eq!(nutrients.carbohydrate, 76.0);
+        // (70 * 56 + 30 * 20) / 76, tavuğun karbonhidratı olmadığı için indeksi sayılmıyor
+        assert_eq!(nutrients.glycemic_index, 59.47);
+        assert_eq!(sum.nutrients().scaled(0.5).rounded().energy, 311.75);
+
+        assert_eq!(NutritionSum::default().nutrients(), Nutrients::default());
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+// Bir yemek kategorisinde kullanılan ev ölçüleri ve yaklaşık gramajları. Varsayılanlar db/standard_portions/default.json'da,
+// kategori yemek etiketleriyle eşleşiyor: "meyve" etiketli her yemek meyve ölçülerini alıyor
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct PortionCategory {
+    pub(crate) category: String,
+    // Ölçü açıklaması -> gram, ör: "1 su bardağı" -> 200
+    pub(crate) portions: BTreeMap<String, f64>,
+}
+
+impl PortionCategory {
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        if self.category.trim().is_empty() {
+            return Err("Ölçü kategorisi boş olamaz".to_owned());
+        }
+        if self.portions.is_empty() {
+            return Err(format!("{} kategorisinin hiç ölçüsü yok", self.category));
+        }
+        for (description, weight) in &self.portions {
+            if description.trim().is_empty() {
+                return Err(format!("{} kategorisinde boş bir ölçü var", self.category));
+            }
+            if !weight.is_finite() || *weight <= 0.0 {
+                return Err(format!(
+                    "{} kategorisindeki {} ölçüsünün gramajı pozitif olmalı",
+                    self.category, description
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Bir yemeğe uygulanabilen standart ölçü, hangi etiketinden geldiğiyle birlikte
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub(crate) struct StandardPortion {
+    pub(crate) description: String,
+    pub(crate) weight: f64,
+    pub(crate) category: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let category = |category: &str, portions: &[(&str, f64)]| PortionCategory {
+            category: category.to_owned(),
+            portions: portions
+                .iter()
+                .map(|(description, weight)| ((*description).to_owned(), *weight))
+                .collect(),
+        };
+        assert!(
+            category("meyve", &[("1 adet (orta)", 150.0)])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            category(" ", &[("1 adet (orta)", 150.0)])
+                .validate()
+                .is_err()
+        );
+        assert!(category("meyve", &[]).validate().is_err());
+        assert!(category("meyve", &[("1 avuç", 0.0)]).validate().is_err());
+        assert!(
+            category("meyve", &[("1 avuç", f64::NAN)])
+                .validate()
+                .is_err()
+        );
+    }
+}
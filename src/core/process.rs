@@ -0,0 +1,56 @@
+use std::{fs, path::PathBuf, process};
+
+use anyhow::{Context, Error};
+use tracing::warn;
+
+// runit, OpenRC gibi init sistemleri servisi PID dosyası üzerinden takip edebiliyor.
+// Dosya oluşturulduğunda sürecin PID'i yazılıyor, değer düşürüldüğünde (kapanışta) dosya siliniyor
+pub(crate) struct PidFile {
+    path: PathBuf,
+}
+
+impl PidFile {
+    pub(crate) fn create(path: impl Into<PathBuf>) -> Result<Self, Error> {
+        let path = path.into();
+        if let Ok(old_pid) = fs::read_to_string(&path) {
+            // Düzgün kapanmamış bir önceki sürecin dosyası kalmış olabilir, üzerine yazıyoruz
+            warn!(
+                "{} zaten var (PID {}), üzerine yazılıyor",
+                path.display(),
+                old_pid.trim()
+            );
+        }
+        fs::write(&path, format!("{}\n", process::id()))
+            .with_context(|| format!("PID dosyası yazılamadı: {}", path.display()))?;
+        Ok(Self { path })
+    }
+}
+
+impl Drop for PidFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            warn!("PID dosyası silinemedi ({}): {}", self.path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_pid_file_is_removed_on_drop() {
+        let path = env::temp_dir().join(format!("besinveri-test-{}.pid", process::id()));
+
+        let pid_file = PidFile::create(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            format!("{}\n", process::id())
+        );
+
+        drop(pid_file);
+        assert!(!path.exists());
+    }
+}
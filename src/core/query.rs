@@ -0,0 +1,94 @@
+use crate::core::str::to_search_key;
+
+// Arama sorgusunun açıklamada geçmesi gereken parçaları: tırnak içindeki ifadeler ("tavuk göğsü") olduğu gibi, geri kalan kelimeler
+// tek tek aranıyor. Kelimelerin hepsi geçmeli ama sırası ve yan yana olması önemli değil; tavuk göğsü araması "Göğsü Tavuk" da buluyor.
+// Parçalar to_search_key ile normalize ediliyor, veritabanındaki search_description kolonlarıyla karşılaştırılabiliyorlar
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SearchQuery {
+    pub(crate) terms: Vec<String>,
+}
+
+impl SearchQuery {
+    pub(crate) fn parse(query: &str) -> Self {
+        let mut terms: Vec<String> = Vec::new();
+        let mut push = |term: String| {
+            if !term.is_empty() && !terms.contains(&term) {
+                terms.push(term);
+            }
+        };
+
+        // Telefon klavyeleri düz tırnak yerine “ ” gönderebiliyor, ikisini de kabul ediyoruz. Kapanmayan tırnak sorgunun sonuna kadar sürüyor
+        for (i, part) in query.split(['"', '“', '”']).enumerate() {
+            if i % 2 == 1 {
+                push(to_search_key(
+                    &part.split_whitespace().collect::<Vec<&str>>().join(" "),
+                ));
+                continue;
+            }
+            for word in part.split_whitespace() {
+                // "tavuk," veya "(çiğ)" gibi kelimelerin etrafındaki noktalama işaretleri eşleşmeyi bozmasın
+                push(to_search_key(
+                    word.trim_matches(|c: char| !c.is_alphanumeric()),
+                ));
+            }
+        }
+
+        Self { terms }
+    }
+
+    // Eş anlamlılar gibi bölünmemesi gereken terimler için tek parçalı sorgu
+    pub(crate) fn phrase(phrase: &str) -> Self {
+        Self {
+            terms: vec![to_search_key(phrase)],
+        }
+    }
+
+    // Sıralama ve eş anlamlı araması için tırnaksız, normalize edilmiş sorgu
+    pub(crate) fn text(&self) -> String {
+        self.terms.join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(query: &str) -> Vec<String> {
+        SearchQuery::parse(query).terms
+    }
+
+    #[test]
+    fn test_parse_tokens_and_phrases() {
+        assert_eq!(terms("tavuk göğüs"), vec!["tavuk", "gogus"]);
+        assert_eq!(terms("\"tavuk göğüs\""), vec!["tavuk gogus"]);
+        assert_eq!(
+            terms("“Tavuk   Göğsü” haşlanmış"),
+            vec!["tavuk gogsu", "haslanmis"]
+        );
+        // Kapanmayan tırnak sonuna kadar ifade sayılıyor
+        assert_eq!(terms("ızgara \"tavuk göğsü"), vec!["izgara", "tavuk gogsu"]);
+        assert_eq!(terms("\"\" elma"), vec!["elma"]);
+        assert!(terms("\"  \"").is_empty());
+    }
+
+    #[test]
+    fn test_parse_turkish_tokens() {
+        // Büyük İ ve I, Türkçe harfler ve noktalama
+        assert_eq!(terms("İZMİR KÖFTE"), vec!["izmir", "kofte"]);
+        assert_eq!(terms("ISPANAK, çiğ"), vec!["ispanak", "cig"]);
+        assert_eq!(terms("Tavuk Göğsü (Çiğ)"), vec!["tavuk", "gogsu", "cig"]);
+        // Kelime içindeki kesme işareti korunuyor
+        assert_eq!(terms("Anne'nin köftesi"), vec!["anne'nin", "koftesi"]);
+        // Tekrarlanan kelimeler tek sayılıyor
+        assert_eq!(terms("süt SÜT sut"), vec!["sut"]);
+    }
+
+    #[test]
+    fn test_text_and_phrase() {
+        assert_eq!(
+            SearchQuery::parse("\"tavuk göğsü\" haşlanmış").text(),
+            "tavuk gogsu haslanmis"
+        );
+        assert_eq!(SearchQuery::phrase("Cin Mısırı").terms, vec!["cin misiri"]);
+    }
+}
@@ -1,7 +1,20 @@
+use std::cmp::Ordering;
+
+use unicode_normalization::UnicodeNormalization;
+
+// Slug'lar URL'lerde ve veritabanında kalıcı, bu dönüşüm değişirse var olan bağlantılar kırılıyor. "İ" önce küçültüldüğü için
+// slug'da noktalı i (i + U+0307) kalıyor; aramalardaki düzeltme sadece to_search_key'de
 pub(crate) fn to_lower_en_kebab_case(s: &str) -> String {
     convert_tr_chars_to_en(&to_kebab_case(&s.to_lowercase()))
 }
 
+// Aramalarda karşılaştırılan biçim: Türkçe harfler İngilizce karşılıklarına çevrilip küçük harfe indiriliyor, "SIĞIR" ve "sigir" -> "sigir".
+// Önce çevirip sonra küçültüyoruz, "İ".to_lowercase() noktalı bir i (i + U+0307) döndürüyor.
+// Veritabanındaki search_description kolonları migration'daki SQL ifadesiyle aynı dönüşümü yapıyor, biri değişirse diğeri de değişmeli
+pub(crate) fn to_search_key(s: &str) -> String {
+    convert_tr_chars_to_en(s).to_lowercase()
+}
+
 pub(crate) fn to_kebab_case(s: &str) -> String {
     s.to_lowercase()
         .replace("(", "") // Bazı yemekler "Tavuk Göğsü (Çiğ)" gibi, slug'larda parantez olmasını istemiyoruz
@@ -40,6 +53,88 @@ pub(crate) fn convert_tr_chars_to_en(s: &str) -> String {
         .collect()
 }
 
+// Bazı klavyeler ve kopyala-yapıştır "ç" gibi harfleri "c" + birleşik çengel olarak gönderiyor, veritabanındaki isimlerle eşleşmesi için NFC'ye çeviriyoruz
+pub(crate) fn normalize_input(s: &str) -> String {
+    s.trim().nfc().collect()
+}
+
+// Sıfır genişlikli ve yön değiştiren karakterler ekranda görünmüyor, aynı görünen farklı sorgular üretmek için kullanılabiliyorlar
+pub(crate) fn is_invisible_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200B}'..='\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2060}'..='\u{2069}' | '\u{FEFF}'
+    )
+}
+
+// PostgreSQL'in pg_trgm eklentisi gibi: her kelimenin başına iki, sonuna bir boşluk eklenip üçlü harf grupları çıkarılıyor.
+// Baştaki boşluklar sayesinde kelime başları daha çok ağırlık taşıyor, "elm" Elma ile Kelime'den daha çok eşleşiyor.
+// Gruplar sıralı ve tekil tutuluyor, aramada binlerce açıklamayla karşılaştırıldığı için HashSet'ten hızlı
+#[derive(Default)]
+pub(crate) struct Trigrams(Vec<[char; 3]>);
+
+impl Trigrams {
+    pub(crate) fn new(s: &str) -> Self {
+        let mut trigrams = Self::default();
+        trigrams.set(s);
+        trigrams
+    }
+
+    // Üçlüleri s'ninkilerle değiştiriyor, aynı Trigrams birçok metin için kullanılınca liste tekrar ayrılmıyor
+    pub(crate) fn set(&mut self, s: &str) {
+        self.0.clear();
+        for word in s
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            // "  " + kelime + " " üzerinde kayan 3 harflik pencere
+            let mut window = [' '; 3];
+            for c in word.chars().chain([' ']) {
+                window = [window[1], window[2], c];
+                self.0.push(window);
+            }
+        }
+        self.0.sort_unstable();
+        self.0.dedup();
+    }
+
+    // Üçlü harf gruplarının Jaccard benzerliği, 0.0 ile 1.0 arası. Kelime sırası sonucu değiştirmiyor
+    pub(crate) fn similarity(&self, other: &Trigrams) -> f64 {
+        let (mut i, mut j, mut shared) = (0, 0, 0);
+        while i < self.0.len() && j < other.0.len() {
+            match self.0[i].cmp(&other.0[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    shared += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        let union = self.0.len() + other.0.len() - shared;
+        if union == 0 {
+            return 0.0;
+        }
+        shared as f64 / union as f64
+    }
+}
+
+// Sorgunun açıklamaya en az kaç harf farkıyla (ekleme, silme, değiştirme veya yer değiştirme) uyduğu. Sorgu, açıklamanın
+// aynı sayıda kelimeden oluşan her parçasıyla da karşılaştırılıyor ki "portakl" "Portakal Suyu"na da yakın olsun
+pub(crate) fn fuzzy_distance(query: &str, description: &str) -> usize {
+    let words: Vec<&str> = description.split_whitespace().collect();
+    let window = query
+        .split_whitespace()
+        .count()
+        .clamp(1, words.len().max(1));
+    words
+        .windows(window)
+        .map(|words| strsim::osa_distance(query, &words.join(" ")))
+        .chain(std::iter::once(strsim::osa_distance(query, description)))
+        .min()
+        .unwrap_or(usize::MAX)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +161,62 @@ mod tests {
         assert_eq!(to_lower_en_kebab_case("Çay   Kahve"), "cay-kahve");
     }
 
+    #[test]
+    fn test_slug_of_dotted_capital_i_is_frozen() {
+        // Yayınlanmış slug'lar bu çıktıya göre üretildi, arama normalizasyonu slug'ları değiştirmemeli
+        assert_eq!(to_lower_en_kebab_case("İncir"), "i\u{0307}ncir");
+        assert_eq!(
+            to_lower_en_kebab_case("İNCE DİLİM"),
+            "i\u{0307}nce-di\u{0307}li\u{0307}m"
+        );
+        assert_eq!(to_search_key("İncir"), "incir");
+    }
+
+    #[test]
+    fn test_to_search_key() {
+        assert_eq!(to_search_key("Süt"), "sut");
+        assert_eq!(to_search_key("SIĞIR"), "sigir");
+        assert_eq!(to_search_key("sığır"), "sigir");
+        assert_eq!(to_search_key("İnce Dilim"), "ince dilim");
+        assert_eq!(to_search_key("Fuji Elma"), "fuji elma");
+    }
+
+    fn trigram_similarity(a: &str, b: &str) -> f64 {
+        Trigrams::new(a).similarity(&Trigrams::new(b))
+    }
+
+    #[test]
+    fn test_trigram_similarity() {
+        assert_eq!(trigram_similarity("elma", "elma"), 1.0);
+        assert_eq!(trigram_similarity("elma fuji", "fuji elma"), 1.0);
+        assert_eq!(trigram_similarity("", ""), 0.0);
+        assert_eq!(trigram_similarity("elma", "muz"), 0.0);
+        // Kelime başıyla eşleşen, ortasında geçenden daha benzer
+        assert!(trigram_similarity("kar", "karpuz") > trigram_similarity("kar", "makarna"));
+        // Kısa açıklama, sorguyu içeren uzun açıklamadan daha benzer
+        assert!(
+            trigram_similarity("elma", "fuji elma")
+                > trigram_similarity("elma", "fuji elma turtasi")
+        );
+    }
+
+    #[test]
+    fn test_trigrams_set_replaces_previous() {
+        let mut trigrams = Trigrams::new("fuji elma turtasi");
+        trigrams.set("elma");
+        assert_eq!(
+            trigrams.0,
+            vec![
+                [' ', ' ', 'e'],
+                [' ', 'e', 'l'],
+                ['e', 'l', 'm'],
+                ['l', 'm', 'a'],
+                ['m', 'a', ' ']
+            ]
+        );
+        assert_eq!(trigrams.similarity(&Trigrams::new("elma")), 1.0);
+    }
+
     #[test]
     fn test_to_kebab_case() {
         // Basit boşluk birleştirme testi
@@ -106,4 +257,30 @@ mod tests {
         // Özel karakter testi
         assert_eq!(convert_tr_chars_to_en("Test@Çözüm!"), "Test@Cozum!");
     }
+
+    #[test]
+    fn test_normalize_input() {
+        // Ayrışık yazılmış Türkçe karakterler birleşik hale gelmeli
+        assert_eq!(normalize_input("c\u{0327}ilek"), "çilek");
+        assert_eq!(normalize_input("s\u{0327}eker"), "şeker");
+        assert_eq!(normalize_input("g\u{0306}"), "ğ");
+        assert_eq!(normalize_input("I\u{0307}ncir"), "İncir");
+
+        // Zaten NFC olan girdi değişmemeli, baştaki ve sondaki boşluklar silinmeli
+        assert_eq!(normalize_input("  Döner Kebap "), "Döner Kebap");
+        assert_eq!(normalize_input("ıhlamur"), "ıhlamur");
+        assert_eq!(normalize_input("   "), "");
+    }
+
+    #[test]
+    fn test_is_invisible_char() {
+        assert!(is_invisible_char('\u{200B}'));
+        assert!(is_invisible_char('\u{202E}'));
+        assert!(is_invisible_char('\u{FEFF}'));
+
+        // Türkçe harfler ve normal noktalama görünür karakterler
+        for c in "çğıöşüÇĞİÖŞÜ'*-".chars() {
+            assert!(!is_invisible_char(c));
+        }
+    }
 }
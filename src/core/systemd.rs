@@ -0,0 +1,81 @@
+use std::{env, io};
+
+use tracing::{debug, warn};
+
+// systemd servisi Type=notify ile çalıştırıldığında NOTIFY_SOCKET değişkeni veriliyor, yoksa bu fonksiyonlar hiçbir şey yapmıyor.
+// READY'yi migration, JSON içe aktarma ve önbelleğin yüklenmesi bittikten, port dinlenmeye başlandıktan sonra gönderiyoruz;
+// böylece systemd servisi hazır olmadan başlatılmış saymıyor, açılışta çöken servis de restart politikasıyla yeniden başlatılıyor
+// Socket activation (LISTEN_FDS) desteklenmiyor, port her zaman açılışta server.port ile bağlanıyor
+pub(crate) fn notify_ready() {
+    notify("READY=1");
+}
+
+pub(crate) fn notify_stopping() {
+    notify("STOPPING=1");
+}
+
+fn notify(state: &str) {
+    let Some(socket_path) = env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+
+    match send(&socket_path.to_string_lossy(), state) {
+        Ok(()) => debug!("systemd'ye bildirildi: {}", state),
+        Err(e) => warn!("systemd'ye {} bildirilemedi: {}", state, e),
+    }
+}
+
+#[cfg(unix)]
+fn send(socket_path: &str, state: &str) -> io::Result<()> {
+    let socket = std::os::unix::net::UnixDatagram::unbound()?;
+    // '@' ile başlayan adresler Linux'un soyut (abstract) soket adresleri, dosya sisteminde karşılıkları yok
+    if let Some(name) = socket_path.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        {
+            use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+            let address = SocketAddr::from_abstract_name(name.as_bytes())?;
+            socket.send_to_addr(state.as_bytes(), &address)?;
+            return Ok(());
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = name;
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "soyut soket adresleri sadece Linux'ta destekleniyor",
+            ));
+        }
+    }
+
+    socket.send_to(state.as_bytes(), socket_path)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn send(_socket_path: &str, _state: &str) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "systemd bildirimleri sadece Unix sistemlerde destekleniyor",
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_send_notification() {
+        let dir = env::temp_dir().join(format!("besinveri-notify-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket_path = dir.join("notify.sock");
+        let _ = std::fs::remove_file(&socket_path);
+        let receiver = std::os::unix::net::UnixDatagram::bind(&socket_path).unwrap();
+
+        send(socket_path.to_str().unwrap(), "READY=1").unwrap();
+        let mut buffer = [0; 64];
+        let received = receiver.recv(&mut buffer).unwrap();
+        assert_eq!(&buffer[..received], b"READY=1");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Error};
+use axum::http::HeaderMap;
+use opentelemetry::{
+    global,
+    propagation::Extractor,
+    trace::{TraceContextExt, TracerProvider},
+};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    propagation::TraceContextPropagator,
+    trace::{Sampler, SdkTracerProvider},
+};
+use tracing::{Level, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::{
+    filter::LevelFilter, fmt::time::UtcTime, layer::SubscriberExt, util::SubscriberInitExt,
+};
+
+use crate::core::config::TelemetryConfig;
+
+// Loglar her zaman stdout'a yazılıyor. Config'de OTLP endpoint'i verilmişse span'lar ayrıca OpenTelemetry ile dışarı aktarılıyor,
+// böylece reverse proxy'den veritabanı sorgularına kadar bir isteğin tamamı Jaeger/Tempo gibi araçlarda tek bir trace olarak görülebiliyor.
+// Dönen provider kapanışta shutdown edilmeli, aksi halde tamponda bekleyen son span'lar kaybolur
+pub(crate) fn init_tracing(
+    level: Level,
+    telemetry: &TelemetryConfig,
+) -> Result<Option<SdkTracerProvider>, Error> {
+    let fmt_layer = tracing_subscriber::fmt::layer().with_timer(UtcTime::rfc_3339());
+    let registry = tracing_subscriber::registry()
+        .with(LevelFilter::from_level(level))
+        .with(fmt_layer);
+
+    let Some(endpoint) = &telemetry.otlp_endpoint else {
+        registry.init();
+        return Ok(None);
+    };
+
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .context("OTLP exporter oluşturulamadı")?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        // Gelen istek örneklenmişse (traceparent'ta sampled bayrağı) biz de örnekliyoruz, zincir yarıda kopmasın
+        .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+            telemetry.sample_ratio,
+        ))))
+        .with_resource(
+            Resource::builder()
+                .with_service_name(telemetry.service_name.clone())
+                .build(),
+        )
+        .build();
+
+    // Gelen traceparent header'larını W3C Trace Context formatında okuyoruz
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    registry
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("besinveri-api")))
+        .init();
+
+    Ok(Some(provider))
+}
+
+// İstek span'ını gelen traceparent header'ındaki trace'in devamı yapıyoruz, header yoksa yeni bir trace başlıyor
+pub(crate) fn set_parent_from_headers(span: &Span, headers: &HeaderMap) {
+    let context =
+        global::get_text_map_propagator(|propagator| propagator.extract(&HeaderExtractor(headers)));
+    if context.span().span_context().is_valid() {
+        let _ = span.set_parent(context);
+    }
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::propagation::TextMapPropagator;
+
+    #[test]
+    fn test_traceparent_is_extracted() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                .parse()
+                .unwrap(),
+        );
+
+        let context = TraceContextPropagator::new().extract(&HeaderExtractor(&headers));
+        let span_context = context.span().span_context().clone();
+        assert!(span_context.is_valid());
+        assert!(span_context.is_sampled());
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+
+        let context = TraceContextPropagator::new().extract(&HeaderExtractor(&HeaderMap::new()));
+        assert!(!context.span().span_context().is_valid());
+    }
+}
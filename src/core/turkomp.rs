@@ -0,0 +1,278 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use anyhow::{Context, Error, anyhow};
+
+use crate::core::{
+    food::Food,
+    nutrition::Nutrients,
+    str::to_search_key,
+    units::{self, Unit},
+};
+
+// TürKomp (Ulusal Gıda Kompozisyon Veri Tabanı) bileşen adları ve karşılık gelen besin değerleri, to_search_key ile
+// normalize edilmiş halleriyle. Listede olmayan bileşenler (amino asitler, tek tek yağ asitleri...) atlanıyor
+const COMPONENTS: [(&str, &str); 32] = [
+    ("enerji", "energy"),
+    ("su", "water"),
+    ("protein", "protein"),
+    ("yag, toplam", "fat"),
+    ("karbonhidrat", "carbohydrate"),
+    ("lif, diyet, toplam", "fiber"),
+    ("seker, toplam", "sugar"),
+    ("polioller, toplam", "polyols"),
+    ("yag asitleri, toplam doymus", "saturated_fat"),
+    ("yag asitleri, toplam trans", "trans_fat"),
+    ("yag asitleri, toplam n-3 coklu doymamis", "omega_3"),
+    ("yag asitleri, toplam n-6 coklu doymamis", "omega_6"),
+    ("kolesterol", "cholesterol"),
+    ("sodyum, na", "sodium"),
+    ("potasyum, k", "potassium"),
+    ("kalsiyum, ca", "calcium"),
+    ("magnezyum, mg", "magnesium"),
+    ("demir, fe", "iron"),
+    ("cinko, zn", "zinc"),
+    ("fosfor, p", "phosphorus"),
+    ("bakir, cu", "copper"),
+    ("selenyum, se", "selenium"),
+    ("vitamin a", "vitamin_a"),
+    ("vitamin d", "vitamin_d"),
+    ("vitamin e", "vitamin_e"),
+    ("vitamin k", "vitamin_k"),
+    ("vitamin b6", "vitamin_b6"),
+    ("vitamin b12", "vitamin_b12"),
+    ("vitamin c", "vitamin_c"),
+    ("niasin", "niacin"),
+    ("folat, toplam", "folate"),
+    ("folat", "folate"),
+];
+
+// Başlıktaki kolon adları, Türkçe ve İngilizce arayüzden alınan dosyalar için
+const NAME_COLUMNS: [&str; 2] = ["gida adi", "food name"];
+const GROUP_COLUMNS: [&str; 2] = ["gida grubu", "food group"];
+const COMPONENT_COLUMNS: [&str; 2] = ["bilesen", "component"];
+const UNIT_COLUMNS: [&str; 2] = ["birim", "unit"];
+const VALUE_COLUMNS: [&str; 3] = ["ortalama", "average", "mean"];
+
+#[derive(Debug, Default)]
+pub(crate) struct Import {
+    pub(crate) foods: Vec<Food>,
+    // Eşlenmeyen bileşen adları, import'u yapan kişi listeyi gözden geçirebilsin diye
+    pub(crate) skipped_components: BTreeSet<String>,
+}
+
+// TürKomp'un her satırda bir yemeğin bir bileşeni olan (uzun biçim) CSV dışa aktarımını yemeklere çeviriyor: Gıda Adı, Bileşen,
+// Birim ve Ortalama kolonları gerekli, Gıda Grubu varsa etiket oluyor. Ayraç başlıktan anlaşılıyor (; veya ,), ondalık virgül
+// de kabul ediliyor. Değerler core::units ile veritabanındaki birimlere çevriliyor; değeri olmayan ("-", "iz") bileşenler 0 kalıyor
+pub(crate) fn parse(content: &str, source: &str) -> Result<Import, Error> {
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (_, header) = lines.next().ok_or_else(|| anyhow!("Dosya boş"))?;
+    let separator = if header.contains(';') { ';' } else { ',' };
+    let header: Vec<String> = split_fields(header, separator)
+        .iter()
+        .map(|column| to_search_key(column.trim()))
+        .collect();
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|column| names.contains(&column.as_str()))
+    };
+    let (Some(name_column), Some(component_column), Some(unit_column), Some(value_column)) = (
+        column(&NAME_COLUMNS),
+        column(&COMPONENT_COLUMNS),
+        column(&UNIT_COLUMNS),
+        column(&VALUE_COLUMNS),
+    ) else {
+        return Err(anyhow!(
+            "Başlıkta Gıda Adı, Bileşen, Birim ve Ortalama kolonları olmalı"
+        ));
+    };
+    let group_column = column(&GROUP_COLUMNS);
+
+    let mut import = Import::default();
+    // Yemeklerin dosyadaki sırası korunuyor, aynı yemeğin satırları ardışık olmak zorunda değil
+    let mut nutrients: Vec<(Nutrients, bool)> = Vec::new();
+    for (index, line) in lines {
+        let line_number = index + 1;
+        let fields = split_fields(line, separator);
+        let field = |column: usize| fields.get(column).map_or("", |field| field.trim());
+        let name = field(name_column);
+        if name.is_empty() {
+            return Err(anyhow!("{}. satırda gıda adı yok", line_number));
+        }
+
+        let position = match import
+            .foods
+            .iter()
+            .position(|food| food.description == name)
+        {
+            Some(position) => position,
+            None => {
+                import.foods.push(Food {
+                    description: name.to_owned(),
+                    source: source.to_owned(),
+                    tags: group_column
+                        .map(field)
+                        .filter(|group| !group.is_empty())
+                        .map(|group| vec![group.to_lowercase()])
+                        .unwrap_or_default(),
+                    ..Default::default()
+                });
+                nutrients.push((Nutrients::default(), false));
+                import.foods.len() - 1
+            }
+        };
+
+        let component = field(component_column);
+        let Some((_, nutrient)) = COMPONENTS
+            .iter()
+            .find(|(name, _)| *name == to_search_key(component))
+        else {
+            import.skipped_components.insert(component.to_owned());
+            continue;
+        };
+        let Some(value) = parse_value(field(value_column)) else {
+            continue;
+        };
+        let unit = parse_unit(field(unit_column)).ok_or_else(|| {
+            anyhow!(
+                "{}. satırda bilinmeyen birim: {}",
+                line_number,
+                field(unit_column)
+            )
+        })?;
+        let stored = units::stored_unit(nutrient)
+            .ok_or_else(|| anyhow!("{} için birim tanımlı değil", nutrient))?;
+        let value = units::convert(nutrient, value, unit, stored)
+            .map_err(|e| anyhow!("{}. satır: {}", line_number, e))?;
+
+        // Enerji hem kcal hem kJ olarak verilebiliyor, yuvarlamadan etkilenmesin diye kcal olanı tercih ediyoruz
+        let (food_nutrients, energy_in_kcal) = &mut nutrients[position];
+        if *nutrient == "energy" {
+            if *energy_in_kcal && unit != Unit::Kcal {
+                continue;
+            }
+            *energy_in_kcal = unit == Unit::Kcal;
+        }
+        food_nutrients.set(nutrient, value);
+    }
+
+    for (food, (food_nutrients, _)) in import.foods.iter_mut().zip(&nutrients) {
+        food_nutrients.apply_to(food);
+    }
+    Ok(import)
+}
+
+// CSV dosyasını okuyup db/foods'a yüklenecek biçimde bir JSON dosyası yazıyor. Sunucu JSON'daki yemekleri bir sonraki
+// açılışta, veritabanında yoksa ekliyor; dosya yazılmadan önce gözden geçirilebilsin diye doğrudan veritabanına eklemiyoruz
+pub(crate) fn import_file(input: &str, output: &str, source: &str) -> Result<(), Error> {
+    if Path::new(output).exists() {
+        return Err(anyhow!(
+            "{} zaten var, üzerine yazılmıyor. Başka bir --output verin",
+            output
+        ));
+    }
+    let content =
+        fs::read_to_string(input).with_context(|| format!("{} dosyası okunamadı", input))?;
+    let import = parse(&content, source)?;
+    fs::write(output, serde_json::to_string_pretty(&import.foods)?)
+        .with_context(|| format!("{} dosyasına yazılamadı", output))?;
+
+    println!("{} yemek {} dosyasına yazıldı", import.foods.len(), output);
+    if !import.skipped_components.is_empty() {
+        println!(
+            "Eşlenmeyen bileşenler: {}",
+            import
+                .skipped_components
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Ok(())
+}
+
+// Tırnak içindeki ayraçları bölmeyen basit bir CSV ayrıştırıcı, tırnak içinde "" bir tırnak. Satırlara bölünmüş alanlar desteklenmiyor
+fn split_fields(line: &str, separator: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+// "1,5" ve "1.5" aynı. "-", "iz" (eser miktar) ve "<0,1" gibi ölçülmemiş değerler None
+fn parse_value(s: &str) -> Option<f64> {
+    s.replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value >= 0.0)
+}
+
+// TürKomp birimleri "µg RAE" veya "mg α-TE" gibi eşdeğerlik ekleriyle yazabiliyor, kütle birimi ilk kelime
+fn parse_unit(s: &str) -> Option<Unit> {
+    let unit = s.split_whitespace().next()?;
+    Unit::parse(unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let content = "\u{feff}Gıda Kodu;Gıda Adı;Gıda Grubu;Bileşen;Birim;Ortalama
+01.001;Elma, kabuklu, çiğ;Meyveler;Enerji;kJ;218
+01.001;Elma, kabuklu, çiğ;Meyveler;Enerji;kcal;52
+01.001;Elma, kabuklu, çiğ;Meyveler;Su;g;85,6
+01.001;\"Elma, kabuklu, çiğ\";Meyveler;Sodyum, Na;mg;1
+01.001;Elma, kabuklu, çiğ;Meyveler;Vitamin A;µg RAE;3
+01.001;Elma, kabuklu, çiğ;Meyveler;Selenyum, Se;µg;iz
+01.001;Elma, kabuklu, çiğ;Meyveler;Triptofan;mg;1
+02.001;Ayran;Süt ürünleri;Enerji;kJ;150
+02.001;Ayran;Süt ürünleri;Kalsiyum, Ca;mg;120
+";
+        let import = parse(content, "TürKomp").unwrap();
+        assert_eq!(import.foods.len(), 2);
+        let elma = &import.foods[0];
+        assert_eq!(elma.description, "Elma, kabuklu, çiğ");
+        assert_eq!(elma.source, "TürKomp");
+        assert_eq!(elma.tags, vec!["meyveler"]);
+        // kcal satırı kJ satırından sonra gelse de tercih ediliyor
+        assert_eq!(elma.energy, 52.0);
+        assert_eq!(elma.water, 85.6);
+        assert_eq!(elma.sodium, 1.0);
+        assert_eq!(elma.vitamin_a, 0.003);
+        assert_eq!(elma.selenium, 0.0);
+        assert_eq!(
+            import.skipped_components,
+            BTreeSet::from(["Triptofan".to_owned()])
+        );
+        // Sadece kJ verilmişse çevriliyor
+        assert_eq!(import.foods[1].energy, 35.85086);
+
+        assert!(parse("Gıda Adı;Birim\nElma;g", "TürKomp").is_err());
+        assert!(
+            parse(
+                "Gıda Adı;Bileşen;Birim;Ortalama\nElma;Su;litre;85",
+                "TürKomp"
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use serde::Serialize;
+
+use crate::core::browse::NUTRIENTS;
+
+// Besin değerlerinin birimleri. Veritabanında her besin tek bir birimle tutuluyor (stored_unit), diğer birimler istek üzerine
+// çevriliyor. IU ve RAE kütle birimi değil, karşılıkları besine göre değişiyor
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Unit {
+    G,
+    Mg,
+    Mcg,
+    // Uluslararası ünite, sadece A, D ve E vitaminleri için
+    Iu,
+    // Retinol aktivite eşdeğeri (µg RAE), sadece A vitamini için
+    Rae,
+    Kcal,
+    Kj,
+}
+
+impl Unit {
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "g" => Some(Unit::G),
+            "mg" => Some(Unit::Mg),
+            "mcg" | "ug" | "µg" => Some(Unit::Mcg),
+            "iu" => Some(Unit::Iu),
+            "rae" | "mcg_rae" => Some(Unit::Rae),
+            "kcal" => Some(Unit::Kcal),
+            "kj" => Some(Unit::Kj),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Unit::G => "g",
+            Unit::Mg => "mg",
+            Unit::Mcg => "mcg",
+            Unit::Iu => "iu",
+            Unit::Rae => "rae",
+            Unit::Kcal => "kcal",
+            Unit::Kj => "kj",
+        }
+    }
+}
+
+// Food'daki değerlerin birimi. Glisemik indeksin birimi yok
+pub(crate) fn stored_unit(nutrient: &str) -> Option<Unit> {
+    match nutrient {
+        "energy" => Some(Unit::Kcal),
+        "carbohydrate" | "protein" | "fat" | "saturated_fat" | "trans_fat" | "sugar"
+        | "added_sugar" | "polyols" | "fiber" | "water" | "omega_3" | "omega_6" => Some(Unit::G),
+        "cholesterol" | "sodium" | "potassium" | "iron" | "magnesium" | "calcium" | "zinc"
+        | "vitamin_a" | "vitamin_b6" | "vitamin_c" | "vitamin_e" | "vitamin_k" | "niacin"
+        | "phosphorus" | "copper" => Some(Unit::Mg),
+        "vitamin_b12" | "vitamin_d" | "folate" | "selenium" => Some(Unit::Mcg),
+        _ => None,
+    }
+}
+
+// Birimin bu besin için ortak bir ölçekteki karşılığı: enerji için kcal, diğerleri için µg. IU karşılıkları, verideki formlar
+// için: A vitamini retinol (1 IU = 0,3 µg), D vitamini (1 IU = 0,025 µg), E vitamini doğal alfa-tokoferol (1 IU = 0,67 mg).
+// Beta-karoten ve sentetik E vitamini için karşılıklar farklı, bu API onları ayrı tutmuyor
+fn factor(nutrient: &str, unit: Unit) -> Option<f64> {
+    if nutrient == "energy" {
+        return match unit {
+            Unit::Kcal => Some(1.0),
+            Unit::Kj => Some(1.0 / 4.184),
+            _ => None,
+        };
+    }
+    match (unit, nutrient) {
+        (Unit::G, _) => Some(1_000_000.0),
+        (Unit::Mg, _) => Some(1_000.0),
+        (Unit::Mcg, _) => Some(1.0),
+        (Unit::Iu, "vitamin_a") => Some(0.3),
+        (Unit::Iu, "vitamin_d") => Some(0.025),
+        (Unit::Iu, "vitamin_e") => Some(670.0),
+        (Unit::Rae, "vitamin_a") => Some(1.0),
+        _ => None,
+    }
+}
+
+// Çevrilen değerler 6 ondalığa yuvarlanıyor: µg'dan g'a çevirirken 2 ondalık değeri sıfırlıyor, hiç yuvarlamamak da
+// 0.27000000000000002 gibi kayan nokta artıkları bırakıyor
+pub(crate) fn convert(nutrient: &str, value: f64, from: Unit, to: Unit) -> Result<f64, String> {
+    if stored_unit(nutrient).is_none() {
+        return Err(format!("Birimi olan bir besin değeri değil: {}", nutrient));
+    }
+    let (Some(from_factor), Some(to_factor)) = (factor(nutrient, from), factor(nutrient, to))
+    else {
+        return Err(format!(
+            "{} için {} biriminden {} birimine çevrilemez",
+            nutrient,
+            from.name(),
+            to.name()
+        ));
+    };
+    Ok((value * from_factor / to_factor * 1_000_000.0).round() / 1_000_000.0)
+}
+
+// units=vitamin_a:iu,vitamin_d:iu gibi, yanıtta hangi besinin hangi birimde de gösterileceği
+pub(crate) fn parse_unit_requests(s: &str) -> Result<Vec<(&'static str, Unit)>, String> {
+    let mut requests = Vec::new();
+    for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let Some((name, unit)) = pair.split_once(':') else {
+            return Err(format!("Birim besin:birim şeklinde olmalı: {}", pair));
+        };
+        let Some(nutrient) = NUTRIENTS.iter().find(|nutrient| **nutrient == name.trim()) else {
+            return Err(format!("Bilinmeyen besin değeri: {}", name));
+        };
+        let Some(unit) = Unit::parse(unit) else {
+            return Err(format!("Bilinmeyen birim: {}", unit));
+        };
+        // Çevrilebilir mi, istek yanıtlanırken hata vermesin diye burada kontrol ediyoruz
+        if stored_unit(nutrient).is_none() || factor(nutrient, unit).is_none() {
+            return Err(format!("{} {} birimine çevrilemez", nutrient, unit.name()));
+        }
+        requests.push((*nutrient, unit));
+    }
+    Ok(requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(convert("vitamin_a", 900.0, Unit::Iu, Unit::Mcg), Ok(270.0));
+        assert_eq!(convert("vitamin_a", 270.0, Unit::Rae, Unit::Mg), Ok(0.27));
+        assert_eq!(convert("vitamin_d", 10.0, Unit::Mcg, Unit::Iu), Ok(400.0));
+        assert_eq!(convert("vitamin_e", 0.67, Unit::Mg, Unit::Iu), Ok(1.0));
+        assert_eq!(convert("sodium", 2400.0, Unit::Mg, Unit::G), Ok(2.4));
+        assert_eq!(convert("energy", 100.0, Unit::Kcal, Unit::Kj), Ok(418.4));
+
+        // IU ve RAE her besin için tanımlı değil, enerji de kütleye çevrilemez
+        assert!(convert("vitamin_c", 1.0, Unit::Mg, Unit::Iu).is_err());
+        assert!(convert("vitamin_d", 1.0, Unit::Mcg, Unit::Rae).is_err());
+        assert!(convert("energy", 1.0, Unit::Kcal, Unit::G).is_err());
+        assert!(convert("kafein", 1.0, Unit::Mg, Unit::G).is_err());
+        assert!(convert("glycemic_index", 1.0, Unit::G, Unit::Mg).is_err());
+    }
+
+    #[test]
+    fn test_parse_unit_requests() {
+        assert_eq!(
+            parse_unit_requests("vitamin_a:iu, energy:kj"),
+            Ok(vec![("vitamin_a", Unit::Iu), ("energy", Unit::Kj)])
+        );
+        assert!(parse_unit_requests("vitamin_a").is_err());
+        assert!(parse_unit_requests("glycemic_index:g").is_err());
+        assert!(parse_unit_requests("zinc:iu").is_err());
+    }
+}
@@ -0,0 +1,709 @@
+use std::{
+    str::FromStr,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
+
+use anyhow::Error;
+use arc_swap::ArcSwap;
+use axum::{
+    Router,
+    error_handling::HandleErrorLayer,
+    extract::Request,
+    middleware::{self, Next},
+    response::Response,
+    routing::{get, post},
+};
+use axum_helmet::{Helmet, HelmetLayer};
+use clap::Parser;
+use lazy_limit::{Duration, LimiterConfig, RuleConfig, initialize_limiter};
+use moka::future::Cache;
+use reqwest::{Method, header};
+use sqlx::{Pool, Sqlite};
+use tokio::net::TcpListener;
+use tower::Layer;
+use tower_http::{
+    cors::CorsLayer,
+    normalize_path::{NormalizePath, NormalizePathLayer},
+};
+use tracing::{debug, info, warn};
+
+use crate::core::{
+    cli::{Cli, Command},
+    config::{APIUrls, Config, RateLimitConfig},
+};
+
+mod api;
+// Benchmark'lar (benches/) ayrı bir crate olarak derlendiği için ölçülen fonksiyonlara bu modülden erişiyor
+#[doc(hidden)]
+pub mod bench;
+mod core;
+#[cfg(test)]
+mod test_app;
+
+// Veritabanı ve config'i, tüm handlerlar içinde kullanabilmek için bir shared_state oluşturuyoruz, cache de dahil
+#[derive(Clone)]
+struct SharedState {
+    api_db: Pool<Sqlite>, // Havuz zaten Arc ile paylaşılıyor, handlerlar kopyalayıp sorguları aynı anda farklı bağlantılarda çalıştırabiliyor
+    config: Arc<ArcSwap<Config>>, // Handlerlar kilitlenmeden okuyor, değişiklikler yeni bir kopya yazılarak yapılıyor
+    urls: Arc<APIUrls>, // Açılışta config'den türetilen base_url, static_url ve route'ların bağlandığı path
+    cache: Arc<api::cache::ResponseCache>, // URL -> JSON şeklinde caching yapacağız, detay/liste/arama yanıtları ayrı önbelleklerde
+    negative_cache: Cache<String, api::cache::CachedResponse>, // Bulunamayan yemekler için URL -> hata JSON'ı, kısa ömürlü
+    search_hot_cache: api::hot_search::HotSearchCache, // Normalize edilmiş sorgu -> arama sonuçları, sadece popüler sorgular için
+    used_signatures: Cache<String, ()>, // İmzalı isteklerde tekrar saldırılarını engellemek için kullanılmış imzalar
+    metrics: Arc<api::metrics::Metrics>,
+    usage: Arc<api::quota::UsageTracker>, // API anahtarlarının günlük/aylık kullanımı, veritabanına periyodik olarak yazılıyor
+    search_analytics: Arc<api::analytics::SearchAnalytics>, // Günlük arama sayaçları, kullanım sayaçlarıyla birlikte yazılıyor
+    suggestions: Arc<api::suggest::SuggestionCorpus>, // /foods/suggest için yemek isimleri, açılışta yükleniyor
+    search_index: Arc<api::search_index::SearchIndex>, // Açıklama ve tüm alan aramaları için bellekteki indeks, yemekler değiştikçe yenileniyor
+    warmed_up: Arc<AtomicBool>, // Önbellek yüklenip sunucu istek kabul etmeye hazır olduğunda true, kapanışta tekrar false
+    started_at: Instant,        // /health'te çalışma süresini göstermek için
+    internet_connection: Arc<RwLock<Option<bool>>>, // Arka planda yapılan son internet kontrolünün sonucu, kontrol kapalıysa veya henüz yapılmadıysa None
+}
+
+impl SharedState {
+    async fn new(config: Config) -> Result<Self, Error> {
+        let api_db = api::database::connect_database(&config.database).await?;
+        Ok(Self::with_pool(config, api_db))
+    }
+
+    // Bağlantısı hazır bir veritabanıyla, benchmark'lar bellekteki bir veritabanı veriyor
+    fn with_pool(config: Config, api_db: Pool<Sqlite>) -> Self {
+        let urls = Arc::new(config.api.urls());
+        let config = Arc::new(ArcSwap::from_pointee(config));
+
+        // Önbellek kapasitesi bayt cinsinden bir bellek bütçesi, girdileri sayıyla değil gövde boyutlarıyla tartıyoruz
+        let cache_capacity_bytes = config.load().core.cache_capacity_bytes;
+        let metrics = Arc::new(api::metrics::Metrics::default());
+        let cache = Arc::new(api::cache::ResponseCache::new(
+            &config.load().response_cache,
+            cache_capacity_bytes,
+            metrics.clone(),
+        ));
+        // Negatif önbellekteki hata gövdeleri çok küçük, bütçenin küçük bir kısmı yeterli
+        let negative_cache = Cache::builder()
+            .weigher(|key: &String, value| api::cache::weigh_entry(key, value))
+            .max_capacity(cache_capacity_bytes / 8)
+            .time_to_live(api::cache::NEGATIVE_CACHE_TTL)
+            .eviction_listener({
+                let metrics = metrics.clone();
+                move |_key, _value, cause| metrics.negative_cache.record_removal(cause)
+            })
+            .build();
+
+        let search_hot_cache = api::hot_search::build(&config.load().search, metrics.clone());
+
+        // Zaman damgası toleransı dışına çıkan imzalar zaten reddediliyor, bu süreden fazla saklamamıza gerek yok
+        let max_clock_skew = config.load().signing.max_clock_skew_seconds;
+        let used_signatures = Cache::builder()
+            .max_capacity(100_000)
+            .time_to_live(std::time::Duration::from_secs(max_clock_skew * 2))
+            .build();
+
+        Self {
+            api_db,
+            config,
+            urls,
+            cache,
+            negative_cache,
+            search_hot_cache,
+            used_signatures,
+            metrics,
+            usage: Arc::new(api::quota::UsageTracker::default()),
+            search_analytics: Arc::new(api::analytics::SearchAnalytics::default()),
+            suggestions: Arc::new(api::suggest::SuggestionCorpus::default()),
+            search_index: Arc::new(api::search_index::SearchIndex::default()),
+            warmed_up: Arc::new(AtomicBool::new(false)),
+            started_at: Instant::now(),
+            internet_connection: Arc::new(RwLock::new(None)),
+        }
+    }
+}
+
+// Sunucunun ve komutların giriş noktası, main.rs sadece bunu çağırıyor
+pub async fn run() -> Result<(), Error> {
+    // Gizli değerler (API anahtarları, admin token'ı...) git'e giren config yerine .env'de tutulabiliyor.
+    // Zaten tanımlı ortam değişkenleri .env'dekileri ezer, dosya yoksa sorun değil
+    if let Err(e) = dotenvy::dotenv()
+        && !e.not_found()
+    {
+        return Err(Error::new(e).context(".env dosyası okunamadı"));
+    }
+    let cli = Cli::parse();
+    let mut config = core::config::load_config_with_defaults(&cli.config, cli.env.as_deref())?;
+    if let Some(port) = cli.port {
+        config.server.port = port;
+    }
+    config.validate()?;
+
+    // Süreç çalıştığı sürece PID dosyası duruyor, run'dan çıkarken siliniyor
+    let _pid_file = cli
+        .pid_file
+        .as_deref()
+        .map(core::process::PidFile::create)
+        .transpose()?;
+
+    match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(config).await,
+        Command::ImportTurkomp {
+            file,
+            output,
+            source,
+        } => core::turkomp::import_file(&file, &output, &source),
+    }
+}
+
+async fn serve(config: Config) -> Result<(), Error> {
+    // Bu state içinde veritabanı, config ve cache'i barındırıyor. Diğer route'lardan erişmek için kullanıyoruz asenkron olarak
+    let shared_state = SharedState::new(config).await?;
+
+    // Config dosyasında son çalıştırmadan beri değişen alanları kaydediyoruz, davranış değişiklikleri config düzenlemeleriyle eşleştirilebilsin
+    {
+        let values = api::config_audit::flatten(&shared_state.config.load())?;
+        let pool = shared_state.api_db.clone();
+        match api::config_audit::record(&pool, values, "startup").await {
+            Ok(0) => {}
+            Ok(changes) => info!("Son çalıştırmadan beri {} config alanı değişti", changes),
+            Err(e) => warn!("Config değişiklikleri kaydedilemedi: {}", e),
+        }
+    }
+
+    // base_url'deki path SharedState oluşturulurken ayrıştırıldı, route'lar onun altına bağlanıyor
+    let api_path = shared_state.urls.api_path.clone();
+
+    // Config'den trace seviyesini alıp kullanıyoruz; config ArcSwap'ta, load() kilit almadan o anki config'e bir Guard veriyor,
+    // değişiklikler (admin'deki rcu gibi) yeni bir Arc ile yapıldığı için okuyanlar beklemiyor
+    let tracer_provider = {
+        let config_guard = shared_state.config.load();
+        let tracing_level = tracing::Level::from_str(&config_guard.core.tracing_level)
+            .unwrap_or(tracing::Level::TRACE);
+        core::telemetry::init_tracing(tracing_level, &config_guard.telemetry)?
+    };
+    // Değer serve bitene kadar tutulmalı, düşürülünce Sentry istemcisi kapanıyor
+    let _error_reporting = core::error_reporting::init(&shared_state.config.load().sentry)?;
+
+    {
+        let database_config = &shared_state.config.load().database;
+        api::metrics::DATABASE_QUERIES.set_slow_threshold(std::time::Duration::from_millis(
+            database_config.slow_query_threshold_ms,
+        ));
+        api::metrics::DATABASE_QUERIES.set_retry_policy(
+            database_config.max_retries,
+            std::time::Duration::from_millis(database_config.retry_base_delay_ms),
+        );
+    }
+
+    debug!("Rate limiter başlatılıyor");
+    init_rate_limiter(&shared_state.config.load().rate_limit).await;
+
+    debug!("BesinVeri API hazırlanıyor");
+    let router = app(&shared_state);
+
+    log_startup_summary(&shared_state, &api_path).await;
+
+    // Önbelleği diskten geri yükleyeceksek bunu dinlemeye başlamadan önce yapıyoruz
+    let cache_snapshot_path = shared_state.config.load().core.cache_snapshot_path.clone();
+    if let Some(path) = &cache_snapshot_path {
+        match api::cache::load_snapshot(&shared_state.cache, path).await {
+            Ok(loaded) => info!("Önbellek diskten yüklendi: {} kayıt", loaded),
+            Err(e) => warn!("Önbellek diskten yüklenemedi: {}", e),
+        }
+    }
+
+    {
+        let pool = shared_state.api_db.clone();
+        match shared_state.suggestions.reload(&pool).await {
+            Ok(loaded) => info!("Öneriler için {} yemek ismi yüklendi", loaded),
+            Err(e) => warn!("Öneriler için yemek isimleri yüklenemedi: {}", e),
+        }
+
+        // Diyet bayraklarının kuralları config'de, kurallar değişmiş olabileceği için her açılışta yeniden hesaplanıyor
+        let rules = shared_state.config.load().diet_flags.clone();
+        match api::database::refresh_diet_flags(&pool, &rules).await {
+            Ok(foods) => info!("{} yemeğin diyet bayrakları hesaplandı", foods),
+            Err(e) => warn!("Diyet bayrakları hesaplanamadı: {}", e),
+        }
+        match api::database::refresh_food_scores(&pool).await {
+            Ok(foods) => info!("{} yemeğin Nutri-Score ve NOVA sınıfı hesaplandı", foods),
+            Err(e) => warn!("Nutri-Score ve NOVA sınıfları hesaplanamadı: {}", e),
+        }
+        // İndeks diyet bayraklarını da tutuyor, bayraklar hesaplandıktan sonra yükleniyor
+        match shared_state.search_index.reload(&pool).await {
+            Ok(loaded) => info!("Arama indeksine {} yemek yüklendi", loaded),
+            Err(e) => warn!(
+                "Arama indeksi yüklenemedi, aramalar veritabanında yapılacak: {}",
+                e
+            ),
+        }
+    }
+
+    // API anahtarı kullanım ve arama sayaçlarını arka planda periyodik olarak veritabanına yazıyoruz
+    {
+        let shared_state = shared_state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(api::quota::USAGE_FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                let pool = shared_state.api_db.clone();
+                shared_state.usage.flush(&pool).await;
+                shared_state.search_analytics.flush(&pool).await;
+            }
+        });
+    }
+
+    // /health'in her çağrıda dış sitelere istek atmaması için internet bağlantısını arka planda kontrol ediyoruz
+    {
+        let (enabled, urls, interval_seconds) = {
+            let config_guard = shared_state.config.load();
+            (
+                config_guard.api.health_internet_check_enabled,
+                config_guard.api.health_internet_check_urls.clone(),
+                config_guard.api.health_internet_check_interval_seconds,
+            )
+        };
+        if enabled {
+            let internet_connection = shared_state.internet_connection.clone();
+            tokio::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+                loop {
+                    interval.tick().await;
+                    let is_connected = api::health::check_internet(&urls).await;
+                    *internet_connection
+                        .write()
+                        .expect("İnternet durumu kilidi zehirlendi") = Some(is_connected);
+                }
+            });
+        }
+    }
+
+    // Bloklayan çağrılar yüzünden tıkanan worker'ları loglamak için, sonuçlar /admin/debug/runtime'da da görünüyor
+    {
+        let metrics = shared_state.metrics.clone();
+        tokio::spawn(async move { metrics.stalls.watch().await });
+    }
+
+    // Webhook ayarlıysa 5xx ve veritabanı hata oranlarını arka planda izliyoruz
+    {
+        let (alerting_config, instance) = {
+            let config_guard = shared_state.config.load();
+            (
+                config_guard.alerting.clone(),
+                config_guard.api.base_url.clone(),
+            )
+        };
+        if alerting_config.webhook_url.is_some() {
+            tokio::spawn(api::alerting::run_monitor(
+                shared_state.metrics.clone(),
+                alerting_config,
+                instance,
+            ));
+        }
+    }
+
+    // Açıksa GTIN'i olan yemeklerin resim, içindekiler ve Nutri-Score'u arka planda Open Food Facts'ten çekiliyor
+    {
+        let open_food_facts_config = shared_state.config.load().open_food_facts.clone();
+        if open_food_facts_config.enabled {
+            tokio::spawn(api::open_food_facts::run_enrichment(
+                shared_state.api_db.clone(),
+                open_food_facts_config,
+            ));
+        }
+    }
+
+    let (server_config, tls_config) = {
+        let config_guard = shared_state.config.load();
+        (config_guard.server.clone(), config_guard.tls.clone())
+    };
+    let listener = TcpListener::bind(("0.0.0.0", server_config.port)).await?;
+    let tls_acceptor = tls_config
+        .as_ref()
+        .map(|tls_config| api::server::tls_acceptor(tls_config, server_config.http2))
+        .transpose()?;
+
+    // TLS açıksa düz HTTP isteklerini ayrı bir portta karşılayıp HTTPS'e yönlendiriyoruz
+    if let Some(tls_config) = &tls_config
+        && let Some(http_redirect_port) = tls_config.http_redirect_port
+    {
+        let https_port = match tls_config.https_port {
+            Some(port) => port,
+            None => listener.local_addr()?.port(),
+        };
+        let redirect_listener = TcpListener::bind(("0.0.0.0", http_redirect_port)).await?;
+        let server_config = server_config.clone();
+        tokio::spawn(async move {
+            if let Err(e) = api::server::serve(
+                redirect_listener,
+                api::server::https_redirect_router(https_port),
+                &server_config,
+                None,
+                std::future::pending(),
+            )
+            .await
+            {
+                warn!("HTTPS yönlendirme sunucusu durdu: {}", e);
+            }
+        });
+        info!(
+            "{} portuna gelen HTTP istekleri HTTPS'e yönlendirilecek",
+            http_redirect_port
+        );
+    }
+
+    info!(
+        "BesinVeri API aktif!{}",
+        if tls_acceptor.is_some() { " (TLS)" } else { "" }
+    );
+    shared_state.warmed_up.store(true, Ordering::Relaxed);
+    core::systemd::notify_ready();
+    // Kapanış başlayınca /health/ready 503 dönüyor, load balancer'lar yeni istekleri diğer sunuculara yönlendirebilsin
+    let shutdown = {
+        let warmed_up = shared_state.warmed_up.clone();
+        async move {
+            shutdown_signal().await;
+            warmed_up.store(false, Ordering::Relaxed);
+        }
+    };
+    api::server::serve(listener, router, &server_config, tls_acceptor, shutdown).await?;
+    info!("BesinVeri API pasif!");
+
+    // Son periyotta sayılan ama henüz yazılmamış kullanımlar ve aramalar kaybolmasın
+    {
+        let pool = shared_state.api_db.clone();
+        shared_state.usage.flush(&pool).await;
+        shared_state.search_analytics.flush(&pool).await;
+    }
+
+    // Kapanırken önbellekteki sıcak kayıtları diske yazıyoruz
+    if let Some(path) = &cache_snapshot_path {
+        match api::cache::save_snapshot(&shared_state.cache, path) {
+            Ok(saved) => info!("Önbellek diske kaydedildi: {} kayıt", saved),
+            Err(e) => warn!("Önbellek diske kaydedilemedi: {}", e),
+        }
+    }
+
+    // Tamponda kalan span'ları göndermeden çıkmıyoruz
+    if let Some(tracer_provider) = tracer_provider
+        && let Err(e) = tracer_provider.shutdown()
+    {
+        warn!("OpenTelemetry span'ları gönderilemedi: {}", e);
+    }
+
+    Ok(())
+}
+
+// Lazy-limit ile rate-limit ayarlıyoruz, kurallar config'den geliyor; varsayılan olarak 1 saniyede maksimum 5 istek.
+// Path'e özel kurallar genel kurala ek olarak uygulanıyor, yani örneğin /foods/search'ü daha sıkı limitleyebiliyoruz
+async fn init_rate_limiter(rate_limit: &RateLimitConfig) {
+    let mut limiter_config = LimiterConfig::new(RuleConfig::new(
+        Duration::Seconds(rate_limit.default.interval_seconds),
+        rate_limit.default.max_requests,
+    ))
+    .with_max_memory(rate_limit.max_memory);
+    for route in &rate_limit.routes {
+        limiter_config = limiter_config.add_route_rule(
+            &route.path,
+            RuleConfig::new(
+                Duration::Seconds(route.interval_seconds),
+                route.max_requests,
+            ),
+        );
+    }
+    initialize_limiter(limiter_config).await;
+}
+
+// api_router'ı base_url'deki path'e bağlayıp eşzamanlı istek limitini, CORS'u ve trailing slash düzeltmesini ekliyor.
+// Sunucunun dinlediği servis bu, testlerdeki TestApp de aynısını kuruyor
+fn app(shared_state: &SharedState) -> NormalizePath<Router> {
+    let api_path = &shared_state.urls.api_path;
+    // Nest'in içine boş path yazarsak Axum sorun çıkartıyor o yüzden böyle yapıyoruz
+    let router = if api_path == "/" {
+        api_router(shared_state.clone())
+    } else {
+        Router::new().nest(api_path, api_router(shared_state.clone()))
+    };
+
+    debug!("Eşzamanlı istek limiti hazırlanıyor");
+    // Aşırı yük altında istekleri veritabanı havuzundan bağlantı beklerken sınırsız kuyrukta tutmak yerine limit dolunca hemen 503 ile reddediyoruz
+    let max_concurrent_requests = shared_state.config.load().server.max_concurrent_requests;
+    let router = router.layer(
+        tower::ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(api::error::handle_overload))
+            .load_shed()
+            .concurrency_limit(max_concurrent_requests),
+    );
+
+    debug!("CORS mekanizması hazırlanıyor");
+    // Web Uygulamalarda tarayıcıların sorun çıkartmaması için CORS header mekanizmasını da ekliyoruz
+    let cors = CorsLayer::new()
+        .allow_origin(tower_http::cors::Any)
+        .allow_methods([Method::GET, Method::POST]) // POST /meals/* JSON gövdeyle çağrılıyor
+        .allow_headers(tower_http::cors::AllowHeaders::mirror_request()) // Preflight'ta istenen header'lar (Content-Type, X-API-Key...) adıyla izinli
+        .expose_headers([
+            header::HeaderName::from_static("x-cache"),
+            header::AGE,
+            header::ETAG,
+            header::RETRY_AFTER,
+            header::HeaderName::from_static(api::request_id::X_REQUEST_ID),
+            header::HeaderName::from_static(api::quota::X_QUOTA_DAILY_LIMIT),
+            header::HeaderName::from_static(api::quota::X_QUOTA_DAILY_REMAINING),
+            header::HeaderName::from_static(api::quota::X_QUOTA_MONTHLY_LIMIT),
+            header::HeaderName::from_static(api::quota::X_QUOTA_MONTHLY_REMAINING),
+            header::HeaderName::from_static(api::foods::X_TOTAL_COUNT),
+        ]) // Tarayıcıdaki istemciler de önbellek durumunu ve bekleme süresini görebilsin
+        .max_age(std::time::Duration::from_secs(3600));
+
+    debug!("Trailing slash çözülüyor");
+    // trim_trailing_slash ile /api/ -> /api şeklinde düzeltiyoruz aksi takdirde routelar çalışmıyor, ayrıca IP adreslerine de ihtiyacımız var rate limit için, connect info ayarlıyoruz
+    NormalizePathLayer::trim_trailing_slash().layer(router.layer(cors))
+}
+
+// Lazy-limit'in limiter'ı süreç genelinde bir kez kurulabiliyor. Aynı süreçte birden fazla uygulama kuran testler ve yük testi
+// için, ilk kurulumun kuralları geçerli oluyor
+static RATE_LIMITER: tokio::sync::OnceCell<()> = tokio::sync::OnceCell::const_new();
+
+async fn init_rate_limiter_once(rate_limit: &RateLimitConfig) {
+    RATE_LIMITER
+        .get_or_init(|| init_rate_limiter(rate_limit))
+        .await;
+}
+
+// Açılış özetinde gösterilen route tablosu, api_router'a route eklenince buraya da eklenmeli; test_app tablodaki her route'u istiyor
+const API_ROUTES: [(&str, &str); 23] = [
+    ("GET", "/"),
+    ("GET", "/additives"),
+    ("GET", "/calc/energy"),
+    ("GET", "/convert"),
+    ("GET", "/health"),
+    ("GET", "/health/live"),
+    ("GET", "/health/ready"),
+    ("GET", "/food/{slug}"),
+    ("GET", "/food/{slug}/portions"),
+    ("GET", "/foods"),
+    ("GET", "/foods/export.ndjson"),
+    ("GET", "/foods/in-season"),
+    ("GET", "/foods/list"),
+    ("GET", "/foods/search"),
+    ("GET", "/foods/summaries"),
+    ("GET", "/foods/suggest"),
+    ("POST", "/meals/analyze"),
+    ("POST", "/meals/gaps"),
+    ("GET", "/recipes"),
+    ("GET", "/recipe/{slug}"),
+    ("GET", "/reference-intakes"),
+    ("GET", "/reference-intake/{slug}"),
+    ("GET", "/tags"),
+];
+
+// base_url'deki path'e göre route'lar iç içe (nest) bağlanıyor; base_url yanlış girildiğinde route'lar beklenmedik bir path'e
+// bağlanabiliyor, bu yüzden açılışta route'ları tam path'leriyle ve etkin config değerleriyle birlikte logluyoruz
+async fn log_startup_summary(shared_state: &SharedState, api_path: &str) {
+    let prefix = api_path.trim_end_matches('/');
+    {
+        let config_guard = shared_state.config.load();
+        info!(
+            base_url = %config_guard.api.base_url,
+            static_url = %config_guard.api.static_url,
+            api_path = %api_path,
+            port = config_guard.server.port,
+            tls = config_guard.tls.is_some(),
+            cache_capacity_bytes = config_guard.core.cache_capacity_bytes,
+            timezone = %config_guard.core.timezone,
+            search_max_limit = config_guard.api.search_max_limit,
+            rate_limit = %format!(
+                "{}/{}s",
+                config_guard.rate_limit.default.max_requests,
+                config_guard.rate_limit.default.interval_seconds
+            ),
+            api_keys = config_guard.api_keys.len(),
+            admin = config_guard.admin.token.is_some(),
+            maintenance = config_guard.maintenance.enabled,
+            "Etkin config"
+        );
+
+        // Admin token'ı yoksa admin route'ları 404 dönüyor, tabloda da göstermiyoruz
+        let admin_routes = if config_guard.admin.token.is_some() {
+            &api::admin::ADMIN_ROUTES[..]
+        } else {
+            &[]
+        };
+        for (method, path) in API_ROUTES.iter().chain(admin_routes) {
+            let path = if *path == "/" && !prefix.is_empty() {
+                prefix.to_owned()
+            } else {
+                format!("{}{}", prefix, path)
+            };
+            info!("Route: {:<4} {}", method, path);
+        }
+    }
+
+    let pool = shared_state.api_db.clone();
+    match (
+        api::database::count_foods(&pool).await,
+        api::database::select_all_tags(&pool).await,
+    ) {
+        (Ok((total, verified)), Ok(tags)) => info!(
+            foods = total,
+            verified_foods = verified,
+            tags = tags.len(),
+            "Veritabanı"
+        ),
+        (Err(e), _) | (_, Err(e)) => warn!("Veritabanı özeti alınamadı: {}", e),
+    }
+}
+
+fn api_router(shared_state: SharedState) -> Router {
+    let router = Router::new()
+        .route("/", get(api::endpoints::endpoints))
+        .route("/additives", get(api::additives::additives))
+        .route("/calc/energy", get(api::calc::energy))
+        .route("/convert", get(api::units::convert))
+        .route("/health", get(api::health::health))
+        .route("/health/live", get(api::health::live))
+        .route("/health/ready", get(api::health::ready))
+        .route("/food/{slug}", get(api::foods::food))
+        .route("/food/{slug}/portions", get(api::portions::food_portions))
+        .route("/foods", get(api::foods::foods))
+        .route("/foods/export.ndjson", get(api::export::foods_export))
+        .route("/foods/in-season", get(api::foods::foods_in_season))
+        .route("/foods/list", get(api::foods::foods_list))
+        .route("/foods/search", get(api::foods::foods_search))
+        .route("/foods/summaries", get(api::foods::foods_summaries))
+        .route("/foods/suggest", get(api::foods::foods_suggest))
+        .route("/meals/analyze", post(api::meals::analyze_meal))
+        .route("/meals/gaps", post(api::meals::meal_gaps))
+        .route("/recipes", get(api::recipes::recipes))
+        .route("/recipe/{slug}", get(api::recipes::recipe))
+        .route(
+            "/reference-intakes",
+            get(api::reference_intakes::reference_intakes),
+        )
+        .route(
+            "/reference-intake/{slug}",
+            get(api::reference_intakes::reference_intake),
+        )
+        .route("/tags", get(api::foods::tags_list))
+        .with_state(shared_state.clone())
+        .fallback(api::error::APIError::not_found_handler)
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            api::cache::cache_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            api::analytics::search_analytics_middleware,
+        )) // Önbellekten dönen aramaları da saymak için önbellek katmanının dışında
+        .route_layer(middleware::from_fn_with_state(
+            shared_state.clone(),
+            api::metrics::latency_middleware,
+        )) // Önbellek isabetleri dahil route bazında gecikmeyi ölçüyor, /admin/slo'da raporlanıyor
+        .merge(api::admin::admin_router(shared_state.clone())) // Admin endpoint'leri önbelleğe girmesin diye route_layer'dan sonra ekliyoruz
+        .layer(
+            tower::ServiceBuilder::new()
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::security::client_ip_middleware,
+                )) // İstemci adresini bağlantıdan, güvenilen reverse proxy'lerin arkasında X-Forwarded-For'dan alıyor; rate limiter'dan önce kurulmalı
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::access_log::access_log_middleware,
+                )) // Config'de açıksa her isteği tek satırda logluyor, iç katmanların reddettiği istekler de dahil
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::security::ip_filter_middleware,
+                )) // Config'deki IP engelli/izinli listeleri, rate limiter'dan önce çalışmalı
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::maintenance::maintenance_middleware,
+                )) // Bakım modunda /health ve /admin dışındaki istekleri 503 ile reddediyor, önbellekten de yanıt dönmüyor
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::signing::signature_middleware,
+                )) // Secret'ı olan API anahtarları için HMAC imzasını doğruluyor
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::rate_limit::rate_limit_middleware,
+                )) // Bu katman rate limiter için, API anahtarı varsa kademesine göre yoksa IP'ye göre limitliyor
+                .layer(middleware::from_fn_with_state(
+                    shared_state.clone(),
+                    api::quota::quota_middleware,
+                )), // Rate limiter'ın doğruladığı anahtarların günlük/aylık kotalarını uyguluyor
+        )
+        .layer(HelmetLayer::new(
+            // Özellikle başkalarının iframe içinde API'yi kullanamaması için bu katmanı ekliyoruz
+            Helmet::new()
+                .add(helmet_core::XContentTypeOptions::nosniff())
+                .add(helmet_core::XFrameOptions::deny())
+                .add(helmet_core::XXSSProtection::on().mode_block()) // Eski tarayıcılar için gerekli
+                .add(
+                    helmet_core::ContentSecurityPolicy::new()
+                        .default_src(vec!["'none'"])
+                        .script_src(vec!["'self'"])
+                        .style_src(vec!["'self'", "'unsafe-inline"])
+                        .img_src(vec!["'self'", "data:"])
+                        .connect_src(vec!["'self'"])
+                        .frame_ancestors(vec!["'none"]),
+                )
+                .add(helmet_core::ReferrerPolicy::no_referrer()),
+        ))
+        .layer(middleware::from_fn(api::error::handle_axum_rejections)) // Bu da axum'un kendi hataları için, özellikle deserializasyon gibi hatalar için JSON çevirici
+        .layer(middleware::from_fn(utf8_header_middleware)) // Content Type header'ına UTF8 eklemek için bu middleware'i kullanıyoruz
+        .layer(middleware::from_fn(api::request_id::request_id_middleware)); // Sentry katmanları hariç en dışta olmalı ki tüm loglar ve hata yanıtları isteğin ID'sini içersin
+
+    // Her isteğe ayrı bir Sentry hub'ı veriyoruz, o istekte raporlanan hatalara ve panic'lere isteğin bilgileri ekleniyor
+    #[cfg(feature = "sentry")]
+    let router = router
+        .layer(sentry::integrations::tower::SentryHttpLayer::new())
+        .layer(sentry::integrations::tower::NewSentryLayer::<Request>::new_from_top());
+
+    router
+}
+
+// Ctrl+C veya SIGTERM geldiğinde sunucuyu düzgün kapatıyoruz, böylece kapanış sonrası işler (önbellek kaydı gibi) çalışabiliyor
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Ctrl+C sinyal dinleyicisi kurulamadı");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("SIGTERM sinyal dinleyicisi kurulamadı")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Kapanış sinyali alındı, BesinVeri API kapatılıyor");
+    // Açık bağlantılar kapanırken systemd'nin servisi hâlâ çalışıyor sanmaması için
+    core::systemd::notify_stopping();
+}
+
+async fn utf8_header_middleware(request: Request, next: Next) -> Response {
+    // Bu middleware'i daha gömülü yapabiliriz gelecekte performansı arttırmak için mevcut cache/route mekanizmalarına
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    if let Some(content_type) = headers.get(header::CONTENT_TYPE)
+        && let Ok(content_type) = content_type.to_str()
+    {
+        // Axum kendisi eklemiyor ama yine de bir teksir durumu olmaması için kontrol edelim charset var mı diye
+        if !content_type.to_lowercase().contains("charset") {
+            let content_type = format!("{}; charset=utf-8", content_type);
+            if let Ok(new_val) = header::HeaderValue::from_str(&content_type) {
+                headers.insert(header::CONTENT_TYPE, new_val);
+            }
+        }
+    }
+    response
+}
@@ -0,0 +1,467 @@
+// Uçtan uca testler için sunucunun dinlediği servisin aynısı: tüm middleware'ler, base_url'e göre iç içe bağlanan route'lar ve
+// bellekte migration'ları uygulanıp FIXTURES ile doldurulmuş bir veritabanı. Durum yük testindeki gibi bench::app_state ile kuruluyor. İstekler ağa çıkmadan oneshot ile servise veriliyor,
+// böylece route'lar, middleware sırası, önbellek ve hataların JSON'a çevrilmesi birlikte test edilebiliyor
+use std::net::SocketAddr;
+
+use anyhow::Error;
+use axum::{
+    Router,
+    body::{Body, Bytes, to_bytes},
+    extract::ConnectInfo,
+    http::{HeaderMap, Request, StatusCode},
+};
+use serde::de::DeserializeOwned;
+use tower::ServiceExt;
+use tower_http::normalize_path::NormalizePath;
+
+use crate::{
+    SharedState,
+    api::database,
+    bench,
+    core::{
+        config::{self, Config},
+        food::{Allergen, Food},
+    },
+};
+
+// Aramalar, alerjen filtresi ve onaylanmamış yemeklerin gizlenmesi denenebilsin diye seçilmiş yemekler
+struct Fixture {
+    description: &'static str,
+    tags: &'static [&'static str],
+    allergens: &'static [&'static str],
+    energy: f64,
+    verified: bool,
+}
+
+const FIXTURES: [Fixture; 5] = [
+    Fixture {
+        description: "Muz",
+        tags: &["meyve"],
+        allergens: &[],
+        energy: 89.0,
+        verified: true,
+    },
+    Fixture {
+        description: "Elma",
+        tags: &["meyve"],
+        allergens: &[],
+        energy: 52.0,
+        verified: true,
+    },
+    Fixture {
+        description: "Elma Turtası",
+        tags: &["tatlı"],
+        allergens: &["gluten"],
+        energy: 237.0,
+        verified: true,
+    },
+    Fixture {
+        description: "Mercimek Çorbası",
+        tags: &["çorba", "baklagil"],
+        allergens: &[],
+        energy: 56.0,
+        verified: true,
+    },
+    Fixture {
+        description: "Armut",
+        tags: &["meyve"],
+        allergens: &[],
+        energy: 57.0,
+        verified: false,
+    },
+];
+
+pub(crate) struct TestApp {
+    pub(crate) state: SharedState,
+    service: NormalizePath<Router>,
+}
+
+pub(crate) struct TestResponse {
+    pub(crate) status: StatusCode,
+    pub(crate) headers: HeaderMap,
+    pub(crate) body: Bytes,
+}
+
+impl TestResponse {
+    pub(crate) fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).expect("Yanıt gövdesi beklenen JSON değil")
+    }
+
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+}
+
+impl TestApp {
+    pub(crate) async fn new() -> Result<Self, Error> {
+        Self::with_config(config::get_default_config()).await
+    }
+
+    pub(crate) async fn with_config(config: Config) -> Result<Self, Error> {
+        let pool = database::memory_pool().await?;
+        for fixture in FIXTURES {
+            database::insert_food(
+                &pool,
+                Food {
+                    tags: fixture.tags.iter().map(|tag| tag.to_string()).collect(),
+                    allergens: fixture
+                        .allergens
+                        .iter()
+                        .map(|allergen| Allergen::contains(allergen))
+                        .collect(),
+                    energy: fixture.energy,
+                    ..database::test_food(fixture.description, fixture.verified)
+                },
+            )
+            .await?;
+        }
+
+        let state = bench::app_state(config, pool).await?;
+        Ok(Self {
+            service: crate::app(&state),
+            state,
+        })
+    }
+
+    pub(crate) async fn get(&self, uri: &str) -> Result<TestResponse, Error> {
+        self.request(Request::get(uri).body(Body::empty())?).await
+    }
+
+    // Sunucu gibi istemci adresini ConnectInfo olarak ekliyor, rate limiter ve loglar adresi buradan okuyor
+    pub(crate) async fn request(&self, mut request: Request<Body>) -> Result<TestResponse, Error> {
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+        let response = self.service.clone().oneshot(request).await?;
+        let (parts, body) = response.into_parts();
+        Ok(TestResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body: to_bytes(body, usize::MAX).await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use axum::http::header::{CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+    use serde_json::Value;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_food_lookup_through_all_layers() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+
+        let response = app.get("/food/muz").await?;
+        assert_eq!(response.status, StatusCode::OK);
+        let food: Value = response.json();
+        assert_eq!(food["description"], "Muz");
+        // Resim URL'leri static_url ile tamamlanıyor, en dıştaki katmanlar charset ve istek ID'si ekliyor
+        assert!(
+            food["image"]["url"]
+                .as_str()
+                .is_some_and(|url| url.starts_with("http") && url.ends_with("/images/muz.webp"))
+        );
+        assert_eq!(
+            response.header(CONTENT_TYPE.as_str()),
+            Some("application/json; charset=utf-8")
+        );
+        assert!(response.header("x-request-id").is_some());
+        assert_eq!(response.header("x-cache"), Some("MISS"));
+
+        // Sondaki / kırpılıyor, aynı önbellek kaydına gidiyor
+        let response = app.get("/food/muz/").await?;
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.header("x-cache"), Some("HIT"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_and_lists_skip_unverified() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+
+        let foods: Vec<Value> = app.get("/foods/search?q=elma").await?.json();
+        let slugs: Vec<&str> = foods
+            .iter()
+            .filter_map(|food| food["slug"].as_str())
+            .collect();
+        assert_eq!(slugs, vec!["elma", "elma-turtasi"]);
+
+        let foods: Vec<Value> = app
+            .get("/foods/search?q=elma&allergen_free=gluten")
+            .await?
+            .json();
+        assert_eq!(foods.len(), 1);
+
+        let list: Value = app.get("/foods/list").await?.json();
+        let list = list.as_object().unwrap();
+        assert_eq!(list.len(), 4);
+        assert!(!list.contains_key("armut"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_mapped_to_json() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+
+        let response = app.get("/food/olmayan-yemek").await?;
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        let error: Value = response.json();
+        assert_eq!(error["error"], "FOOD_NOT_FOUND");
+        // Hata gövdesindeki istek ID'si header'dakiyle aynı
+        assert_eq!(
+            error["request_id"].as_str(),
+            response.header("x-request-id")
+        );
+
+        let error: Value = app.get("/olmayan-endpoint").await?.json();
+        assert_eq!(error["error"], "ENDPOINT_NOT_FOUND");
+
+        let response = app.get("/foods/search?q=elma&limit=100000").await?;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert_eq!(response.json::<Value>()["error"], "SEARCH_LIMIT_EXCEEDED");
+
+        // Axum'un düz metin deserializasyon hatası da aynı JSON formatında
+        let response = app.get("/foods/search?q=elma&limit=bes").await?;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        assert!(response.json::<Value>()["error"].is_string());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_queries_run_concurrently() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+
+        // Bir bağlantı açık bir transaction'da sorgunun ortasındayken başka bir istek kendi bağlantısıyla cevaplanabiliyor
+        let mut transaction = app.state.api_db.begin().await?;
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM foods")
+            .fetch_one(&mut *transaction)
+            .await?;
+        let response =
+            tokio::time::timeout(std::time::Duration::from_secs(5), app.get("/food/muz")).await??;
+        assert_eq!(response.status, StatusCode::OK);
+        assert!(app.state.api_db.size() >= 2);
+
+        let count_again: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM foods")
+            .fetch_one(&mut *transaction)
+            .await?;
+        assert_eq!(count, count_again);
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_offenses_are_recorded_for_the_connecting_address() -> Result<(), Error> {
+        let mut config = config::get_default_config();
+        config.security.trusted_proxies = Vec::new();
+        let app = TestApp::with_config(config).await?;
+
+        // Doğrudan bağlanan istemci header'la başka bir adresmiş gibi görünemiyor, şüpheli trafik bağlantının adresine yazılıyor
+        let response = app
+            .request(
+                Request::get(format!("/food/{}", "a".repeat(200)))
+                    .header("x-real-ip", "10.9.9.9")
+                    .header("x-forwarded-for", "10.9.9.9")
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(response.status, StatusCode::BAD_REQUEST);
+        let offenders = app.state.metrics.suspicious.top_offenders(10);
+        assert_eq!(offenders.len(), 1);
+        assert_eq!(offenders[0].ip.to_string(), "127.0.0.1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_database_errors_are_not_404() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+        app.state.api_db.close().await;
+
+        // Veritabanı hatası "sonuç yok" gibi gösterilmiyor
+        let response = app.get("/foods/search?q=meyve&mode=tag").await?;
+        assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.json::<Value>()["error"], "DATABASE_ERROR");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_and_conditional_requests() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+
+        let first = app.get("/tags").await?;
+        assert_eq!(first.header("x-cache"), Some("MISS"));
+        let etag = first.header(ETAG.as_str()).unwrap().to_owned();
+
+        let second = app.get("/tags").await?;
+        assert_eq!(second.header("x-cache"), Some("HIT"));
+        assert_eq!(second.body, first.body);
+
+        let not_modified = app
+            .request(
+                Request::get("/tags")
+                    .header(IF_NONE_MATCH, &etag)
+                    .body(Body::empty())?,
+            )
+            .await?;
+        assert_eq!(not_modified.status, StatusCode::NOT_MODIFIED);
+        assert!(not_modified.body.is_empty());
+
+        // Probe'lar anlık durumu göstermeli, önbelleğe girmiyorlar
+        let ready = app.get("/health/ready").await?;
+        assert_eq!(ready.status, StatusCode::OK);
+        assert_eq!(ready.header("x-cache"), Some("BYPASS"));
+        assert_eq!(app.state.metrics.cache.bypasses.load(Ordering::Relaxed), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_allows_json_posts() -> Result<(), Error> {
+        let app = TestApp::new().await?;
+
+        // Tarayıcılar JSON gövdeli POST'tan önce OPTIONS ile izin soruyor
+        for path in ["/meals/analyze", "/meals/gaps"] {
+            let response = app
+                .request(
+                    Request::options(path)
+                        .header("origin", "https://ornek.com")
+                        .header("access-control-request-method", "POST")
+                        .header("access-control-request-headers", "content-type")
+                        .body(Body::empty())?,
+                )
+                .await?;
+            assert_eq!(response.status, StatusCode::OK);
+            assert!(
+                response
+                    .header("access-control-allow-methods")
+                    .is_some_and(|methods| methods.contains("POST"))
+            );
+            assert!(
+                response
+                    .header("access-control-allow-headers")
+                    .is_some_and(|headers| headers.contains("content-type"))
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_follows_endpoint_class_ttl() -> Result<(), Error> {
+        let mut config = config::get_default_config();
+        config.response_cache.detail.ttl_seconds = 7200;
+        config.response_cache.list.ttl_seconds = 1800;
+        config.response_cache.search.ttl_seconds = 120;
+        let app = TestApp::with_config(config).await?;
+
+        for (uri, max_age) in [
+            ("/food/muz", "public, max-age=7200"),
+            ("/tags", "public, max-age=1800"),
+            ("/foods/search?q=elma", "public, max-age=120"),
+        ] {
+            // Önbellekten dönen yanıtta da aynı süre
+            for _ in 0..2 {
+                let response = app.get(uri).await?;
+                assert_eq!(response.header("cache-control"), Some(max_age), "{}", uri);
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_purge_by_slug_and_reload() -> Result<(), Error> {
+        let mut config = config::get_default_config();
+        config.admin.token = Some("gizli".to_owned());
+        let app = TestApp::with_config(config).await?;
+        let admin_post = |uri: &str, body: &'static str| {
+            Request::post(uri)
+                .header("authorization", "Bearer gizli")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+        };
+
+        for uri in ["/food/muz", "/food/muz/portions", "/food/elma"] {
+            app.get(uri).await?;
+            assert_eq!(
+                app.get(uri).await?.header("x-cache"),
+                Some("HIT"),
+                "{}",
+                uri
+            );
+        }
+
+        let purge = app
+            .request(admin_post("/admin/cache/purge", r#"{"slug": "muz"}"#)?)
+            .await?;
+        assert_eq!(purge.status, StatusCode::OK);
+        assert_eq!(purge.json::<Value>()["purged"], 2);
+        assert_eq!(app.get("/food/muz").await?.header("x-cache"), Some("MISS"));
+        assert_eq!(
+            app.get("/food/muz/portions").await?.header("x-cache"),
+            Some("MISS")
+        );
+        assert_eq!(app.get("/food/elma").await?.header("x-cache"), Some("HIT"));
+
+        // Yeniden yükleme ayrı bir işlem, önbelleğe dokunmuyor
+        let reload = app.request(admin_post("/admin/reload", "")?).await?;
+        assert_eq!(reload.status, StatusCode::OK);
+        let verified = FIXTURES.iter().filter(|fixture| fixture.verified).count();
+        assert_eq!(reload.json::<Value>()["suggestions"], verified);
+        assert_eq!(app.get("/food/muz").await?.header("x-cache"), Some("HIT"));
+        Ok(())
+    }
+
+    // Açılış özetindeki route tabloları router'dan ayrı yazılıyor, tablodaki her route'un router'da da olduğunu kontrol ediyoruz
+    #[tokio::test]
+    async fn test_listed_routes_are_routed() -> Result<(), Error> {
+        let mut config = config::get_default_config();
+        config.admin.token = Some("gizli".to_owned());
+        let app = TestApp::with_config(config).await?;
+
+        for (method, path) in crate::API_ROUTES
+            .iter()
+            .chain(&crate::api::admin::ADMIN_ROUTES)
+        {
+            let uri = path
+                .replace("{slug}", "muz")
+                .replace("{name}", "ornek")
+                .replace("{id}", "1");
+            let request = Request::builder()
+                .method(*method)
+                .uri(&uri)
+                .header("authorization", "Bearer gizli")
+                .body(Body::empty())?;
+            let response = app.request(request).await?;
+
+            assert_ne!(
+                response.status,
+                StatusCode::METHOD_NOT_ALLOWED,
+                "{} {}",
+                method,
+                path
+            );
+            if response.status == StatusCode::NOT_FOUND {
+                assert_ne!(
+                    response.json::<Value>()["error"],
+                    "ENDPOINT_NOT_FOUND",
+                    "{} {}",
+                    method,
+                    path
+                );
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_routes_are_nested_under_base_url_path() -> Result<(), Error> {
+        let mut config = config::get_default_config();
+        config.api.base_url = "https://ornek.com/api".to_owned();
+        let app = TestApp::with_config(config).await?;
+
+        assert_eq!(app.get("/api/food/muz").await?.status, StatusCode::OK);
+        assert_eq!(app.get("/food/muz").await?.status, StatusCode::NOT_FOUND);
+        Ok(())
+    }
+}